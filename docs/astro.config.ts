@@ -56,6 +56,8 @@ export default defineConfig({
                 { label: "View", link: "/features/detail_view/" },
                 { label: "Header", link: "/features/header/" },
                 { label: "Units", link: "/features/units/" },
+                { label: "Records", link: "/features/record_view/" },
+                { label: "Snapshots", link: "/features/snapshots/" },
               ],
             },
             {
@@ -73,6 +75,8 @@ export default defineConfig({
                 { label: "Symlinks", link: "/features/symlinks/" },
                 { label: "Collapse", link: "/features/collapse/" },
                 { label: "Alignment", link: "/features/alignment/" },
+                { label: "Hyperlinks", link: "/features/hyperlinks/" },
+                { label: "ASCII", link: "/features/ascii/" },
               ],
             },
             {
@@ -80,11 +84,20 @@ export default defineConfig({
               items: [
                 { label: "Name filter", link: "/features/name_filter/" },
                 { label: "Type filter", link: "/features/type_filter/" },
+                { label: "Size filter", link: "/features/size_filter/" },
+                { label: "Time filter", link: "/features/time_filter/" },
                 { label: "Importance", link: "/features/importance/" },
+                { label: "Risks", link: "/features/risks/" },
+                { label: "Tags", link: "/features/tags/" },
               ],
             },
             { label: "Sorting", link: "/features/sorting/" },
             { label: "Colors", link: "/features/colors/" },
+            { label: "Watch", link: "/features/watch/" },
+            { label: "Interactive", link: "/features/interactive/" },
+            { label: "Serve", link: "/features/serve/" },
+            { label: "Popup", link: "/features/popup/" },
+            { label: "Rename", link: "/features/rename/" },
             { label: "Upcoming", link: "/features/upcoming/" },
           ],
         },
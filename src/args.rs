@@ -18,5 +18,6 @@ mod files_group;
 mod group;
 mod input;
 
+pub use files_group::FilesGroup;
 pub use group::Group;
 pub use input::Input;
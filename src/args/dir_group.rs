@@ -1,14 +1,20 @@
 use crate::args::input::Input;
-use crate::enums::DetailField;
+use crate::config::{Conf, ConfMan};
+use crate::enums::{DetailField, SortField, Typ};
 use crate::exc::Exc;
-use crate::models::{Node, OwnerMan};
+use crate::models::{
+	Archive, CodeOwners, Daemon, Decorator, History, Ignores, Node, OwnerMan, Stat,
+};
 use crate::traits::Imp;
+use crate::utils::mounts;
+use crate::utils::templates::fill;
 use crate::PLS;
-use log::debug;
+use log::{debug, warn};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::fs::DirEntry;
-use std::os::unix::ffi::OsStrExt;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 // ======
 // Models
@@ -41,15 +47,46 @@ impl DirGroup {
 	///
 	/// Since nodes can be nested, the function uses the flattened output of
 	/// each node's [`Node::entries`].
+	///
+	/// Along with the entries, this function returns the number of children
+	/// that were hidden by `--find`.
 	pub fn entries(
 		&self,
 		owner_man: &mut OwnerMan,
-	) -> Result<Vec<HashMap<DetailField, String>>, Exc> {
-		let mut nodes = self.nodes()?;
+	) -> Result<(Vec<HashMap<DetailField, String>>, usize), Exc> {
+		let (mut nodes, hidden) = self.nodes()?;
+		if PLS.args.decorate {
+			Decorator::decorate(&self.input.conf.decorators, &self.input.path, &mut nodes);
+		}
+		if PLS.args.uses_history() {
+			let mut histories = History::of_children(&self.input.path);
+			for node in nodes.iter_mut() {
+				node.history = histories.remove(&node.name);
+			}
+		}
+		if PLS.args.uses(DetailField::CodeOwners) {
+			if let Some(code_owners) = CodeOwners::discover(&self.input.path) {
+				for node in nodes.iter_mut() {
+					node.code_owners = code_owners
+						.owners_of(&node.name, node.typ == Typ::Dir)
+						.map(<[_]>::to_vec);
+				}
+			}
+		}
+		if PLS.args.uses(DetailField::IgnoredBy) {
+			if let Some(ignores) = Ignores::discover(&self.input.path) {
+				for node in nodes.iter_mut() {
+					node.ignored_by = ignores.source_of(&node.name, node.typ == Typ::Dir);
+				}
+			}
+		}
 		if PLS.args.collapse {
 			nodes = Self::make_tree(nodes);
 		}
-		Self::re_sort(&mut nodes, owner_man);
+		if PLS.args.tree {
+			nodes = self.grow_tree(nodes, 0);
+		}
+		Self::re_sort(&mut nodes, &self.input.conf.section_sorts, owner_man);
 
 		let entries = nodes
 			.iter()
@@ -64,38 +101,238 @@ impl DirGroup {
 				)
 			})
 			.collect();
-		Ok(entries)
+		Ok((entries, hidden))
+	}
+
+	/// Get the paths of this directory's children, in the order they are
+	/// listed, along with the number of children hidden by `--find`.
+	pub fn paths(&self, owner_man: &mut OwnerMan) -> Result<(Vec<PathBuf>, usize), Exc> {
+		let (mut nodes, hidden) = self.nodes()?;
+		Self::re_sort(&mut nodes, &self.input.conf.section_sorts, owner_man);
+		let paths = nodes.into_iter().map(|node| node.path).collect();
+		Ok((paths, hidden))
+	}
+
+	/// Call the given function with each of this directory's children, and
+	/// theirs with `--tree`, in the order they are listed.
+	pub fn visit(
+		&self,
+		owner_man: &mut OwnerMan,
+		f: &mut dyn FnMut(&Node, &Conf, &mut OwnerMan),
+	) -> Result<(), Exc> {
+		let (mut nodes, _) = self.nodes()?;
+		if PLS.args.tree {
+			nodes = self.grow_tree(nodes, 0);
+		}
+		Self::re_sort(&mut nodes, &self.input.conf.section_sorts, owner_man);
+		for node in &nodes {
+			node.walk(&mut |node| f(node, &self.input.conf, owner_man));
+		}
+		Ok(())
+	}
+
+	/// Get this group followed by the groups of its subdirectories, listed
+	/// recursively in the order they appear in this group.
+	///
+	/// Subdirectories are descended into even if their names do not match
+	/// `--only` or `--find`, since their contents might, but not if they
+	/// match `--exclude`. Symlinks to directories are not followed. The
+	/// recursion stops at the `--depth` limit, if one is set.
+	///
+	/// # Arguments
+	///
+	/// * `conf_man` - the config manager to prepare the config of each group
+	/// * `depth` - the number of levels this group is below the entered path
+	pub fn recurse(self, conf_man: &ConfMan, depth: usize) -> Vec<Self> {
+		let subdirs = if PLS.args.depth.is_some_and(|max_depth| depth >= max_depth) {
+			vec![]
+		} else {
+			match self.subdirs(&mut OwnerMan::default()) {
+				Ok(paths) => paths,
+				Err(exc) => {
+					debug!("Could not descend into {:?}: {exc}", self.input.path);
+					vec![]
+				}
+			}
+		};
+
+		let mut groups = vec![self];
+		for path in subdirs {
+			match Input::new(&path, conf_man) {
+				Ok(input) => groups.extend(Self::new(input).recurse(conf_man, depth + 1)),
+				Err(exc) => warn!("Could not descend into {path:?}: {exc}"),
+			}
+		}
+		groups
+	}
+
+	/// Get whether this group lists the contents of an archive, as opposed to
+	/// those of a directory, because of `--as-dir`.
+	pub fn is_archive(&self) -> bool {
+		PLS.args.as_dir && self.input.typ == Typ::File && Archive::is_archive(&self.input.path)
+	}
+
+	/// Get the title to show above the entries of this directory.
+	///
+	/// The title is built from the `path_header` template in the config, with
+	/// `count` being the number of entries shown. The placeholders are filled
+	/// in a single pass, so that paths containing placeholders are shown as
+	/// they are, and the paths are escaped, so that they cannot inject markup.
+	///
+	/// This function returns a marked-up string.
+	pub fn title(&self, count: usize) -> String {
+		let conf = &self.input.conf;
+		fill(&conf.app_const.path_header, |key| match key {
+			"path" => Some(self.input.path.display().to_string().replace('<', "\\<")),
+			"abs" => Some(self.input.abs.display().to_string().replace('<', "\\<")),
+			"count" => Some(count.to_string()),
+			"icon" => {
+				let mut node = Node::new(&self.input.path);
+				node.match_specs(conf);
+				Some(node.icon(conf, &conf.entry_const).render(""))
+			}
+			_ => None,
+		})
 	}
 
 	// =======
 	// Private
 	// =======
 
-	/// Convert the directory entry into a [`Node`] instance.
+	/// Nest the visible contents of each directory node under it, recursively,
+	/// for `--tree`.
 	///
-	/// This option converts the directory entry into a `Node` instance,
-	/// associates it with the right set of specs and then returns it if the
-	/// entry matches the following criteria:
+	/// Unlike [`make_tree`](DirGroup::make_tree), which nests nodes based on
+	/// the collapse specs, this nests nodes based on the actual directories.
+	/// Symlinks to directories and summarised directories are not expanded.
+	/// The nesting stops at the `--depth` limit, if one is set.
 	///
-	/// * passes the name-based `--only` and `--exclude` filters
+	/// Directories are nested into even if their names do not match `--only`,
+	/// `--find` or the filter typed in `--interactive`, and are then kept only
+	/// if they lead to nodes that do.
+	///
+	/// # Arguments
+	///
+	/// * `nodes` - the nodes to nest the contents under
+	/// * `depth` - the number of levels the nodes are below this directory
+	fn grow_tree<'a>(&'a self, nodes: Vec<Node<'a>>, depth: usize) -> Vec<Node<'a>> {
+		let can_nest = !PLS.args.depth.is_some_and(|max_depth| depth >= max_depth);
+		nodes
+			.into_iter()
+			.map(|mut node| {
+				// Summarised directories stand in for their contents.
+				if !can_nest || node.typ != Typ::Dir || node.summary.is_some() {
+					return node;
+				}
+				let Ok(entries) = mounts::read_dir(&node.path, Self::stat_timeout()) else {
+					return node;
+				};
+				let children: Vec<_> = entries
+					.into_iter()
+					.filter_map(|entry| {
+						let is_dir = entry.file_type().is_ok_and(|typ| typ.is_dir());
+						self.node(&entry.path(), None, is_dir)
+					})
+					.map(Node::tree_child)
+					.collect();
+				let children = self.grow_tree(children, depth + 1);
+				if children.is_empty() {
+					return node;
+				}
+
+				// Keep the nodes already nested by the collapse specs.
+				let mut all_children = std::mem::take(&mut node.children);
+				all_children.extend(children);
+				node.tree_parent(all_children)
+			})
+			.filter(|node| {
+				!node.children.is_empty()
+					|| node
+						.path
+						.file_name()
+						.is_some_and(|name| Self::is_name_included(name, false))
+			})
+			.collect()
+	}
+
+	/// Get the paths of this directory's subdirectories to descend into with
+	/// `--recurse`, in the order they are listed.
+	///
+	/// The subdirectories go through the same filters as the other children,
+	/// except for the name filters that only select what is shown.
+	fn subdirs(&self, owner_man: &mut OwnerMan) -> Result<Vec<PathBuf>, Exc> {
+		// Archives have no subdirectories on disk.
+		if self.is_archive() {
+			return Ok(vec![]);
+		}
+		let mut nodes: Vec<_> = self
+			.children()?
+			.into_iter()
+			.filter(|(path, _)| path.symlink_metadata().is_ok_and(|meta| meta.is_dir()))
+			.filter_map(|(path, stat)| self.node(&path, stat, true))
+			.collect();
+		Self::re_sort(&mut nodes, &self.input.conf.section_sorts, owner_man);
+		Ok(nodes.into_iter().map(|node| node.path).collect())
+	}
+
+	/// Convert the path of a child of the directory into a [`Node`] instance.
+	///
+	/// This option converts the child into a `Node` instance, associates it
+	/// with the right set of specs and then returns it if the child matches
+	/// the following criteria:
+	///
+	/// * passes the name-based `--only`, `--find` and `--exclude` filters,
+	///   and the filter typed in `--interactive`, of which directories that
+	///   are descended into only need to pass `--exclude`
 	/// * is of a type accepted by the `--typ` filter
-	/// * is above the minimum importance cutoff for visibility
+	/// * is above the minimum importance cutoff for visibility, unless hidden
+	///   nodes are shown in `--interactive`
+	/// * is flagged by a risk rule, with `--audit`
+	/// * has the tag given with `--tag`
+	/// * has a size within the bounds given with `--size`
 	///
 	/// If any criteria is not met, the node is not to be rendered and `None` is
 	/// returned.
-	fn node(&self, entry: DirEntry) -> Option<Node> {
-		let name = entry.file_name();
+	///
+	/// If the metadata of the child was cached by the daemon, it is used
+	/// instead of reading the metadata again.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the path of the child
+	/// * `stat` - the metadata of the child, if it was cached by the daemon
+	/// * `is_descended` - whether the child is a directory being descended
+	///   into, with `--recurse` or `--tree`
+	fn node(&self, path: &Path, stat: Option<Stat>, is_descended: bool) -> Option<Node<'_>> {
+		let name = path.file_name()?;
+		if !Self::is_name_included(name, is_descended) {
+			return None;
+		}
+		let node = match stat {
+			Some(stat) => Node::cached(path, stat),
+			None => Node::new(path),
+		};
+		self.admit(node)
+	}
+
+	/// Determine whether the name passes the name-based `--only`, `--find` and
+	/// `--exclude` filters, and the filter typed in `--interactive`.
+	///
+	/// Directories that are descended into only need to pass `--exclude`, so
+	/// that the matches nested inside them can still be found.
+	fn is_name_included(name: &OsStr, is_descended: bool) -> bool {
 		debug!("Checking visibility of name {name:?}.");
-		let haystack = name.as_bytes();
+		let haystack = name.as_encoded_bytes();
 
-		let include = PLS
-			.args
-			.only
-			.as_ref()
-			.map_or(true, |pat| pat.is_match(haystack));
+		let view = PLS.view();
+		let include = is_descended
+			|| [&PLS.args.only, &PLS.args.find, &view.filter]
+				.into_iter()
+				.flatten()
+				.all(|pat| pat.is_match(haystack));
 		if !include {
-			debug!("Name {name:?} did not match `--only`.");
-			return None;
+			debug!("Name {name:?} did not match `--only`, `--find` or the filter.");
+			return false;
 		}
 
 		let exclude = PLS
@@ -105,59 +342,228 @@ impl DirGroup {
 			.is_some_and(|pat| pat.is_match(haystack));
 		if exclude {
 			debug!("Name {name:?} matched `--exclude`.");
-			return None;
+			return false;
 		}
 
-		let mut node = Node::new(&entry.path());
+		true
+	}
 
+	/// Apply the type, spec, visibility, `--audit`, `--tag` and `--size`
+	/// filters to the node, returning it only if it is to be rendered.
+	///
+	/// The node is also summarised here, if its specs ask for it.
+	fn admit<'a>(&'a self, mut node: Node<'a>) -> Option<Node<'a>> {
 		debug!("Checking visibility of typ {:?}.", node.typ);
 		if !PLS.args.typs.contains(&node.typ) {
 			return None;
 		}
 
-		node.match_specs(&self.input.conf.specs);
+		node.match_specs(&self.input.conf);
 
-		if !node.is_visible(&self.input.conf) {
+		if !PLS.view().show_hidden && !node.is_visible(&self.input.conf) {
 			return None;
 		}
 
+		node.match_risks(&self.input.conf);
+		node.redact(&self.input.conf);
+		// Directories lead to flagged nodes when descending into them.
+		let is_descended = node.typ == Typ::Dir && (PLS.args.recurse || PLS.args.tree);
+		if PLS.args.audit && node.risks.is_empty() && !is_descended {
+			debug!("Node {:?} was not flagged by `--audit`.", node.name);
+			return None;
+		}
+		if let Some(tag) = &PLS.args.tag {
+			if !is_descended && !node.tags(&self.input.conf.entry_const).contains(tag) {
+				debug!("Node {:?} was not tagged {tag:?}.", node.name);
+				return None;
+			}
+		}
+		if !is_descended && !node.is_size_included() {
+			debug!("Node {:?} did not match `--size`.", node.name);
+			return None;
+		}
+		if !is_descended && !node.is_mtime_included() {
+			debug!(
+				"Node {:?} was not modified within the time window.",
+				node.name
+			);
+			return None;
+		}
+
+		node.summarize();
+
 		Some(node)
 	}
 
+	/// Get a list of the nodes inside the given directory of the archive,
+	/// nesting the contents of subdirectories for `--tree`.
+	///
+	/// The nodes go through the same filters as the children of a directory.
+	/// Only the nodes at the top level that do not match `--find` are added
+	/// to `hidden`.
+	///
+	/// # Arguments
+	///
+	/// * `archive` - the archive read from the path of this group
+	/// * `dir` - the directory inside the archive, empty for its root
+	/// * `depth` - the number of levels the directory is below the root
+	/// * `hidden` - the number of nodes hidden by `--find`
+	fn archive_nodes<'a>(
+		&'a self,
+		archive: &Archive,
+		dir: &Path,
+		depth: usize,
+		hidden: &mut usize,
+	) -> Vec<Node<'a>> {
+		let mut nodes = vec![];
+		for entry in archive.children(dir) {
+			let path = self.input.path.join(&entry.path);
+			let Some(name) = path.file_name() else {
+				continue;
+			};
+			if depth == 0 && !PLS.args.is_found(name.as_encoded_bytes()) {
+				*hidden += 1;
+				continue;
+			}
+			if !Self::is_name_included(name, false) {
+				continue;
+			}
+			let Some(node) = self.admit(Node::archived(&path, entry.typ, entry.meta.clone()))
+			else {
+				continue;
+			};
+
+			let can_nest = PLS.args.tree
+				&& node.typ == Typ::Dir
+				&& !PLS.args.depth.is_some_and(|max_depth| depth >= max_depth);
+			let children: Vec<_> = if can_nest {
+				self.archive_nodes(archive, &entry.path, depth + 1, hidden)
+					.into_iter()
+					.map(Node::tree_child)
+					.collect()
+			} else {
+				vec![]
+			};
+			nodes.push(if children.is_empty() {
+				node
+			} else {
+				node.tree_parent(children)
+			});
+		}
+		nodes
+	}
+
 	/// Get a list of all nodes that are a children of this directory.
 	///
 	/// Unlike [`FilesGroup`](crate::args::files_group::FilesGroup), this
-	/// function filters out nodes based on visibility.
-	fn nodes(&self) -> Result<Vec<Node>, Exc> {
-		let entries = self.input.path.read_dir().map_err(Exc::Io)?;
+	/// function filters out nodes based on visibility. The number of nodes
+	/// that did not match `--find` is returned alongside the list.
+	fn nodes(&self) -> Result<(Vec<Node<'_>>, usize), Exc> {
+		if self.is_archive() {
+			let archive = Archive::open(&self.input.path)?;
+			let mut hidden = 0;
+			let nodes = self.archive_nodes(&archive, Path::new(""), 0, &mut hidden);
+			return Ok((nodes, hidden));
+		}
 
-		let entries = entries
-			.filter_map(|entry| entry.ok().and_then(|entry| self.node(entry)))
+		let mut hidden = 0;
+		let nodes = self
+			.children()?
+			.into_iter()
+			.filter_map(|(path, stat)| {
+				let name = path.file_name()?;
+				let is_found = PLS.args.is_found(name.as_encoded_bytes());
+				// With `--tree`, directories whose names do not match are kept
+				// until their contents are known.
+				let is_descended = PLS.args.tree
+					&& !(is_found && Self::is_name_included(name, false))
+					&& path.symlink_metadata().is_ok_and(|meta| meta.is_dir());
+				if !is_found && !is_descended {
+					hidden += 1;
+					return None;
+				}
+				self.node(&path, stat, is_descended)
+			})
 			.collect();
-		Ok(entries)
+		Ok((nodes, hidden))
+	}
+
+	/// Get the paths of this directory's children, along with their metadata
+	/// if it was cached by the daemon.
+	///
+	/// Children that could not be read are skipped.
+	fn children(&self) -> Result<Vec<(PathBuf, Option<Stat>)>, Exc> {
+		let children = match PLS
+			.args
+			.from_daemon
+			.then(|| Daemon::list(&self.input.path))
+			.flatten()
+		{
+			Some(children) => children,
+			None => mounts::read_dir(&self.input.path, Self::stat_timeout())
+				.map_err(Exc::Io)?
+				.into_iter()
+				.map(|entry| (entry.path(), None))
+				.collect(),
+		};
+		Ok(children)
 	}
 
 	// ======
 	// Static
 	// ======
 
+	/// Get the time that reading a directory or the metadata of a node may
+	/// take, as set by `--stat-timeout`.
+	fn stat_timeout() -> Option<Duration> {
+		PLS.args.stat_timeout.map(Duration::from_millis)
+	}
+
 	/// Recursively sort the given list of nodes and their children.
 	///
 	/// This function iterates over all the sort bases and sorts the given list
 	/// of nodes. It is invoked both from the top-level and from each parent
 	/// node to sort its children.
-	fn re_sort(nodes: &mut [Node], owner_man: &mut OwnerMan) {
+	///
+	/// If the first sort base places the nodes in sections, like `cat` does
+	/// for directories and files, each section with its own sort fields in
+	/// `section_sorts` is then sorted again by those fields. Finally, pinned
+	/// nodes are lifted to the top, keeping their sorted order.
+	fn re_sort(
+		nodes: &mut [Node],
+		section_sorts: &HashMap<Typ, Vec<SortField>>,
+		owner_man: &mut OwnerMan,
+	) {
 		if nodes.len() <= 1 {
 			return;
 		}
-		PLS.args.sort_bases.iter().rev().for_each(|field| {
-			nodes.sort_by(|a, b| field.compare(a, b, owner_man));
-		});
+		// The view is not held while sorting, since sorting can take a while.
+		let sort_bases = PLS.view().sort_bases.clone();
+		Self::sort_by_fields(nodes, &sort_bases, owner_man);
+		if let Some(first) = sort_bases.first().filter(|_| !section_sorts.is_empty()) {
+			for section in nodes.chunk_by_mut(|a, b| first.section(a.typ) == first.section(b.typ)) {
+				let fields = first
+					.section(section[0].typ)
+					.and_then(|key| section_sorts.get(&key));
+				if let Some(fields) = fields {
+					Self::sort_by_fields(section, fields, owner_man);
+				}
+			}
+		}
+		nodes.sort_by_key(|node| !node.is_pinned());
 		for node in nodes {
-			Self::re_sort(&mut node.children, owner_man);
+			Self::re_sort(&mut node.children, section_sorts, owner_man);
 		}
 	}
 
+	/// Sort the given list of nodes by the given fields, the first of which
+	/// takes precedence.
+	fn sort_by_fields(nodes: &mut [Node], fields: &[SortField], owner_man: &mut OwnerMan) {
+		fields.iter().rev().for_each(|field| {
+			nodes.sort_by(|a, b| field.compare(a, b, owner_man));
+		});
+	}
+
 	/// Recursively move children nodes into their parent nodes.
 	fn re_make_node<'a>(
 		node: Node<'a>,
@@ -1,8 +1,9 @@
 use crate::args::input::Input;
 use crate::config::{Conf, ConfMan};
-use crate::enums::DetailField;
-use crate::models::{Node, OwnerMan};
+use crate::enums::{DetailField, Typ};
+use crate::models::{CodeOwners, History, Ignores, Node, OwnerMan};
 use crate::utils::paths::common_ancestor;
+use crate::PLS;
 use log::debug;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -37,6 +38,11 @@ impl FilesGroup {
 		let common_ancestor = common_ancestor(&abs);
 		let mut conf = conf_man.get(common_ancestor.as_ref()).unwrap_or_default();
 		conf.app_const.massage_imps();
+		conf.apply_palette();
+		conf.apply_icon_set();
+		conf.apply_ascii();
+		conf.apply_ls_colors();
+		conf.entry_const.resolve_refs();
 
 		Self {
 			inputs,
@@ -49,13 +55,32 @@ impl FilesGroup {
 	// Public
 	// ======
 
+	/// Get the paths of the files that match `--find`, as entered in the CLI,
+	/// along with the number of files that do not.
+	pub fn paths(&self) -> (Vec<PathBuf>, usize) {
+		let (inputs, hidden) = self.found();
+		let paths = inputs.iter().map(|input| input.path.clone()).collect();
+		(paths, hidden)
+	}
+
+	/// Call the given function with each of the files.
+	pub fn visit(&self, owner_man: &mut OwnerMan, f: &mut dyn FnMut(&Node, &Conf, &mut OwnerMan)) {
+		for (node, conf) in self.nodes().0 {
+			f(&node, conf, owner_man);
+		}
+	}
+
 	/// Convert this list of files into entries for the output layout.
 	///
 	/// Since individual nodes are not nested, the function uses each node's
 	/// [`Node::row`] instead of the flattened output of each node's
 	/// [`Node::entries`].
-	pub fn entries(&self, owner_man: &mut OwnerMan) -> Vec<HashMap<DetailField, String>> {
-		self.nodes()
+	///
+	/// Along with the entries, this function returns the number of files that
+	/// were hidden by `--find`.
+	pub fn entries(&self, owner_man: &mut OwnerMan) -> (Vec<HashMap<DetailField, String>>, usize) {
+		let (nodes, hidden) = self.nodes();
+		let entries = nodes
 			.iter()
 			.map(|(node, conf)| {
 				node.row(
@@ -66,7 +91,8 @@ impl FilesGroup {
 					&[],
 				)
 			})
-			.collect()
+			.collect();
+		(entries, hidden)
 	}
 
 	// =======
@@ -78,18 +104,87 @@ impl FilesGroup {
 	/// Unlike [`DirGroup`](crate::args::dir_group::DirGroup), this function
 	/// does not filter out nodes based on their visibility. This is because the
 	/// files in this group have been explicitly provided by the user and should
-	/// be rendered regardless of their visibility.
-	fn nodes(&self) -> Vec<(Node, &Conf)> {
-		self.inputs
-			.iter()
+	/// be rendered regardless of their visibility. Only `--audit`, `--tag` and
+	/// `--size` filter them, keeping the files flagged by risk rules, with the
+	/// tag or within the bounds. Like the children of a directory, the files
+	/// are also filtered by `--find`, and the number of files that do not match
+	/// is returned alongside the list.
+	fn nodes(&self) -> (Vec<(Node<'_>, &Conf)>, usize) {
+		let use_history = PLS.args.uses_history();
+		// mapping of parent directories to the histories of their children
+		let mut parent_histories: HashMap<PathBuf, HashMap<String, History>> = HashMap::new();
+		let use_code_owners = PLS.args.uses(DetailField::CodeOwners);
+		// mapping of parent directories to the code owners of their children
+		let mut parent_code_owners: HashMap<PathBuf, Option<CodeOwners>> = HashMap::new();
+		let use_ignores = PLS.args.uses(DetailField::IgnoredBy);
+		// mapping of parent directories to the ignore files of their children
+		let mut parent_ignores: HashMap<PathBuf, Option<Ignores>> = HashMap::new();
+
+		let (inputs, hidden) = self.found();
+		let nodes = inputs
+			.into_iter()
 			.map(|input| {
 				let display_name = input.path.to_string_lossy().to_string();
 				let mut node = Node::new(&input.path).solo_file(display_name);
 				debug!("Currently {} specs", input.conf.specs.len());
-				node.match_specs(&input.conf.specs);
+				node.match_specs(&input.conf);
+				node.match_risks(&input.conf);
+				node.redact(&input.conf);
+				node.summarize();
+				if use_history {
+					if let Some(parent) = input.abs.parent() {
+						node.history = parent_histories
+							.entry(parent.to_path_buf())
+							.or_insert_with(|| History::of_children(parent))
+							.remove(&node.name);
+					}
+				}
+				if use_code_owners {
+					if let Some(parent) = input.abs.parent() {
+						node.code_owners = parent_code_owners
+							.entry(parent.to_path_buf())
+							.or_insert_with(|| CodeOwners::discover(parent))
+							.as_ref()
+							.and_then(|code_owners| {
+								code_owners.owners_of(&node.name, node.typ == Typ::Dir)
+							})
+							.map(<[_]>::to_vec);
+					}
+				}
+				if use_ignores {
+					if let Some(parent) = input.abs.parent() {
+						node.ignored_by = parent_ignores
+							.entry(parent.to_path_buf())
+							.or_insert_with(|| Ignores::discover(parent))
+							.as_ref()
+							.and_then(|ignores| {
+								ignores.source_of(&node.name, node.typ == Typ::Dir)
+							});
+					}
+				}
 				(node, &input.conf)
 			})
-			.collect()
+			.filter(|(node, _)| !PLS.args.audit || !node.risks.is_empty())
+			.filter(|(node, conf)| {
+				PLS.args
+					.tag
+					.as_ref()
+					.map_or(true, |tag| node.tags(&conf.entry_const).contains(tag))
+			})
+			.filter(|(node, _)| node.is_size_included())
+			.filter(|(node, _)| node.is_mtime_included())
+			.collect();
+		(nodes, hidden)
+	}
+
+	/// Get the inputs whose names match `--find`, along with the number of
+	/// inputs that do not.
+	fn found(&self) -> (Vec<&Input>, usize) {
+		let (found, missed): (Vec<_>, Vec<_>) = self.inputs.iter().partition(|input| {
+			let name = input.path.file_name().unwrap_or(input.path.as_os_str());
+			PLS.args.is_found(name.as_encoded_bytes())
+		});
+		(found, missed.len())
 	}
 }
 
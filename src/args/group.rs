@@ -5,10 +5,11 @@ use crate::config::{Conf, ConfMan};
 use crate::enums::{DetailField, Typ};
 use crate::exc::Exc;
 use crate::fmt::render;
-use crate::models::OwnerMan;
-use crate::output::{Grid, Table};
+use crate::models::{Archive, Node, OwnerMan, Quota};
+use crate::output::{Grid, Record, Sink, Table};
 use crate::PLS;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 // ======
 // Models
@@ -36,12 +37,19 @@ impl Group {
 	///
 	/// Each directory becomes its own group, denoted by [`DirGroup`], while
 	/// all files are collected into a single group denoted by [`FilesGroup`].
-	/// This separation is an implementation detail.
+	/// This separation is an implementation detail. With `--recurse`, each
+	/// subdirectory also becomes its own group, right after its parent. With
+	/// `--as-dir`, each archive also becomes its own group, like a directory.
 	pub fn partition(inputs: Vec<Input>, conf_man: &ConfMan) -> Vec<Self> {
 		let mut groups = vec![];
 		let mut files = vec![];
 		for input in inputs {
-			if input.typ == Typ::Dir {
+			if input.typ == Typ::Dir && PLS.args.recurse {
+				let dir_groups = DirGroup::new(input).recurse(conf_man, 0);
+				groups.extend(dir_groups.into_iter().map(Self::Dir));
+			} else if input.typ == Typ::Dir
+				|| (PLS.args.as_dir && input.typ == Typ::File && Archive::is_archive(&input.path))
+			{
 				groups.push(Self::Dir(DirGroup::new(input)));
 			} else {
 				files.push(input);
@@ -53,27 +61,52 @@ impl Group {
 		groups
 	}
 
-	pub fn render(&self, show_title: bool, owner_man: &mut OwnerMan) -> Result<(), Exc> {
+	/// Render the group to the output.
+	///
+	/// This function returns the number of entries that were shown and the
+	/// number of entries that were hidden by `--find`.
+	pub fn render(
+		&self,
+		show_title: bool,
+		owner_man: &mut OwnerMan,
+	) -> Result<(usize, usize), Exc> {
+		if let Some(quoting) = PLS.args.shell_quote {
+			let (paths, hidden) = self.paths(owner_man)?;
+			for path in &paths {
+				Sink::println(quoting.quote(&path.to_string_lossy()));
+			}
+			return Ok((paths.len(), hidden));
+		}
+
+		let (entries, hidden) = self.entries(owner_man)?;
+		let shown = entries.len();
+
 		if show_title {
 			if let Self::Dir(group) = self {
-				println!(
-					"\n{}",
-					render(format!("<bold>{}:</bold>", group.input.path.display()))
-				);
+				Sink::println(format!("\n{}", render(group.title(shown))));
 			}
 		}
 
-		let entries = self.entries(owner_man)?;
+		if PLS.args.quota {
+			if let Some(quota) = self.quota() {
+				let conf = self.conf();
+				let label = quota.label(owner_man, &conf.app_const, &conf.entry_const);
+				Sink::println(render(label));
+			}
+		}
 
 		if PLS.args.grid {
-			let grid = Grid::new(entries);
+			let grid = Grid::new(entries, &self.conf().app_const.grid_details);
 			grid.render(&self.conf().app_const);
+		} else if PLS.args.record {
+			let record = Record::new(entries);
+			record.render(&self.conf().app_const)?;
 		} else {
 			let table = Table::new(entries, matches!(self, Self::Files(_)));
-			table.render(&self.conf().app_const);
+			table.render(&self.conf().app_const)?;
 		}
 
-		Ok(())
+		Ok((shown, hidden))
 	}
 
 	/// Get the config for this group.
@@ -81,19 +114,56 @@ impl Group {
 	/// For a directory, the config file inside the directory is used. For a
 	/// group of files, the config file in the common ancestor directory is
 	/// used.
-	fn conf(&self) -> &Conf {
+	pub fn conf(&self) -> &Conf {
 		match self {
 			Self::Dir(group) => &group.input.conf,
 			Self::Files(group) => &group.parent_conf,
 		}
 	}
 
+	/// Get the disk quota of the owner of the directory, for `--quota`.
+	///
+	/// Groups of files span any number of directories, so they have no quota.
+	pub fn quota(&self) -> Option<Quota> {
+		match self {
+			Self::Dir(group) => Quota::of(&group.input.path),
+			Self::Files(_) => None,
+		}
+	}
+
+	/// Get the paths of the nodes in this group, in the order they are listed,
+	/// along with the number of nodes hidden by `--find`.
+	pub fn paths(&self, owner_man: &mut OwnerMan) -> Result<(Vec<PathBuf>, usize), Exc> {
+		match self {
+			Self::Dir(group) => group.paths(owner_man),
+			Self::Files(group) => Ok(group.paths()),
+		}
+	}
+
+	/// Call the given function with each node in this group, in the order
+	/// they are listed, along with the config that applies to it.
+	///
+	/// This is used by the outputs that are written alongside the listing,
+	/// like `--fix-script` and `--snapshot`.
+	pub fn visit(
+		&self,
+		owner_man: &mut OwnerMan,
+		f: &mut dyn FnMut(&Node, &Conf, &mut OwnerMan),
+	) -> Result<(), Exc> {
+		match self {
+			Self::Dir(group) => group.visit(owner_man, f)?,
+			Self::Files(group) => group.visit(owner_man, f),
+		}
+		Ok(())
+	}
+
 	/// Convert this group into a vector of entries that can be passed into the
-	/// layout to be rendered.
+	/// layout to be rendered, along with the number of entries hidden by
+	/// `--find`.
 	pub fn entries(
 		&self,
 		owner_man: &mut OwnerMan,
-	) -> Result<Vec<HashMap<DetailField, String>>, Exc> {
+	) -> Result<(Vec<HashMap<DetailField, String>>, usize), Exc> {
 		match self {
 			Self::Dir(group) => group.entries(owner_man),
 			Self::Files(group) => Ok(group.entries(owner_man)),
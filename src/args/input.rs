@@ -2,6 +2,7 @@ use crate::config::{Conf, ConfMan};
 use crate::enums::Typ;
 use crate::exc::Exc;
 use crate::ext::Abs;
+use crate::PLS;
 use log::debug;
 use std::path::{Path, PathBuf};
 
@@ -13,7 +14,7 @@ use std::path::{Path, PathBuf};
 ///
 /// The path entered in the CLI can be a file or a directory. The path may be a
 /// symlink, which should not be resolved, and treated as a file, even if it
-/// points to a directory.
+/// points to a directory, unless `--dereference` is set.
 pub struct Input {
 	/// the path as entered in the CLI
 	pub path: PathBuf,
@@ -43,14 +44,38 @@ impl std::fmt::Debug for Input {
 
 impl Input {
 	pub fn new(path: &Path, conf_man: &ConfMan) -> Result<Self, Exc> {
+		// With `--dereference`, symlinks to directories are listed like the
+		// directories themselves.
+		let typ = match path.metadata() {
+			Ok(meta) if PLS.args.dereference => (&meta).into(),
+			_ => path.try_into()?,
+		};
+		Self::with_typ(path, typ, conf_man)
+	}
+
+	/// Create an `Input` for a path that does not exist on the file system.
+	///
+	/// This is used for paths that are not entered by the user directly, like
+	/// bookmarks, where a missing path should be shown rather than skipped.
+	pub fn missing(path: &Path, conf_man: &ConfMan) -> Result<Self, Exc> {
+		Self::with_typ(path, Typ::Unknown, conf_man)
+	}
+
+	fn with_typ(path: &Path, typ: Typ, conf_man: &ConfMan) -> Result<Self, Exc> {
 		let path_buf = path.to_path_buf();
 		let abs = path.abs();
 
-		let typ = path.try_into()?;
-
 		let mut conf = conf_man.get(Some(&path))?;
 		debug!("{path:?} {:?}", conf.specs);
 		conf.app_const.massage_imps();
+		conf.apply_palette();
+		conf.apply_icon_set();
+		conf.apply_ascii();
+		conf.apply_ls_colors();
+		conf.entry_const.resolve_refs();
+		if typ == Typ::Dir {
+			conf.apply_spec_packs(&abs);
+		}
 
 		Ok(Self {
 			path: path_buf,
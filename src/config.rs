@@ -9,22 +9,25 @@
 //! shown in a given run, whereas the `.pls.yml` file can be used to change the
 //! individual name for these columns.
 //!
-//! The public interface of the module consists of five structs:
+//! The public interface of the module consists of six structs:
 //!
 //! * [`AppConst`]
 //! * [`Args`]
+//! * [`Bookmarks`]
 //! * [`Conf`]
 //! * [`EntryConst`]
 //! * [`ConfMan`]
 
 mod app_const;
 mod args;
+mod bookmarks;
 mod conf;
 mod entry_const;
 mod man;
 
-pub use app_const::AppConst;
+pub use app_const::{AppConst, InteractiveInfo};
 pub use args::Args;
+pub use bookmarks::Bookmarks;
 pub use conf::Conf;
 pub use entry_const::EntryConst;
 pub use man::ConfMan;
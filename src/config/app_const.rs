@@ -1,4 +1,5 @@
-use crate::enums::DetailField;
+use crate::enums::{DetailField, KeyAction, SortField, SymState, Typ};
+use crate::PLS;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -6,8 +7,29 @@ use std::collections::HashMap;
 pub struct AppConst {
 	/// configuration for the table view
 	pub table: TableInfo,
+	/// the details to show next to the name of each node in the grid view,
+	/// like `size` or `mtime`
+	pub grid_details: Vec<DetailField>,
 	/// shapes to use to print trees
 	pub tree: TreeInfo,
+	/// shapes to use to print trees when the locale does not support UTF-8,
+	/// or with `--ascii`
+	pub ascii_tree: TreeInfo,
+	/// glyphs to use in place of the non-ASCII ones with `--ascii`
+	pub ascii: AsciiInfo,
+	/// template for the title shown above each directory when listing multiple
+	/// paths; supports the placeholders `{path}` (as entered), `{abs}`
+	/// (absolute), `{icon}` and `{count}` (number of entries shown)
+	pub path_header: String,
+	/// configuration for the line showing the quota of the owner of each
+	/// directory
+	pub quota: QuotaInfo,
+	/// configuration for the prompt view
+	pub prompt: PromptInfo,
+	/// configuration for `pls daemon`
+	pub daemon: DaemonInfo,
+	/// configuration for interactive mode
+	pub interactive: InteractiveInfo,
 	/// pairings of importance levels with styling directives
 	pub imp_styles: Vec<(i8, String)>,
 
@@ -22,6 +44,11 @@ pub struct TableInfo {
 	pub column_names: HashMap<DetailField, String>,
 	/// styles to apply to the text in the header row
 	pub header_style: String,
+	/// styles to apply to the column names in the note about elided columns
+	pub elided_style: String,
+	/// whether to wrap names that do not fit in the terminal onto indented
+	/// continuation lines, instead of letting them overflow
+	pub wrap_names: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -36,41 +63,188 @@ pub struct TreeInfo {
 	pub bend_dash: String,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct AsciiInfo {
+	/// mapping of symlink states to the separators shown between symlinks
+	/// and their targets
+	pub symlink: HashMap<SymState, String>,
+	/// mapping of node types to the suffixes placed after their names
+	pub suffix: HashMap<Typ, String>,
+	/// mapping of icon names to glyphs; icons not in this map fall back to
+	/// the icon of the node type
+	pub icons: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct QuotaInfo {
+	/// style for the name of the user whose quota is shown
+	pub style: String,
+	/// style for the name of the user when the quota has been exceeded
+	pub exceeded_style: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PromptInfo {
+	/// the time, in milliseconds, that expensive probes like Git status may
+	/// take before they are abandoned
+	pub budget_ms: u64,
+	/// the maximum number of important nodes to name
+	pub top_nodes: usize,
+	/// the maximum width of the name of each important node, beyond which
+	/// it is truncated
+	pub name_width: usize,
+	/// style for the name of the Git branch
+	pub branch_style: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DaemonInfo {
+	/// the directories whose listings the daemon keeps warm; these may use
+	/// `~` and environment variables
+	pub dirs: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InteractiveInfo {
+	/// mapping of keys to the actions they perform; keys are single
+	/// characters, the names `enter`, `esc`, `backspace` and `tab`, or
+	/// `ctrl-` followed by a letter
+	pub keys: HashMap<String, KeyAction>,
+	/// the fields that the `cycle_sort` action steps through
+	pub sort_cycle: Vec<SortField>,
+	/// style for the status line shown above the listing
+	pub status_style: String,
+}
+
 impl Default for AppConst {
 	fn default() -> Self {
 		Self {
 			table: TableInfo {
 				header_style: String::from("bold italic underline"),
+				elided_style: String::from("dimmed"),
+				wrap_names: false,
 				column_names: [
 					(DetailField::Dev, "Device"),
+					(DetailField::Rdev, "Dev No."),
 					(DetailField::Ino, "inode"),
 					(DetailField::Nlink, "Link#"),
 					(DetailField::Typ, "T"),
 					(DetailField::Perm, "Permissions"),
+					(DetailField::Attrs, "Attrs"),
+					(DetailField::Xattr, "@"),
+					(DetailField::Acl, "ACL"),
 					(DetailField::Oct, "SUGO"),
 					(DetailField::User, "User"),
 					(DetailField::Uid, "UID"),
 					(DetailField::Group, "Group"),
 					(DetailField::Gid, "GID"),
 					(DetailField::Size, "Size"),
+					(DetailField::SizeRaw, "Bytes"),
 					(DetailField::Blocks, "Blocks"),
 					(DetailField::Btime, "Created"),
 					(DetailField::Ctime, "Changed"),
 					(DetailField::Mtime, "Modified"),
 					(DetailField::Atime, "Accessed"),
 					(DetailField::Git, "Git"),
+					(DetailField::Commits, "Commits"),
+					(DetailField::GitAuthor, "Author"),
+					(DetailField::GitDate, "Committed"),
+					(DetailField::CodeOwners, "Owners"),
+					(DetailField::IgnoredBy, "Ignored by"),
+					(DetailField::Marker, "Imp"),
+					(DetailField::Comm, "Command"),
+					(DetailField::Role, "Role"),
+					(DetailField::Fix, "Fix"),
+					(DetailField::Style, "Style"),
+					(DetailField::Icon, "Icon"),
 					(DetailField::Name, "Name"),
 				]
 				.into_iter()
 				.map(|(k, v)| (k, v.to_string()))
 				.collect(),
 			},
+			grid_details: vec![],
 			tree: TreeInfo {
 				pipe_space: String::from("│  "),
 				space_space: String::from("   "),
 				tee_dash: String::from("├─ "),
 				bend_dash: String::from("└─ "),
 			},
+			ascii_tree: TreeInfo {
+				pipe_space: String::from("|  "),
+				space_space: String::from("   "),
+				tee_dash: String::from("|- "),
+				bend_dash: String::from("`- "),
+			},
+			ascii: AsciiInfo {
+				symlink: [
+					(SymState::Ok, "->"),
+					(SymState::Broken, "-x"),
+					(SymState::Cyclic, "<>"),
+					(SymState::Error, "-!"),
+				]
+				.into_iter()
+				.map(|(k, v)| (k, v.to_string()))
+				.collect(),
+				suffix: [
+					(Typ::Dir, "<dimmed>/</>"),
+					(Typ::Symlink, "<dimmed>@</>"),
+					(Typ::Junction, "<dimmed>@</>"),
+					(Typ::Fifo, "<dimmed>|</>"),
+					(Typ::Socket, "<dimmed>=</>"),
+				]
+				.into_iter()
+				.map(|(k, v)| (k, v.to_string()))
+				.collect(),
+				icons: [
+					("missing", "!"),
+					("exec", "*"),
+					("file", "-"),
+					("dir", "+"),
+					("symlink", "~"),
+					("junction", "~"),
+					("reparse", "%"),
+					("fifo", "|"),
+					("socket", "="),
+					("char_device", "c"),
+					("block_device", "b"),
+					("process", "#"),
+				]
+				.into_iter()
+				.map(|(k, v)| (k.to_string(), v.to_string()))
+				.collect(),
+			},
+			path_header: String::from("<bold>{path}:</>"),
+			quota: QuotaInfo {
+				style: String::from("dimmed"),
+				exceeded_style: String::from("red bold"),
+			},
+			prompt: PromptInfo {
+				budget_ms: 50,
+				top_nodes: 3,
+				name_width: 20,
+				branch_style: String::from("magenta"),
+			},
+			daemon: DaemonInfo { dirs: vec![] },
+			interactive: InteractiveInfo {
+				keys: [
+					("/", KeyAction::Filter),
+					("s", KeyAction::CycleSort),
+					(".", KeyAction::ToggleHidden),
+					("i", KeyAction::ToggleIcons),
+					("q", KeyAction::Quit),
+				]
+				.into_iter()
+				.map(|(k, v)| (k.to_string(), v))
+				.collect(),
+				sort_cycle: vec![
+					SortField::Size_,
+					SortField::Mtime_,
+					SortField::Ext,
+					SortField::Cname,
+				],
+				status_style: String::from("reversed"),
+			},
 			imp_styles: [(-1, "dimmed"), (1, "italic"), (2, "underline")]
 				.into_iter()
 				.map(|(k, v)| (k, v.to_string()))
@@ -98,6 +272,18 @@ impl AppConst {
 		self.imp_styles.sort_by_cached_key(|entry| entry.0);
 	}
 
+	/// Get the shapes to use to print trees.
+	///
+	/// The ASCII shapes are used if the locale does not support UTF-8, as the
+	/// box-drawing characters would otherwise be garbled, or with `--ascii`.
+	pub fn tree_shapes(&self) -> &TreeInfo {
+		if PLS.supports_utf8 && !PLS.args.ascii {
+			&self.tree
+		} else {
+			&self.ascii_tree
+		}
+	}
+
 	/// Get the lowest configured importance level, i.e. zeroth index in `imp`.
 	pub fn min_imp(&self) -> i8 {
 		self.get_imp(0)
@@ -1,13 +1,28 @@
-use crate::enums::{DetailField, SortField, Typ, UnitSys};
+use crate::config::ConfMan;
+use crate::enums::{
+	Background, Cmd, Collation, ColorMode, DetailField, Direction, Dirs, Export, ExportIcons,
+	IconSet, Palette, PatternSyntax, Quoting, SizeFilter, SortField, TimeBound, TimeStyle, Typ,
+	UnitSys,
+};
 use crate::fmt::render;
+use crate::gfx::detect_background;
+use crate::models::{regex_source, Popup};
+use crate::utils::templates::keys;
 use crate::utils::urls::get_osc;
-use clap::Parser;
-use log::warn;
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::error::{Error, ErrorKind};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, Command, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use log::{warn, LevelFilter};
 use regex::bytes::{Regex, RegexBuilder};
 use regex::Error as RegexError;
+use std::collections::HashMap;
+use std::ffi::OsStr;
 #[cfg(test)]
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Parse the given string into a [`Regex`] while turning off Unicode mode.
 ///
@@ -21,6 +36,41 @@ fn regex_parser(s: &str) -> Result<Regex, RegexError> {
 	RegexBuilder::new(s).unicode(false).build()
 }
 
+/// Parses the values of `--det`, which can be detail fields or the names of
+/// detail presets.
+///
+/// Presets come from the config files, so the values cannot be validated
+/// while parsing. The detail fields are still listed as the possible values
+/// so that they show up in the help text.
+#[derive(Clone)]
+struct DetailParser;
+
+impl TypedValueParser for DetailParser {
+	type Value = String;
+
+	fn parse_ref(&self, cmd: &Command, _arg: Option<&Arg>, value: &OsStr) -> Result<String, Error> {
+		value
+			.to_str()
+			.map(String::from)
+			.ok_or_else(|| Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))
+	}
+
+	fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+		Some(Box::new(
+			DetailField::value_variants()
+				.iter()
+				.filter_map(ValueEnum::to_possible_value),
+		))
+	}
+}
+
+/// Determine whether the given path is the `/dev` directory, following any
+/// symlinks.
+fn is_devfs(path: &Path) -> bool {
+	path.canonicalize()
+		.is_ok_and(|path| path == Path::new("/dev"))
+}
+
 /// Represents the command-line arguments to `pls`.
 ///
 /// `pls` picks sane defaults for the CLI arguments. If you prefer different
@@ -43,27 +93,287 @@ fn regex_parser(s: &str) -> Result<Regex, RegexError> {
 		get_osc("https://github.com/pls-rs/pls/", None),
 		get_osc("https://github.com/sponsors/dhruvkb/", None),
 	)),
+	after_long_help = render(
+		"<bold>Precedence:</>
+When flags cannot be honoured together, the first one listed wins.
+  --record  over --grid, --tree, --collapse, --tile and --xattrs
+  --det     over --grid
+  --grid    over --header, --sym, --collapse and --tree
+  --tree    over --recurse and --tile
+  --quiet   over --header, --elide-uniform and --quota
+  --bookmarks enables the standard details unless --det is given.
+  --shell-quote enables --quiet.
+  --tree keeps the nodes nested by --collapse under their parents.
+
+<bold>Conflicts:</>
+--open, --prompt, --rename-edit and --bookmarks select what to do and cannot be
+combined, and neither --open nor --prompt can be combined with --shell-quote.
+--watch cannot be combined with any of these, --export or --render-to.
+--serve cannot be combined with any of these or --watch.
+--interactive cannot be combined with any of these, --export, --render-to,
+--watch, --serve or --popup.
+--popup cannot be combined with --open, --prompt, --rename-edit, --export,
+--watch, --serve or --width.
+--fix-script and --snapshot cannot be combined with --open, --prompt,
+--rename-edit, --watch, --serve or --interactive, and --compare cannot be
+combined with --open, --prompt, --rename-edit, --watch or --serve.
+
+<bold>Pipes:</>
+When the output is not a terminal, --grid, --icon, --suffix, --sym,
+--collapse and --align are off unless given, so that each line holds one
+plain name, like ls(1)."
+	),
     args_override_self = true,
 )]
 pub struct Args {
+	#[command(subcommand)]
+	pub cmd: Option<Cmd>,
+
 	/// the paths to list, each of which may be a file or directory
 	#[clap(default_value = ".")]
 	pub paths: Vec<PathBuf>,
 
-	/// the data points to show about each node
+	/// list the bookmarked paths instead of the given paths
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub bookmarks: bool,
+
+	/// print a single compact line about the given directory for shell prompts
+	#[clap(
+		long,
+		default_value = "false",
+		action = clap::ArgAction::Set,
+		conflicts_with_all = ["bookmarks", "shell_quote"]
+	)]
+	pub prompt: bool,
+
+	/// print only entry rows, without path titles, column headers or info logs
+	#[clap(short, long, default_value = "false", action = clap::ArgAction::Set)]
+	pub quiet: bool,
+
+	/// print only the paths, one per line, quoted for the given shell
+	#[clap(long, value_enum)]
+	pub shell_quote: Option<Quoting>,
+
+	/// print the detailed view as CSV or TSV records, a NUON table or JSON for
+	/// PowerShell, without formatting
+	#[clap(long, value_enum, conflicts_with_all = ["prompt", "shell_quote"])]
+	pub export: Option<Export>,
+
+	/// write sizes, IDs, counts and timestamps as raw numbers, which
+	/// `--export` always does
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub raw_values: bool,
+
+	/// how to export the icons of nodes, in a column of their own
+	#[clap(long, default_value = "none", value_enum)]
+	pub export_icons: ExportIcons,
+
+	/// open the only given file with its opener instead of listing it
+	#[clap(
+		long,
+		default_value = "false",
+		action = clap::ArgAction::Set,
+		conflicts_with_all = ["bookmarks", "prompt", "shell_quote", "export"]
+	)]
+	pub open: bool,
+
+	/// write the listed paths to a file, open it in `$EDITOR` and print the
+	/// `mv` commands for the paths that were changed
+	#[clap(
+		long,
+		default_value = "false",
+		action = clap::ArgAction::Set,
+		conflicts_with_all = ["bookmarks", "prompt", "export", "open"]
+	)]
+	pub rename_edit: bool,
+
+	/// perform the renames of `--rename-edit` instead of printing them
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub apply: bool,
+
+	/// use only the given config file, instead of looking for config files
+	#[clap(long, value_name = "FILE")]
+	pub config: Option<PathBuf>,
+
+	/// use only the default config, ignoring all config files
+	#[clap(
+		long,
+		default_value = "false",
+		default_missing_value = "true",
+		num_args = 0..=1,
+		action = clap::ArgAction::Set,
+		conflicts_with = "config"
+	)]
+	pub no_config: bool,
+
+	/// make the output reproducible, measuring ages from the given Unix time
+	/// and ignoring the terminal, the locale and the time zone
+	#[clap(long, value_name = "EPOCH", allow_negative_numbers = true)]
+	pub deterministic: Option<i64>,
+
+	/// write the styled output, with its escape sequences, to the given file
+	/// instead of STDOUT
+	#[clap(long, value_name = "FILE")]
+	pub render_to: Option<PathBuf>,
+
+	/// list the paths again whenever their contents change, until interrupted
+	#[clap(
+		long,
+		default_value = "false",
+		action = clap::ArgAction::Set,
+		conflicts_with_all = ["bookmarks", "prompt", "shell_quote", "export", "open", "rename_edit", "render_to"]
+	)]
+	pub watch: bool,
+
+	/// answer JSON-RPC requests for listings on STDIN, instead of listing the
+	/// given paths, until STDIN is closed
+	#[clap(
+		long,
+		default_value = "false",
+		action = clap::ArgAction::Set,
+		conflicts_with_all = ["bookmarks", "prompt", "shell_quote", "export", "open", "rename_edit", "render_to", "watch"]
+	)]
+	pub serve: bool,
+
+	/// list the paths on a screen of their own and change the listing with
+	/// keys, like `/` to filter and `s` to sort, until quit
+	#[clap(
+		long,
+		default_value = "false",
+		action = clap::ArgAction::Set,
+		conflicts_with_all = ["bookmarks", "prompt", "shell_quote", "export", "open", "rename_edit", "render_to", "watch", "serve", "popup"]
+	)]
+	pub interactive: bool,
+
+	/// get directory listings from `pls daemon`, if it keeps them warm
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub from_daemon: bool,
+
+	/// list subdirectories recursively, each as its own section
+	#[clap(short = 'R', long, default_value = "false", action = clap::ArgAction::Set)]
+	pub recurse: bool,
+
+	/// list subdirectories recursively, nested under their parents as a tree
+	#[clap(short = 'T', long, default_value = "false", action = clap::ArgAction::Set)]
+	pub tree: bool,
+
+	/// the maximum number of levels of subdirectories to list recursively
+	#[clap(long)]
+	pub depth: Option<usize>,
+
+	/// show the details of the targets of symlinks instead of the links
+	#[clap(short = 'L', long, default_value = "false", action = clap::ArgAction::Set)]
+	pub dereference: bool,
+
+	/// skip owner lookups, block counts and symlink targets for nodes on
+	/// network file systems, like NFS, SMB or FUSE
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub fast: bool,
+
+	/// the time, in milliseconds, that reading a directory or the metadata of
+	/// a node on a network file system may take before it is treated as
+	/// unreadable
+	#[clap(long, value_name = "MS")]
+	pub stat_timeout: Option<u64>,
+
+	/// list device nodes by subsystem, enabled automatically for `/dev`
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub devfs: bool,
+
+	/// list the contents of tar and zip archives as if they were directories
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub as_dir: bool,
+
+	/// list only the nodes flagged by the risk rules in the config, like
+	/// private keys or world-writable scripts
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub audit: bool,
+
+	/// write a shell script of the commands that would fix the risks found by
+	/// `--audit`, for review, without changing any node
+	#[clap(
+		long,
+		value_name = "FILE",
+		conflicts_with_all = ["prompt", "open", "rename_edit", "watch", "serve", "interactive"]
+	)]
+	pub fix_script: Option<PathBuf>,
+
+	/// write the permissions and owners of the listed nodes to a file, to
+	/// compare later listings against with `--compare`
+	#[clap(
+		long,
+		value_name = "FILE",
+		conflicts_with_all = ["prompt", "open", "rename_edit", "watch", "serve", "interactive"]
+	)]
+	pub snapshot: Option<PathBuf>,
+
+	/// show the permissions and owners that changed since the snapshot in the
+	/// given file as `old → new`
+	#[clap(
+		long,
+		value_name = "FILE",
+		conflicts_with_all = ["prompt", "open", "rename_edit", "watch", "serve"]
+	)]
+	pub compare: Option<PathBuf>,
+
+	/// hide the sizes and timestamps of sensitive nodes, like keys and tokens,
+	/// and dim their names
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub redact: bool,
+
+	/// show the disk quota of the owner of each listed directory, on file
+	/// systems that enforce quotas
+	#[clap(long, default_value = "false", action = clap::ArgAction::Set)]
+	pub quota: bool,
+
+	/// the data points to show about each node, or the names of presets from
+	/// the `detail_presets` in the user config
 	#[clap(
 		help_heading = "Detail view",
-		short,
+		short = 'd',
 		long = "det",
 		default_value = "none",
-		value_enum
+		value_parser = DetailParser,
+		value_name = "DETAILS"
 	)]
+	pub detail_names: Vec<String>,
+
+	/// show the standard details, like `ls -l`, in addition to `--det`
+	#[clap(help_heading = "Detail view", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub long: bool,
+
+	/// the data points to show about each node, after expanding presets
+	#[clap(skip)]
 	pub details: Vec<DetailField>,
 
+	/// mapping of preset names to the detail fields they expand into
+	#[clap(skip)]
+	pub detail_presets: HashMap<String, Vec<DetailField>>,
+
+	/// list of the names of the derived columns with their templates, sorted
+	/// by name
+	#[clap(skip)]
+	pub columns: Vec<(String, String)>,
+
 	/// show headers above columnar data
 	#[clap(help_heading = "Detail view", short = 'H', long, default_value = "true", action = clap::ArgAction::Set)]
 	pub header: bool,
 
+	/// hide columns in which every row has the same value, noting the value
+	/// once above the table
+	#[clap(help_heading = "Detail view", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub elide_uniform: bool,
+
+	/// split short tables into tiles placed side by side, if the terminal is
+	/// wide enough
+	#[clap(help_heading = "Detail view", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub tile: bool,
+
+	/// display each node as a block of lines, with one labelled line per
+	/// detail, instead of a row in a table
+	#[clap(help_heading = "Detail view", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub record: bool,
+
 	/// the type of units to use for the node sizes
 	#[clap(
 		help_heading = "Detail view",
@@ -74,14 +384,39 @@ pub struct Args {
 	)]
 	pub unit: UnitSys,
 
+	/// list the extended attributes of nodes, with their values, beneath them
+	#[clap(help_heading = "Detail view", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub xattrs: bool,
+
+	/// show the total size of the files inside directories as their size
+	#[clap(help_heading = "Detail view", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub du: bool,
+
+	/// the style in which to show timestamps
+	#[clap(
+		help_heading = "Detail view",
+		long,
+		default_value = "absolute",
+		value_enum
+	)]
+	pub time_style: TimeStyle,
+
 	/// display node names in multiple columns
 	#[clap(help_heading = "Grid view", short, long, default_value = "false", action = clap::ArgAction::Set)]
 	pub grid: bool,
 
-	/// display node names column-first
-	#[clap(help_heading = "Grid view", short = 'D', long, default_value = "false", action = clap::ArgAction::Set)]
+	/// the order in which node names fill the grid
+	#[clap(help_heading = "Grid view", long, value_enum, default_value = "row")]
+	pub direction: Direction,
+
+	/// display node names column-first; shorthand for `--direction column`
+	#[clap(help_heading = "Grid view", short = 'D', long, default_value = "false", action = clap::ArgAction::Set, conflicts_with = "direction")]
 	pub down: bool,
 
+	/// make all columns equally wide instead of packing them to fit more
+	#[clap(help_heading = "Grid view", long, default_value = "true", action = clap::ArgAction::Set)]
+	pub balance: bool,
+
 	/// display icons next to node names
 	#[clap(help_heading = "Presentation", short, long, default_value = "true", action = clap::ArgAction::Set)]
 	pub icon: bool,
@@ -102,6 +437,87 @@ pub struct Args {
 	#[clap(help_heading = "Presentation", short, long, default_value = "true", action = clap::ArgAction::Set)]
 	pub align: bool,
 
+	/// the number of columns to lay the output out in, instead of the width of
+	/// the terminal
+	#[clap(help_heading = "Presentation", long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+	pub width: Option<u16>,
+
+	/// fit the output in a fixed-size overlay, like a tmux popup, cutting the
+	/// lines that are too wide and the rows that do not fit
+	#[clap(
+		help_heading = "Presentation",
+		long,
+		value_name = "WIDTHxHEIGHT",
+		value_parser = Popup::from_str,
+		conflicts_with_all = ["width", "export", "serve", "watch", "prompt", "open", "rename_edit"]
+	)]
+	pub popup: Option<Popup>,
+
+	/// show small previews of PNG, JPEG and WebP images in place of their
+	/// icons in the grid view, in terminals that support graphics
+	#[clap(help_heading = "Presentation", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub thumbs: bool,
+
+	/// sniff the first bytes of files without an extension, like scripts and
+	/// binaries, to pick their icons and styles
+	#[clap(help_heading = "Presentation", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub sniff: bool,
+
+	/// the set of glyphs to draw icons from, overriding the config; `emoji`
+	/// needs no patched font
+	#[clap(help_heading = "Presentation", long, value_enum)]
+	pub icon_set: Option<IconSet>,
+
+	/// replace all glyphs, like tree shapes, symlink arrows, suffixes and
+	/// icons, with ASCII ones, for logs, CI and legacy terminals
+	#[clap(help_heading = "Presentation", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub ascii: bool,
+
+	/// the palette to remap the semantic styles to, overriding the config
+	#[clap(help_heading = "Presentation", long, value_enum)]
+	pub palette: Option<Palette>,
+
+	/// the theme to apply over the config, either one of the built-in `dark`
+	/// and `light` themes or one defined by the user
+	#[clap(help_heading = "Presentation", long, value_name = "THEME")]
+	pub theme: Option<String>,
+
+	/// when to style the output with colors, instead of writing plain text
+	#[clap(
+		help_heading = "Presentation",
+		long,
+		default_value = "auto",
+		value_enum
+	)]
+	pub color: ColorMode,
+
+	/// the background of the terminal, for which the default styles are
+	/// adjusted, instead of detecting it
+	#[clap(help_heading = "Presentation", long, value_enum)]
+	pub background: Option<Background>,
+
+	/// link the names of nodes to their paths, so that terminals that support
+	/// OSC 8 hyperlinks can open them on click
+	#[clap(help_heading = "Presentation", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub hyperlinks: bool,
+
+	/// apply the colors from the `LS_COLORS` environment variable, as used by
+	/// GNU ls, on top of the config
+	#[clap(help_heading = "Presentation", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub ls_colors: bool,
+
+	/// style node names by the age of the latest commit touching them
+	#[clap(help_heading = "Presentation", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub age: bool,
+
+	/// run the decorator programs configured for the listed directories
+	#[clap(help_heading = "Presentation", long, default_value = "false", action = clap::ArgAction::Set)]
+	pub decorate: bool,
+
+	/// the pattern to highlight within node names
+	#[clap(help_heading = "Presentation", long, value_parser = regex_parser)]
+	pub highlight: Option<Regex>,
+
 	/// the set of node types to include in the output
 	#[clap(
 		help_heading = "Filtering",
@@ -118,28 +534,156 @@ pub struct Args {
 	pub imp: i8,
 
 	/// the pattern of files to selectively hide from the output
-	#[clap(help_heading = "Filtering", short, long, value_parser = regex_parser)]
+	#[clap(
+		help_heading = "Filtering",
+		short,
+		long = "exclude",
+		id = "exclude",
+		value_name = "PATTERN"
+	)]
+	pub exclude_src: Option<String>,
+	#[clap(skip)]
 	pub exclude: Option<Regex>,
 
 	/// the pattern of files to exclusively show in the output
-	#[clap(help_heading = "Filtering", short, long, value_parser = regex_parser)]
+	#[clap(
+		help_heading = "Filtering",
+		short,
+		long = "only",
+		id = "only",
+		value_name = "PATTERN"
+	)]
+	pub only_src: Option<String>,
+	#[clap(skip)]
 	pub only: Option<Regex>,
 
+	/// like `--only`, but also report the number of matches and exit with an
+	/// error if nothing matched
+	#[clap(
+		help_heading = "Filtering",
+		long = "find",
+		id = "find",
+		value_name = "PATTERN"
+	)]
+	pub find_src: Option<String>,
+	#[clap(skip)]
+	pub find: Option<Regex>,
+
+	/// the syntax of the patterns given to `--exclude`, `--only` and `--find`
+	#[clap(help_heading = "Filtering", long, value_enum, default_value = "regex")]
+	pub filter_mode: PatternSyntax,
+
+	/// the bounds on the sizes of files to show, like '>1M' or '<100K'
+	#[clap(help_heading = "Filtering", long, value_parser = SizeFilter::from_str)]
+	pub size: Vec<SizeFilter>,
+
+	/// list only the nodes modified after the given time, like '2d' (ago) or
+	/// '2024-01-31'
+	#[clap(help_heading = "Filtering", long, value_name = "TIME", value_parser = TimeBound::from_str)]
+	pub newer_than: Option<TimeBound>,
+
+	/// list only the nodes modified before the given time, like '1h' (ago) or
+	/// '2024-01-31 18:30'
+	#[clap(help_heading = "Filtering", long, value_name = "TIME", value_parser = TimeBound::from_str)]
+	pub older_than: Option<TimeBound>,
+
+	/// list only the nodes with the given tag, read from an extended attribute
+	#[clap(help_heading = "Filtering", long, value_name = "NAME")]
+	pub tag: Option<String>,
+
 	/// the set of fields to sort by, trailing `_` reverses the direction
 	#[clap(help_heading = "Sorting", short, long = "sort", default_values = ["cat", "cname"], value_enum)]
 	pub sort_bases: Vec<SortField>,
+
+	/// the pattern of nodes to lift to the top of the listing, regardless of
+	/// the sort fields
+	#[clap(help_heading = "Sorting", long, value_name = "PATTERN", value_parser = regex_parser)]
+	pub pin: Option<Regex>,
+
+	/// where to place directories, ahead of the other sort fields
+	#[clap(help_heading = "Sorting", long, value_enum)]
+	pub dirs: Option<Dirs>,
+
+	/// the way in which to compare names when sorting by name, overriding the
+	/// config
+	#[clap(help_heading = "Sorting", long, value_enum)]
+	pub sort_collation: Option<Collation>,
+
+	/// the way in which to compare names when sorting by name, after applying
+	/// the config
+	#[clap(skip)]
+	pub collation: Collation,
 }
 
 impl Default for Args {
 	/// Create a new instance of `Args` parsing real command-line arguments.
 	fn default() -> Self {
-		let mut args = Args::parse();
-		args.post_process();
-		args
+		Self::new().0
 	}
 }
 
 impl Args {
+	/// Create a new instance of `Args` parsing real command-line arguments,
+	/// along with the config manager selected by `--config` and `--no-config`.
+	///
+	/// The detail presets and the sort collation are read from the base
+	/// configuration, so that they apply regardless of the paths being listed.
+	pub fn new() -> (Self, ConfMan) {
+		let matches = Args::command().get_matches();
+		let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+		if let Err(err) = args.compile_patterns() {
+			err.exit();
+		}
+		if args.render_to.is_none() && !io::stdout().is_terminal() {
+			args.pipe_defaults(&matches);
+		}
+		let background = args.background();
+		let mut conf_man = if args.no_config {
+			ConfMan::explicit(None, background)
+		} else if let Some(path) = &args.config {
+			ConfMan::explicit(Some(path), background)
+		} else {
+			ConfMan::discovered(background)
+		};
+		if let Some(name) = &args.theme {
+			if let Err(err) = conf_man.select_theme(name) {
+				Args::command()
+					.error(
+						ErrorKind::InvalidValue,
+						format!("invalid value '{name}' for '--theme': {err}"),
+					)
+					.exit();
+			}
+		}
+		args.detail_presets = conf_man
+			.base
+			.extract_inner("detail_presets")
+			.unwrap_or_else(|err| {
+				warn!("Could not read detail presets: {err}");
+				HashMap::new()
+			});
+		let columns: HashMap<String, String> = conf_man
+			.base
+			.extract_inner("columns")
+			.unwrap_or_else(|err| {
+				warn!("Could not read derived columns: {err}");
+				HashMap::new()
+			});
+		args.columns = columns.into_iter().collect();
+		args.columns.sort();
+		args.collation = args.sort_collation.unwrap_or_else(|| {
+			conf_man
+				.base
+				.extract_inner("sort_collation")
+				.unwrap_or_else(|err| {
+					warn!("Could not read sort collation: {err}");
+					Collation::default()
+				})
+		});
+		args.post_process();
+		(args, conf_man)
+	}
+
 	/// Create a new instance of `Args` parsing the given arguments.
 	#[cfg(test)]
 	pub fn raw<I, T>(itr: I) -> Self
@@ -150,11 +694,88 @@ impl Args {
 		Args::parse_from(itr)
 	}
 
+	/// Compile the patterns of the name filters, written in the syntax chosen
+	/// with `--filter-mode`.
+	///
+	/// The patterns are compiled after parsing, because the syntax is only
+	/// known once all arguments have been read.
+	fn compile_patterns(&mut self) -> Result<(), Error> {
+		let syntax = self.filter_mode;
+		let compile = |src: &Option<String>, flag: &str| {
+			src.as_deref()
+				.map(|src| {
+					regex_parser(&regex_source(src, syntax)).map_err(|err| {
+						Args::command().error(
+							ErrorKind::ValueValidation,
+							format!("invalid value '{src}' for '--{flag}': {err}"),
+						)
+					})
+				})
+				.transpose()
+		};
+		self.exclude = compile(&self.exclude_src, "exclude")?;
+		self.only = compile(&self.only_src, "only")?;
+		self.find = compile(&self.find_src, "find")?;
+		Ok(())
+	}
+
+	/// Compile the given pattern, typed in `--interactive`, in the syntax
+	/// chosen with `--filter-mode`, like the patterns of the name filters.
+	pub fn filter_regex(&self, src: &str) -> Result<Regex, RegexError> {
+		regex_parser(&regex_source(src, self.filter_mode))
+	}
+
+	/// Get the background of the terminal, for which the default styles are
+	/// adjusted.
+	///
+	/// The background is only detected when the output goes to the terminal,
+	/// and no theme is chosen, since a theme sets the styles itself. The
+	/// output of `--deterministic` must not depend on the terminal, so it
+	/// always uses a dark background.
+	fn background(&self) -> Background {
+		if let Some(background) = self.background {
+			return background;
+		}
+		if self.deterministic.is_some()
+			|| self.theme.is_some()
+			|| self.render_to.is_some()
+			|| !io::stdout().is_terminal()
+		{
+			return Background::default();
+		}
+		detect_background().unwrap_or_default()
+	}
+
+	/// Turn off the presentation arguments that get in the way of tools that
+	/// read the output, like icons, suffixes and alignment spaces, so that
+	/// every line holds one plain name.
+	///
+	/// This is used when the output is not a terminal. Arguments given on the
+	/// command line are left as they are.
+	fn pipe_defaults(&mut self, matches: &ArgMatches) {
+		let is_default = |id| matches.value_source(id) == Some(ValueSource::DefaultValue);
+		for (id, flag) in [
+			("grid", &mut self.grid),
+			("icon", &mut self.icon),
+			("suffix", &mut self.suffix),
+			("sym", &mut self.sym),
+			("collapse", &mut self.collapse),
+			("align", &mut self.align),
+		] {
+			if is_default(id) {
+				*flag = false;
+			}
+		}
+	}
+
 	/// Clean the parsed arguments and log any warnings that are raised.
 	///
 	/// The output of this function is similar to the format used by
 	/// [`Exc`](crate::exc::Exc) as they serve similar purposes.
 	fn post_process(&mut self) {
+		if self.quiet {
+			log::set_max_level(log::max_level().min(LevelFilter::Warn));
+		}
 		self.clean().iter().for_each(|warning| {
 			warn!("{warning}");
 		});
@@ -163,16 +784,154 @@ impl Args {
 	/// Clean the parsed arguments to resolve conflicting arguments.
 	///
 	/// `pls` is intentionally lax about conflicting arguments, and will attempt
-	/// to resolve conflicts in a way that is least surprising to the user. So,
-	/// while the clean function generates warnings, they are not surfaced to
-	/// the user and are primarily used for debugging.
+	/// to resolve conflicts in a way that is least surprising to the user,
+	/// following the precedence documented in the long help. So, while the
+	/// clean function generates warnings, they are not surfaced to the user
+	/// and are primarily used for debugging.
+	///
+	/// Arguments that select mutually exclusive actions, like `--open` and
+	/// `--prompt`, are rejected by the parser instead.
 	fn clean(&mut self) -> Vec<&str> {
 		let mut warnings = vec![];
 
-		self.details = DetailField::clean(&self.details);
+		let mut details = vec![];
+		for name in &self.detail_names {
+			if let Ok(field) = DetailField::from_str(name, false) {
+				details.push(field);
+			} else if let Some(preset) = self.detail_presets.get(name) {
+				details.extend_from_slice(preset);
+			} else if let Some(idx) = self.columns.iter().position(|(col, _)| col == name) {
+				details.push(DetailField::Derived(idx));
+			} else {
+				warnings.push("Unknown detail preset was ignored.");
+			}
+		}
+		if self.long {
+			details.push(DetailField::Std);
+		}
+		self.details = DetailField::clean(&details);
 		self.sort_bases = SortField::clean(&self.sort_bases);
+		if self.deterministic.is_some() {
+			// Nodes that tie on every sort field would otherwise be listed in
+			// the order the file system returns them.
+			self.sort_bases.push(SortField::Name);
+			self.sort_bases = SortField::clean(&self.sort_bases);
+		}
 		self.typs = Typ::clean(&self.typs);
 
+		if self.down {
+			self.direction = Direction::Column;
+		}
+
+		if self.export.is_some() && self.record {
+			// Exported records always have one node per row.
+			warnings.push("Export disabled record view.");
+			self.record = false;
+		}
+
+		if self.record && self.grid {
+			// Records are listed one below the other.
+			warnings.push("Record view disabled grid view.");
+			self.grid = false;
+		}
+
+		if self.record && self.tree {
+			// The branches of the tree would be cut by the detail lines.
+			warnings.push("Record view disabled tree view.");
+			self.tree = false;
+		}
+
+		if self.record && self.collapse {
+			// Nested nodes would be cut by the detail lines.
+			warnings.push("Record view disabled collapsing.");
+			self.collapse = false;
+		}
+
+		if self.record && self.tile {
+			// Records are already narrow, so they are not tiled.
+			warnings.push("Record view disabled table tiling.");
+			self.tile = false;
+		}
+
+		if self.record && self.xattrs {
+			// Extended attributes are listed in rows beneath the nodes.
+			warnings.push("Record view disabled extended attributes.");
+			self.xattrs = false;
+		}
+
+		if self.tree && self.tile {
+			// The branches of the tree would be cut across tiles.
+			warnings.push("Tree view disabled table tiling.");
+			self.tile = false;
+		}
+
+		if self.tree && self.recurse {
+			// The tree already contains the contents of every subdirectory.
+			warnings.push("Tree view disabled recursive sections.");
+			self.recurse = false;
+		}
+
+		if self.depth.is_some() && !self.recurse && !self.tree {
+			// A depth limit is only meaningful for recursive listings.
+			warnings.push("Depth limit enabled recursion.");
+			self.recurse = true;
+		}
+
+		if !self.devfs && self.paths.iter().all(|path| is_devfs(path)) {
+			self.devfs = true;
+		}
+
+		if self.devfs {
+			// Device numbers identify the subsystem, so they are shown and
+			// the devices are grouped by them unless another sort is chosen.
+			self.details.push(DetailField::Rdev);
+			self.details = DetailField::clean(&self.details);
+			if self.sort_bases == [SortField::Cat, SortField::Cname] {
+				self.sort_bases = vec![SortField::Typ, SortField::Rdev, SortField::Cname];
+			}
+		}
+
+		if let Some(dirs) = self.dirs {
+			// The category is the first sort field, so that directories are
+			// grouped regardless of the other sort fields.
+			self.sort_bases
+				.retain(|&field| !matches!(field, SortField::Cat | SortField::Cat_));
+			match dirs {
+				Dirs::First => self.sort_bases.insert(0, SortField::Cat),
+				Dirs::Last => self.sort_bases.insert(0, SortField::Cat_),
+				Dirs::Mixed => {}
+			}
+		}
+
+		if self.export.is_some() {
+			// Exported records are read by programs, which need raw numbers.
+			self.raw_values = true;
+		}
+
+		if self.raw_values && self.export.is_none() && !self.serve {
+			// Raw values are not readable in the formatted views.
+			warnings.push("Lack of export format disabled raw values.");
+			self.raw_values = false;
+		}
+
+		if self.export.is_some() && self.grid {
+			// Exported records always have one node per row.
+			warnings.push("Export disabled grid view.");
+			self.grid = false;
+		}
+
+		if self.serve && self.grid {
+			// Served rows are read by programs, which lay them out themselves.
+			warnings.push("Server mode disabled grid view.");
+			self.grid = false;
+		}
+
+		if self.bookmarks && !self.is_detailed() {
+			// Bookmarks are a dashboard, so they show the standard details.
+			warnings.push("Bookmarks enabled the standard details.");
+			self.details = DetailField::clean(&[DetailField::Std]);
+		}
+
 		if self.grid && self.is_detailed() {
 			// Multi-column mode is disabled when detailed mode is enabled.
 			warnings.push("Detailed view disabled grid view.");
@@ -189,6 +948,54 @@ impl Args {
 			self.header = false;
 		}
 
+		if self.apply && !self.rename_edit {
+			// There are no renames to perform without editing the paths.
+			warnings.push("Apply was ignored without rename edit.");
+			self.apply = false;
+		}
+
+		if self.fix_script.is_some() && !self.audit {
+			// Only the nodes flagged by the audit have fixes.
+			warnings.push("Fix script was ignored without audit.");
+			self.fix_script = None;
+		}
+
+		if self.shell_quote.is_some() && !self.quiet {
+			// Quoted paths are meant to be spliced into commands.
+			warnings.push("Shell quoting enabled quiet mode.");
+			self.quiet = true;
+		}
+
+		if self.quiet && self.header {
+			// Quiet mode prints nothing but the entry rows.
+			warnings.push("Quiet mode disabled column headers.");
+			self.header = false;
+		}
+
+		if self.quiet && self.elide_uniform {
+			// The elided values would be lost without the note above the rows.
+			warnings.push("Quiet mode disabled column elision.");
+			self.elide_uniform = false;
+		}
+
+		if self.quiet && self.quota {
+			// The quota line is not an entry row.
+			warnings.push("Quiet mode disabled the quota line.");
+			self.quota = false;
+		}
+
+		if self.export.is_some() && self.elide_uniform {
+			// Exported records keep every column, so that they can be combined.
+			warnings.push("Export disabled column elision.");
+			self.elide_uniform = false;
+		}
+
+		if self.ascii && self.thumbs {
+			// Thumbnails are images, which cannot be written as text.
+			warnings.push("ASCII output disabled thumbnails.");
+			self.thumbs = false;
+		}
+
 		if self.grid && self.sym {
 			// Symlink targets cannot be shown in grid view.
 			warnings.push("Grid view disabled symlink targets.");
@@ -201,6 +1008,36 @@ impl Args {
 			self.collapse = false;
 		}
 
+		if self.grid && self.xattrs {
+			// Extended attributes are listed in rows beneath the nodes.
+			warnings.push("Grid view disabled extended attributes.");
+			self.xattrs = false;
+		}
+
+		if self.grid && self.tree {
+			// Nested nodes cannot be shown in grid view.
+			warnings.push("Grid view disabled tree view.");
+			self.tree = false;
+		}
+
+		if self.export.is_some() || self.serve {
+			// Exported icons are placed in their own column, before the names,
+			// so that consumers need not strip glyphs from the names.
+			self.icon = false;
+			if self.export_icons != ExportIcons::None {
+				self.details
+					.insert(self.details.len() - 1, DetailField::Icon);
+			}
+		} else if self.export_icons != ExportIcons::None {
+			warnings.push("Lack of export format disabled exported icons.");
+			self.export_icons = ExportIcons::None;
+		}
+
+		if self.grid {
+			// The grid view paints each cell with the styles of its node.
+			self.details.push(DetailField::Style);
+		}
+
 		warnings
 	}
 
@@ -212,11 +1049,50 @@ impl Args {
 	fn is_detailed(&self) -> bool {
 		self.details.len() >= 2
 	}
+
+	/// Get whether the given field is shown, either as a column of its own or
+	/// as part of a derived column.
+	pub fn uses(&self, field: DetailField) -> bool {
+		self.fields().contains(&field)
+	}
+
+	/// Get whether the given name matches the `--find` pattern.
+	///
+	/// Without `--find`, every name matches. Only the names that do not
+	/// match are reported as hidden by `--find`, not the nodes hidden by the
+	/// other filters.
+	pub fn is_found(&self, name: &[u8]) -> bool {
+		self.find.as_ref().map_or(true, |pat| pat.is_match(name))
+	}
+
+	/// Get whether the Git history of the nodes needs to be computed.
+	pub fn uses_history(&self) -> bool {
+		self.age || self.fields().iter().any(DetailField::uses_history)
+	}
+
+	/// Get the fields that are shown, including the fields named by the
+	/// placeholders in the templates of the derived columns.
+	fn fields(&self) -> Vec<DetailField> {
+		let mut fields = self.details.clone();
+		for det in &self.details {
+			if let DetailField::Derived(idx) = det {
+				fields.extend(
+					keys(&self.columns[*idx].1)
+						.into_iter()
+						.filter_map(|key| DetailField::from_str(key, false).ok()),
+				);
+			}
+		}
+		fields
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::Args;
+	use crate::enums::{DetailField, Direction, SortField};
+	use clap::error::ErrorKind;
+	use clap::{CommandFactory, FromArgMatches, Parser};
 
 	macro_rules! make_warning_test {
         ($($name:ident: $argv:expr => $msg:expr,)*) => {
@@ -236,6 +1112,80 @@ mod tests {
 		test_multi_col_and_header: ["pls", "--grid", "true", "--header", "true"] => "Grid view disabled column headers.",
 		test_multi_col_and_sym: ["pls", "--grid", "true", "--sym", "true"] => "Grid view disabled symlink targets.",
 		test_multi_col_and_col: ["pls", "--grid", "true", "--collapse", "true"] => "Grid view disabled collapsing.",
+		test_bookmarks: ["pls", "--bookmarks", "true"] => "Bookmarks enabled the standard details.",
+		test_quiet_and_header: ["pls", "--det", "std", "--quiet", "true"] => "Quiet mode disabled column headers.",
+		test_shell_quote: ["pls", "--shell-quote", "posix"] => "Shell quoting enabled quiet mode.",
+		test_apply_without_rename_edit: ["pls", "--apply", "true"] => "Apply was ignored without rename edit.",
+		test_fix_script_without_audit: ["pls", "--fix-script", "fix.sh"] => "Fix script was ignored without audit.",
+		test_quiet_and_elide: ["pls", "--quiet", "true", "--elide-uniform", "true"] => "Quiet mode disabled column elision.",
+		test_export_and_elide: ["pls", "--export", "csv", "--elide-uniform", "true"] => "Export disabled column elision.",
+		test_quiet_and_quota: ["pls", "--quiet", "true", "--quota", "true"] => "Quiet mode disabled the quota line.",
+		test_tree_and_tile: ["pls", "--tree", "true", "--tile", "true"] => "Tree view disabled table tiling.",
+		test_record_and_grid: ["pls", "--record", "true", "--grid", "true"] => "Record view disabled grid view.",
+		test_record_and_tree: ["pls", "--record", "true", "--tree", "true"] => "Record view disabled tree view.",
+		test_export_and_record: ["pls", "--export", "csv", "--record", "true"] => "Export disabled record view.",
+		test_depth: ["pls", "--depth", "2"] => "Depth limit enabled recursion.",
+		test_tree_and_recurse: ["pls", "--tree", "true", "--recurse", "true"] => "Tree view disabled recursive sections.",
+		test_multi_col_and_tree: ["pls", "--grid", "true", "--tree", "true"] => "Grid view disabled tree view.",
+		test_multi_col_and_xattrs: ["pls", "--grid", "true", "--xattrs", "true"] => "Grid view disabled extended attributes.",
+		test_unknown_preset: ["pls", "--det", "unknown"] => "Unknown detail preset was ignored.",
+		test_raw_values: ["pls", "--raw-values", "true"] => "Lack of export format disabled raw values.",
+		test_export_and_grid: ["pls", "--export", "csv", "--grid", "true"] => "Export disabled grid view.",
+		test_serve_and_grid: ["pls", "--serve", "true", "--grid", "true"] => "Server mode disabled grid view.",
+		test_export_icons: ["pls", "--export-icons", "name"] => "Lack of export format disabled exported icons.",
+		test_ascii_and_thumbs: ["pls", "--ascii", "true", "--thumbs", "true"] => "ASCII output disabled thumbnails.",
+	);
+
+	macro_rules! make_conflict_test {
+		( $($name:ident: $argv:expr => $is_err:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let res = Args::try_parse_from($argv);
+					assert_eq!(
+						res.map_err(|err| err.kind()).err(),
+						$is_err.then_some(ErrorKind::ArgumentConflict)
+					);
+				}
+			)*
+		};
+	}
+
+	make_conflict_test!(
+		test_open_and_prompt: ["pls", "--open", "true", "--prompt", "true"] => true,
+		test_open_and_bookmarks: ["pls", "--open", "true", "--bookmarks", "true"] => true,
+		test_open_and_shell_quote: ["pls", "--open", "true", "--shell-quote", "posix"] => true,
+		test_prompt_and_bookmarks: ["pls", "--prompt", "true", "--bookmarks", "true"] => true,
+		test_prompt_and_shell_quote: ["pls", "--prompt", "true", "--shell-quote", "fish"] => true,
+		test_bookmarks_and_shell_quote: ["pls", "--bookmarks", "true", "--shell-quote", "fish"] => false,
+		test_export_and_shell_quote: ["pls", "--export", "csv", "--shell-quote", "fish"] => true,
+		test_export_and_open: ["pls", "--export", "tsv", "--open", "true"] => true,
+		test_config_and_no_config: ["pls", "--config", "pls.yml", "--no-config"] => true,
+		test_no_config_flag: ["pls", "--no-config", "--det", "ino"] => false,
+		test_tree_and_collapse: ["pls", "--tree", "true", "--collapse", "true"] => false,
+		test_down_and_direction: ["pls", "--down", "true", "--direction", "row"] => true,
+		test_watch_and_export: ["pls", "--watch", "true", "--export", "csv"] => true,
+		test_watch_and_tree: ["pls", "--watch", "true", "--tree", "true"] => false,
+		test_serve_and_watch: ["pls", "--serve", "true", "--watch", "true"] => true,
+		test_serve_and_export: ["pls", "--serve", "true", "--export", "csv"] => true,
+		test_interactive_and_watch: ["pls", "--interactive", "true", "--watch", "true"] => true,
+		test_interactive_and_export: ["pls", "--interactive", "true", "--export", "csv"] => true,
+		test_interactive_and_popup: ["pls", "--interactive", "true", "--popup", "80x24"] => true,
+		test_interactive_and_tree: ["pls", "--interactive", "true", "--tree", "true"] => false,
+		test_popup_and_width: ["pls", "--popup", "80x24", "--width", "40"] => true,
+		test_popup_and_watch: ["pls", "--popup", "80x24", "--watch", "true"] => true,
+		test_popup_and_grid: ["pls", "--popup", "80x24", "--grid", "true"] => false,
+		test_rename_edit_and_open: ["pls", "--rename-edit", "true", "--open", "true"] => true,
+		test_rename_edit_and_interactive: ["pls", "--rename-edit", "true", "--interactive", "true"] => true,
+		test_rename_edit_and_shell_quote: ["pls", "--rename-edit", "true", "--shell-quote", "fish"] => false,
+		test_fix_script_and_watch: ["pls", "--fix-script", "fix.sh", "--watch", "true"] => true,
+		test_fix_script_and_interactive: ["pls", "--fix-script", "fix.sh", "--interactive", "true"] => true,
+		test_fix_script_and_export: ["pls", "--fix-script", "fix.sh", "--export", "csv"] => false,
+		test_snapshot_and_serve: ["pls", "--snapshot", "snap.json", "--serve", "true"] => true,
+		test_compare_and_prompt: ["pls", "--compare", "snap.json", "--prompt", "true"] => true,
+		test_snapshot_and_compare: ["pls", "--snapshot", "new.json", "--compare", "old.json"] => false,
+		test_snapshot_and_interactive: ["pls", "--snapshot", "snap.json", "--interactive", "true"] => true,
+		test_compare_and_interactive: ["pls", "--compare", "snap.json", "--interactive", "true"] => false,
 	);
 
 	macro_rules! make_clean_test {
@@ -253,6 +1203,26 @@ mod tests {
 
 	make_clean_test!(
 		test_details_beats_multi_col: ["pls", "--det", "ino", "--grid", "true"] => grid, false,
+		test_depth_enables_recurse: ["pls", "--depth", "1"] => recurse, true,
+		test_depth_with_tree: ["pls", "--depth", "1", "--tree", "true"] => recurse, false,
+		test_tree_keeps_collapse: ["pls", "--tree", "true", "--collapse", "true"] => collapse, true,
+		test_record_beats_tree: ["pls", "--record", "true", "--tree", "true"] => tree, false,
+		test_record_disables_collapse: ["pls", "--record", "true"] => collapse, false,
+		test_direction_default: ["pls", "--grid", "true"] => direction, Direction::Row,
+		test_direction_column: ["pls", "--direction", "column"] => direction, Direction::Column,
+		test_down_is_column: ["pls", "--down", "true"] => direction, Direction::Column,
+
+		// Device mode shows and sorts by device numbers.
+		test_devfs_on_dev: ["pls", "/dev"] => devfs, true,
+		test_devfs_elsewhere: ["pls", "/tmp"] => devfs, false,
+		test_devfs_details: ["pls", "--devfs", "true"] => details, vec![DetailField::Rdev, DetailField::Name],
+		test_devfs_sort: ["pls", "--devfs", "true"] => sort_bases, vec![SortField::Typ, SortField::Rdev, SortField::Cname],
+		test_devfs_keeps_sort: ["pls", "--devfs", "true", "--sort", "name"] => sort_bases, vec![SortField::Name],
+		test_dirs_first: ["pls", "--sort", "mtime", "--dirs", "first"] => sort_bases, vec![SortField::Cat, SortField::Mtime],
+		test_dirs_last: ["pls", "--dirs", "last"] => sort_bases, vec![SortField::Cat_, SortField::Cname],
+		test_dirs_mixed: ["pls", "--dirs", "mixed"] => sort_bases, vec![SortField::Cname],
+		test_dirs_first_moves_cat: ["pls", "--sort", "name", "--sort", "cat_", "--dirs", "first"] => sort_bases, vec![SortField::Cat, SortField::Name],
+		test_dirs_with_devfs: ["pls", "--devfs", "true", "--dirs", "last"] => sort_bases, vec![SortField::Cat_, SortField::Typ, SortField::Rdev, SortField::Cname],
 
 		// Symlink target is only shown in detailed view.
 		test_default_sym: ["pls"] => sym, true,
@@ -271,5 +1241,154 @@ mod tests {
 		test_default_header_when_detailed: ["pls", "--det", "ino"] => header, true,
 		test_default_header_when_multi_col: ["pls", "--grid", "true"] => header, false,
 		test_multi_col_beats_header: ["pls", "--grid", "true", "--header", "true"] => header, false,
+
+		// Bookmarks are shown with details, unless details were specified.
+		test_bookmarks_header: ["pls", "--bookmarks", "true"] => header, true,
+		test_quiet_beats_header: ["pls", "--det", "std", "--quiet", "true"] => header, false,
+		test_quiet_beats_elide: ["pls", "--det", "std", "--quiet", "true", "--elide-uniform", "true"] => elide_uniform, false,
+		test_elide: ["pls", "--det", "std", "--elide-uniform", "true"] => elide_uniform, true,
+		test_quiet_beats_quota: ["pls", "--quiet", "true", "--quota", "true"] => quota, false,
+		test_quota: ["pls", "--quota", "true"] => quota, true,
+		test_tree_beats_tile: ["pls", "--tree", "true", "--tile", "true"] => tile, false,
+		test_tile: ["pls", "--tile", "true"] => tile, true,
+		test_shell_quote_implies_quiet: ["pls", "--shell-quote", "fish"] => quiet, true,
+		test_bookmarks_keeps_details: ["pls", "--bookmarks", "true", "--det", "ino"] => details, vec![DetailField::Ino, DetailField::Name],
+
+		// Long mode adds the standard details to the chosen ones.
+		test_long: ["pls", "--long", "true"] => details, DetailField::clean(&[DetailField::Std]),
+		test_long_keeps_details: ["pls", "--det", "ino", "--long", "true"] => details, DetailField::clean(&[DetailField::Ino, DetailField::Std]),
+		test_raw_values_need_export: ["pls", "--raw-values", "true"] => raw_values, false,
+		test_raw_values_with_export: ["pls", "--export", "csv", "--raw-values", "true"] => raw_values, true,
+		test_raw_values_implied_by_export: ["pls", "--export", "csv"] => raw_values, true,
+		test_export_hides_icons: ["pls", "--export", "csv"] => icon, false,
+		test_export_icons_column: ["pls", "--export", "csv", "--export-icons", "glyph", "--det", "ino"] => details, vec![DetailField::Ino, DetailField::Icon, DetailField::Name],
+		test_export_no_icons_column: ["pls", "--export", "csv", "--det", "ino"] => details, vec![DetailField::Ino, DetailField::Name],
+		test_grid_style_column: ["pls", "--grid", "true"] => details, vec![DetailField::Name, DetailField::Style],
+		test_detailed_no_style_column: ["pls", "--grid", "true", "--det", "ino"] => details, vec![DetailField::Ino, DetailField::Name],
+		// Deterministic mode breaks ties by name.
+		test_deterministic_sort: ["pls", "--deterministic", "0"] => sort_bases, vec![SortField::Cat, SortField::Cname, SortField::Name],
+		test_deterministic_keeps_name: ["pls", "--deterministic", "0", "--sort", "name_", "--sort", "name"] => sort_bases, vec![SortField::Name_, SortField::Name],
+		test_deterministic_after_none: ["pls", "--deterministic", "0", "--sort", "none"] => sort_bases, vec![SortField::Name],
+		test_unknown_preset_is_ignored: ["pls", "--det", "ino", "--det", "unknown"] => details, vec![DetailField::Ino, DetailField::Name],
+	);
+
+	#[test]
+	fn test_presets_expand() {
+		let mut args = Args::raw(["pls", "--det", "ino", "--det", "mine"]);
+		args.detail_presets.insert(
+			String::from("mine"),
+			vec![DetailField::Std, DetailField::Git],
+		);
+		args.clean();
+		assert_eq!(
+			args.details,
+			DetailField::clean(&[DetailField::Ino, DetailField::Std, DetailField::Git])
+		);
+	}
+
+	#[test]
+	fn test_fields_shadow_presets() {
+		let mut args = Args::raw(["pls", "--det", "ino"]);
+		args.detail_presets
+			.insert(String::from("ino"), vec![DetailField::Dev]);
+		args.clean();
+		assert_eq!(args.details, vec![DetailField::Ino, DetailField::Name]);
+	}
+
+	#[test]
+	fn test_columns_resolve() {
+		let mut args = Args::raw(["pls", "--det", "stamp", "--det", "owner"]);
+		args.columns = vec![
+			(String::from("owner"), String::from("{user}:{group}")),
+			(String::from("stamp"), String::from("{mtime} ({age})")),
+		];
+		args.clean();
+		assert_eq!(
+			args.details,
+			vec![
+				DetailField::Derived(1),
+				DetailField::Derived(0),
+				DetailField::Name
+			]
+		);
+		assert!(args.uses(DetailField::Group));
+		assert!(!args.uses(DetailField::Size));
+	}
+
+	#[test]
+	fn test_is_found() {
+		let mut args = Args::raw(["pls", "--find", r"\.rs$"]);
+		args.compile_patterns().unwrap();
+		assert!(args.is_found(b"main.rs"));
+		assert!(!args.is_found(b"Cargo.toml"));
+		assert!(Args::raw(["pls"]).is_found(b"Cargo.toml"));
+	}
+
+	#[test]
+	fn test_filter_regex_uses_filter_mode() {
+		let args = Args::raw(["pls", "--filter-mode", "glob"]);
+		let pat = args.filter_regex("*.rs").unwrap();
+		assert!(pat.is_match(b"main.rs"));
+		assert!(!pat.is_match(b"main.rs.bak"));
+		assert!(Args::raw(["pls"]).filter_regex("*.rs").is_err());
+	}
+
+	#[test]
+	fn test_columns_use_history() {
+		let mut args = Args::raw(["pls", "--det", "last"]);
+		args.columns = vec![(String::from("last"), String::from("{git-author}"))];
+		args.clean();
+		assert!(args.uses_history());
+	}
+
+	macro_rules! make_pattern_test {
+        ($($name:ident: $argv:expr, $node_name:expr => $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let mut args = Args::raw($argv);
+                    args.compile_patterns().unwrap();
+                    let only = args.only.unwrap();
+                    assert_eq!(only.is_match($node_name.as_bytes()), $expected);
+                }
+            )*
+        }
+    }
+
+	make_pattern_test!(
+		test_regex_only: ["pls", "--only", r"\.rs$"], "main.rs" => true,
+		test_regex_only_is_unanchored: ["pls", "--only", "main"], "main.rs" => true,
+		test_glob_only: ["pls", "--filter-mode", "glob", "--only", "*.rs"], "main.rs" => true,
+		test_glob_only_is_anchored: ["pls", "--filter-mode", "glob", "--only", "main"], "main.rs" => false,
+	);
+
+	#[test]
+	fn test_invalid_regex_is_rejected() {
+		let mut args = Args::raw(["pls", "--only", "*.rs"]);
+		let err = args.compile_patterns().unwrap_err();
+		assert_eq!(err.kind(), ErrorKind::ValueValidation);
+	}
+
+	macro_rules! make_pipe_test {
+        ($($name:ident: $argv:expr => $key:ident, $val:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let matches = Args::command().get_matches_from($argv);
+                    let mut args = Args::from_arg_matches(&matches).unwrap();
+                    args.pipe_defaults(&matches);
+                    assert_eq!(args.$key, $val);
+                }
+            )*
+        }
+    }
+
+	make_pipe_test!(
+		test_pipe_disables_icons: ["pls"] => icon, false,
+		test_pipe_disables_align: ["pls"] => align, false,
+		test_pipe_disables_grid: ["pls"] => grid, false,
+		test_pipe_keeps_explicit_icons: ["pls", "--icon", "true"] => icon, true,
+		test_pipe_keeps_explicit_grid: ["pls", "--grid", "true"] => grid, true,
+		test_pipe_keeps_other_args: ["pls", "--header", "true"] => header, true,
 	);
 }
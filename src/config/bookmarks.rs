@@ -0,0 +1,55 @@
+use crate::config::ConfMan;
+use crate::exc::Exc;
+use figment::providers::{Format, Yaml};
+use figment::Figment;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::PathBuf;
+
+/// Represents the list of frequently used paths that can be listed with the
+/// `--bookmarks` CLI argument.
+///
+/// Bookmarks are read from `bookmarks.yml` inside the `pls` config directory.
+/// Paths can use `~` for the home directory and `$VAR` for environment
+/// variables.
+///
+/// ```yaml
+/// bookmarks:
+///   - ~/Documents
+///   - $XDG_CONFIG_HOME/pls
+/// ```
+#[derive(Default, Serialize, Deserialize)]
+pub struct Bookmarks {
+	/// the paths of the bookmarked nodes
+	pub bookmarks: Vec<String>,
+}
+
+impl Bookmarks {
+	/// Load the bookmarks from the bookmarks file.
+	///
+	/// If the bookmarks file does not exist, the list of bookmarks is empty.
+	pub fn load() -> Result<Self, Exc> {
+		let Some(file) = ConfMan::config_dir().map(|dir| dir.join("bookmarks.yml")) else {
+			return Ok(Self::default());
+		};
+		debug!("Reading bookmarks from {file:?}.");
+		Figment::from(Yaml::file(file))
+			.extract()
+			.map_err(|err| Exc::Conf(Box::new(err)))
+	}
+
+	/// Get the bookmarked paths, with `~` and environment variables expanded.
+	pub fn paths(&self) -> Vec<PathBuf> {
+		self.bookmarks
+			.iter()
+			.map(|path| {
+				let home_dir = || home::home_dir().map(|home| home.to_string_lossy().to_string());
+				let path = shellexpand::full_with_context_no_errors(path, home_dir, |var| {
+					env::var(var).ok()
+				});
+				PathBuf::from(path.as_ref())
+			})
+			.collect()
+	}
+}
@@ -1,9 +1,18 @@
 use crate::config::app_const::AppConst;
 use crate::config::entry_const::EntryConst;
-use crate::enums::Collapse;
-use crate::models::Spec;
+use crate::enums::{
+	Collapse, Collation, DetailField, Icon, IconSet, IconSource, Palette, SortField, SpecPack, Typ,
+};
+use crate::gfx::QuirkOverrides;
+use crate::models::{Decorator, LsColors, Risk, Spec};
+use crate::PLS;
+use figment::value::Value;
+use log::warn;
+use regex::bytes::{Regex, RegexSet};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
 
 /// Create a [`HashMap`] from a list of key-value pairs.
 macro_rules! map_str_str {
@@ -24,14 +33,206 @@ macro_rules! map_str_str {
 /// Refer to [`Args`](crate::config::Args) for those.
 #[derive(Serialize, Deserialize)]
 pub struct Conf {
-	/// mapping of icon names to actual glyphs from Nerd Fonts or paths to SVGs
+	/// mapping of icon names to actual glyphs from Nerd Fonts or paths to SVG,
+	/// PNG, JPEG or WebP images
 	pub icons: HashMap<String, String>,
+	/// mapping of icon names to emoji, used instead of `icons` with the emoji
+	/// icon set
+	pub emoji_icons: HashMap<String, String>,
+	/// the set of glyphs to draw icons from, unless set with `--icon-set`
+	pub icon_set: IconSet,
+	/// the source of the icons drawn in terminals that support graphics
+	pub icon_source: IconSource,
+	/// mapping of icon names to the number of cells their glyphs span, for
+	/// glyphs that the terminal draws wider than they are measured
+	pub icon_widths: HashMap<String, usize>,
 	/// list of node specs, in ascending order of specificity
 	pub specs: Vec<Spec>,
+	/// mapping of built-in spec packs to whether they are enabled
+	pub spec_packs: HashMap<SpecPack, bool>,
+	/// list of external programs that decorate nodes, run with `--decorate`
+	pub decorators: Vec<Decorator>,
+	/// list of rules that flag risky nodes, like private keys
+	pub risks: Vec<Risk>,
+	/// regex patterns matching the names of sensitive nodes, whose details
+	/// are hidden with `--redact`
+	#[serde(with = "serde_regex")]
+	pub sensitive: Vec<Regex>,
 	/// constants that determine the appearance and styling of each entry
 	pub entry_const: EntryConst,
 	/// constants that determine the appearance and styling of the entire UI
 	pub app_const: AppConst,
+	/// the built-in palette to remap the semantic styles to
+	pub palette: Palette,
+	/// mapping of terminal names to changes to their built-in quirks
+	pub terminal_overrides: HashMap<String, QuirkOverrides>,
+	/// mapping of preset names to the detail fields selected by `--det` with
+	/// that name
+	pub detail_presets: HashMap<String, Vec<DetailField>>,
+	/// mapping of column names to the templates from which the values of the
+	/// column are derived, with placeholders like `{user}` for other fields
+	pub columns: HashMap<String, String>,
+	/// mapping of theme names to the overrides of `entry_const` and
+	/// `app_const` selected by `--theme` with that name
+	pub themes: HashMap<String, Value>,
+	/// the way in which names are compared when sorting by name, unless set
+	/// with `--sort-collation`
+	pub sort_collation: Collation,
+	/// mapping of node types to the sort fields used within their section,
+	/// when the first sort field places the nodes in sections by type or
+	/// category
+	pub section_sorts: HashMap<Typ, Vec<SortField>>,
+	/// the patterns of all specs compiled into one set, built on first use
+	#[serde(skip)]
+	spec_set: OnceLock<Option<RegexSet>>,
+}
+
+impl Conf {
+	/// Remap the semantic styles in the entry constants to the palette.
+	///
+	/// The palette from the `--palette` CLI argument takes precedence over the
+	/// one from the config files.
+	pub fn apply_palette(&mut self) {
+		PLS.args
+			.palette
+			.unwrap_or(self.palette)
+			.apply(&mut self.entry_const);
+	}
+
+	/// Replace the icons with those of the chosen icon set.
+	///
+	/// The icon set from the `--icon-set` CLI argument takes precedence over
+	/// the one from the config files. With the emoji set, image icons are
+	/// kept, since they need no font, and with no set, all icons are dropped.
+	pub fn apply_icon_set(&mut self) {
+		self.icon_set = PLS.args.icon_set.unwrap_or(self.icon_set);
+		match self.icon_set {
+			IconSet::Nerd => {}
+			IconSet::Emoji => {
+				self.icons.retain(|_, icon| Icon::is_image(icon));
+				self.icons.extend(self.emoji_icons.clone());
+			}
+			IconSet::None => self.icons.clear(),
+		}
+	}
+
+	/// Replace the glyphs of symlink separators, suffixes and icons with the
+	/// ASCII glyphs from `app_const`, with `--ascii`.
+	pub fn apply_ascii(&mut self) {
+		if PLS.args.ascii {
+			self.use_ascii();
+		}
+	}
+
+	/// Replace the glyphs of symlink separators, suffixes and icons with the
+	/// ASCII glyphs from `app_const`.
+	///
+	/// The icons are replaced as a whole, so that icons without an ASCII glyph,
+	/// like those of file types, fall back to the icon of the node type. Image
+	/// and bundled icons are dropped as well, since images are not text.
+	fn use_ascii(&mut self) {
+		let ascii = &self.app_const.ascii;
+		for (state, info) in self.entry_const.symlink.iter_mut() {
+			info.sep = ascii.symlink.get(state).cloned().unwrap_or_default();
+		}
+		for (typ, info) in self.entry_const.typ.iter_mut() {
+			info.suffix = ascii.suffix.get(typ).cloned().unwrap_or_default();
+		}
+		if self.icon_set != IconSet::None {
+			self.icons = ascii.icons.clone();
+		}
+		self.icon_source = IconSource::Glyph;
+		self.icon_widths.clear(); // ASCII glyphs always span one cell.
+	}
+
+	/// Apply the styles from the `LS_COLORS` environment variable, with
+	/// `--ls-colors`.
+	///
+	/// The specs translated from it are placed after all other specs so that
+	/// their styles take precedence over those from the config files.
+	pub fn apply_ls_colors(&mut self) {
+		if !PLS.args.ls_colors {
+			return;
+		}
+		let Some(ls_colors) = LsColors::from_env() else {
+			return;
+		};
+		ls_colors.apply(&mut self.entry_const);
+		self.specs.extend(ls_colors.specs());
+		self.spec_set = OnceLock::new(); // The specs changed.
+	}
+
+	/// Add the specs of the enabled spec packs that apply to the given
+	/// absolute directory.
+	///
+	/// The specs of the packs are placed before all other specs so that specs
+	/// from the config files can override them.
+	pub fn apply_spec_packs(&mut self, dir: &Path) {
+		let mut specs: Vec<_> = self
+			.spec_packs
+			.iter()
+			.filter(|(pack, enabled)| **enabled && pack.applies_to(dir))
+			.flat_map(|(pack, _)| pack.specs())
+			.collect();
+		if specs.is_empty() {
+			return;
+		}
+		specs.append(&mut self.specs);
+		self.specs = specs;
+		self.spec_set = OnceLock::new(); // The indices of the specs changed.
+	}
+
+	/// Get the number of cells spanned by the glyphs of the icons in
+	/// `icon_widths`, keyed by the glyphs themselves.
+	///
+	/// Icons that are not defined, or that are images, are skipped.
+	pub fn glyph_widths(&self) -> HashMap<String, usize> {
+		self.icon_widths
+			.iter()
+			.filter_map(|(name, width)| {
+				let glyph = self.icons.get(name)?;
+				(!Icon::is_image(glyph)).then(|| (glyph.clone(), *width))
+			})
+			.collect()
+	}
+
+	/// Determine whether the given node name matches any of the sensitive
+	/// patterns.
+	pub fn is_sensitive(&self, name: &[u8]) -> bool {
+		self.sensitive.iter().any(|pattern| pattern.is_match(name))
+	}
+
+	/// Get the specs whose patterns match the given node name, in the order in
+	/// which they are defined.
+	///
+	/// All patterns are compiled into a single [`RegexSet`] on first use, so
+	/// that each name is scanned once regardless of the number of specs. If
+	/// the set cannot be compiled, such as when it exceeds the size limit, each
+	/// pattern is tried individually instead.
+	///
+	/// Patterns are compiled in Unicode mode, so names that are not valid
+	/// UTF-8 are matched with the invalid bytes replaced by `U+FFFD`.
+	pub fn matching_specs(&self, name: &[u8]) -> Vec<&Spec> {
+		let name = String::from_utf8_lossy(name);
+		let name = name.as_bytes();
+		let set = self.spec_set.get_or_init(|| {
+			RegexSet::new(self.specs.iter().map(|spec| spec.pattern.as_str()))
+				.map_err(|err| warn!("Could not compile spec patterns into a set: {err}"))
+				.ok()
+		});
+		match set {
+			Some(set) => set
+				.matches(name)
+				.into_iter()
+				.map(|idx| &self.specs[idx])
+				.collect(),
+			None => self
+				.specs
+				.iter()
+				.filter(|spec| spec.pattern.is_match(name))
+				.collect(),
+		}
+	}
 }
 
 impl Default for Conf {
@@ -41,16 +242,21 @@ impl Default for Conf {
 				// pls
 				"pls"          => "", // nf-oct-primitive_dot
 				"missing"      => "", // nf-cod-error
+				"exec"         => "", // nf-oct-gear
 				// Node types
 				"file"         => "",
 				"dir"          => "", // nf-fa-folder
 				"symlink"      => "󰌹", // nf-md-link-variant
+				"junction"     => "󰌷", // nf-md-link
+				"reparse"      => "󰅟", // nf-md-cloud
 				"fifo"         => "󰟥", // nf-md-pipe
 				"socket"       => "󰟨", // nf-md-power_socket_uk
 				"char_device"  => "", // nf-fa-paragraph
 				"block_device" => "󰋊", // nf-md-harddisk
+				"process"      => "", // nf-oct-cpu
 				// Generic
 				"audio"        => "󰓃", // nf-md-speaker
+				"binary"       => "", // nf-oct-file_binary
 				"book"         => "", // nf-fa-book
 				"broom"        => "󰃢", // nf-md-broom
 				"config"       => "", // nf-seti-config
@@ -74,6 +280,51 @@ impl Default for Conf {
 				"markdown"     => "", // nf-oct-markdown
 				"rust"         => "", // nf-seti-rust
 			),
+			emoji_icons: map_str_str!(
+				// pls
+				"pls"          => "🔹",
+				"missing"      => "🚫",
+				"exec"         => "⚙️",
+				// Node types
+				"file"         => "📄",
+				"dir"          => "📁",
+				"symlink"      => "🔗",
+				"junction"     => "🪢",
+				"reparse"      => "☁️",
+				"fifo"         => "🚰",
+				"socket"       => "🔌",
+				"char_device"  => "🖨️",
+				"block_device" => "💽",
+				"process"      => "🖥️",
+				// Generic
+				"audio"        => "🔊",
+				"binary"       => "🔢",
+				"book"         => "📖",
+				"broom"        => "🧹",
+				"config"       => "🔧",
+				"container"    => "🐳",
+				"env"          => "🌱",
+				"image"        => "🖼️",
+				"json"         => "🧾",
+				"law"          => "⚖️",
+				"lock"         => "🔒",
+				"package"      => "📦",
+				"runner"       => "🏃",
+				"shell"        => "🐚",
+				"source"       => "💻",
+				"test"         => "🧪",
+				"text"         => "📝",
+				"video"        => "🎞️",
+				// Brands
+				"apple"        => "🍎",
+				"git"          => "🔀",
+				"github"       => "🐙",
+				"markdown"     => "📑",
+				"rust"         => "🦀",
+			),
+			icon_set: IconSet::default(),
+			icon_source: IconSource::default(),
+			icon_widths: HashMap::new(),
 			specs: vec![
 				// Extensions
 				Spec::new(r"\.sh$", "shell"),
@@ -85,6 +336,7 @@ impl Default for Conf {
 				Spec::new(r"\.(jpg|jpeg|png|svg|webp|gif|ico)$", "image"),
 				Spec::new(r"\.(mov|mp4|mkv|webm|avi|flv)$", "video"),
 				Spec::new(r"\.(mp3|flac|ogg|wav)$", "audio"),
+				Spec::new(r"\.(exe|dll|so|dylib)$", "binary"),
 				// Partial names
 				Spec::new(r"^\.env\b", "env"),
 				Spec::new(r"^README\b", "book").importance(2),
@@ -104,9 +356,219 @@ impl Default for Conf {
 					.importance(-1)
 					.collapse(Collapse::Name(String::from("Cargo.toml"))),
 				Spec::new(r"^rustfmt.toml$", "broom"),
+				Spec::new(r"^(node_modules|target|\.venv|__pycache__)$", "package").summarize(),
+			],
+			spec_packs: [(SpecPack::Procfs, true), (SpecPack::Devfs, true)]
+				.into_iter()
+				.collect(),
+			decorators: vec![],
+			risks: vec![
+				Risk::new("world-writable", r"\.(sh|bash|zsh|fish|py|rb|pl)$")
+					.world_writable()
+					.fix("chmod o-w"),
+				Risk::new(
+					"secrets",
+					r"^\.env(\.(local|dev|development|prod|production|staging|test))*$",
+				)
+				.fix("chmod go-rwx"),
+				Risk::new(
+					"secrets",
+					r"(?i)^(secrets?|credentials?|passwords?)(\.(json|ya?ml|toml|txt))?$",
+				)
+				.fix("chmod go-rwx"),
+				Risk::new(
+					"private key",
+					r"^id_(rsa|dsa|ecdsa|ed25519)$|\.(pem|key|p12|pfx)$",
+				)
+				.fix("chmod go-rwx"),
 			],
+			sensitive: [
+				r"^id_(rsa|dsa|ecdsa|ed25519)$|\.(pem|key|p12|pfx)$",
+				r"^\.env\b",
+				r"(?i)(secret|credential|token|password)s?\b",
+				r"^(\.netrc|\.pgpass|\.npmrc|\.pypirc)$",
+				r"^kubeconfig$|\.kubeconfig$",
+			]
+			.into_iter()
+			.map(|pattern| Regex::new(pattern).unwrap())
+			.collect(),
 			entry_const: EntryConst::default(),
 			app_const: AppConst::default(),
+			palette: Palette::default(),
+			terminal_overrides: HashMap::new(),
+			detail_presets: [
+				(
+					"perms",
+					vec![
+						DetailField::Perm,
+						DetailField::Oct,
+						DetailField::User,
+						DetailField::Uid,
+						DetailField::Group,
+						DetailField::Gid,
+					],
+				),
+				(
+					"times",
+					vec![
+						DetailField::Btime,
+						DetailField::Ctime,
+						DetailField::Mtime,
+						DetailField::Atime,
+					],
+				),
+				(
+					"history",
+					vec![
+						DetailField::Git,
+						DetailField::Commits,
+						DetailField::GitAuthor,
+						DetailField::GitDate,
+					],
+				),
+			]
+			.into_iter()
+			.map(|(name, fields)| (String::from(name), fields))
+			.collect(),
+			columns: HashMap::new(),
+			themes: HashMap::new(),
+			sort_collation: Collation::default(),
+			section_sorts: HashMap::new(),
+			spec_set: OnceLock::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Conf;
+	use crate::models::Spec;
+
+	macro_rules! make_matching_specs_test {
+		( $($name:ident: $node_name:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let conf = Conf {
+						specs: vec![
+							Spec::new(r"\.rs$", "rust"),
+							Spec::new(r"^README\b", "book"),
+							Spec::new(r"^main", "main"),
+							Spec::new(r"\.rs$", "source"),
+						],
+						..Conf::default()
+					};
+					let icons: Vec<_> = conf
+						.matching_specs($node_name.as_bytes())
+						.into_iter()
+						.flat_map(|spec| spec.icons.clone().unwrap_or_default())
+						.collect();
+					assert_eq!(icons, $expected as Vec<&str>);
+				}
+			)*
+		};
+	}
+
+	make_matching_specs_test!(
+		test_matches_nothing: "Cargo.toml" => vec![],
+		test_matches_one: "README.md" => vec!["book"],
+		test_matches_in_order: "main.rs" => vec!["rust", "main", "source"],
+		test_matches_word_boundary: "READMEs" => vec![],
+	);
+
+	#[test]
+	fn test_matches_non_utf8_name() {
+		let conf = Conf {
+			specs: vec![Spec::new(r"^a.b$", "any")],
+			..Conf::default()
+		};
+		assert_eq!(conf.matching_specs(b"a\xffb").len(), 1);
+	}
+
+	#[test]
+	fn test_matches_unicode_class() {
+		let conf = Conf {
+			specs: vec![Spec::new(r"^\p{Lu}", "upper"), Spec::new(r"^\w+$", "word")],
+			..Conf::default()
+		};
+		assert_eq!(conf.matching_specs("Éclair".as_bytes()).len(), 2);
+		assert_eq!(conf.matching_specs("éclair".as_bytes()).len(), 1);
+	}
+
+	#[test]
+	fn test_spec_packs_reset_set() {
+		let mut conf = Conf {
+			specs: vec![Spec::new(r"^1$", "one")],
+			..Conf::default()
+		};
+		assert_eq!(conf.matching_specs(b"1").len(), 1);
+		conf.apply_spec_packs(std::path::Path::new("/proc"));
+		assert_eq!(conf.matching_specs(b"1").len(), 2);
+	}
+
+	macro_rules! make_is_sensitive_test {
+		( $($name:ident: $node_name:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Conf::default().is_sensitive($node_name.as_bytes()), $expected);
+				}
+			)*
+		};
+	}
+
+	make_is_sensitive_test!(
+		test_sensitive_key: "id_rsa" => true,
+		test_sensitive_env: ".env.production" => true,
+		test_sensitive_token: "github_token.txt" => true,
+		test_sensitive_kubeconfig: "prod.kubeconfig" => true,
+		test_sensitive_netrc: ".netrc" => true,
+		test_not_sensitive: "tokenizer.rs" => false,
+		test_not_sensitive_readme: "README.md" => false,
+	);
+	#[test]
+	fn test_emoji_icons_cover_icons() {
+		let conf = Conf::default();
+		let mut missing: Vec<_> = conf
+			.icons
+			.keys()
+			.filter(|name| !conf.emoji_icons.contains_key(*name))
+			.collect();
+		missing.sort();
+		assert_eq!(missing, Vec::<&String>::new());
+	}
+
+	#[test]
+	fn test_glyph_widths() {
+		let mut conf = Conf::default();
+		conf.icons
+			.insert(String::from("logo"), String::from("logo.svg"));
+		conf.icon_widths = [("dir", 2), ("logo", 2), ("unknown", 2)]
+			.into_iter()
+			.map(|(name, width)| (String::from(name), width))
+			.collect();
+		let widths = conf.glyph_widths();
+		assert_eq!(widths.len(), 1);
+		assert_eq!(widths.get(&conf.icons["dir"]), Some(&2));
+	}
+
+	#[test]
+	fn test_use_ascii() {
+		let mut conf = Conf::default();
+		conf.use_ascii();
+		let glyphs: Vec<_> = conf
+			.entry_const
+			.symlink
+			.values()
+			.map(|info| &info.sep)
+			.chain(conf.entry_const.typ.values().map(|info| &info.suffix))
+			.chain(conf.icons.values())
+			.collect();
+		assert!(!glyphs.is_empty());
+		for glyph in glyphs {
+			assert!(glyph.is_ascii(), "{glyph} is not ASCII");
 		}
+		assert_eq!(conf.icons["dir"], "+");
+		assert!(!conf.icons.contains_key("rust"));
 	}
 }
@@ -1,11 +1,17 @@
 use crate::enums::{DetailField, Oct, Sym, SymState, Typ};
+use log::warn;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize)]
 pub struct EntryConst {
 	/// style for the device number
 	pub dev_style: String,
+	/// style for the major and minor numbers of device nodes
+	pub rdev_style: String,
+	/// style for the name of the driver of device nodes
+	pub driver_style: String,
 	/// style for the inode number
 	pub ino_style: String,
 	/// styles for the number of hard links
@@ -22,18 +28,71 @@ pub struct EntryConst {
 	pub group_styles: OwnerStyles,
 	/// style for magnitude and unit (prefix and base) of node size
 	pub size_styles: SizeStyles,
+	/// style for the exact node size in bytes
+	pub size_raw_style: String,
+	/// style for the Windows file attributes
+	pub attrs_style: String,
 	/// style for the number of blocks occupied by the file
 	pub blocks_style: String,
+	/// style for the number of commits touching the node
+	pub commits_style: String,
+	/// style for the author of the latest commit touching the node
+	pub git_author_style: String,
+	/// style for the owners of the node declared in `CODEOWNERS`
+	pub code_owners_style: String,
+	/// style for the ignore rule that causes Git to ignore the node
+	pub ignored_by_style: String,
+	/// style for the command names of processes
+	pub comm_style: String,
+	/// style for the role of the node in the project
+	pub role_style: String,
+	/// style for the summary of the contents of a directory
+	pub summary_style: String,
+	/// pairings of minimum ages, in days, of the latest commit touching the
+	/// node with the styles for the node name, used with `--age`
+	pub age_styles: Vec<(u64, String)>,
+	/// pairings of minimum relative importance levels with a glyph and its
+	/// style, used for the importance marker
+	pub imp_markers: Vec<(i8, String, String)>,
 	/// mapping of timestamp fields to the human-readable format
 	pub timestamp_formats: HashMap<DetailField, String>,
+	/// mapping of timestamp fields to the format used with
+	/// `--time-style relative`, where `[relative]` is replaced by the time
+	/// elapsed, like "3 hours ago"
+	pub relative_timestamp_formats: HashMap<DetailField, String>,
 	/// mapping of symlink state to more symlink state info (including style)
 	pub symlink: HashMap<SymState, SymlinkInfo>,
+	/// markers for cloud placeholders, whose content is not stored locally
+	pub cloud: CloudInfo,
+	/// marker and styles for extended attributes
+	pub xattr: XattrInfo,
+	/// attribute and styles for the tags of nodes
+	pub tag: TagInfo,
+	/// marker and style for access control lists
+	pub acl: AclInfo,
+	/// badge and style for nodes flagged by risk rules
+	pub risk: RiskInfo,
+	/// style for the names of sensitive nodes, with `--redact`
+	pub redacted_style: String,
+	/// style for the old values of the permissions and owners that changed
+	/// since the snapshot, with `--compare`
+	pub changed_style: String,
+	/// style for the parts of the name matching the `--highlight` pattern
+	pub highlight_style: String,
+	/// style for nodes that do not exist or cannot be read
+	pub missing_style: String,
+	/// style for regular files that can be executed, like `ls` colors them
+	pub exec_style: String,
+	/// the badge to show after the name of a pinned node, when icons are shown
+	pub pin_badge: String,
 }
 
 impl Default for EntryConst {
 	fn default() -> Self {
 		Self {
 			dev_style: String::default(),
+			rdev_style: String::from("bold"),
+			driver_style: String::from("dimmed"),
 			ino_style: String::default(),
 			nlink_styles: NlinkStyles {
 				file_sing: String::from(""),
@@ -44,6 +103,8 @@ impl Default for EntryConst {
 			typ: [
 				(Typ::Dir, "d", "<dimmed>/</>", "dir", "blue"),
 				(Typ::Symlink, "l", "<dimmed>@</>", "symlink", ""),
+				(Typ::Junction, "j", "<dimmed>@</>", "junction", ""),
+				(Typ::Reparse, "r", "", "reparse", "dimmed"),
 				(Typ::Fifo, "p", "<dimmed>|</>", "fifo", ""),
 				(Typ::Socket, "s", "<dimmed>=</>", "socket", ""),
 				(Typ::BlockDevice, "b", "", "block_device", ""),
@@ -85,23 +146,64 @@ impl Default for EntryConst {
 			.collect(),
 			user_styles: OwnerStyles {
 				curr: String::from("blue bold"),
+				system: String::from("yellow"),
 				other: String::from("dimmed"),
+				system_ranges: vec![(0, 999), (65534, 65534)],
 			},
 			group_styles: OwnerStyles {
 				curr: String::from("blue"),
+				system: String::from("yellow"),
 				other: String::from("dimmed"),
+				system_ranges: vec![(0, 999), (65534, 65534)],
 			},
 			size_styles: SizeStyles {
 				mag: String::from("bold"),
 				prefix: String::default(),
 				base: String::from("dimmed"),
+				base_label: String::from("B"),
+				binary_prefixes: ["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "Zi", "Yi"]
+					.map(String::from)
+					.to_vec(),
+				decimal_prefixes: ["", "k", "M", "G", "T", "P", "E", "Z", "Y"]
+					.map(String::from)
+					.to_vec(),
+				fixed_prefix: None,
 			},
+			size_raw_style: String::default(),
+			attrs_style: String::from("dimmed"),
 			blocks_style: String::default(),
+			commits_style: String::default(),
+			git_author_style: String::from("dimmed"),
+			code_owners_style: String::from("cyan"),
+			ignored_by_style: String::from("dimmed"),
+			comm_style: String::from("green"),
+			role_style: String::from("dimmed"),
+			summary_style: String::from("dimmed"),
+			age_styles: [
+				(0, "bright_green"),
+				(7, "green"),
+				(30, "yellow"),
+				(180, "bright_black"),
+				(365, "dimmed"),
+			]
+			.into_iter()
+			.map(|(k, v)| (k, v.to_string()))
+			.collect(),
+			imp_markers: [
+				(-1, "·", "dimmed"),
+				(0, "", ""),
+				(1, "*", "yellow"),
+				(2, "!!", "bold red"),
+			]
+			.into_iter()
+			.map(|(k, glyph, style)| (k, glyph.to_string(), style.to_string()))
+			.collect(),
 			timestamp_formats: [
 				(DetailField::Btime, "green"),
 				(DetailField::Ctime, "yellow"),
 				(DetailField::Mtime, "yellow"),
 				(DetailField::Atime, "blue"),
+				(DetailField::GitDate, "magenta"),
 			]
 			.into_iter()
 			.map(|(k, v)| {
@@ -114,6 +216,16 @@ impl Default for EntryConst {
 				)
 			})
 			.collect(),
+			relative_timestamp_formats: [
+				(DetailField::Btime, "green"),
+				(DetailField::Ctime, "yellow"),
+				(DetailField::Mtime, "yellow"),
+				(DetailField::Atime, "blue"),
+				(DetailField::GitDate, "magenta"),
+			]
+			.into_iter()
+			.map(|(k, v)| (k, format!("<bold {v}>[relative]</>")))
+			.collect(),
 			symlink: [
 				(SymState::Ok, "󰁔", "magenta", ""), // nf-md-arrow_right
 				(SymState::Broken, "󱞣", "red", "strikethrough"), // nf-md-arrow_down_right
@@ -132,10 +244,153 @@ impl Default for EntryConst {
 				)
 			})
 			.collect(),
+			cloud: CloudInfo {
+				badge: String::from("<blue>󰅟</>"), // nf-md-cloud
+				size_note: String::from("<dimmed>(logical)</>"),
+			},
+			xattr: XattrInfo {
+				marker: String::from("<cyan>@</>"),
+				name_style: String::from("cyan"),
+				value_style: String::from("dimmed"),
+			},
+			tag: TagInfo {
+				attr: String::from("user.pls.tag"),
+				palette: [
+					"black bg:cyan",
+					"black bg:magenta",
+					"black bg:yellow",
+					"black bg:green",
+					"black bg:blue",
+					"black bg:red",
+				]
+				.into_iter()
+				.map(String::from)
+				.collect(),
+				styles: HashMap::new(),
+			},
+			acl: AclInfo {
+				marker: String::from("<yellow>+</>"),
+				style: String::from("yellow"),
+			},
+			risk: RiskInfo {
+				badge: String::from(""), // nf-fa-warning
+				style: String::from("red bold"),
+			},
+			redacted_style: String::from("dimmed"),
+			changed_style: String::from("dimmed strikethrough"),
+			highlight_style: String::from("bold reversed"),
+			missing_style: String::from("red strikethrough"),
+			exec_style: String::from("green"),
+			pin_badge: String::from("<yellow>󰐃</>"), // nf-md-pin
 		}
 	}
 }
 
+impl EntryConst {
+	/// Get the style for a node whose latest commit is the given number of
+	/// days old.
+	///
+	/// This is the style of the bucket with the highest minimum age that does
+	/// not exceed the given age, so the buckets need not be sorted.
+	pub fn age_style(&self, days: u64) -> Option<&String> {
+		self.age_styles
+			.iter()
+			.filter(|(min_days, _)| *min_days <= days)
+			.max_by_key(|(min_days, _)| *min_days)
+			.map(|(_, style)| style)
+	}
+
+	/// Get the glyph and style of the marker for the given relative
+	/// importance.
+	///
+	/// This is the marker with the highest minimum importance that does not
+	/// exceed the given importance, so the markers need not be sorted.
+	pub fn imp_marker(&self, imp: i8) -> Option<(&String, &String)> {
+		self.imp_markers
+			.iter()
+			.filter(|(min_imp, _, _)| *min_imp <= imp)
+			.max_by_key(|(min_imp, _, _)| *min_imp)
+			.map(|(_, glyph, style)| (glyph, style))
+	}
+
+	/// Resolve references to other styles in the entry constants.
+	///
+	/// A directive of the form `$key.subkey` is replaced with the style found
+	/// at that path in the entry constants. The first segment may omit the
+	/// `_style` or `_styles` suffix of the field, so `$user.other` refers to
+	/// `user_styles.other`. References can be chained, and unresolvable or
+	/// cyclic references are dropped with a warning.
+	pub fn resolve_refs(&mut self) {
+		let Ok(tree) = serde_json::to_value(&*self) else {
+			return;
+		};
+		let mut resolved = tree.clone();
+		resolve_value(&mut resolved, &tree);
+		match serde_json::from_value(resolved) {
+			Ok(entry_const) => *self = entry_const,
+			Err(err) => warn!("Could not resolve style references: {err}"),
+		}
+	}
+}
+
+/// Recursively resolve the references in all strings inside the value.
+fn resolve_value(value: &mut Value, tree: &Value) {
+	match value {
+		Value::String(style) if style.contains('$') => *style = resolve_style(style, tree, &[]),
+		Value::Array(items) => items.iter_mut().for_each(|item| resolve_value(item, tree)),
+		Value::Object(map) => map.values_mut().for_each(|item| resolve_value(item, tree)),
+		_ => {}
+	}
+}
+
+/// Replace the references in the style with the styles they refer to.
+///
+/// The `seen` argument holds the references being resolved up the chain, and
+/// is used to detect cycles.
+fn resolve_style(style: &str, tree: &Value, seen: &[&str]) -> String {
+	style
+		.split_whitespace()
+		.filter_map(|directive| {
+			let Some(path) = directive.strip_prefix('$') else {
+				return Some(directive.to_string());
+			};
+			if seen.contains(&path) {
+				warn!("Style reference {directive:?} is cyclic.");
+				return None;
+			}
+			match lookup(tree, path) {
+				Some(target) => Some(resolve_style(target, tree, &[seen, &[path]].concat())),
+				None => {
+					warn!("Could not resolve style reference {directive:?}.");
+					None
+				}
+			}
+		})
+		.filter(|directive| !directive.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// Find the string at the given dot-separated path in the entry constants.
+fn lookup<'tree>(tree: &'tree Value, path: &str) -> Option<&'tree str> {
+	let mut segments = path.split('.');
+	let first = segments.next()?;
+	let mut node = [
+		first.to_string(),
+		format!("{first}_style"),
+		format!("{first}_styles"),
+	]
+	.iter()
+	.find_map(|key| tree.get(key))?;
+	for segment in segments {
+		node = match node {
+			Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+			_ => node.get(segment)?,
+		};
+	}
+	node.as_str()
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct NlinkStyles {
 	/// style to use when file has one hard link
@@ -176,8 +431,73 @@ pub struct TypInfo {
 pub struct OwnerStyles {
 	/// style for when the node is owned by the current user/group
 	pub curr: String,
+	/// style for when the node is owned by a system user/group
+	pub system: String,
 	/// style for when the node is owned by a different user/group
 	pub other: String,
+	/// inclusive ranges of IDs that belong to system users/groups
+	pub system_ranges: Vec<(u32, u32)>,
+}
+
+impl OwnerStyles {
+	/// Get whether the given ID belongs to a system user/group.
+	pub fn is_system(&self, id: u32) -> bool {
+		self.system_ranges
+			.iter()
+			.any(|(start, end)| (*start..=*end).contains(&id))
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CloudInfo {
+	/// the badge to show after the name of a cloud placeholder
+	pub badge: String,
+	/// the note to show after the size of a cloud placeholder, as its size is
+	/// the logical size of the content and not the space used locally
+	pub size_note: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct XattrInfo {
+	/// the marker to show in the `xattr` column for nodes with extended
+	/// attributes
+	pub marker: String,
+	/// style for the names of extended attributes, listed with `--xattrs`
+	pub name_style: String,
+	/// style for the values of extended attributes, listed with `--xattrs`
+	pub value_style: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TagInfo {
+	/// the name of the extended attribute holding the comma-separated tags of
+	/// a node
+	pub attr: String,
+	/// styles for the chips of tags, of which each tag gets one based on its
+	/// name
+	pub palette: Vec<String>,
+	/// mapping of tag names to the styles for their chips, overriding the
+	/// palette
+	pub styles: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RiskInfo {
+	/// the badge to show, with the label of the risk, after the name of a
+	/// flagged node
+	pub badge: String,
+	/// style for the names and badges of flagged nodes, unless the risk rule
+	/// sets its own
+	pub style: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AclInfo {
+	/// the marker to append to the symbolic permissions of nodes with an
+	/// extended ACL
+	pub marker: String,
+	/// style for the entries of the ACL in the `acl` column
+	pub style: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -188,6 +508,17 @@ pub struct SizeStyles {
 	pub prefix: String,
 	/// style for the node size base unit
 	pub base: String,
+	/// the label of the base unit
+	pub base_label: String,
+	/// the labels of the unit prefixes for `--unit binary`, in increasing
+	/// powers of 1024, starting with the label for no prefix
+	pub binary_prefixes: Vec<String>,
+	/// the labels of the unit prefixes for `--unit decimal`, in increasing
+	/// powers of 1000, starting with the label for no prefix
+	pub decimal_prefixes: Vec<String>,
+	/// the label of the prefix to always use, so that all sizes in a column
+	/// have the same unit, instead of the largest one that fits each size
+	pub fixed_prefix: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -199,3 +530,83 @@ pub struct SymlinkInfo {
 	/// the style to use for the symlink reference
 	pub ref_style: String, // applies to reference only
 }
+
+#[cfg(test)]
+mod tests {
+	use super::EntryConst;
+
+	macro_rules! make_resolve_refs_test {
+		( $($name:ident: $style:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let mut entry_const = EntryConst::default();
+					entry_const.user_styles.curr = String::from("blue bold");
+					entry_const.group_styles.other = String::from("$user.other");
+					entry_const.blocks_style = String::from($style);
+					entry_const.resolve_refs();
+					assert_eq!(entry_const.blocks_style, $expected);
+				}
+			)*
+		};
+	}
+
+	make_resolve_refs_test!(
+		test_no_refs_unchanged: "red  italic" => "red  italic",
+		test_resolves_alias: "$user.curr" => "blue bold",
+		test_resolves_full_key: "$user_styles.curr underline" => "blue bold underline",
+		test_resolves_map_key: "$perm.write" => "red",
+		test_resolves_chains: "$group.other" => "dimmed",
+		test_drops_unknown: "$nope.nada italic" => "italic",
+		test_drops_cycles: "$blocks" => "",
+	);
+
+	macro_rules! make_age_style_test {
+		( $($name:ident: $days:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let entry_const = EntryConst {
+						age_styles: vec![
+							(30, String::from("yellow")),
+							(7, String::from("green")),
+							(365, String::from("dimmed")),
+						],
+						..EntryConst::default()
+					};
+					let expected: Option<&str> = $expected;
+					assert_eq!(entry_const.age_style($days).map(String::as_str), expected);
+				}
+			)*
+		};
+	}
+
+	make_age_style_test!(
+		test_below_all_buckets: 3 => None,
+		test_at_bucket_start: 7 => Some("green"),
+		test_inside_bucket: 100 => Some("yellow"),
+		test_beyond_last_bucket: 5000 => Some("dimmed"),
+	);
+
+	macro_rules! make_imp_marker_test {
+		( $($name:ident: $imp:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let entry_const = EntryConst::default();
+					let expected: Option<&str> = $expected;
+					let glyph = entry_const.imp_marker($imp).map(|(glyph, _)| glyph.as_str());
+					assert_eq!(glyph, expected);
+				}
+			)*
+		};
+	}
+
+	make_imp_marker_test!(
+		test_marker_below_all_levels: -2 => None,
+		test_marker_for_low_imp: -1 => Some("·"),
+		test_marker_for_default_imp: 0 => Some(""),
+		test_marker_for_high_imp: 2 => Some("!!"),
+		test_marker_beyond_last_level: 5 => Some("!!"),
+	);
+}
@@ -1,11 +1,53 @@
 use crate::config::Conf;
+use crate::enums::Background;
 use crate::exc::Exc;
 use figment::providers::{Data, Format, Serialized, Yaml};
+use figment::value::Dict;
 use figment::Figment;
 use git2::Repository;
 use log::{debug, info};
 use std::env;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// the sections of the config that a theme can override
+const THEME_KEYS: [&str; 2] = ["entry_const", "app_const"];
+
+/// the built-in themes, as pairs of their name and their overrides
+///
+/// The default styles are designed for dark backgrounds, so the dark theme
+/// keeps them. The light theme replaces the yellows, which are hard to read on
+/// light backgrounds.
+const BUILTIN_THEMES: [(&str, &str); 2] = [
+	("dark", "{}"),
+	(
+		"light",
+		"
+entry_const:
+  perm_styles:
+    read: blue
+  user_styles:
+    system: magenta
+  group_styles:
+    system: magenta
+  symlink:
+    cyclic:
+      style: cyan
+  acl:
+    marker: <magenta>+</>
+    style: magenta
+  pin_badge: <magenta>󰐃</>
+",
+	),
+];
+
+/// Get the overrides of the built-in theme with the given name.
+fn builtin_theme(name: &str) -> Option<&'static str> {
+	BUILTIN_THEMES
+		.iter()
+		.find(|(builtin, _)| *builtin == name)
+		.map(|(_, yaml)| *yaml)
+}
 
 /// Manages the configuration system of the application. This manager provides
 /// `Conf` instances tailored to each path, while caching the base configuration
@@ -13,17 +55,37 @@ use std::path::Path;
 pub struct ConfMan {
 	/// the base configuration, i.e. the serialized output of [`Conf::default`]
 	pub base: Figment,
+	/// whether to look for `.pls.yml` files around the listed paths
+	pub discover: bool,
+	/// the overrides of the theme chosen with `--theme`, if any
+	pub theme: Option<Figment>,
 }
 
 impl Default for ConfMan {
-	/// This includes config files from the one of the following locations:
+	/// This includes the global config file, found by
+	/// [`global_conf`](ConfMan::global_conf), and over it, the config file
+	/// from one of the following locations:
 	///
 	/// * the file referenced in the `PLS_CONFIG` environment variable
 	/// * `.pls.yml` in the user's home directory
+	///
+	/// The default styles are those for a dark background.
 	fn default() -> Self {
+		Self::discovered(Background::Dark)
+	}
+}
+
+impl ConfMan {
+	/// Create a config manager that discovers config files, as described in
+	/// [`default`](ConfMan::default), with the default styles adjusted for the
+	/// given background.
+	pub fn discovered(background: Background) -> Self {
 		info!("Preparing base configuration.");
 
-		let mut base = Figment::from(Serialized::defaults(Conf::default()));
+		let mut base = Self::defaults(background);
+		if let Some(global_yaml) = Self::global_conf() {
+			base = base.admerge(global_yaml);
+		}
 		if let Ok(config_path) = env::var("PLS_CONFIG") {
 			base = base.admerge(Yaml::file(config_path));
 		} else if let Some(home_yaml) = home::home_dir().and_then(Self::conf_at) {
@@ -31,11 +93,139 @@ impl Default for ConfMan {
 		}
 
 		info!("Base configuration prepared.");
-		Self { base }
+		Self {
+			base,
+			discover: true,
+			theme: None,
+		}
+	}
+
+	/// Create a config manager that uses only the given config file, or only
+	/// the default config if no file is given.
+	///
+	/// This bypasses the discovery of config files entirely, so that scripts
+	/// and tests are not affected by the config files of the user. Unlike
+	/// discovered files, the given file must exist.
+	pub fn explicit(path: Option<&Path>, background: Background) -> Self {
+		let mut base = Self::defaults(background);
+		if let Some(path) = path {
+			debug!("Using only config file {path:?}.");
+			base = base.admerge(Yaml::file_exact(path));
+		}
+		Self {
+			base,
+			discover: false,
+			theme: None,
+		}
+	}
+
+	/// Select the theme with the given name, whose overrides are merged over
+	/// all config files.
+	///
+	/// The theme is the first one found from these sources:
+	///
+	/// * the `themes` map in the base config
+	/// * `themes/<name>.yml` in the [config directory](ConfMan::config_dir) or
+	///   the [platform-specific config directory](ConfMan::platform_config_dir)
+	/// * the built-in themes, `dark` and `light`
+	///
+	/// Only the `entry_const` and `app_const` of the theme are used, since a
+	/// theme only changes the appearance of the output.
+	///
+	/// This function returns an error if the theme cannot be found or read.
+	pub fn select_theme(&mut self, name: &str) -> Result<(), String> {
+		let source = if let Ok(value) = self.base.find_value(&format!("themes.{name}")) {
+			Figment::from(Serialized::defaults(value))
+		} else if let Some(theme_file) = [Self::config_dir(), Self::platform_config_dir()]
+			.into_iter()
+			.flatten()
+			.map(|dir| dir.join("themes").join(format!("{name}.yml")))
+			.find(|theme_file| theme_file.exists())
+		{
+			debug!("Found theme file {theme_file:?}.");
+			Figment::from(Yaml::file_exact(theme_file))
+		} else if let Some(yaml) = builtin_theme(name) {
+			Figment::from(Yaml::string(yaml))
+		} else {
+			return Err(String::from("no such theme was found"));
+		};
+
+		let overrides: Dict = source.extract().map_err(|err| err.to_string())?;
+		let theme = THEME_KEYS
+			.into_iter()
+			.filter_map(|key| overrides.get(key).map(|value| (key, value)))
+			.fold(Figment::new(), |theme, (key, value)| {
+				theme.admerge(Serialized::default(key, value))
+			});
+		self.theme = Some(theme);
+		Ok(())
+	}
+
+	/// Get the default config, adjusted for the given background.
+	///
+	/// For a light background, the built-in `light` theme is merged over the
+	/// defaults, and so underneath all config files, which can still override
+	/// its styles.
+	fn defaults(background: Background) -> Figment {
+		let defaults = Figment::from(Serialized::defaults(Conf::default()));
+		match (background, builtin_theme("light")) {
+			(Background::Light, Some(yaml)) => defaults.admerge(Yaml::string(yaml)),
+			_ => defaults,
+		}
+	}
+
+	/// Get the directory for `pls`-specific files in the user's config
+	/// directory.
+	///
+	/// This is `$XDG_CONFIG_HOME/pls` if the environment variable is set, or
+	/// `~/.config/pls` otherwise.
+	pub fn config_dir() -> Option<PathBuf> {
+		env::var_os("XDG_CONFIG_HOME")
+			.filter(|dir| !dir.is_empty())
+			.map(PathBuf::from)
+			.or_else(|| home::home_dir().map(|home| home.join(".config")))
+			.map(|dir| dir.join("pls"))
+	}
+
+	/// Get the platform-specific directory for `pls`-specific files in the
+	/// user's config directory, if the platform has one.
+	///
+	/// This is `~/Library/Application Support/pls` on macOS and `%APPDATA%\pls`
+	/// on Windows.
+	fn platform_config_dir() -> Option<PathBuf> {
+		if cfg!(target_os = "macos") {
+			home::home_dir().map(|home| home.join("Library/Application Support/pls"))
+		} else if cfg!(windows) {
+			env::var_os("APPDATA")
+				.filter(|dir| !dir.is_empty())
+				.map(|dir| PathBuf::from(dir).join("pls"))
+		} else {
+			None
+		}
+	}
+
+	/// Look for the global config file and return its contents.
+	///
+	/// The global config file is `pls.yml`, without the leading dot, in the
+	/// first of these directories that has one:
+	///
+	/// * the [config directory](ConfMan::config_dir)
+	/// * the [platform-specific config directory](ConfMan::platform_config_dir)
+	///
+	/// It holds machine-wide defaults, so it is merged underneath all other
+	/// config files.
+	fn global_conf() -> Option<Data<Yaml>> {
+		[Self::config_dir(), Self::platform_config_dir()]
+			.into_iter()
+			.flatten()
+			.map(|dir| dir.join("pls.yml"))
+			.find(|conf_file| conf_file.exists())
+			.map(|conf_file| {
+				debug!("Found global config file {conf_file:?}.");
+				Yaml::file(conf_file)
+			})
 	}
-}
 
-impl ConfMan {
 	/// Look for a config file in the given directory and return its contents.
 	///
 	/// This function will return `None` if no config file is found inside the
@@ -58,32 +248,37 @@ impl ConfMan {
 	/// * all parent directories up to the Git root, if Git-tracked
 	/// * the given path, if a directory, or it's parent
 	///
+	/// The files are ordered from the Git root down to the given path, i.e. in
+	/// ascending order of specificity.
+	///
 	/// # Arguments
 	///
 	/// * `path` - the path to scan for config files
 	fn yaml_contents(path: &Path) -> Vec<Data<Yaml>> {
 		// the given path, if a directory, or it's parent; Note that symlinks
 		// are treated as files in this situation.
-		let mut curr = if !path.is_symlink() && path.is_dir() {
-			path.to_path_buf()
+		let curr = if !path.is_symlink() && path.is_dir() {
+			path
 		} else {
 			match path.parent() {
-				Some(par) => par.to_path_buf(),
+				Some(par) if par.as_os_str().is_empty() => Path::new("."),
+				Some(par) => par,
 				None => return vec![],
 			}
 		};
+		// Relative paths like `.` cannot be walked up to the Git root, so the
+		// directory is resolved to an absolute path first.
+		let mut curr = fs::canonicalize(curr).unwrap_or_else(|_| curr.to_path_buf());
 
 		let mut paths = vec![curr.clone()];
 
-		let repo_root = Repository::discover(path)
+		let repo_root = Repository::discover(&curr)
 			.ok()
-			.and_then(|repo| repo.workdir().map(Path::to_path_buf));
-		if let Some(repo_root) = repo_root {
-			while curr.pop() {
+			.and_then(|repo| repo.workdir().map(Path::to_path_buf))
+			.and_then(|root| fs::canonicalize(root).ok());
+		if let Some(repo_root) = repo_root.filter(|root| curr.starts_with(root)) {
+			while curr != repo_root && curr.pop() {
 				paths.push(curr.clone());
-				if curr == repo_root {
-					break;
-				}
 			}
 		}
 		debug!("Checking for configs in {paths:?}.");
@@ -96,18 +291,159 @@ impl ConfMan {
 	/// This merges the path-specific config files with the base and returns the
 	/// resulting [`Conf`] instance, If there is an error parsing the config
 	/// files, an [`Exc`] instance will be returned.
+	///
+	/// Config files are merged in cascade rather than the closest one winning.
+	/// Maps, like `icons` and the constants, are combined key by key with the
+	/// closer files overriding individual keys, while lists, like `specs`, are
+	/// concatenated in ascending order of specificity. The theme, if one is
+	/// chosen, is merged over all of them.
 	pub fn get<P>(&self, path: Option<P>) -> Result<Conf, Exc>
 	where
 		P: AsRef<Path>,
 	{
 		let mut fig = self.base.clone();
 
-		if let Some(path) = path {
+		if let Some(path) = path.filter(|_| self.discover) {
 			for file in Self::yaml_contents(path.as_ref()) {
 				fig = fig.admerge(file);
 			}
 		}
+		if let Some(theme) = &self.theme {
+			fig = fig.admerge(theme.clone());
+		}
+
+		fig.extract().map_err(|err| Exc::Conf(Box::new(err)))
+	}
+}
+
+#[cfg(test)]
+// `Jail::expect_with` requires closures that return `figment::Error`.
+#[allow(clippy::result_large_err)]
+mod tests {
+	use super::{ConfMan, BUILTIN_THEMES};
+	use crate::enums::Background;
+	use figment::Jail;
+
+	#[test]
+	fn test_global_conf_is_merged_under_home_conf() {
+		Jail::expect_with(|jail| {
+			let dir = jail.directory().to_path_buf();
+			jail.set_env("HOME", dir.display());
+			jail.set_env("XDG_CONFIG_HOME", dir.display());
+			std::env::remove_var("PLS_CONFIG"); // It would replace the home config.
+
+			jail.create_dir("pls")?;
+			jail.create_file("pls/pls.yml", "icons:\n  global: G\n  shared: G\n")?;
+			jail.create_file(".pls.yml", "icons:\n  shared: H\n")?;
+
+			let conf = ConfMan::default().get(None::<&str>).unwrap();
+			assert_eq!(conf.icons.get("global"), Some(&String::from("G")));
+			assert_eq!(conf.icons.get("shared"), Some(&String::from("H")));
+			Ok(())
+		});
+	}
+
+	#[test]
+	fn test_confs_cascade_from_git_root() {
+		Jail::expect_with(|jail| {
+			git2::Repository::init(jail.directory()).unwrap();
+			jail.create_dir("sub")?;
+			jail.create_file(
+				".pls.yml",
+				"icons:\n  outer: O\n  shared: O\nspecs:\n  - pattern: ^outer$\n",
+			)?;
+			jail.create_file(
+				"sub/.pls.yml",
+				"icons:\n  shared: I\nspecs:\n  - pattern: ^inner$\n",
+			)?;
+			jail.change_dir("sub")?;
+
+			let conf = ConfMan::default().get(Some(".")).unwrap();
+			assert_eq!(conf.icons.get("outer"), Some(&String::from("O")));
+			assert_eq!(conf.icons.get("shared"), Some(&String::from("I")));
+			let patterns: Vec<_> = conf
+				.specs
+				.iter()
+				.map(|spec| spec.pattern.as_str())
+				.filter(|pattern| matches!(*pattern, "^outer$" | "^inner$"))
+				.collect();
+			assert_eq!(patterns, ["^outer$", "^inner$"]);
+			Ok(())
+		});
+	}
+
+	#[test]
+	fn test_builtin_themes_are_valid() {
+		for (name, _) in BUILTIN_THEMES {
+			let mut conf_man = ConfMan::explicit(None, Background::Dark);
+			conf_man.select_theme(name).unwrap();
+			assert!(conf_man.get(None::<&str>).is_ok());
+		}
+	}
+
+	#[test]
+	fn test_theme_overrides_config_files() {
+		Jail::expect_with(|jail| {
+			jail.create_file(
+				"explicit.yml",
+				"entry_const:\n  exec_style: red\n  missing_style: blue\nthemes:\n  mine:\n    entry_const:\n      exec_style: cyan\n    icons:\n      ignored: I\n",
+			)?;
+
+			let explicit = jail.directory().join("explicit.yml");
+			let mut conf_man = ConfMan::explicit(Some(&explicit), Background::Dark);
+			conf_man.select_theme("mine").unwrap();
+			let conf = conf_man.get(None::<&str>).unwrap();
+			assert_eq!(conf.entry_const.exec_style, "cyan");
+			assert_eq!(conf.entry_const.missing_style, "blue");
+			assert_eq!(conf.icons.get("ignored"), None);
+			Ok(())
+		});
+	}
+
+	#[test]
+	fn test_theme_file_in_config_dir() {
+		Jail::expect_with(|jail| {
+			let dir = jail.directory().to_path_buf();
+			jail.set_env("XDG_CONFIG_HOME", dir.display());
+			jail.create_dir("pls")?;
+			jail.create_dir("pls/themes")?;
+			jail.create_file(
+				"pls/themes/light.yml",
+				"app_const:\n  table:\n    header_style: red\n",
+			)?;
+
+			let mut conf_man = ConfMan::explicit(None, Background::Dark);
+			conf_man.select_theme("light").unwrap();
+			let conf = conf_man.get(None::<&str>).unwrap();
+			assert_eq!(conf.app_const.table.header_style, "red");
+			assert!(conf_man.select_theme("missing").is_err());
+			Ok(())
+		});
+	}
+
+	#[test]
+	fn test_explicit_conf_skips_discovery() {
+		Jail::expect_with(|jail| {
+			jail.create_file(".pls.yml", "icons:\n  found: F\n")?;
+			jail.create_file("explicit.yml", "icons:\n  given: G\n")?;
+
+			let explicit = jail.directory().join("explicit.yml");
+			let conf = ConfMan::explicit(Some(&explicit), Background::Dark)
+				.get(Some("."))
+				.unwrap();
+			assert_eq!(conf.icons.get("given"), Some(&String::from("G")));
+			assert_eq!(conf.icons.get("found"), None);
+
+			let conf = ConfMan::explicit(None, Background::Dark)
+				.get(Some("."))
+				.unwrap();
+			assert_eq!(conf.icons.get("found"), None);
 
-		fig.extract().map_err(Exc::Conf)
+			let missing = jail.directory().join("missing.yml");
+			assert!(ConfMan::explicit(Some(&missing), Background::Dark)
+				.get(Some("."))
+				.is_err());
+			Ok(())
+		});
 	}
 }
@@ -1,21 +1,61 @@
 mod appearance;
+mod background;
+mod cmd;
 mod collapse;
+mod collation;
+mod color_mode;
 mod detail_field;
+mod direction;
+mod dirs;
 mod entity;
+mod export;
+mod gfx_protocol;
 mod icon;
+mod icon_set;
+mod icon_source;
+mod key;
+mod key_action;
+mod palette;
+mod pattern_syntax;
 mod perm;
+mod quoting;
+mod role;
+mod size_filter;
 mod sort_field;
+mod spec_pack;
 mod sym;
+mod time_bound;
+mod time_style;
 mod typ;
 mod unit_sys;
 
 pub use appearance::Appearance;
+pub use background::Background;
+pub use cmd::{Cmd, TagAction};
 pub use collapse::Collapse;
+pub use collation::Collation;
+pub use color_mode::ColorMode;
 pub use detail_field::DetailField;
+pub use direction::Direction;
+pub use dirs::Dirs;
 pub use entity::Entity;
+pub use export::{Export, ExportIcons};
+pub use gfx_protocol::GfxProtocol;
 pub use icon::Icon;
+pub use icon_set::IconSet;
+pub use icon_source::IconSource;
+pub use key::Key;
+pub use key_action::KeyAction;
+pub use palette::Palette;
+pub use pattern_syntax::PatternSyntax;
 pub use perm::{Oct, Sym};
+pub use quoting::Quoting;
+pub use role::Role;
+pub use size_filter::SizeFilter;
 pub use sort_field::SortField;
+pub use spec_pack::SpecPack;
 pub use sym::{SymState, SymTarget};
+pub use time_bound::TimeBound;
+pub use time_style::TimeStyle;
 pub use typ::Typ;
 pub use unit_sys::UnitSys;
@@ -0,0 +1,32 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the kinds of terminal backgrounds that the default
+/// styles can be adjusted for.
+///
+/// The background is detected from the terminal, unless given with
+/// `--background`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Background {
+	#[default]
+	Dark, // dark background, for which the default styles are designed
+	Light, // light background, on which yellows are hard to read
+}
+
+impl Background {
+	/// Get the background with the given color, based on its perceived
+	/// brightness.
+	///
+	/// # Arguments
+	///
+	/// * `rgb` - the red, green and blue channels of the color, from 0 to 1
+	pub fn from_rgb(rgb: (f32, f32, f32)) -> Self {
+		let luma = 0.2126 * rgb.0 + 0.7152 * rgb.1 + 0.0722 * rgb.2;
+		if luma > 0.5 {
+			Background::Light
+		} else {
+			Background::Dark
+		}
+	}
+}
@@ -0,0 +1,38 @@
+use clap::Subcommand;
+use std::path::PathBuf;
+
+/// This enum contains the subcommands of `pls`.
+///
+/// When no subcommand is given, `pls` lists the given paths.
+#[derive(Clone, Debug, PartialEq, Eq, Subcommand)]
+pub enum Cmd {
+	/// keep the listings of the configured directories warm and serve them
+	/// to `pls --from-daemon` over a Unix socket
+	Daemon,
+	/// add or remove the tags of nodes, stored in an extended attribute
+	Tag {
+		#[command(subcommand)]
+		action: TagAction,
+	},
+}
+
+/// This enum contains the subcommands of `pls tag`.
+#[derive(Clone, Debug, PartialEq, Eq, Subcommand)]
+pub enum TagAction {
+	/// add the tag to the nodes at the given paths
+	Add {
+		/// the name of the tag
+		name: String,
+		/// the paths of the nodes to tag
+		#[arg(required = true)]
+		paths: Vec<PathBuf>,
+	},
+	/// remove the tag from the nodes at the given paths
+	Remove {
+		/// the name of the tag
+		name: String,
+		/// the paths of the nodes to untag
+		#[arg(required = true)]
+		paths: Vec<PathBuf>,
+	},
+}
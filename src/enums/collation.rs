@@ -0,0 +1,94 @@
+use crate::utils::locale::collation_locale;
+use clap::ValueEnum;
+use icu_collator::{Collator, CollatorOptions};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+thread_local! {
+	/// the collator for the language of the locale, created on first use
+	static COLLATOR: Option<Collator> = new_collator(collation_locale().as_deref());
+}
+
+/// This enum contains the ways in which node names can be compared when
+/// sorting by name.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Collation {
+	#[default]
+	Binary, // by code point, so uppercase letters sort before lowercase ones
+	Nocase, // by code point, ignoring case
+	Locale, // by the conventions of the language of the locale
+}
+
+impl Collation {
+	/// Compare the two given names using this collation.
+	///
+	/// Names that are equal under the collation, like "README" and "readme"
+	/// when ignoring case, are compared by code point, so that the order is
+	/// always the same.
+	pub fn compare(&self, a: &str, b: &str) -> Ordering {
+		let ord = match self {
+			Collation::Binary => Ordering::Equal,
+			Collation::Nocase => a.to_lowercase().cmp(&b.to_lowercase()),
+			Collation::Locale => COLLATOR.with(|collator| {
+				collator
+					.as_ref()
+					.map_or(Ordering::Equal, |collator| collator.compare(a, b))
+			}),
+		};
+		ord.then_with(|| a.cmp(b))
+	}
+}
+
+/// Create a collator for the language with the given BCP-47 tag.
+///
+/// Without a tag, or with a tag that cannot be parsed, the collator uses the
+/// language-neutral order of the Unicode Collation Algorithm.
+fn new_collator(tag: Option<&str>) -> Option<Collator> {
+	let locale = tag.and_then(|tag| tag.parse().ok()).unwrap_or_default();
+	Collator::try_new(&locale, CollatorOptions::new()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{new_collator, Collation};
+	use std::cmp::Ordering;
+
+	macro_rules! make_compare_test {
+		( $($name:ident: $collation:expr, $a:expr, $b:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!($collation.compare($a, $b), $expected);
+				}
+			)*
+		};
+	}
+
+	make_compare_test!(
+		test_binary_upper_first: Collation::Binary, "Zebra", "apple" => Ordering::Less,
+		test_nocase_ignores_case: Collation::Nocase, "Zebra", "apple" => Ordering::Greater,
+		test_nocase_ties_by_code_point: Collation::Nocase, "README", "readme" => Ordering::Less,
+		test_binary_accents: Collation::Binary, "élan", "erde" => Ordering::Greater,
+	);
+
+	macro_rules! make_collator_test {
+		( $($name:ident: $tag:expr, $a:expr, $b:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let collator = new_collator($tag).unwrap();
+					assert_eq!(collator.compare($a, $b), $expected);
+				}
+			)*
+		};
+	}
+
+	make_collator_test!(
+		test_collator_ignores_case: None, "Zebra", "apple" => Ordering::Greater,
+		test_collator_accents: None, "élan", "erde" => Ordering::Less,
+		test_collator_swedish: Some("sv-SE"), "öl", "zebra" => Ordering::Greater,
+		test_collator_german: Some("de-DE"), "öl", "zebra" => Ordering::Less,
+		test_collator_invalid_tag: Some("not a tag"), "b", "a" => Ordering::Greater,
+	);
+}
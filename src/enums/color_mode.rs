@@ -0,0 +1,14 @@
+use clap::ValueEnum;
+
+/// This enum contains the choices for whether the output is styled with
+/// colors and other ANSI escape sequences.
+///
+/// The choice is made with `--color`, and the automatic choice follows the
+/// conventions of `NO_COLOR`, `CLICOLOR` and `CLICOLOR_FORCE`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+	#[default]
+	Auto, // style the output if it goes to a terminal, unless the environment says otherwise
+	Always, // style the output, even if it goes to a file or another program
+	Never,  // write plain text, even to a terminal
+}
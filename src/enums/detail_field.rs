@@ -1,6 +1,7 @@
 use crate::config::AppConst;
 use crate::output::Cell;
 use crate::utils::vectors::dedup;
+use crate::PLS;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::fmt::Alignment;
@@ -39,12 +40,16 @@ static ALL_FIELDS: LazyLock<Vec<DetailField>> = LazyLock::new(|| {
 #[serde(rename_all = "snake_case")]
 pub enum DetailField {
 	Dev,   // device ID
+	Rdev,  // device number of a device node, with its subsystem
 	Ino,   // inode number
 	Nlink, // number of hard links
 	Typ,   // node type
 
-	Perm, // symbolic permissions
-	Oct,  // octal permissions
+	Perm,  // symbolic permissions
+	Oct,   // octal permissions
+	Attrs, // Windows file attributes
+	Xattr, // marker for extended attributes
+	Acl,   // entries of the access control list
 
 	User, // owner user name
 	Uid,  // owner user ID
@@ -52,8 +57,9 @@ pub enum DetailField {
 	Group, // owner group name
 	Gid,   // owner group ID
 
-	Size,   // storage space
-	Blocks, // number of blocks
+	Size,    // storage space
+	SizeRaw, // storage space in exact bytes
+	Blocks,  // number of blocks
 
 	// Uses OS-normalized timestamp field
 	// [`created`](std::fs::Metadata::created).
@@ -72,12 +78,33 @@ pub enum DetailField {
 	// [`accessed`](std::fs::Metadata::accessed).
 	Atime, // accessed at
 
-	Git, // git status
+	Git,       // git status
+	Commits,   // number of commits touching the node
+	GitAuthor, // author of the latest commit touching the node
+	GitDate,   // time of the latest commit touching the node
+
+	CodeOwners, // owners declared in the `CODEOWNERS` file
+	IgnoredBy,  // ignore rule that causes Git to ignore the node
+
+	Marker, // glyph indicating the relative importance
+
+	Comm, // command name of the process, for process directories in `/proc`
+
+	Role, // role in the project, inferred from conventional directory names
+
+	Fix, // commands suggested to fix the risks flagged on the node
 
 	None, // shorthand: no details
 	Std,  // shorthand: the standard set of details
 	All,  // shorthand: all details
 
+	#[clap(skip)]
+	#[serde(skip)]
+	Derived(usize), // column derived from other fields, defined in the config (not a CLI argument)
+	#[clap(skip)]
+	Style, // node style directives, used to paint grid cells (not a CLI argument)
+	#[clap(skip)]
+	Icon, // node icon, exported with `--export-icons` (not a CLI argument)
 	#[clap(skip)]
 	Name, // node name (not a CLI argument)
 }
@@ -127,7 +154,9 @@ impl DetailField {
 			| DetailField::Uid
 			| DetailField::Gid
 			| DetailField::Size
-			| DetailField::Blocks => Alignment::Right,
+			| DetailField::SizeRaw
+			| DetailField::Blocks
+			| DetailField::Commits => Alignment::Right,
 			_ => Alignment::Left,
 		};
 		Cell::new(alignment, (0, 1))
@@ -142,22 +171,79 @@ impl DetailField {
 			self,
 			DetailField::Typ
 				| DetailField::Oct
+				| DetailField::Attrs
 				| DetailField::Btime
 				| DetailField::Ctime
 				| DetailField::Mtime
 				| DetailField::Atime
 				| DetailField::Git
+				| DetailField::GitDate
+		)
+	}
+
+	/// Get whether the field is derived from the Git history of the node.
+	///
+	/// The Git history is expensive to compute, so it is only computed when
+	/// at least one such field is requested.
+	pub fn uses_history(&self) -> bool {
+		matches!(
+			self,
+			DetailField::Commits | DetailField::GitAuthor | DetailField::GitDate
 		)
 	}
 
+	/// Get the unit of the raw values of the field, exported with
+	/// `--raw-values`, or `None` if the field has no raw values.
+	///
+	/// The unit is empty for fields whose values are plain numbers, like IDs.
+	pub fn raw_unit(&self) -> Option<&'static str> {
+		let unit = match self {
+			DetailField::Size | DetailField::SizeRaw => "bytes",
+			DetailField::Blocks => "512 B",
+			DetailField::Ino
+			| DetailField::Nlink
+			| DetailField::Uid
+			| DetailField::Gid
+			| DetailField::Commits => "",
+			DetailField::Btime
+			| DetailField::Ctime
+			| DetailField::Mtime
+			| DetailField::Atime
+			| DetailField::GitDate => "epoch seconds",
+			_ => return None,
+		};
+		Some(unit)
+	}
+
+	/// Get the key of the detail field in structured output, like the rows of
+	/// `--serve` and `--export=nuon`.
+	///
+	/// The key is the name of the field in the config, like `size_raw`, and
+	/// columns derived from other fields use their key in `columns`.
+	pub fn key(&self) -> String {
+		if let DetailField::Derived(idx) = self {
+			return PLS.args.columns[*idx].0.clone();
+		}
+		serde_json::to_value(self)
+			.ok()
+			.and_then(|key| key.as_str().map(String::from))
+			.unwrap_or_default()
+	}
+
 	// ===========
 	// Renderables
 	// ===========
 
 	/// Get the name of the detail field to be used in the column header.
 	///
+	/// Columns derived from other fields are named after their key in the
+	/// `columns` of the config.
+	///
 	/// This function returns a marked-up string.
 	pub fn name(&self, app_const: &AppConst) -> String {
+		if let DetailField::Derived(idx) = self {
+			return PLS.args.columns[*idx].0.clone();
+		}
 		app_const.table.column_names.get(self).cloned().unwrap()
 	}
 }
@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the orders in which the grid view places entries in its
+/// cells.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+	Row,    // across each row before moving to the next row
+	Column, // down each column before moving to the next column, like `ls`
+}
@@ -0,0 +1,12 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the positions of directories relative to other nodes in
+/// a listing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Dirs {
+	First, // directories before all other nodes
+	Last,  // directories after all other nodes
+	Mixed, // directories among other nodes, as per the sort fields
+}
@@ -0,0 +1,85 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the text formats that the detailed view can be exported
+/// to, so that it can be opened in spreadsheets or processed by other programs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Export {
+	Csv,        // comma-separated values, as per RFC 4180
+	Tsv,        // tab-separated values
+	Nuon,       // a table in Nushell Object Notation, with typed values
+	Powershell, // a JSON array of objects, shaped for PowerShell's `ConvertFrom-Json`
+}
+
+/// This enum contains the ways in which the icons of nodes can be included in
+/// exported records.
+///
+/// Icons are exported in their own column, so that the names of the nodes are
+/// not prefixed with font-dependent glyphs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportIcons {
+	None,  // no icons
+	Glyph, // the glyph of the icon, as shown in the terminal
+	Name,  // the name of the icon in the config, like "folder"
+}
+
+impl Export {
+	/// Get the character that separates the fields in a record.
+	pub fn sep(&self) -> char {
+		match self {
+			Export::Csv | Export::Nuon | Export::Powershell => ',',
+			Export::Tsv => '\t',
+		}
+	}
+
+	/// Escape the given text so that it is read as a single field.
+	///
+	/// CSV fields containing separators, quotes or line breaks are wrapped in
+	/// double quotes, with the quotes inside doubled. TSV has no quoting, so
+	/// tabs and line breaks are replaced with spaces instead. NUON and JSON
+	/// strings are always wrapped in double quotes, with quotes, backslashes
+	/// and control characters escaped.
+	pub fn escape(&self, text: &str) -> String {
+		match self {
+			Export::Csv => {
+				if text.contains([',', '"', '\n', '\r']) {
+					format!("\"{}\"", text.replace('"', "\"\""))
+				} else {
+					text.to_string()
+				}
+			}
+			Export::Tsv => text.replace(['\t', '\n', '\r'], " "),
+			Export::Nuon | Export::Powershell => serde_json::to_string(text).unwrap_or_default(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Export;
+
+	macro_rules! make_escape_test {
+		( $($name:ident: $export:expr, $text:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!($export.escape($text), $expected);
+				}
+			)*
+		};
+	}
+
+	make_escape_test!(
+		test_csv_plain: Export::Csv, "main.rs" => "main.rs",
+		test_csv_empty: Export::Csv, "" => "",
+		test_csv_comma: Export::Csv, "a,b" => "\"a,b\"",
+		test_csv_quote: Export::Csv, "say \"hi\"" => "\"say \"\"hi\"\"\"",
+		test_csv_newline: Export::Csv, "a\nb" => "\"a\nb\"",
+		test_tsv_plain: Export::Tsv, "a,b" => "a,b",
+		test_tsv_tab: Export::Tsv, "a\tb\nc" => "a b c",
+		test_nuon_plain: Export::Nuon, "main.rs" => "\"main.rs\"",
+		test_nuon_quote: Export::Nuon, "say \"hi\"\n" => "\"say \\\"hi\\\"\\n\"",
+	);
+}
@@ -0,0 +1,11 @@
+/// This enum contains the terminal graphics protocols that can be used to
+/// render SVG icons.
+///
+/// The Kitty protocol is preferred because the terminal keeps the images and
+/// only needs them to be sent once. Sixel images are sent again every time
+/// they are shown, but are supported by more terminals.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GfxProtocol {
+	Kitty, // Kitty's terminal graphics protocol
+	Sixel, // DEC Sixel graphics, from the VT300 series
+}
@@ -1,7 +1,11 @@
-use crate::gfx::{compute_hash, get_rgba, render_image, send_image};
+use crate::enums::GfxProtocol;
+use crate::gfx::{
+	builtin_icon, compute_hash, get_builtin_rgba, get_raster_rgba, get_rgba, get_thumbnail,
+	render_image, render_sixel, send_image,
+};
 use crate::PLS;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, Mutex};
 
 struct ImageData {
@@ -24,17 +28,29 @@ struct ImageData {
 static IMAGE_DATA: LazyLock<Mutex<HashMap<u32, ImageData>>> =
 	LazyLock::new(|| Mutex::new(HashMap::new()));
 
-/// This enum contains the two formats of icons supported by `pls`.
+/// the Sixel sequences of the images displayed so far, keyed by their hash,
+/// since Sixel images must be sent again every time they are shown
+static SIXEL_DATA: LazyLock<Mutex<HashMap<u32, String>>> =
+	LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// the extensions of the image files that icons can be drawn from
+const IMAGE_EXTS: [&str; 5] = [".svg", ".png", ".jpg", ".jpeg", ".webp"];
+
+/// This enum contains the four formats of icons supported by `pls`.
 pub enum Icon {
 	/// a Nerd Font or emoji icon
 	Text(String),
-	/// the path to an SVG icon
+	/// the path to an SVG, PNG, JPEG or WebP icon
 	Image(String),
+	/// the name of an SVG icon bundled with `pls`
+	Builtin(String),
+	/// the path to a PNG, JPEG or WebP image, previewed in place of its icon
+	Thumbnail(PathBuf),
 }
 
 impl From<&str> for Icon {
 	fn from(s: &str) -> Self {
-		if s.ends_with(".svg") {
+		if Icon::is_image(s) {
 			Icon::Image(s.to_string())
 		} else {
 			Icon::Text(s.to_string())
@@ -43,6 +59,12 @@ impl From<&str> for Icon {
 }
 
 impl Icon {
+	/// Check whether the given icon, from the `icons` section of the config,
+	/// is the path to an image file rather than a glyph.
+	pub fn is_image(icon: &str) -> bool {
+		IMAGE_EXTS.iter().any(|ext| icon.ends_with(ext))
+	}
+
 	/// Get the size of the icon in pixels.
 	///
 	/// The icon size is determined by the width of a cell in the terminal
@@ -54,7 +76,7 @@ impl Icon {
 			.unwrap_or(1.0f32)
 			.min(2.0); // We only allocate two cells for an icon.
 
-		(scale * PLS.window.as_ref().unwrap().cell_width() as f32) // Convert to px.s
+		(scale * PLS.window().unwrap().cell_width() as f32) // Convert to px.s
 			.round() as u8
 	}
 
@@ -62,8 +84,10 @@ impl Icon {
 	///
 	/// * For text icons, it generates the markup string with the
 	///   directives.
-	/// * For image icons, it generates the Kitty terminal graphics APC
-	///   sequence. If that fails, it falls back to a blank text icon.
+	/// * For image icons, bundled icons and thumbnails, it generates the Kitty terminal
+	///   graphics APC sequence, or the Sixel DCS sequence on terminals that
+	///   only support Sixel. If that fails, it falls back to a blank text
+	///   icon.
 	///
 	/// The formatting directives for textual icons are a subset of the
 	/// formatting directives for text.
@@ -86,37 +110,111 @@ impl Icon {
 			}
 
 			Icon::Image(path) => {
-				let default = String::from("  ");
-
-				// SVG icons support expanding environment variables in
+				// Image icons support expanding environment variables in
 				// the path for theming purposes.
 				let path = match shellexpand::env(path) {
-					Ok(path) => path,
-					Err(_) => return default,
+					Ok(path) => PathBuf::from(path.as_ref()),
+					Err(_) => return String::from("  "),
 				};
 
 				let size = Icon::size();
-				let hash = compute_hash(&PathBuf::from(path.as_ref()), size);
-
-				let mut image_data_store = IMAGE_DATA.lock().unwrap();
-				let data = image_data_store
-					.entry(hash)
-					.or_insert_with(|| ImageData { count: 0, id: 0 });
-
-				data.count += 1;
-				if data.count == 1 {
-					// If the image is appearing for the first time in
-					// this session, we send it to the terminal and get
-					// an ID assigned to it.
-					match get_rgba(hash, &PathBuf::from(path.as_ref()), size) {
-						Some(rgba_data) => {
-							data.id = send_image(hash, size, &rgba_data).unwrap();
-						}
-						None => return default,
-					}
+				let hash = compute_hash(&path, size);
+				if path.extension().is_some_and(|ext| ext == "svg") {
+					Icon::draw(hash, size, || get_rgba(hash, &path, size))
+				} else {
+					Icon::draw(hash, size, || get_raster_rgba(hash, &path, size))
+				}
+			}
+
+			Icon::Builtin(name) => {
+				let Some(svg_data) = builtin_icon(name) else {
+					return String::from("  ");
+				};
+
+				let size = Icon::size();
+				let hash = compute_hash(Path::new(&format!("builtin:{name}")), size);
+				Icon::draw(hash, size, || get_builtin_rgba(hash, svg_data, size))
+			}
+
+			Icon::Thumbnail(path) => {
+				let size = Icon::size();
+				let hash = compute_hash(path, size);
+				Icon::draw(hash, size, || get_thumbnail(path, size))
+			}
+		}
+	}
+
+	/// Draw the image with the given hash using the graphics protocol of the
+	/// terminal, falling back to a blank text icon if it cannot be drawn.
+	///
+	/// # Arguments
+	///
+	/// * `hash` - the hash of the image, identifying it across calls
+	/// * `size` - the size of the image, in pixels
+	/// * `get_rgba` - the function that computes the RGBA data of the image,
+	///   only called if the image has not been drawn before
+	fn draw<F>(hash: u32, size: u8, get_rgba: F) -> String
+	where
+		F: FnOnce() -> Option<Vec<u8>>,
+	{
+		let default = String::from("  ");
+
+		if PLS.gfx == Some(GfxProtocol::Sixel) {
+			let mut sixel_data_store = SIXEL_DATA.lock().unwrap();
+			if let Some(sixel) = sixel_data_store.get(&hash) {
+				return sixel.clone();
+			}
+			return match get_rgba() {
+				Some(rgba_data) => {
+					let sixel = render_sixel(&rgba_data, size);
+					sixel_data_store.insert(hash, sixel.clone());
+					sixel
 				}
-				render_image(data.id, size, data.count)
+				None => default,
+			};
+		}
+
+		let mut image_data_store = IMAGE_DATA.lock().unwrap();
+		let data = image_data_store
+			.entry(hash)
+			.or_insert_with(|| ImageData { count: 0, id: 0 });
+
+		data.count += 1;
+		if data.count == 1 {
+			// If the image is appearing for the first time in this session,
+			// we send it to the terminal and get an ID assigned to it.
+			match get_rgba() {
+				Some(rgba_data) => {
+					data.id = send_image(hash, size, &rgba_data).unwrap();
+				}
+				None => return default,
 			}
 		}
+		render_image(data.id, size, data.count)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Icon;
+
+	macro_rules! make_is_image_test {
+		( $($name:ident: $icon:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Icon::is_image($icon), $expected);
+				}
+			)*
+		};
 	}
+
+	make_is_image_test!(
+		test_glyph: "\u{f07b}" => false,
+		test_svg: "~/icons/rust.svg" => true,
+		test_png: "$ICONS/folder.png" => true,
+		test_jpeg: "~/icons/photo.jpg" => true,
+		test_webp: "folder.webp" => true,
+		test_svg_name: "svg" => false,
+	);
 }
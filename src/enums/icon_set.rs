@@ -0,0 +1,16 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the sets of glyphs that icons can be drawn from.
+///
+/// The Nerd Font set needs a patched font, so the emoji set is offered for
+/// terminals without one. The set only affects textual icons; SVG icons are
+/// drawn by the terminal regardless of the font.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum IconSet {
+	#[default]
+	Nerd, // glyphs from Nerd Fonts, from the `icons` section
+	Emoji, // plain emoji, from the `emoji_icons` section
+	None,  // no icons at all
+}
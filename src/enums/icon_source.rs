@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the sources that the icons of terminals with graphics
+/// support can be drawn from.
+///
+/// The built-in source draws the SVG icons bundled with `pls`, so that such
+/// terminals show icons without a Nerd Font or any SVG files on disk.
+/// Terminals without graphics support always draw the glyphs of the icon set.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IconSource {
+	#[default]
+	Glyph, // the glyphs of the icon set, or SVG files from the `icons` section
+	Builtin, // the SVG icons bundled with `pls`, where one exists
+}
@@ -0,0 +1,136 @@
+/// the byte that starts escape sequences, and is sent alone by the Esc key
+const ESC: u8 = 0x1b;
+
+/// This enum contains the keys that can be read from a terminal in raw mode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Key {
+	Char(char), // a printable character
+	Enter,      // Enter or Return
+	Esc,        // Escape
+	Backspace,  // Backspace or Delete, as sent by most terminals
+	Tab,        // Tab
+	Ctrl(char), // a letter pressed with Ctrl
+	Unknown,    // any other key, like the arrows or function keys
+}
+
+impl Key {
+	/// Get the keys whose bytes were read from the terminal.
+	///
+	/// Keys like the arrows send escape sequences, which are read as one
+	/// [`Key::Unknown`] so that their bytes are not mistaken for characters.
+	/// An Escape followed by anything other than a sequence is read as the
+	/// Esc key, followed by the keys after it.
+	///
+	/// # Arguments
+	///
+	/// * `bytes` - the bytes read from the terminal in one go
+	pub fn parse(bytes: &[u8]) -> Vec<Self> {
+		let mut keys = vec![];
+		let mut rest = bytes;
+		while let Some((&byte, tail)) = rest.split_first() {
+			let (key, len) = match byte {
+				ESC => match tail.first() {
+					Some(b'[' | b'O') => {
+						// The sequence ends with the first byte in `@` to `~`.
+						let end = tail[1..]
+							.iter()
+							.position(|byte| (0x40..=0x7e).contains(byte))
+							.map_or(tail.len(), |idx| idx + 2);
+						(Key::Unknown, 1 + end)
+					}
+					_ => (Key::Esc, 1),
+				},
+				b'\r' | b'\n' => (Key::Enter, 1),
+				b'\t' => (Key::Tab, 1),
+				0x7f | 0x08 => (Key::Backspace, 1),
+				0x01..=0x1a => (Key::Ctrl(char::from(b'a' + byte - 1)), 1),
+				0x00 | 0x1c..=0x1f => (Key::Unknown, 1),
+				_ => {
+					let len = match byte {
+						0xc0..=0xdf => 2,
+						0xe0..=0xef => 3,
+						0xf0..=0xf7 => 4,
+						_ => 1,
+					};
+					let ch = rest
+						.get(..len)
+						.and_then(|bytes| std::str::from_utf8(bytes).ok())
+						.and_then(|text| text.chars().next());
+					match ch {
+						Some(ch) => (Key::Char(ch), len),
+						None => (Key::Unknown, 1),
+					}
+				}
+			};
+			keys.push(key);
+			rest = &rest[len.min(rest.len())..];
+		}
+		keys
+	}
+
+	/// Get the name of the key, as used in the `keys` map of
+	/// [`AppConst::interactive`](crate::config::AppConst).
+	///
+	/// Unknown keys have an empty name, so that they cannot be bound.
+	pub fn name(&self) -> String {
+		match self {
+			Key::Char(ch) => ch.to_string(),
+			Key::Enter => String::from("enter"),
+			Key::Esc => String::from("esc"),
+			Key::Backspace => String::from("backspace"),
+			Key::Tab => String::from("tab"),
+			Key::Ctrl(ch) => format!("ctrl-{ch}"),
+			Key::Unknown => String::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Key;
+
+	macro_rules! make_parse_test {
+		( $($name:ident: $bytes:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Key::parse($bytes), $expected);
+				}
+			)*
+		};
+	}
+
+	make_parse_test!(
+		test_parse_chars: b"s." => vec![Key::Char('s'), Key::Char('.')],
+		test_parse_multibyte: "é".as_bytes() => vec![Key::Char('é')],
+		test_parse_enter: b"\r" => vec![Key::Enter],
+		test_parse_backspace: b"\x7f" => vec![Key::Backspace],
+		test_parse_tab: b"\t" => vec![Key::Tab],
+		test_parse_ctrl_c: b"\x03" => vec![Key::Ctrl('c')],
+		test_parse_lone_esc: b"\x1b" => vec![Key::Esc],
+		test_parse_esc_then_char: b"\x1bq" => vec![Key::Esc, Key::Char('q')],
+		test_parse_arrow: b"\x1b[Aq" => vec![Key::Unknown, Key::Char('q')],
+		test_parse_ss3_arrow: b"\x1bOB" => vec![Key::Unknown],
+		test_parse_modified_arrow: b"\x1b[1;5C/" => vec![Key::Unknown, Key::Char('/')],
+		test_parse_cut_sequence: b"\x1b[1;" => vec![Key::Unknown],
+		test_parse_invalid_utf8: b"\xffa" => vec![Key::Unknown, Key::Char('a')],
+	);
+
+	macro_rules! make_name_test {
+		( $($name:ident: $key:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!($key.name(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_name_test!(
+		test_name_char: Key::Char('/') => "/",
+		test_name_enter: Key::Enter => "enter",
+		test_name_ctrl: Key::Ctrl('r') => "ctrl-r",
+		test_name_unknown: Key::Unknown => "",
+	);
+}
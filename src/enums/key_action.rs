@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the actions that keys can be bound to in interactive
+/// mode, with the `keys` map in [`AppConst::interactive`](crate::config::AppConst).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+	Filter,       // type a pattern that node names must match
+	CycleSort,    // sort by the next field in the sort cycle
+	ToggleHidden, // show or hide the nodes below the importance cutoff
+	ToggleIcons,  // show or hide icons
+	Quit,         // leave interactive mode
+}
+
+impl KeyAction {
+	/// Get the short label of the action, shown in the key hints.
+	pub fn label(&self) -> &'static str {
+		match self {
+			KeyAction::Filter => "filter",
+			KeyAction::CycleSort => "sort",
+			KeyAction::ToggleHidden => "hidden",
+			KeyAction::ToggleIcons => "icons",
+			KeyAction::Quit => "quit",
+		}
+	}
+}
@@ -0,0 +1,151 @@
+use crate::config::EntryConst;
+use crate::enums::{Oct, Sym, SymState};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the built-in color palettes.
+///
+/// Palettes other than the default one are friendly to people with the
+/// corresponding type of color blindness. Instead of asking users to redefine
+/// every style, a palette remaps the semantic styles, like permission bits
+/// and symlink states, to colors that remain distinguishable.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+	#[default]
+	Default, // styles as configured
+	Deuteranopia, // safe for reduced sensitivity to green
+	Protanopia,   // safe for reduced sensitivity to red
+	Tritanopia,   // safe for reduced sensitivity to blue
+}
+
+/// Represents the colors that a palette assigns to each semantic role.
+struct Swatch {
+	/// for read permissions
+	read: &'static str,
+	/// for write permissions
+	write: &'static str,
+	/// for execute permissions and owner bits
+	execute: &'static str,
+	/// for special permissions
+	special: &'static str,
+	/// for healthy states, like valid symlinks and recent commits
+	good: &'static str,
+	/// for cautionary states, like cyclic symlinks
+	warn: &'static str,
+	/// for broken states, like missing nodes and broken symlinks
+	bad: &'static str,
+}
+
+impl Palette {
+	/// Remap the semantic styles in the given entry constants to the colors of
+	/// this palette.
+	///
+	/// The default palette leaves the styles untouched. Other palettes replace
+	/// the semantic styles, including any overrides from the config files.
+	pub fn apply(&self, entry_const: &mut EntryConst) {
+		let Some(swatch) = self.swatch() else {
+			return;
+		};
+
+		for (sym, style) in [
+			(Sym::Read, swatch.read),
+			(Sym::Write, swatch.write),
+			(Sym::Execute, swatch.execute),
+			(Sym::Special, swatch.special),
+		] {
+			entry_const.perm_styles.insert(sym, style.to_string());
+		}
+
+		for (oct, style) in [
+			(Oct::Special, swatch.special.to_string()),
+			(Oct::User, swatch.execute.to_string()),
+			(Oct::Group, format!("{} dimmed", swatch.execute)),
+		] {
+			entry_const.oct_styles.insert(oct, style);
+		}
+
+		for (state, style) in [
+			(SymState::Ok, swatch.good),
+			(SymState::Broken, swatch.bad),
+			(SymState::Cyclic, swatch.warn),
+			(SymState::Error, swatch.bad),
+		] {
+			if let Some(info) = entry_const.symlink.get_mut(&state) {
+				info.style = style.to_string();
+			}
+		}
+
+		entry_const.missing_style = format!("{} strikethrough", swatch.bad);
+		entry_const.age_styles = vec![
+			(0, format!("{} bold", swatch.good)),
+			(7, swatch.good.to_string()),
+			(30, swatch.warn.to_string()),
+			(365, String::from("dimmed")),
+		];
+	}
+
+	/// Get the colors of the palette, or `None` for the default palette.
+	///
+	/// The colors are drawn from the Okabe-Ito palette, which is designed to
+	/// be distinguishable with all common forms of color blindness.
+	fn swatch(&self) -> Option<Swatch> {
+		match self {
+			Palette::Default => None,
+			Palette::Deuteranopia => Some(Swatch {
+				read: "rgb(240,228,66)",
+				write: "rgb(213,94,0)",
+				execute: "rgb(0,114,178)",
+				special: "rgb(204,121,167)",
+				good: "rgb(86,180,233)",
+				warn: "rgb(240,228,66)",
+				bad: "rgb(213,94,0)",
+			}),
+			Palette::Protanopia => Some(Swatch {
+				read: "rgb(240,228,66)",
+				write: "rgb(230,159,0)",
+				execute: "rgb(0,114,178)",
+				special: "rgb(86,180,233)",
+				good: "rgb(86,180,233)",
+				warn: "rgb(240,228,66)",
+				bad: "rgb(230,159,0)",
+			}),
+			Palette::Tritanopia => Some(Swatch {
+				read: "rgb(0,158,115)",
+				write: "rgb(213,94,0)",
+				execute: "rgb(204,121,167)",
+				special: "rgb(230,159,0)",
+				good: "rgb(0,158,115)",
+				warn: "rgb(230,159,0)",
+				bad: "rgb(213,94,0)",
+			}),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Palette;
+	use crate::config::EntryConst;
+	use crate::enums::{Sym, SymState};
+
+	#[test]
+	fn test_default_palette_keeps_styles() {
+		let mut entry_const = EntryConst::default();
+		Palette::Default.apply(&mut entry_const);
+		assert_eq!(entry_const.perm_styles[&Sym::Write], "red");
+		assert_eq!(entry_const.missing_style, "red strikethrough");
+	}
+
+	#[test]
+	fn test_palette_remaps_semantic_styles() {
+		let mut entry_const = EntryConst::default();
+		Palette::Deuteranopia.apply(&mut entry_const);
+		assert_eq!(entry_const.perm_styles[&Sym::Execute], "rgb(0,114,178)");
+		assert_eq!(
+			entry_const.symlink[&SymState::Broken].style,
+			"rgb(213,94,0)"
+		);
+		assert_eq!(entry_const.missing_style, "rgb(213,94,0) strikethrough");
+	}
+}
@@ -0,0 +1,12 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the syntaxes in which patterns for node names can be
+/// written, both on the command line and in specs.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternSyntax {
+	#[default]
+	Regex, // regular expressions, matching anywhere in the name
+	Glob, // shell-style globs, like `*.rs`, matching the whole name
+}
@@ -0,0 +1,65 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the shell dialects that paths can be quoted for, so that
+/// they can be spliced safely into commands like `cp` and `mv`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Quoting {
+	Posix, // POSIX shells like `sh`, `bash` and `zsh`
+	Fish,  // the fish shell
+}
+
+impl Quoting {
+	/// Quote the given text so that the shell reads it as a single word.
+	///
+	/// Text consisting only of characters that have no special meaning to the
+	/// shell is returned as-is. Other text is wrapped in single quotes, with
+	/// the characters that are special inside single quotes escaped.
+	pub fn quote(&self, text: &str) -> String {
+		let is_safe = !text.is_empty()
+			&& text
+				.chars()
+				.all(|ch| ch.is_ascii_alphanumeric() || "_@%+=:,./-".contains(ch));
+		if is_safe {
+			return text.to_string();
+		}
+
+		let escaped = match self {
+			// POSIX single quotes cannot contain a single quote, so we close
+			// the quotes, add an escaped quote and reopen them.
+			Quoting::Posix => text.replace('\'', r"'\''"),
+			// Fish allows escaping backslashes and single quotes inside them.
+			Quoting::Fish => text.replace('\\', r"\\").replace('\'', r"\'"),
+		};
+		format!("'{escaped}'")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Quoting;
+
+	macro_rules! make_quote_test {
+		( $($name:ident: $quoting:expr, $text:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!($quoting.quote($text), $expected);
+				}
+			)*
+		};
+	}
+
+	make_quote_test!(
+		test_posix_safe: Quoting::Posix, "src/main.rs" => "src/main.rs",
+		test_posix_empty: Quoting::Posix, "" => "''",
+		test_posix_space: Quoting::Posix, "my file" => "'my file'",
+		test_posix_quote: Quoting::Posix, "it's" => r"'it'\''s'",
+		test_posix_backslash: Quoting::Posix, r"a\b" => r"'a\b'",
+		test_posix_glob: Quoting::Posix, "*.rs" => "'*.rs'",
+		test_fish_safe: Quoting::Fish, "a-b_c" => "a-b_c",
+		test_fish_quote: Quoting::Fish, "it's" => r"'it\'s'",
+		test_fish_backslash: Quoting::Fish, r"a\b" => r"'a\\b'",
+	);
+}
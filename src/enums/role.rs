@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// This enum contains the roles that directories play in a project, inferred
+/// from the names that projects conventionally give to such directories.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+	Tests,    // automated tests and their fixtures
+	Docs,     // documentation
+	Examples, // examples and demos of usage
+	Benches,  // benchmarks
+	Assets,   // images, fonts and other static files
+}
+
+impl Display for Role {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		let name = match self {
+			Role::Tests => "tests",
+			Role::Docs => "docs",
+			Role::Examples => "examples",
+			Role::Benches => "benches",
+			Role::Assets => "assets",
+		};
+		write!(f, "{name}")
+	}
+}
+
+impl Role {
+	/// Get the role of the directory with the given name, if the name is one
+	/// that projects conventionally use for that role.
+	///
+	/// Names are matched case-insensitively.
+	pub fn from_dir_name(name: &str) -> Option<Self> {
+		let role = match name.to_lowercase().as_str() {
+			"test" | "tests" | "__tests__" | "spec" | "specs" | "testing" | "e2e" | "fixtures" => {
+				Role::Tests
+			}
+			"doc" | "docs" | "documentation" | "man" | "manual" => Role::Docs,
+			"example" | "examples" | "sample" | "samples" | "demo" | "demos" => Role::Examples,
+			"bench" | "benches" | "benchmark" | "benchmarks" => Role::Benches,
+			"asset" | "assets" | "static" | "public" | "images" | "img" | "media" | "fonts" => {
+				Role::Assets
+			}
+			_ => return None,
+		};
+		Some(role)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Role;
+
+	macro_rules! make_role_test {
+		( $($name:ident: $dir_name:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Role::from_dir_name($dir_name), $expected);
+				}
+			)*
+		};
+	}
+
+	make_role_test!(
+		test_tests: "tests" => Some(Role::Tests),
+		test_jest_tests: "__tests__" => Some(Role::Tests),
+		test_docs_mixed_case: "Docs" => Some(Role::Docs),
+		test_examples: "examples" => Some(Role::Examples),
+		test_benches: "benches" => Some(Role::Benches),
+		test_assets: "static" => Some(Role::Assets),
+		test_source: "src" => None,
+		test_partial_name: "testsuite" => None,
+	);
+}
@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+/// the prefixes of the binary units accepted in sizes, in increasing order of
+/// magnitude, starting with bytes
+const PREFIXES: [&str; 6] = ["", "k", "m", "g", "t", "p"];
+
+/// This enum contains the bounds that can be placed on the sizes of nodes with
+/// the `--size` filter.
+///
+/// The bounds are parsed from strings like ">1M" or "<100K", in which the
+/// units are binary, so "1M" is 1,048,576 bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SizeFilter {
+	Above(u64), // larger than the given number of bytes
+	Below(u64), // smaller than the given number of bytes
+}
+
+impl FromStr for SizeFilter {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		let (ctor, size): (fn(u64) -> Self, _) = if let Some(size) = s.strip_prefix('>') {
+			(SizeFilter::Above, size)
+		} else if let Some(size) = s.strip_prefix('<') {
+			(SizeFilter::Below, size)
+		} else {
+			return Err(String::from("size must start with '>' or '<', like '>1M'"));
+		};
+		parse_size(size.trim()).map(ctor)
+	}
+}
+
+impl SizeFilter {
+	/// Determine whether the given size is within this bound.
+	pub fn matches(&self, size: u64) -> bool {
+		match self {
+			SizeFilter::Above(bound) => size > *bound,
+			SizeFilter::Below(bound) => size < *bound,
+		}
+	}
+}
+
+/// Parse a size, like "100K" or "1.5G", into a number of bytes.
+///
+/// The unit is case-insensitive and may be written in full, like "KiB" or
+/// "KB", both of which mean 1,024 bytes. Without a unit, the size is in bytes.
+fn parse_size(size: &str) -> Result<u64, String> {
+	let split = size
+		.find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+		.unwrap_or(size.len());
+	let (num, unit) = size.split_at(split);
+	let num: f64 = num
+		.parse()
+		.map_err(|_| format!("'{size}' does not start with a number"))?;
+
+	let unit = unit.trim().to_lowercase();
+	let prefix = unit
+		.strip_suffix('b')
+		.unwrap_or(&unit)
+		.trim_end_matches('i');
+	let exp = PREFIXES
+		.iter()
+		.position(|known| *known == prefix)
+		.ok_or_else(|| format!("'{unit}' is not a known unit, like K, M or G"))?;
+	Ok((num * 1024_f64.powi(exp as i32)) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SizeFilter;
+	use std::str::FromStr;
+
+	macro_rules! make_parse_test {
+		( $($name:ident: $input:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(SizeFilter::from_str($input).ok(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_parse_test!(
+		test_above_megabytes: ">1M" => Some(SizeFilter::Above(1_048_576)),
+		test_below_kilobytes: "<100K" => Some(SizeFilter::Below(102_400)),
+		test_bytes: ">512" => Some(SizeFilter::Above(512)),
+		test_explicit_bytes: "<2B" => Some(SizeFilter::Below(2)),
+		test_fraction: ">1.5G" => Some(SizeFilter::Above(1_610_612_736)),
+		test_full_unit: "> 2 KiB" => Some(SizeFilter::Above(2_048)),
+		test_lowercase_unit: "<3mb" => Some(SizeFilter::Below(3_145_728)),
+		test_missing_comparison: "1M" => None,
+		test_missing_number: ">M" => None,
+		test_unknown_unit: ">1X" => None,
+	);
+
+	macro_rules! make_matches_test {
+		( $($name:ident: $filter:expr, $size:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!($filter.matches($size), $expected);
+				}
+			)*
+		};
+	}
+
+	make_matches_test!(
+		test_above_larger: SizeFilter::Above(10), 11 => true,
+		test_above_equal: SizeFilter::Above(10), 10 => false,
+		test_below_smaller: SizeFilter::Below(10), 9 => true,
+		test_below_equal: SizeFilter::Below(10), 10 => false,
+	);
+}
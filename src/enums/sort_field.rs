@@ -1,13 +1,13 @@
-use crate::enums::DetailField;
+use crate::enums::{DetailField, SymState, SymTarget, Typ};
+use crate::ext::PlatformMeta;
 use crate::models::{Node, OwnerMan};
-use crate::traits::{Detail, Name};
+use crate::traits::{Detail, Name, Sym};
+use crate::PLS;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter, Result as FmtResult};
-#[cfg(unix)]
-use std::os::unix::fs::MetadataExt;
 
 /// This enum contains all the different ways two nodes can be compared to
 /// determine the sorting order.
@@ -26,6 +26,7 @@ use std::os::unix::fs::MetadataExt;
 #[serde(rename_all = "snake_case")]
 pub enum SortField {
 	Dev,   // device ID
+	Rdev,  // device number of a device node
 	Ino,   // inode number
 	Nlink, // number of hard links
 	Typ,   // node type
@@ -45,8 +46,8 @@ pub enum SortField {
 	Btime, // created at
 
 	// Uses Unix-specific extension fields
-	// [`ctime`](MetadataExt::ctime) and
-	// [`ctime_nsec`](MetadataExt::ctime_nsec).
+	// [`ctime`](std::os::unix::fs::MetadataExt::ctime) and
+	// [`ctime_nsec`](std::os::unix::fs::MetadataExt::ctime_nsec).
 	Ctime, // changed at
 
 	// Uses OS-normalised timestamp field
@@ -61,7 +62,11 @@ pub enum SortField {
 	Cname, // canonical name (name in lower case with leading symbols stripped)
 	Ext,   // file extension
 
+	Sym, // symlink state and target
+
 	// Reversed sort by the field
+	#[clap(name = "rdev_")]
+	Rdev_,
 	#[clap(name = "inode_")]
 	Inode_,
 	#[clap(name = "nlinks_")]
@@ -96,6 +101,8 @@ pub enum SortField {
 	Cname_,
 	#[clap(name = "ext_")]
 	Ext_,
+	#[clap(name = "sym_")]
+	Sym_,
 
 	None, // shorthand: no sorting
 }
@@ -140,6 +147,19 @@ impl SortField {
 		cleaned
 	}
 
+	/// Get the section in which this field places a node of the given type,
+	/// if the field places nodes in sections.
+	///
+	/// Sorting by category places directories and files in two sections, and
+	/// sorting by type places each type in a section of its own.
+	pub fn section(&self, typ: Typ) -> Option<Typ> {
+		match self {
+			SortField::Cat | SortField::Cat_ => Some(typ.cat()),
+			SortField::Typ | SortField::Typ_ => Some(typ),
+			_ => None,
+		}
+	}
+
 	/// Compare the two given nodes, using this sort field.
 	///
 	/// This function handles reverse sort fields, the fields suffixed with '_',
@@ -149,8 +169,13 @@ impl SortField {
 
 		let ord = basis
 			.compare_no_meta(a, b, owner_man)
-			.or_else(|| basis.compare_meta(a, b))
-			.or_else(|| basis.compare_time(a, b))
+			.or_else(|| {
+				if basis.is_time() {
+					basis.compare_time(a, b)
+				} else {
+					basis.compare_meta(a, b)
+				}
+			})
 			.unwrap_or(Ordering::Equal);
 
 		if is_reverse {
@@ -183,19 +208,35 @@ impl SortField {
 		}
 	}
 
+	/// Check whether this field is one of the timestamp fields, which are
+	/// compared by [`SortField::compare_time`].
+	fn is_time(&self) -> bool {
+		matches!(
+			self,
+			SortField::Btime | SortField::Ctime | SortField::Mtime | SortField::Atime
+		)
+	}
+
 	/// Compare the two given nodes based on a non-metadata field.
 	///
 	/// This function can perform comparisons based on fields that do not need
 	/// metadata at all, or account for the `meta` field being `Err`.
 	fn compare_no_meta(&self, a: &Node, b: &Node, owner_man: &mut OwnerMan) -> Option<Ordering> {
 		let ord = match self {
-			SortField::Name => a.name.cmp(&b.name),
+			SortField::Name => PLS.args.collation.compare(&a.name, &b.name),
 			SortField::Cname => a.cname().cmp(&b.cname()),
 			SortField::Ext => a.ext().cmp(&b.ext()),
 			SortField::Typ => a.typ.cmp(&b.typ),
 			SortField::Cat => a.typ.cat().cmp(&b.typ.cat()),
+			SortField::Rdev => a.rdev_val().cmp(&b.rdev_val()),
 			SortField::User => a.user_val(owner_man).cmp(&b.user_val(owner_man)),
 			SortField::Group => a.group_val(owner_man).cmp(&b.group_val(owner_man)),
+			SortField::Sym => sym_key(a).cmp(&sym_key(b)),
+			// Nodes inside archives only have the sizes from the archive headers,
+			// and directories only have sizes with `--du`.
+			SortField::Size if PLS.args.du || a.archived.is_some() || b.archived.is_some() => {
+				a.size_val().cmp(&b.size_val())
+			}
 			_ => return None,
 		};
 		Some(ord)
@@ -208,13 +249,13 @@ impl SortField {
 		match (&a.meta_ok(), &b.meta_ok()) {
 			(Some(a), Some(b)) => {
 				let ord = match self {
-					SortField::Dev => a.dev().cmp(&b.dev()),
-					SortField::Ino => a.ino().cmp(&b.ino()),
-					SortField::Nlink => a.nlink().cmp(&b.nlink()),
-					SortField::Uid => a.uid().cmp(&b.uid()),
-					SortField::Gid => a.gid().cmp(&b.gid()),
-					SortField::Size => a.size().cmp(&b.size()),
-					SortField::Blocks => a.blocks().cmp(&b.blocks()),
+					SortField::Dev => a.dev_id().cmp(&b.dev_id()),
+					SortField::Ino => a.ino_num().cmp(&b.ino_num()),
+					SortField::Nlink => a.nlink_count().cmp(&b.nlink_count()),
+					SortField::Uid => a.owner_uid().cmp(&b.owner_uid()),
+					SortField::Gid => a.owner_gid().cmp(&b.owner_gid()),
+					SortField::Size => a.len().cmp(&b.len()),
+					SortField::Blocks => a.block_count().cmp(&b.block_count()),
 					_ => return None,
 				};
 				Some(ord)
@@ -244,9 +285,29 @@ impl SortField {
 	}
 }
 
+/// Get the key used to sort the given node by its symlink state and target.
+///
+/// Symlinks are ordered by their [`SymState`], in the order of declaration,
+/// and then by their target paths, as written in the link. Nodes that are not
+/// symlinks are placed after all symlinks.
+fn sym_key(node: &Node) -> (bool, Option<SymState>, Option<String>) {
+	let Some(target) = node.target() else {
+		return (true, None, None);
+	};
+	let path = match &target {
+		SymTarget::Ok(node) => Some(node.display_name.clone()),
+		SymTarget::Broken(path) | SymTarget::Cyclic(path) => {
+			Some(path.to_string_lossy().to_string())
+		}
+		SymTarget::Error(_) => None,
+	};
+	(false, Some((&target).into()), path)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::SortField;
+	use crate::enums::Typ;
 
 	macro_rules! make_clean_test {
 		( $($name:ident: $input:expr => $expected:expr,)* ) => {
@@ -268,4 +329,22 @@ mod tests {
 			SortField::User,
 		],
 	);
+
+	macro_rules! make_section_test {
+		( $($name:ident: $field:expr, $typ:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!($field.section($typ), $expected);
+				}
+			)*
+		};
+	}
+
+	make_section_test!(
+		test_cat_section_of_dir: SortField::Cat, Typ::Dir => Some(Typ::Dir),
+		test_cat_section_of_symlink: SortField::Cat_, Typ::Symlink => Some(Typ::File),
+		test_typ_section_of_symlink: SortField::Typ, Typ::Symlink => Some(Typ::Symlink),
+		test_name_has_no_sections: SortField::Name, Typ::File => None,
+	);
 }
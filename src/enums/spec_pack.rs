@@ -0,0 +1,102 @@
+use crate::models::Spec;
+use crate::utils::procfs::is_pid;
+use crate::PLS;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// This enum contains the built-in packs of specs that only apply to specific
+/// directories, and can be turned on or off in the config.
+///
+/// Unlike regular specs, which apply everywhere, a pack is only added to the
+/// specs of the directories it is meant for. This allows it to make sweeping
+/// changes, like demoting every node, without affecting other directories.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecPack {
+	Procfs, // the `/proc` file system on Linux
+	Devfs,  // the `/dev` directory of device nodes
+}
+
+impl SpecPack {
+	/// Determine whether the pack applies to the given absolute directory.
+	///
+	/// The `procfs` pack applies to `/proc` and to the `task` directories of
+	/// processes, which list the threads of the process. The `devfs` pack
+	/// applies to `/dev` and its subdirectories, and everywhere with `--devfs`.
+	pub fn applies_to(&self, dir: &Path) -> bool {
+		match self {
+			SpecPack::Devfs => PLS.args.devfs || dir.starts_with("/dev"),
+			SpecPack::Procfs => {
+				dir == Path::new("/proc")
+					|| (dir.file_name().is_some_and(|name| name == "task")
+						&& dir.parent().is_some_and(|par| {
+							par.parent() == Some(Path::new("/proc"))
+								&& par
+									.file_name()
+									.and_then(|name| name.to_str())
+									.is_some_and(is_pid)
+						}))
+			}
+		}
+	}
+
+	/// Get the specs in the pack, in ascending order of specificity.
+	pub fn specs(&self) -> Vec<Spec> {
+		match self {
+			SpecPack::Procfs => vec![
+				// Entries that are not processes, like `cpuinfo` and `self`
+				Spec {
+					icons: None,
+					..Spec::new(r"[^0-9]", "")
+				}
+				.importance(-1),
+				Spec::new(r"^[0-9]+$", "process"),
+			],
+			SpecPack::Devfs => vec![
+				Spec::new(r"^(tty|pts$|ptmx$|console$)", "shell").style("yellow"),
+				Spec::new(
+					r"^(sd[a-z]|hd[a-z]|vd[a-z]|xvd[a-z]|nvme[0-9]|mmcblk[0-9]|loop[0-9]|dm-|md[0-9]|sr[0-9])",
+					"block_device",
+				)
+				.style("blue"),
+				Spec {
+					icons: None,
+					..Spec::new(r"^(null|zero|full|random|urandom)$", "")
+				}
+				.style("dimmed italic"),
+			],
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SpecPack;
+	use std::path::Path;
+
+	macro_rules! make_applies_to_test {
+		( $($name:ident: $dir:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(SpecPack::Procfs.applies_to(Path::new($dir)), $expected);
+				}
+			)*
+		};
+	}
+
+	make_applies_to_test!(
+		test_procfs_root: "/proc" => true,
+		test_procfs_task: "/proc/42/task" => true,
+		test_procfs_process: "/proc/42" => false,
+		test_procfs_non_pid_task: "/proc/self/task" => false,
+		test_procfs_elsewhere: "/home/proc" => false,
+	);
+
+	#[test]
+	fn test_devfs_applies_to_dev() {
+		assert!(SpecPack::Devfs.applies_to(Path::new("/dev")));
+		assert!(SpecPack::Devfs.applies_to(Path::new("/dev/disk/by-id")));
+		assert!(!SpecPack::Devfs.applies_to(Path::new("/device")));
+	}
+}
@@ -8,8 +8,8 @@ use std::path::PathBuf;
 /// fine and the rest are problematic.
 ///
 /// This enum is a unitary enum intended only for use as a `HashMap` key when
-/// defining the constants in the config.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// defining the constants in the config, and for sorting symlinks by state.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SymState {
 	Ok,
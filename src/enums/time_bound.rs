@@ -0,0 +1,163 @@
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use time::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+/// the units accepted in durations, paired with their length in seconds
+const UNITS: [(char, u64); 5] = [
+	('s', 1),
+	('m', 60),
+	('h', 60 * 60),
+	('d', 24 * 60 * 60),
+	('w', 7 * 24 * 60 * 60),
+];
+
+/// This enum contains the points in time that can be given to the
+/// `--newer-than` and `--older-than` filters.
+///
+/// The points are parsed either from durations like "2d" or "1h30m", which
+/// count back from the current time, or from local dates like "2024-01-31" or
+/// "2024-01-31 18:30".
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimeBound {
+	Ago(Duration),         // the given duration before the current time
+	At(PrimitiveDateTime), // the given date and time, in the local time zone
+}
+
+impl FromStr for TimeBound {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if s.chars().nth(4) == Some('-') {
+			parse_date(s).map(TimeBound::At)
+		} else {
+			parse_duration(s).map(TimeBound::Ago)
+		}
+	}
+}
+
+impl TimeBound {
+	/// Get the instant that this bound refers to.
+	///
+	/// # Arguments
+	///
+	/// * `now` - the current time, from which durations count back
+	/// * `offset` - the offset of the local time zone, in which dates are read
+	pub fn instant(&self, now: SystemTime, offset: UtcOffset) -> SystemTime {
+		match self {
+			TimeBound::Ago(duration) => now.checked_sub(*duration).unwrap_or(UNIX_EPOCH),
+			TimeBound::At(dt) => {
+				let dt: OffsetDateTime = dt.assume_offset(offset);
+				dt.into()
+			}
+		}
+	}
+}
+
+/// Parse a duration, like "2d" or "1h30m", into a `Duration`.
+///
+/// A duration is a sequence of whole numbers, each followed by one of the
+/// units `s`, `m`, `h`, `d` or `w`.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+	let invalid = || format!("'{s}' is neither a duration like '2d' nor a date like '2024-01-31'");
+	let mut secs = 0;
+	let mut num = String::new();
+	for ch in s.chars() {
+		if ch.is_ascii_digit() {
+			num.push(ch);
+			continue;
+		}
+		let (_, len) = UNITS
+			.iter()
+			.find(|(unit, _)| *unit == ch.to_ascii_lowercase())
+			.ok_or_else(invalid)?;
+		let count: u64 = num.parse().map_err(|_| invalid())?;
+		secs += count * len;
+		num.clear();
+	}
+	if s.is_empty() || !num.is_empty() {
+		return Err(invalid());
+	}
+	Ok(Duration::from_secs(secs))
+}
+
+/// Parse a date, optionally followed by a time, like "2024-01-31" or
+/// "2024-01-31T18:30:00", into a `PrimitiveDateTime`.
+///
+/// Dates without a time refer to the start of the day.
+fn parse_date(s: &str) -> Result<PrimitiveDateTime, String> {
+	let invalid = |err| format!("'{s}' is not a valid date: {err}");
+	let s = s.replacen('T', " ", 1);
+	let format = match s.len() {
+		10 => "[year]-[month]-[day]",
+		16 => "[year]-[month]-[day] [hour]:[minute]",
+		_ => "[year]-[month]-[day] [hour]:[minute]:[second]",
+	};
+	let format = format_description::parse_borrowed::<2>(format).unwrap();
+	if s.len() == 10 {
+		Date::parse(&s, &format)
+			.map(Date::midnight)
+			.map_err(invalid)
+	} else {
+		PrimitiveDateTime::parse(&s, &format).map_err(invalid)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::TimeBound;
+	use std::str::FromStr;
+	use std::time::{Duration, UNIX_EPOCH};
+	use time::{Date, Month, UtcOffset};
+
+	/// Get the bound at the given date and time.
+	fn at(year: i32, month: u8, day: u8, hms: (u8, u8, u8)) -> TimeBound {
+		let month = Month::try_from(month).unwrap();
+		let date = Date::from_calendar_date(year, month, day).unwrap();
+		TimeBound::At(date.with_hms(hms.0, hms.1, hms.2).unwrap())
+	}
+
+	macro_rules! make_parse_test {
+		( $($name:ident: $input:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(TimeBound::from_str($input).ok(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_parse_test!(
+		test_seconds: "45s" => Some(TimeBound::Ago(Duration::from_secs(45))),
+		test_days: "2d" => Some(TimeBound::Ago(Duration::from_secs(172_800))),
+		test_compound: "1h30m" => Some(TimeBound::Ago(Duration::from_secs(5_400))),
+		test_uppercase_unit: "1W" => Some(TimeBound::Ago(Duration::from_secs(604_800))),
+		test_missing_unit: "12" => None,
+		test_missing_number: "h" => None,
+		test_unknown_unit: "3y" => None,
+		test_empty: "" => None,
+		test_date: "2024-01-31" => Some(at(2024, 1, 31, (0, 0, 0))),
+		test_date_time: "2024-01-31 18:30" => Some(at(2024, 1, 31, (18, 30, 0))),
+		test_date_t_time: "2024-01-31T18:30:15" => Some(at(2024, 1, 31, (18, 30, 15))),
+		test_invalid_date: "2024-02-31" => None,
+	);
+
+	#[test]
+	fn test_instant_ago() {
+		let now = UNIX_EPOCH + Duration::from_secs(1_000);
+		let bound = TimeBound::Ago(Duration::from_secs(400));
+		assert_eq!(
+			bound.instant(now, UtcOffset::UTC),
+			UNIX_EPOCH + Duration::from_secs(600)
+		);
+	}
+
+	#[test]
+	fn test_instant_at_offset() {
+		let bound = at(1970, 1, 1, (1, 0, 0));
+		let offset = UtcOffset::from_hms(1, 0, 0).unwrap();
+		assert_eq!(bound.instant(UNIX_EPOCH, offset), UNIX_EPOCH);
+	}
+}
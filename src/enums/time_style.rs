@@ -0,0 +1,10 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// This enum contains the styles in which timestamps can be shown.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeStyle {
+	Absolute, // dates and times, using the `timestamp_formats`
+	Relative, // time elapsed, using the `relative_timestamp_formats`
+}
@@ -2,9 +2,11 @@ use crate::config::EntryConst;
 use crate::exc::Exc;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
-use std::fs::FileType;
+use std::fs::{FileType, Metadata};
 #[cfg(unix)]
 use std::os::unix::fs::FileTypeExt;
+#[cfg(windows)]
+use std::os::windows::fs::FileTypeExt;
 use std::path::Path;
 use std::sync::LazyLock;
 
@@ -23,6 +25,9 @@ static ALL_TYP: LazyLock<Vec<Typ>> = LazyLock::new(|| {
 /// * a shorthand that can be used to refer to any and all other variants
 /// * an unknown variant that is used when a node type is unrecognised
 ///
+/// On Windows, it additionally contains variants for junctions and reparse
+/// points, which have no equivalent on UNIX-like operating systems.
+///
 /// The names for the variants are used in accordance with the naming scheme of
 /// the [`FileType`] struct. A variant of this enum can be created using `into`
 /// on `FileType`.
@@ -33,6 +38,8 @@ static ALL_TYP: LazyLock<Vec<Typ>> = LazyLock::new(|| {
 pub enum Typ {
 	Dir,         // regular folder
 	Symlink,     // symbolic link
+	Junction,    // Windows junction or directory symlink
+	Reparse,     // other Windows reparse point, like a cloud placeholder
 	Fifo,        // named pipe
 	Socket,      // file-based socket
 	BlockDevice, // block special device file
@@ -49,11 +56,19 @@ pub enum Typ {
 impl From<FileType> for Typ {
 	fn from(value: FileType) -> Self {
 		match value {
+			// Junctions and directory symlinks cannot be told apart by `std`,
+			// so both are represented as junctions.
+			#[cfg(windows)]
+			_ if value.is_symlink_dir() => Typ::Junction,
 			_ if value.is_dir() => Typ::Dir,
 			_ if value.is_symlink() => Typ::Symlink,
+			#[cfg(unix)]
 			_ if value.is_fifo() => Typ::Fifo,
+			#[cfg(unix)]
 			_ if value.is_socket() => Typ::Socket,
+			#[cfg(unix)]
 			_ if value.is_block_device() => Typ::BlockDevice,
+			#[cfg(unix)]
 			_ if value.is_char_device() => Typ::CharDevice,
 			_ if value.is_file() => Typ::File,
 			_ => Typ::Unknown,
@@ -61,17 +76,42 @@ impl From<FileType> for Typ {
 	}
 }
 
+impl From<&Metadata> for Typ {
+	fn from(value: &Metadata) -> Self {
+		#[cfg(windows)]
+		if is_reparse_placeholder(value) {
+			return Typ::Reparse;
+		}
+		value.file_type().into()
+	}
+}
+
 impl TryFrom<&Path> for Typ {
 	type Error = Exc;
 
 	fn try_from(value: &Path) -> Result<Self, Self::Error> {
 		value
 			.symlink_metadata()
-			.map(|meta| meta.file_type().into())
+			.map(|meta| (&meta).into())
 			.map_err(Self::Error::Io)
 	}
 }
 
+/// Determine whether the metadata belongs to a file that is a reparse point
+/// but not a link, such as a OneDrive placeholder.
+///
+/// Directories that are reparse points, like OneDrive folders, are still
+/// listed as directories.
+#[cfg(windows)]
+fn is_reparse_placeholder(meta: &Metadata) -> bool {
+	use std::os::windows::fs::MetadataExt;
+
+	const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+	meta.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0
+		&& !meta.is_dir()
+		&& !meta.file_type().is_symlink()
+}
+
 impl Typ {
 	/// Clean the given input.
 	///
@@ -108,6 +148,13 @@ impl Typ {
 		}
 	}
 
+	/// Get whether the node is a link whose target can be resolved.
+	///
+	/// Junctions are resolved just like symlinks.
+	pub fn is_link(&self) -> bool {
+		matches!(self, Typ::Symlink | Typ::Junction)
+	}
+
 	/// Get the style directives associated with the node type.
 	///
 	/// These directives are combined with directives from other sources to form
@@ -184,6 +231,8 @@ mod tests {
 		test_expands_shorthand: &[Typ::All] => vec![
 			Typ::Dir,
 			Typ::Symlink,
+			Typ::Junction,
+			Typ::Reparse,
 			Typ::Fifo,
 			Typ::Socket,
 			Typ::BlockDevice,
@@ -216,6 +265,8 @@ mod tests {
 	make_name_components_test!(
 		test_icon_suffix_for_dir: Typ::Dir => "dir", "<dimmed>/</>",
 		test_icon_suffix_for_symlink: Typ::Symlink => "symlink", "<dimmed>@</>",
+		test_icon_suffix_for_junction: Typ::Junction => "junction", "<dimmed>@</>",
+		test_icon_suffix_for_reparse: Typ::Reparse => "reparse", "",
 		test_icon_suffix_for_fifo: Typ::Fifo => "fifo", "<dimmed>|</>",
 		test_icon_suffix_for_socket: Typ::Socket => "socket", "<dimmed>=</>",
 		test_icon_suffix_for_block_device: Typ::BlockDevice => "block_device", "",
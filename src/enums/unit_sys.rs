@@ -1,6 +1,5 @@
 use crate::config::EntryConst;
 use clap::ValueEnum;
-use number_prefix::NumberPrefix;
 use serde::{Deserialize, Serialize};
 
 /// This enum contains different unit systems to express large numbers,
@@ -17,27 +16,54 @@ impl UnitSys {
 	/// Split a natural number into a fractional magnitude and a unit prefix.
 	/// This method should not be invoked on enum variant `UnitSys::None`.
 	///
+	/// The prefix is the largest one that keeps the magnitude at least 1, or
+	/// the fixed prefix from the config, if it is one of the prefixes of this
+	/// unit system.
+	///
 	/// # Arguments
 	///
 	/// * `size` - the natural number to split into magnitude and unit
+	/// * `entry_const` - the constants that define the labels of the prefixes
 	///
 	/// # Returns
 	///
-	/// * the length of the prefix
+	/// * the length of the longest prefix
 	/// * the fractional magnitude
 	/// * the prefix of the unit
-	fn convert(&self, size: u64) -> (usize, f64, &'static str) {
-		let size = size as f64;
-		let (len, prefixed) = match self {
-			UnitSys::Binary => (2, NumberPrefix::binary(size)),
-			UnitSys::Decimal => (1, NumberPrefix::decimal(size)),
+	fn convert<'conf>(
+		&self,
+		size: u64,
+		entry_const: &'conf EntryConst,
+	) -> (usize, f64, &'conf str) {
+		let size_styles = &entry_const.size_styles;
+		let (divisor, prefixes) = match self {
+			UnitSys::Binary => (1024.0, &size_styles.binary_prefixes),
+			UnitSys::Decimal => (1000.0, &size_styles.decimal_prefixes),
 			_ => panic!("UnitSys::None cannot be converted."),
 		};
-		let (mag, prefix) = match prefixed {
-			NumberPrefix::Standalone(mag) => (mag, ""),
-			NumberPrefix::Prefixed(prefix, mag) => (mag, prefix.symbol()),
+		let len = prefixes.iter().map(|prefix| prefix.chars().count()).max();
+
+		let mut mag = size as f64;
+		let fixed = size_styles
+			.fixed_prefix
+			.as_ref()
+			.and_then(|fixed| prefixes.iter().position(|prefix| prefix == fixed));
+		let power = match fixed {
+			Some(power) => {
+				mag /= f64::powi(divisor, power as i32);
+				power
+			}
+			None => {
+				let mut power = 0;
+				while mag >= divisor && power + 1 < prefixes.len() {
+					mag /= divisor;
+					power += 1;
+				}
+				power
+			}
 		};
-		(len, mag, prefix)
+		let prefix = prefixes.get(power).map_or("", String::as_str);
+		(len.unwrap_or_default(), mag, prefix)
 	}
 
 	/// Convert the given number of bytes to a size string that uses the
@@ -45,20 +71,22 @@ impl UnitSys {
 	///
 	/// This function returns a marked-up string.
 	pub fn size(&self, size: u64, entry_const: &EntryConst) -> String {
-		let mag_directive = &entry_const.size_styles.mag;
-		let base_directive = &entry_const.size_styles.base;
+		let size_styles = &entry_const.size_styles;
+		let mag_directive = &size_styles.mag;
+		let base_directive = &size_styles.base;
+		let base = &size_styles.base_label;
 
 		if self == &UnitSys::None {
-			return format!("<{mag_directive}>{size}</> <{base_directive}>B</>");
+			return format!("<{mag_directive}>{size}</> <{base_directive}>{base}</>");
 		}
 
-		let prefix_directive = &entry_const.size_styles.prefix;
+		let prefix_directive = &size_styles.prefix;
 
-		let (width, mag, prefix) = self.convert(size);
+		let (width, mag, prefix) = self.convert(size, entry_const);
 		format!(
 			"<{mag_directive}>{mag:.1}</> \
 			 <{prefix_directive}>{prefix:>width$}</>\
-			 <{base_directive}>B</>",
+			 <{base_directive}>{base}</>",
 			width = width
 		)
 	}
@@ -98,4 +126,29 @@ mod tests {
 		decimal_shows_m_unit_for_pow2:  UnitSys::Decimal, 1000_u64.pow(2) => "<bold>1.0</> <>M</><dimmed>B</>",
 		decimal_shows_g_unit_for_pow3:  UnitSys::Decimal, 1000_u64.pow(3) => "<bold>1.0</> <>G</><dimmed>B</>",
 	);
+
+	macro_rules! make_labels_test {
+		( $($name:ident: $unit:expr, $fixed:expr, $num:expr => $str:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let mut entry_const = EntryConst::default();
+					entry_const.size_styles.base_label = String::from("o");
+					entry_const.size_styles.decimal_prefixes = ["", "K", "M"].map(String::from).to_vec();
+					entry_const.size_styles.fixed_prefix = $fixed.map(String::from);
+					let text = $unit.size($num, &entry_const);
+					assert_eq!(text, $str);
+				}
+			)*
+		};
+	}
+
+	make_labels_test!(
+		labels_replace_base: UnitSys::None, None::<&str>, 617 => "<bold>617</> <dimmed>o</>",
+		labels_replace_prefix: UnitSys::Decimal, None::<&str>, 1000 => "<bold>1.0</> <>K</><dimmed>o</>",
+		labels_stop_at_last_prefix: UnitSys::Decimal, None::<&str>, 1000_u64.pow(3) => "<bold>1000.0</> <>M</><dimmed>o</>",
+		fixed_prefix_scales_up: UnitSys::Decimal, Some("M"), 1000 => "<bold>0.0</> <>M</><dimmed>o</>",
+		fixed_prefix_scales_down: UnitSys::Decimal, Some("K"), 2_000_000 => "<bold>2000.0</> <>K</><dimmed>o</>",
+		fixed_prefix_of_other_system: UnitSys::Binary, Some("K"), 1024 => "<bold>1.0</> <>Ki</><dimmed>o</>",
+	);
 }
@@ -6,25 +6,40 @@ pub enum Exc {
 	/// wraps all occurrences of errors in I/O operations
 	Io(std::io::Error),
 	/// wraps all occurrences of errors in SVG operations
-	Svg(resvg::usvg::Error),
-	Conf(figment::Error),
+	Svg(Box<resvg::usvg::Error>),
+	Conf(Box<figment::Error>),
+	/// wraps all occurrences of errors in Git operations
+	Git(git2::Error),
+	/// wraps all occurrences of errors in JSON (de)serialisation
+	Json(serde_json::Error),
 	/// wraps exceptions from the `xterm-query` crate
+	#[cfg(unix)]
 	Xterm(xterm_query::XQError),
 	/// wraps all other errors
 	Other(String),
 }
 
-impl Display for Exc {
-	fn fmt(&self, f: &mut Formatter) -> FmtResult {
-		let attn = "<bold red>error:</>";
-		let err = match self {
+impl Exc {
+	/// Get the message of the wrapped error, without the styled prefix that
+	/// is shown to users, for programs that read it.
+	pub fn message(&self) -> String {
+		match self {
 			Exc::Io(err) => err.to_string(),
 			Exc::Conf(err) => err.to_string(),
+			Exc::Git(err) => err.to_string(),
+			Exc::Json(err) => err.to_string(),
 			Exc::Svg(err) => err.to_string(),
 			Exc::Other(text) => text.to_string(),
+			#[cfg(unix)]
 			Exc::Xterm(err) => err.to_string(),
-		};
-		let msg = format!("{attn} {err}");
+		}
+	}
+}
+
+impl Display for Exc {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		let attn = "<bold red>error:</>";
+		let msg = format!("{attn} {}", self.message());
 		write!(f, "{}", render(msg))
 	}
 }
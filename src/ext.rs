@@ -4,9 +4,15 @@
 //!
 //! * [`Abs`]
 //! * [`Ctime`]
+//! * [`PlatformMeta`]
+//! * [`Xattr`]
 
 mod abs;
 mod ctime;
+mod meta;
+mod xattr;
 
 pub use abs::Abs;
 pub use ctime::Ctime;
+pub use meta::PlatformMeta;
+pub use xattr::Xattr;
@@ -6,7 +6,9 @@ use std::fs::Metadata;
 use std::io::Result as IoResult;
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::SystemTime;
+#[cfg(unix)]
+use std::time::{Duration, UNIX_EPOCH};
 
 // =====
 // Trait
@@ -34,6 +36,7 @@ pub trait Ctime {
 // Implementations
 // ===============
 
+#[cfg(unix)]
 impl Ctime for Metadata {
 	fn c_time(&self) -> IoResult<SystemTime> {
 		let sec = self.ctime();
@@ -42,3 +45,10 @@ impl Ctime for Metadata {
 		Ok(ctime)
 	}
 }
+
+#[cfg(not(unix))]
+impl Ctime for Metadata {
+	fn c_time(&self) -> IoResult<SystemTime> {
+		self.created()
+	}
+}
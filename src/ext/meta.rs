@@ -0,0 +1,127 @@
+//! This module provides a trait [`PlatformMeta`], that can be used to extend
+//! `Metadata` with methods that read platform-specific fields, like the inode
+//! number or the owner, in a way that compiles on every platform.
+
+use std::fs::Metadata;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+#[cfg(windows)]
+use std::os::windows::fs::MetadataExt;
+
+// =====
+// Trait
+// =====
+
+/// This trait provides methods for fields that only exist on some platforms.
+///
+/// Each method returns `None` on platforms where the field does not exist, so
+/// that callers can leave the corresponding details blank. Most of these are
+/// Unix fields, which Windows either lacks or only exposes through unstable
+/// APIs, while file attributes are only present on Windows.
+pub trait PlatformMeta {
+	/// Get the ID of the device containing the node.
+	fn dev_id(&self) -> Option<u64>;
+	/// Get the inode number of the node.
+	fn ino_num(&self) -> Option<u64>;
+	/// Get the number of hard links pointing to the node.
+	fn nlink_count(&self) -> Option<u64>;
+	/// Get the permission bits of the node.
+	///
+	/// Windows controls access with ACLs instead of permission bits, which
+	/// cannot be represented as bits without losing information, so there are
+	/// no bits there. The read-only attribute is shown among the attributes.
+	fn mode_bits(&self) -> Option<u32>;
+	/// Get the UID of the user that owns the node.
+	fn owner_uid(&self) -> Option<u32>;
+	/// Get the GID of the group that owns the node.
+	fn owner_gid(&self) -> Option<u32>;
+	/// Get the number of 512-byte blocks allocated to the node.
+	fn block_count(&self) -> Option<u64>;
+	/// Get the major and minor numbers of the device, for device nodes.
+	fn rdev_num(&self) -> Option<(u32, u32)>;
+	/// Get the Windows file attributes of the node.
+	fn attributes(&self) -> Option<u32>;
+}
+
+// ===============
+// Implementations
+// ===============
+
+#[cfg(unix)]
+impl PlatformMeta for Metadata {
+	fn dev_id(&self) -> Option<u64> {
+		Some(self.dev())
+	}
+
+	fn ino_num(&self) -> Option<u64> {
+		Some(self.ino())
+	}
+
+	fn nlink_count(&self) -> Option<u64> {
+		Some(self.nlink())
+	}
+
+	fn mode_bits(&self) -> Option<u32> {
+		Some(self.mode())
+	}
+
+	fn owner_uid(&self) -> Option<u32> {
+		Some(self.uid())
+	}
+
+	fn owner_gid(&self) -> Option<u32> {
+		Some(self.gid())
+	}
+
+	fn block_count(&self) -> Option<u64> {
+		Some(self.blocks())
+	}
+
+	fn rdev_num(&self) -> Option<(u32, u32)> {
+		let rdev = self.rdev() as libc::dev_t;
+		Some((libc::major(rdev) as u32, libc::minor(rdev) as u32))
+	}
+
+	fn attributes(&self) -> Option<u32> {
+		None
+	}
+}
+
+#[cfg(windows)]
+impl PlatformMeta for Metadata {
+	fn dev_id(&self) -> Option<u64> {
+		None
+	}
+
+	fn ino_num(&self) -> Option<u64> {
+		None
+	}
+
+	fn nlink_count(&self) -> Option<u64> {
+		None
+	}
+
+	fn mode_bits(&self) -> Option<u32> {
+		None
+	}
+
+	fn owner_uid(&self) -> Option<u32> {
+		None
+	}
+
+	fn owner_gid(&self) -> Option<u32> {
+		None
+	}
+
+	fn block_count(&self) -> Option<u64> {
+		None
+	}
+
+	fn rdev_num(&self) -> Option<(u32, u32)> {
+		None
+	}
+
+	fn attributes(&self) -> Option<u32> {
+		Some(self.file_attributes())
+	}
+}
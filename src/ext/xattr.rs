@@ -0,0 +1,206 @@
+//! This module provides a trait [`Xattr`], that can be used to extend `Path`
+//! with methods that read and write the extended attributes of the node at the
+//! path.
+//!
+//! Extended attributes are read with the system calls of Linux and macOS,
+//! which differ only in their signatures. Symlinks are never followed, so the
+//! attributes are those of the link itself. On other platforms, nodes are
+//! treated as having no extended attributes, and cannot be given any.
+
+use std::io::Result as IoResult;
+use std::path::Path;
+
+// =====
+// Trait
+// =====
+
+/// This trait provides methods for reading and writing the extended
+/// attributes of a node.
+pub trait Xattr {
+	/// Get the names of the extended attributes of the node.
+	fn xattr_names(&self) -> IoResult<Vec<String>>;
+	/// Get the value of the extended attribute with the given name.
+	fn xattr_value(&self, name: &str) -> IoResult<Vec<u8>>;
+	/// Set the value of the extended attribute with the given name, creating
+	/// the attribute if it does not exist.
+	fn set_xattr(&self, name: &str, value: &[u8]) -> IoResult<()>;
+	/// Remove the extended attribute with the given name.
+	fn remove_xattr(&self, name: &str) -> IoResult<()>;
+}
+
+// ===============
+// Implementations
+// ===============
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+impl Xattr for Path {
+	fn xattr_names(&self) -> IoResult<Vec<String>> {
+		let path = sys::c_string(self.as_os_str().as_encoded_bytes())?;
+		let buf = sys::read(|buf, size| unsafe { sys::list(&path, buf, size) })?;
+		// The names are separated, and terminated, by null bytes.
+		Ok(buf
+			.split(|byte| *byte == 0)
+			.filter(|name| !name.is_empty())
+			.map(|name| String::from_utf8_lossy(name).to_string())
+			.collect())
+	}
+
+	fn xattr_value(&self, name: &str) -> IoResult<Vec<u8>> {
+		let path = sys::c_string(self.as_os_str().as_encoded_bytes())?;
+		let name = sys::c_string(name.as_bytes())?;
+		sys::read(|buf, size| unsafe { sys::get(&path, &name, buf, size) })
+	}
+
+	fn set_xattr(&self, name: &str, value: &[u8]) -> IoResult<()> {
+		let path = sys::c_string(self.as_os_str().as_encoded_bytes())?;
+		let name = sys::c_string(name.as_bytes())?;
+		sys::check(unsafe { sys::set(&path, &name, value) })
+	}
+
+	fn remove_xattr(&self, name: &str) -> IoResult<()> {
+		let path = sys::c_string(self.as_os_str().as_encoded_bytes())?;
+		let name = sys::c_string(name.as_bytes())?;
+		sys::check(unsafe { sys::remove(&path, &name) })
+	}
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+impl Xattr for Path {
+	fn xattr_names(&self) -> IoResult<Vec<String>> {
+		Ok(vec![])
+	}
+
+	fn xattr_value(&self, _name: &str) -> IoResult<Vec<u8>> {
+		Err(std::io::ErrorKind::Unsupported.into())
+	}
+
+	fn set_xattr(&self, _name: &str, _value: &[u8]) -> IoResult<()> {
+		Err(std::io::ErrorKind::Unsupported.into())
+	}
+
+	fn remove_xattr(&self, _name: &str) -> IoResult<()> {
+		Err(std::io::ErrorKind::Unsupported.into())
+	}
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod sys {
+	use libc::{c_char, c_int, c_void, size_t, ssize_t};
+	use std::ffi::CString;
+	use std::io::{Error, ErrorKind, Result as IoResult};
+
+	/// Convert the given bytes into a null-terminated C string.
+	pub fn c_string(bytes: &[u8]) -> IoResult<CString> {
+		CString::new(bytes).map_err(|err| Error::new(ErrorKind::InvalidInput, err))
+	}
+
+	/// Read a variable-length buffer with the given system call.
+	///
+	/// The call is first made with an empty buffer to get the required size.
+	/// Since attributes can change between the two calls, the process is
+	/// repeated if the buffer turns out to be too small.
+	pub fn read<F>(call: F) -> IoResult<Vec<u8>>
+	where
+		F: Fn(*mut c_void, size_t) -> ssize_t,
+	{
+		loop {
+			let size = call(std::ptr::null_mut(), 0);
+			if size < 0 {
+				return Err(Error::last_os_error());
+			}
+			let mut buf = vec![0u8; size as usize];
+			let size = call(buf.as_mut_ptr().cast(), buf.len());
+			if size < 0 {
+				let err = Error::last_os_error();
+				if err.raw_os_error() == Some(libc::ERANGE) {
+					continue;
+				}
+				return Err(err);
+			}
+			buf.truncate(size as usize);
+			return Ok(buf);
+		}
+	}
+
+	/// Convert the return value of a system call that returns nothing into a
+	/// result.
+	pub fn check(res: c_int) -> IoResult<()> {
+		if res != 0 {
+			return Err(Error::last_os_error());
+		}
+		Ok(())
+	}
+
+	/// List the names of the extended attributes, without following symlinks.
+	#[cfg(target_os = "linux")]
+	pub unsafe fn list(path: &CString, buf: *mut c_void, size: size_t) -> ssize_t {
+		libc::llistxattr(path.as_ptr(), buf as *mut c_char, size)
+	}
+
+	/// Get the value of an extended attribute, without following symlinks.
+	#[cfg(target_os = "linux")]
+	pub unsafe fn get(path: &CString, name: &CString, buf: *mut c_void, size: size_t) -> ssize_t {
+		libc::lgetxattr(path.as_ptr(), name.as_ptr(), buf, size)
+	}
+
+	/// Set the value of an extended attribute, without following symlinks.
+	#[cfg(target_os = "linux")]
+	pub unsafe fn set(path: &CString, name: &CString, value: &[u8]) -> c_int {
+		libc::lsetxattr(
+			path.as_ptr(),
+			name.as_ptr(),
+			value.as_ptr().cast(),
+			value.len(),
+			0,
+		)
+	}
+
+	/// Remove an extended attribute, without following symlinks.
+	#[cfg(target_os = "linux")]
+	pub unsafe fn remove(path: &CString, name: &CString) -> c_int {
+		libc::lremovexattr(path.as_ptr(), name.as_ptr())
+	}
+
+	/// List the names of the extended attributes, without following symlinks.
+	#[cfg(target_os = "macos")]
+	pub unsafe fn list(path: &CString, buf: *mut c_void, size: size_t) -> ssize_t {
+		libc::listxattr(
+			path.as_ptr(),
+			buf as *mut c_char,
+			size,
+			libc::XATTR_NOFOLLOW,
+		)
+	}
+
+	/// Get the value of an extended attribute, without following symlinks.
+	#[cfg(target_os = "macos")]
+	pub unsafe fn get(path: &CString, name: &CString, buf: *mut c_void, size: size_t) -> ssize_t {
+		libc::getxattr(
+			path.as_ptr(),
+			name.as_ptr(),
+			buf,
+			size,
+			0,
+			libc::XATTR_NOFOLLOW,
+		)
+	}
+
+	/// Set the value of an extended attribute, without following symlinks.
+	#[cfg(target_os = "macos")]
+	pub unsafe fn set(path: &CString, name: &CString, value: &[u8]) -> c_int {
+		libc::setxattr(
+			path.as_ptr(),
+			name.as_ptr(),
+			value.as_ptr().cast(),
+			value.len(),
+			0,
+			libc::XATTR_NOFOLLOW,
+		)
+	}
+
+	/// Remove an extended attribute, without following symlinks.
+	#[cfg(target_os = "macos")]
+	pub unsafe fn remove(path: &CString, name: &CString) -> c_int {
+		libc::removexattr(path.as_ptr(), name.as_ptr(), libc::XATTR_NOFOLLOW)
+	}
+}
@@ -10,12 +10,30 @@
 //! a list of supported directives. Tags can be nested, with inner tags capable
 //! of overwriting directives from outer tags.
 //!
-//! The public interface of the module consists of two functions:
+//! Whether markup is rendered with styles at all is decided once, at startup,
+//! by [`configure_color`].
 //!
+//! The public interface of the module consists of eleven functions:
+//!
+//! * [`clip`]
+//! * [`configure_color`]
+//! * [`configure_widths`]
+//! * [`is_styled`]
 //! * [`len`]
+//! * [`link`]
+//! * [`plain`]
 //! * [`render`]
+//! * [`render_into`]
+//! * [`truncate`]
+//! * [`wrap`]
 
+mod ansi;
+mod color;
 mod format;
 mod markup;
+mod width;
 
-pub use markup::{len, render};
+pub use ansi::clip;
+pub use color::{configure_color, is_styled};
+pub use markup::{len, link, plain, render, render_into, truncate, wrap};
+pub use width::configure_widths;
@@ -0,0 +1,120 @@
+use crate::fmt::markup::ELLIPSIS;
+use crate::fmt::width::width;
+use unicode_segmentation::UnicodeSegmentation;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+const ST: &str = "\x1b\\";
+
+/// Clip a rendered string so that it spans at most the given width.
+///
+/// Unlike [`truncate`](crate::fmt::truncate), which works on markup, this
+/// works on text that has already been rendered into ANSI escape sequences.
+/// The sequences take up no space and are all retained, even after the cut,
+/// so that styles are reset and hyperlinks are closed. An ellipsis marks the
+/// cut.
+///
+/// # Arguments
+///
+/// * `text` - the rendered string to be clipped
+/// * `width` - the maximum width of the result, including the ellipsis
+/// * `emoji_width` - the number of cells the terminal uses to draw an emoji
+pub fn clip(text: &str, width: usize, emoji_width: usize) -> String {
+	let mut total = 0;
+	let mut rest = text;
+	while !rest.is_empty() {
+		let (unit, unit_width) = next_unit(rest, emoji_width);
+		total += unit_width;
+		rest = &rest[unit.len()..];
+	}
+	if total <= width {
+		return text.to_string();
+	}
+
+	let budget = width.saturating_sub(1); // Leave space for the ellipsis.
+	let mut used = 0;
+	let mut is_cut = width == 0; // Without any width, there is no ellipsis.
+
+	let mut out = String::with_capacity(text.len());
+	let mut rest = text;
+	while !rest.is_empty() {
+		let (unit, unit_width) = next_unit(rest, emoji_width);
+		rest = &rest[unit.len()..];
+		if unit_width == 0 {
+			out.push_str(unit); // Escape sequences are kept, even after the cut.
+		} else if !is_cut && used + unit_width <= budget {
+			used += unit_width;
+			out.push_str(unit);
+		} else if !is_cut {
+			out.push(ELLIPSIS);
+			is_cut = true;
+		}
+	}
+	out
+}
+
+/// Get the next indivisible unit of rendered text, along with its width.
+///
+/// A unit is an escape sequence or a grapheme cluster. CSI sequences end with
+/// their final byte, OSC sequences with BEL or ST, DCS and APC sequences, like
+/// images, with ST, and all other sequences after the character that follows
+/// the escape. Only the sequences that move the cursor forward, like the ones
+/// that make space for images, take up space.
+fn next_unit(text: &str, emoji_width: usize) -> (&str, usize) {
+	let Some(seq) = text.strip_prefix(ESC) else {
+		let grapheme = text.graphemes(true).next().unwrap_or(text);
+		return (grapheme, width(grapheme, emoji_width));
+	};
+	let st_end = || seq.find(ST).map(|idx| idx + ST.len());
+	let end = match seq.chars().next() {
+		Some('[') => seq[1..]
+			.find(|ch| ('@'..='~').contains(&ch))
+			.map(|idx| idx + 2),
+		Some(']') => {
+			let bel_end = seq.find(BEL).map(|idx| idx + 1);
+			match (bel_end, st_end()) {
+				(Some(bel), Some(st)) => Some(bel.min(st)),
+				(bel, st) => bel.or(st),
+			}
+		}
+		Some('P' | '_') => st_end(),
+		Some(ch) => Some(ch.len_utf8()),
+		None => Some(0),
+	};
+	let end = end.map_or(text.len(), |end| end + 1);
+	let unit = &text[..end];
+	let width = unit
+		.strip_prefix("\x1b[")
+		.and_then(|params| params.strip_suffix('C'))
+		.map_or(0, |count| count.parse().unwrap_or(1));
+	(unit, width)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::clip;
+
+	macro_rules! make_clip_test {
+		( $($name:ident: $text:expr, $width:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(clip($text, $width, 2), $expected);
+				}
+			)*
+		};
+	}
+
+	make_clip_test!(
+		test_clip_fits: "name", 4 => "name",
+		test_clip_cuts: "name", 3 => "na…",
+		test_clip_zero: "name", 0 => "",
+		test_clip_keeps_styles: "\x1b[1mname\x1b[0m", 3 => "\x1b[1mna…\x1b[0m",
+		test_clip_styles_take_no_space: "\x1b[38;2;10;20;30mname\x1b[0m", 4 => "\x1b[38;2;10;20;30mname\x1b[0m",
+		test_clip_keeps_links: "\x1b]8;;file:///a\x07name\x1b]8;;\x07", 2 => "\x1b]8;;file:///a\x07n…\x1b]8;;\x07",
+		test_clip_keeps_st_links: "\x1b]8;;file:///a\x1b\\name\x1b]8;;\x1b\\", 2 => "\x1b]8;;file:///a\x1b\\n…\x1b]8;;\x1b\\",
+		test_clip_keeps_images: "\x1b_Ga=p;\x1b\\\x1b[2Cname", 4 => "\x1b_Ga=p;\x1b\\\x1b[2Cn…",
+		test_clip_emoji: "😀😀x", 3 => "😀…",
+		test_clip_unterminated: "ab\x1b[1", 1 => "…\x1b[1",
+	);
+}
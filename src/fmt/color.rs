@@ -0,0 +1,94 @@
+use crate::enums::ColorMode;
+use std::env;
+use std::io::{self, IsTerminal};
+
+/// Decide whether the output is styled, and apply the decision to all markup
+/// rendered from now on.
+///
+/// With `--color=auto`, the output is styled if it goes to a terminal, unless
+/// the environment says otherwise, so that piping `pls` into files or other
+/// programs yields plain text.
+///
+/// # Arguments
+///
+/// * `mode` - the choice made with `--color`
+/// * `to_terminal` - whether the output is known to be meant for a terminal,
+///   like the file written by `--render-to`, even if STDOUT is not one
+pub fn configure_color(mode: ColorMode, to_terminal: bool) {
+	let styled = match mode {
+		ColorMode::Always => true,
+		ColorMode::Never => false,
+		ColorMode::Auto => {
+			to_terminal || auto(|var| env::var(var).ok(), io::stdout().is_terminal())
+		}
+	};
+	colored::control::set_override(styled);
+}
+
+/// Get whether the output is styled, as decided by [`configure_color`].
+///
+/// Other escape sequences, like hyperlinks, should only be written when this
+/// is true, so that plain text stays plain.
+pub fn is_styled() -> bool {
+	colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// Decide whether the output is styled with `--color=auto`.
+///
+/// The environment variables take precedence over the terminal, in this order:
+///
+/// * `NO_COLOR`, if set to any non-empty value, turns styles off
+/// * `CLICOLOR_FORCE`, if set to anything but `0`, turns styles on
+/// * `CLICOLOR`, if set to `0`, turns styles off
+///
+/// # Arguments
+///
+/// * `var` - the function that gets the value of an environment variable
+/// * `is_terminal` - whether STDOUT is a terminal
+fn auto(var: impl Fn(&str) -> Option<String>, is_terminal: bool) -> bool {
+	let is_set = |name| var(name).is_some_and(|val| !val.is_empty());
+	if is_set("NO_COLOR") {
+		return false;
+	}
+	if is_set("CLICOLOR_FORCE") && var("CLICOLOR_FORCE").as_deref() != Some("0") {
+		return true;
+	}
+	if var("CLICOLOR").as_deref() == Some("0") {
+		return false;
+	}
+	is_terminal
+}
+
+#[cfg(test)]
+mod tests {
+	use super::auto;
+
+	macro_rules! make_auto_test {
+		( $($name:ident: $vars:expr, $is_terminal:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let vars: &[(&str, &str)] = &$vars;
+					let var = |name: &str| {
+						vars.iter()
+							.find(|(key, _)| *key == name)
+							.map(|(_, val)| val.to_string())
+					};
+					assert_eq!(auto(var, $is_terminal), $expected);
+				}
+			)*
+		};
+	}
+
+	make_auto_test!(
+		test_terminal: [], true => true,
+		test_pipe: [], false => false,
+		test_no_color: [("NO_COLOR", "1")], true => false,
+		test_empty_no_color: [("NO_COLOR", "")], true => true,
+		test_no_color_beats_force: [("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")], true => false,
+		test_force_on_pipe: [("CLICOLOR_FORCE", "1")], false => true,
+		test_force_zero: [("CLICOLOR_FORCE", "0")], false => false,
+		test_clicolor_off: [("CLICOLOR", "0")], true => false,
+		test_force_beats_clicolor: [("CLICOLOR", "0"), ("CLICOLOR_FORCE", "1")], false => true,
+	);
+}
@@ -105,6 +105,8 @@ fn apply_directive(string: ColoredString, directive: &str) -> ColoredString {
 /// You can see the comprehensive list of escape codes for
 /// [ANSI colours on Wikipedia](https://en.wikipedia.org/wiki/ANSI_escape_code#Colors).
 #[cfg(test)]
+// `Jail::expect_with` requires closures that return `figment::Error`.
+#[allow(clippy::result_large_err)]
 mod tests {
 	use super::fmt;
 	use figment::Jail;
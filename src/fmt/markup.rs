@@ -1,9 +1,21 @@
 use crate::fmt::format::fmt;
+use crate::fmt::width::width;
+use crate::gfx::strip_image;
+use std::borrow::Cow;
 use std::iter::Peekable;
 use std::str::Chars;
 use unicode_segmentation::UnicodeSegmentation;
 
 const ESCAPE: char = '\\';
+pub(super) const ELLIPSIS: char = '…';
+const IMAGE_START: &str = "\x1b_G";
+const IMAGE_END: &str = "\x1b\\";
+const IMAGE_ADVANCE: &str = "\x1b[2C";
+const SIXEL_START: &str = "\x1b7\x1bP";
+const SIXEL_END: &str = "\x1b\\\x1b8";
+const LINK_START: &str = "\x1b]8;";
+// BEL, unlike ST, has no backslash that could escape a tag that follows it.
+const LINK_END: &str = "\x07";
 const TAG_OPEN: char = '<';
 const TAG_CLOSE: char = '>';
 
@@ -95,50 +107,321 @@ where
 /// # Arguments
 ///
 /// * `markup` - the marked-up string to be rendered
-#[allow(clippy::needless_borrows_for_generic_args)]
 pub fn render<S>(markup: S) -> String
 where
 	S: AsRef<str>,
 {
-	reduce_markup(markup, String::default(), |stack, curr, acc| {
-		let mut acc = acc;
+	let mut out = String::default();
+	render_into(markup, &mut out);
+	out
+}
+
+/// Render the given markup string into ANSI escape codes, appending them to
+/// the given buffer.
+///
+/// This is the allocation-friendly counterpart of [`render`], meant for hot
+/// loops that can reuse one buffer across many calls.
+///
+/// # Arguments
+///
+/// * `markup` - the marked-up string to be rendered
+/// * `out` - the buffer to which the rendered string is appended
+#[allow(clippy::needless_borrows_for_generic_args)]
+pub fn render_into<S>(markup: S, out: &mut String)
+where
+	S: AsRef<str>,
+{
+	reduce_markup(markup, out, |stack, curr, out| {
 		if !curr.is_empty() {
 			let directives: Vec<_> = stack.iter().flatten().collect();
-			if !directives.contains(&&String::from("hidden")) {
-				acc.push_str(&fmt(&curr, &directives));
+			if !directives.iter().any(|directive| *directive == "hidden") {
+				out.push_str(&fmt(&curr, &directives));
 			}
 			curr.clear();
 		}
-		acc
-	})
+		out
+	});
+}
+
+/// Wrap the given markup in an OSC 8 hyperlink to the given URL.
+///
+/// The link sequences are understood by all functions in this module, so the
+/// result can be measured, truncated and wrapped like any other markup. The
+/// URL must not contain `<`, like the URLs made by
+/// [`file_url`](crate::utils::urls::file_url).
+///
+/// # Arguments
+///
+/// * `url` - the URL that the text links to
+/// * `markup` - the marked-up text to show for the link
+pub fn link(url: &str, markup: &str) -> String {
+	format!("{LINK_START};{url}{LINK_END}{markup}{}", link_close())
+}
+
+/// Get the OSC 8 sequence that closes the active hyperlink.
+fn link_close() -> String {
+	format!("{LINK_START};{LINK_END}")
+}
+
+/// Remove the OSC 8 hyperlink sequences from a string, keeping the text that
+/// they link.
+///
+/// Hyperlinks take up no space on the screen, so they must not be counted
+/// when measuring the text.
+fn strip_links(text: &str) -> Cow<'_, str> {
+	if !text.contains(LINK_START) {
+		return Cow::Borrowed(text);
+	}
+	let mut out = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(start) = rest.find(LINK_START) {
+		out.push_str(&rest[..start]);
+		rest = &rest[start..];
+		let end = rest
+			.find(LINK_END)
+			.map_or(rest.len(), |idx| idx + LINK_END.len());
+		rest = &rest[end..];
+	}
+	out.push_str(rest);
+	Cow::Owned(out)
+}
+
+/// Get the plain text of a markup string.
+///
+/// This drops all tags, hidden text, hyperlinks and Kitty images, leaving only the text
+/// that would be visible on the screen, without any formatting. This is used
+/// when the output is consumed by programs rather than read in a terminal.
+///
+/// # Arguments
+///
+/// * `markup` - the marked-up string to be stripped
+pub fn plain<S>(markup: S) -> String
+where
+	S: AsRef<str>,
+{
+	reduce_markup(
+		strip_links(&strip_image(markup)),
+		String::default(),
+		|stack, curr, mut acc| {
+			if !stack.iter().flatten().any(|tag| tag == "hidden") {
+				acc.push_str(curr);
+			}
+			curr.clear();
+			acc
+		},
+	)
 }
 
 /// Get the true length of a markup string.
 ///
-/// This counts the number of graphemes (not characters, not bytes) and excludes
-/// markup tags from the count. This length can be used to align tables.
+/// This counts the cells spanned by each grapheme (not character, not byte)
+/// and excludes markup tags and hyperlinks from the count. Wide characters,
+/// like CJK ideographs, span two cells, and emoji span `emoji_width` cells,
+/// since most terminals draw them wider than other graphemes. This length can
+/// be used to align tables.
 ///
 /// # Arguments
 ///
 /// * `markup` - the marked-up string to be measured
-pub fn len<S>(markup: S) -> usize
+/// * `emoji_width` - the number of cells the terminal uses to draw an emoji
+pub fn len<S>(markup: S, emoji_width: usize) -> usize
 where
 	S: AsRef<str>,
 {
-	reduce_markup(markup, 0, |stack, curr, acc| {
+	reduce_markup(strip_links(markup.as_ref()), 0, |stack, curr, acc| {
 		let count = if curr.is_empty() || stack.iter().flatten().any(|tag| tag == "hidden") {
 			0
 		} else {
-			curr.graphemes(true).count()
+			curr.graphemes(true)
+				.map(|grapheme| width(grapheme, emoji_width))
+				.sum()
 		};
 		curr.clear();
 		acc + count
 	})
 }
 
+/// Truncate a markup string so that it spans at most the given width.
+///
+/// The text is cut on grapheme cluster boundaries, so that combining marks and
+/// emoji ZWJ sequences are never split, and an ellipsis marks the cut. Kitty
+/// images are kept or dropped as a whole. All tags and hyperlinks are retained,
+/// even after the cut, so that the result remains well-formed markup.
+///
+/// # Arguments
+///
+/// * `markup` - the marked-up string to be truncated
+/// * `width` - the maximum width of the result, including the ellipsis
+/// * `emoji_width` - the number of cells the terminal uses to draw an emoji
+pub fn truncate<S>(markup: S, width: usize, emoji_width: usize) -> String
+where
+	S: AsRef<str>,
+{
+	let markup = markup.as_ref();
+	if len(strip_image(markup), emoji_width) <= width {
+		return markup.to_string();
+	}
+
+	let budget = width.saturating_sub(1); // Leave space for the ellipsis.
+	let mut used = 0;
+	let mut is_cut = width == 0; // Without any width, there is no ellipsis.
+	let mut hidden = vec![]; // whether each active tag hides its contents
+
+	let mut out = String::with_capacity(markup.len());
+	let mut rest = markup;
+	while let Some(next_char) = rest.chars().next() {
+		// Tags are copied as-is, regardless of the cut.
+		if next_char == TAG_OPEN {
+			let end = rest.find(TAG_CLOSE).map_or(rest.len(), |idx| idx + 1);
+			let tag = &rest[..end];
+			if tag == "</>" {
+				hidden.pop();
+			} else {
+				hidden.push(
+					tag[1..tag.len() - 1]
+						.split(' ')
+						.any(|directive| directive == "hidden"),
+				);
+			}
+			out.push_str(tag);
+			rest = &rest[end..];
+			continue;
+		}
+
+		let (unit, unit_width) = next_unit(rest, emoji_width);
+		rest = &rest[unit.len()..];
+		// Hyperlinks are kept, even after the cut, so that each one is closed.
+		if unit.starts_with(LINK_START) {
+			out.push_str(unit);
+			continue;
+		}
+		if is_cut {
+			continue;
+		}
+		let unit_width = if hidden.iter().any(|is_hidden| *is_hidden) {
+			0
+		} else {
+			unit_width
+		};
+		if used + unit_width > budget {
+			out.push(ELLIPSIS);
+			is_cut = true;
+			continue;
+		}
+		out.push_str(unit);
+		used += unit_width;
+	}
+	out
+}
+
+/// Wrap a markup string into lines that each span at most the given width.
+///
+/// Like [`truncate`], the text is broken on grapheme cluster boundaries and
+/// Kitty images are kept whole. Tags and hyperlinks that are open at a break
+/// are closed at the end of the line and reopened at the start of the next, so
+/// that each line is well-formed markup by itself. A unit wider than the whole width is
+/// placed on a line of its own.
+///
+/// # Arguments
+///
+/// * `markup` - the marked-up string to be wrapped
+/// * `width` - the maximum width of each line
+/// * `emoji_width` - the number of cells the terminal uses to draw an emoji
+pub fn wrap<S>(markup: S, width: usize, emoji_width: usize) -> Vec<String>
+where
+	S: AsRef<str>,
+{
+	let markup = markup.as_ref();
+	if len(strip_image(markup), emoji_width) <= width {
+		return vec![markup.to_string()];
+	}
+
+	let mut lines = vec![];
+	let mut used = 0;
+	let mut open: Vec<&str> = vec![]; // the active tags, in order of opening
+	let mut open_link: Option<&str> = None; // the start of the active hyperlink
+
+	let mut line = String::with_capacity(markup.len());
+	let mut rest = markup;
+	while let Some(next_char) = rest.chars().next() {
+		if next_char == TAG_OPEN {
+			let end = rest.find(TAG_CLOSE).map_or(rest.len(), |idx| idx + 1);
+			let tag = &rest[..end];
+			if tag == "</>" {
+				open.pop();
+			} else {
+				open.push(tag);
+			}
+			line.push_str(tag);
+			rest = &rest[end..];
+			continue;
+		}
+
+		let (unit, unit_width) = next_unit(rest, emoji_width);
+		rest = &rest[unit.len()..];
+		if unit.starts_with(LINK_START) {
+			// A link without a URL closes the active link.
+			open_link = Some(unit).filter(|unit| unit.len() > LINK_START.len() + 2);
+			line.push_str(unit);
+			continue;
+		}
+		let is_hidden = open
+			.iter()
+			.any(|tag| tag[1..tag.len() - 1].split(' ').any(|dir| dir == "hidden"));
+		let unit_width = if is_hidden { 0 } else { unit_width };
+		if used > 0 && used + unit_width > width {
+			if open_link.is_some() {
+				line.push_str(&link_close());
+			}
+			line.push_str(&"</>".repeat(open.len()));
+			lines.push(line);
+			line = open.concat();
+			line.extend(open_link);
+			used = 0;
+		}
+		line.push_str(unit);
+		used += unit_width;
+	}
+	lines.push(line);
+	lines
+}
+
+/// Get the next indivisible unit of text, along with its width.
+///
+/// A unit is an escaped `<`, a Kitty or Sixel image sequence along with the
+/// cursor movement after it, an OSC 8 hyperlink sequence, which takes up no
+/// space, or a grapheme cluster.
+fn next_unit(text: &str, emoji_width: usize) -> (&str, usize) {
+	if text.starts_with(ESCAPE) && text[1..].starts_with(TAG_OPEN) {
+		return (&text[..2], 1);
+	}
+	for (start, end) in [(IMAGE_START, IMAGE_END), (SIXEL_START, SIXEL_END)] {
+		if !text.starts_with(start) {
+			continue;
+		}
+		let end = text.find(end).map_or(text.len(), |idx| idx + end.len());
+		// The image is followed by a cursor movement that makes space for it.
+		if text[end..].starts_with(IMAGE_ADVANCE) {
+			return (&text[..end + IMAGE_ADVANCE.len()], 2);
+		}
+		return (&text[..end], 0);
+	}
+	if text.starts_with(IMAGE_ADVANCE) {
+		return (&text[..IMAGE_ADVANCE.len()], 2);
+	}
+	if text.starts_with(LINK_START) {
+		let end = text
+			.find(LINK_END)
+			.map_or(text.len(), |idx| idx + LINK_END.len());
+		return (&text[..end], 0);
+	}
+	let grapheme = text.graphemes(true).next().unwrap_or(text);
+	(grapheme, width(grapheme, emoji_width))
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{len, render, select_while};
+	use super::{len, link, plain, render, render_into, select_while, truncate, wrap};
 
 	macro_rules! make_select_while_test {
         ( $($name:ident: $predicate:expr => $selected:expr,)* ) => {
@@ -195,13 +478,22 @@ mod tests {
 		test_render_keeps_existing_ansi: "\x1b[34m<dimmed>.</>git\x1b[0m<dimmed>/</>" => "\x1b[34m\x1b[2m.\x1b[0mgit\x1b[0m\x1b[2m/\x1b[0m",
 	);
 
+	#[test]
+	fn test_render_into_appends_to_buffer() {
+		colored::control::set_override(true); // needed when running tests in CLion
+		let mut out = String::from("pre ");
+		render_into("<bold>bold</>", &mut out);
+		render_into(" post", &mut out);
+		assert_eq!(out, "pre \x1b[1mbold\x1b[0m post");
+	}
+
 	macro_rules! make_len_test {
 		( $($name:ident: $markup:expr => $length:expr,)* ) => {
 			$(
 				#[test]
 				fn $name() {
 					colored::control::set_override(true); // needed when running tests in CLion
-                    let length = len($markup);
+                    let length = len($markup, 1);
                     assert_eq!(length, $length);
 				}
 			)*
@@ -218,8 +510,100 @@ mod tests {
 		test_len_handles_extended_grapheme_cluster_emoji: "🤦🏽‍♂️" => 1, // ^ + ‍(zero-width joiner) + ♂(male sign) + ️(variation selector-16)
 
 		test_len_handles_nerd_font: "" => 1, // nf-fa-folder
+		test_len_handles_cjk: "漢字" => 4,
 
 		test_len_ignores_tags: "<bold>bold</>" => 4,
 		test_len_drops_hidden_text: "<blue>blue<hidden>hidden</></>" => 4,
+		test_len_ignores_links: "\x1b]8;;file:///a.rs\x07<bold>a.rs</>\x1b]8;;\x07" => 4,
 	);
+
+	macro_rules! make_plain_test {
+		( $($name:ident: $markup:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(plain($markup), $expected);
+				}
+			)*
+		};
+	}
+
+	make_plain_test!(
+		test_plain_drops_tags: "<bold>bold</> <red>red</>" => "bold red",
+		test_plain_drops_hidden_text: "<blue>blue<hidden>hidden</></>" => "blue",
+		test_plain_unescapes_tags: "\\<tag>" => "<tag>",
+		test_plain_drops_image: "\x1b_Ga=p,i=1;\x1b\\\x1b[2Cname" => "  name",
+		test_plain_drops_sixel: "\x1b7\x1bPq#0;2;0;0;0#0!4~-\x1b\\\x1b8\x1b[2Cname" => "  name",
+		test_plain_drops_links: "\x1b]8;;file:///a.rs\x07a.rs\x1b]8;;\x07/" => "a.rs/",
+	);
+
+	#[test]
+	fn test_len_widens_emoji() {
+		assert_eq!(len("🤦🏽‍♂️ a", 2), 4);
+		assert_eq!(len("<bold>♂️</>", 2), 2); // ♂(male sign) + ️(variation selector-16)
+		assert_eq!(len("\u{f07b}", 2), 1); // nf-fa-folder is not an emoji.
+	}
+
+	macro_rules! make_truncate_test {
+		( $($name:ident: $markup:expr, $width:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(truncate($markup, $width, 2), $expected);
+				}
+			)*
+		};
+	}
+
+	make_truncate_test!(
+		test_truncate_keeps_short_text: "short", 5 => "short",
+		test_truncate_cuts_ascii: "longer", 4 => "lon…",
+		test_truncate_to_zero: "text", 0 => "",
+		test_truncate_keeps_combining_marks: "e\u{301}e\u{301}e\u{301}", 2 => "e\u{301}…",
+		test_truncate_keeps_devanagari: "मैंमैंमैं", 2 => "मैं…",
+		test_truncate_keeps_zwj_sequence: "🤦🏽‍♂️🤦🏽‍♂️", 3 => "🤦🏽‍♂️…",
+		test_truncate_drops_wide_emoji: "a🤦🏽‍♂️b", 3 => "a…",
+		test_truncate_keeps_tags: "<bold>bold</> text", 4 => "<bold>bol…</>",
+		test_truncate_keeps_tags_after_cut: "ab<bold>cd</>", 2 => "a…<bold></>",
+		test_truncate_skips_hidden_text: "<hidden>hidden</>abcd", 3 => "<hidden>hidden</>ab…",
+		test_truncate_keeps_escaped_tag: "\\<tag>", 3 => "\\<t…",
+		test_truncate_keeps_image: "\x1b_Ga=p,i=1;\x1b\\\x1b[2Cname", 4 => "\x1b_Ga=p,i=1;\x1b\\\x1b[2Cn…",
+		test_truncate_drops_image: "ab\x1b_Ga=p,i=1;\x1b\\\x1b[2Cname", 3 => "ab…",
+		test_truncate_keeps_sixel: "\x1b7\x1bPq#0~\x1b\\\x1b8\x1b[2Cname", 4 => "\x1b7\x1bPq#0~\x1b\\\x1b8\x1b[2Cn…",
+		test_truncate_keeps_link_close: "\x1b]8;;file:///a\x07longer\x1b]8;;\x07", 4 => "\x1b]8;;file:///a\x07lon…\x1b]8;;\x07",
+	);
+
+	macro_rules! make_wrap_test {
+		( $($name:ident: $markup:expr, $width:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(wrap($markup, $width, 2), $expected);
+				}
+			)*
+		};
+	}
+
+	make_wrap_test!(
+		test_wrap_keeps_short_text: "<bold>name</>", 8 => vec!["<bold>name</>"],
+		test_wrap_breaks_plain_text: "abcdefgh", 3 => vec!["abc", "def", "gh"],
+		test_wrap_reopens_tags: "<blue>ab<bold>cd</>ef</>", 3 => vec![
+			"<blue>ab<bold>c</></>",
+			"<blue><bold>d</>ef</>",
+		],
+		test_wrap_ignores_hidden_text: "ab<hidden>xyz</>cd", 2 => vec!["ab<hidden>xyz</>", "cd"],
+		test_wrap_keeps_wide_unit: "a🤦b", 1 => vec!["a", "🤦", "b"],
+		test_wrap_reopens_links: "<bold>\x1b]8;;file:///a\x07abcd\x1b]8;;\x07</>", 2 => vec![
+			"<bold>\x1b]8;;file:///a\x07ab\x1b]8;;\x07</>",
+			"<bold>\x1b]8;;file:///a\x07cd\x1b]8;;\x07</>",
+		],
+	);
+
+	#[test]
+	fn test_link_wraps_markup() {
+		assert_eq!(
+			link("file:///a.rs", "<bold>a.rs</>"),
+			"\x1b]8;;file:///a.rs\x07<bold>a.rs</>\x1b]8;;\x07"
+		);
+	}
 }
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use unicode_width::UnicodeWidthStr;
+
+/// the number of cells that the terminal uses to draw certain glyphs, like
+/// Nerd Font icons, overriding their measured width
+static WIDTHS: OnceLock<HashMap<String, usize>> = OnceLock::new();
+
+/// Set the number of cells that the terminal uses to draw certain glyphs,
+/// applying it to all markup measured from now on.
+///
+/// Nerd Font icons live in the private use areas, which have no defined
+/// width, so they are measured as one cell even when the font draws them over
+/// two. Overriding their width keeps the grid and the table aligned.
+///
+/// # Arguments
+///
+/// * `widths` - the mapping of glyphs to the number of cells they span
+pub fn configure_widths(widths: HashMap<String, usize>) {
+	// The widths are only set once, at startup.
+	let _ = WIDTHS.set(widths);
+}
+
+/// Get the number of cells that the terminal uses to draw the grapheme.
+///
+/// The width set by [`configure_widths`] takes precedence. Emoji span
+/// `emoji_width` cells, and all other graphemes span their width as per
+/// Unicode, which is two cells for wide characters, like CJK ideographs, and
+/// one cell for most others.
+///
+/// # Arguments
+///
+/// * `grapheme` - the grapheme cluster to measure
+/// * `emoji_width` - the number of cells the terminal uses to draw an emoji
+pub(super) fn width(grapheme: &str, emoji_width: usize) -> usize {
+	measure(grapheme, emoji_width, WIDTHS.get())
+}
+
+/// Get the number of cells that the grapheme spans, with the given overrides.
+fn measure(grapheme: &str, emoji_width: usize, widths: Option<&HashMap<String, usize>>) -> usize {
+	if let Some(width) = widths.and_then(|widths| widths.get(grapheme)) {
+		return *width;
+	}
+	if is_emoji(grapheme) {
+		return emoji_width;
+	}
+	grapheme.width()
+}
+
+/// Check whether the grapheme is drawn as an emoji.
+///
+/// This covers pictographs from the supplementary planes and any grapheme that
+/// explicitly requests emoji presentation. Nerd Font icons, which live in the
+/// private use areas, are not emoji.
+fn is_emoji(grapheme: &str) -> bool {
+	grapheme
+		.chars()
+		.next()
+		.is_some_and(|ch| ('\u{1F000}'..='\u{1FAFF}').contains(&ch))
+		|| grapheme.contains('\u{FE0F}')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::measure;
+	use std::collections::HashMap;
+
+	macro_rules! make_measure_test {
+		( $($name:ident: $grapheme:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let widths = HashMap::from([(String::from("\u{e7a8}"), 2)]);
+					assert_eq!(measure($grapheme, 2, Some(&widths)), $expected);
+				}
+			)*
+		};
+	}
+
+	make_measure_test!(
+		test_ascii: "a" => 1,
+		test_combining_mark: "e\u{301}" => 1,
+		test_cjk: "漢" => 2,
+		test_emoji: "🦀" => 2,
+		test_emoji_presentation: "♂\u{fe0f}" => 2,
+		test_nerd_font: "\u{f07b}" => 1, // nf-fa-folder
+		test_nerd_font_override: "\u{e7a8}" => 2, // nf-dev-rust
+	);
+
+	#[test]
+	fn test_emoji_width() {
+		assert_eq!(measure("🦀", 1, None), 1);
+		assert_eq!(measure("🦀", 3, None), 3);
+	}
+}
@@ -2,21 +2,43 @@
 //!
 //! Kitty terminal graphics protocol provides ways to render images in
 //! the terminal. We use this protocol to show icons beyond the standard
-//! collection present in Nerd Fonts.
+//! collection present in Nerd Fonts. Terminals without it, like xterm and
+//! mlterm, can show the same icons as Sixel images. The same protocols show
+//! thumbnails of PNG, JPEG and WebP images in place of their icons, and draw
+//! the SVG icons bundled with `pls`.
 //!
-//! The public interface of the module consists of five functions:
+//! Since terminals differ in their support for such features, the module also
+//! keeps a registry of the quirks of known terminals, as [`Quirks`], and
+//! detects whether the background of the terminal is dark or light.
 //!
+//! The public interface of the module consists of thirteen functions:
+//!
+//! * [`builtin_icon`]
 //! * [`compute_hash`]
-//! * [`is_supported`]
+//! * [`detect_background`]
+//! * [`detect_graphics`]
+//! * [`detect_sixel`]
 //! * [`render_image`]
+//! * [`render_sixel`]
 //! * [`send_image`]
 //! * [`strip_image`]
+//! * [`get_builtin_rgba`]
+//! * [`get_raster_rgba`]
 //! * [`get_rgba`]
+//! * [`get_thumbnail`]
 
+mod builtin;
 mod hash;
 mod kitty;
+mod sixel;
 mod svg;
+mod term;
+mod thumb;
 
+pub use builtin::builtin_icon;
 pub use hash::compute_hash;
-pub use kitty::{is_supported, render_image, send_image, strip_image};
-pub use svg::get_rgba;
+pub use kitty::{detect_graphics, render_image, send_image, strip_image};
+pub use sixel::render_sixel;
+pub use svg::{get_builtin_rgba, get_rgba};
+pub use term::{detect_background, detect_sixel, QuirkOverrides, Quirks};
+pub use thumb::{get_raster_rgba, get_thumbnail};
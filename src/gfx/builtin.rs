@@ -0,0 +1,80 @@
+/// Include the SVG icon with the given name from the `icons` directory.
+macro_rules! icon {
+	($name:literal) => {
+		($name, include_bytes!(concat!("icons/", $name, ".svg")))
+	};
+}
+
+/// the SVG icons bundled with `pls`, keyed by the names of the icons in the
+/// `icons` section of the config
+///
+/// The icons are compiled into the binary, so they are paged in from the
+/// executable only when read, and are rasterized only when first drawn. Brand
+/// icons are not bundled, so they keep their glyphs.
+const BUILTIN_ICONS: [(&str, &[u8]); 24] = [
+	// pls
+	icon!("pls"),
+	icon!("missing"),
+	icon!("exec"),
+	// Node types
+	icon!("file"),
+	icon!("dir"),
+	icon!("symlink"),
+	icon!("junction"),
+	icon!("reparse"),
+	icon!("fifo"),
+	icon!("socket"),
+	icon!("char_device"),
+	icon!("block_device"),
+	icon!("process"),
+	// Generic
+	icon!("audio"),
+	icon!("binary"),
+	icon!("config"),
+	icon!("image"),
+	icon!("json"),
+	icon!("lock"),
+	icon!("package"),
+	icon!("shell"),
+	icon!("source"),
+	icon!("text"),
+	icon!("video"),
+];
+
+/// Get the SVG data of the bundled icon with the given name, if there is one.
+pub fn builtin_icon(name: &str) -> Option<&'static [u8]> {
+	BUILTIN_ICONS
+		.iter()
+		.find(|(icon_name, _)| *icon_name == name)
+		.map(|(_, data)| *data)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{builtin_icon, BUILTIN_ICONS};
+	use crate::config::Conf;
+	use crate::gfx::svg::rasterize;
+
+	#[test]
+	fn test_builtin_icons_are_named_after_icons() {
+		let conf = Conf::default();
+		for (name, _) in BUILTIN_ICONS {
+			assert!(conf.icons.contains_key(name), "{name} is not an icon");
+		}
+	}
+
+	#[test]
+	fn test_builtin_icons_render() {
+		for (name, data) in BUILTIN_ICONS {
+			let rgba = rasterize(data, 16).unwrap();
+			assert_eq!(rgba.len(), 16 * 16 * 4);
+			assert!(rgba.iter().any(|byte| *byte != 0), "{name} is blank");
+		}
+	}
+
+	#[test]
+	fn test_unknown_icon() {
+		assert!(builtin_icon("dir").is_some());
+		assert!(builtin_icon("rust").is_none());
+	}
+}
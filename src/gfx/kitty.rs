@@ -1,56 +1,52 @@
 use crate::exc::Exc;
 use crate::PLS;
 use base64::prelude::*;
+#[cfg(unix)]
 use crossterm::terminal::*;
 use log::debug;
 use regex::Regex;
-use std::env;
 use std::sync::LazyLock;
 
-const CHUNK_SIZE: usize = 4096;
-
-static KITTY_IMAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b_G.*?\x1b\\").unwrap());
+static KITTY_IMAGE: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"\x1b_G.*?\x1b\\|\x1b7\x1bP.*?\x1b\\\x1b8").unwrap());
 static IMAGE_ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"i=(?P<id>\d+)").unwrap());
 
-/// Check if the terminal supports Kitty's terminal graphics protocol.
-///
-/// Since Kitty support is restricted to a handful of terminals, all of
-/// which can be easily and reliably detected, we use that to determine
-/// if the terminal supports graphics.
-///
-/// Additionally, testing for Kitty support using a CSI sequence is
-/// unreliable and breaks down in some cases like the macOS Terminal or
-/// `to-html`.
-pub fn is_supported() -> bool {
-	// Detect Kitty by the `TERM` or `TERMINAL` environment variables.
-	for env_var in ["TERM", "TERMINAL"] {
-		if let Ok(env_val) = env::var(env_var) {
-			let env_val = env_val.to_ascii_lowercase();
-			if env_val.contains("kitty") {
-				debug!("Detected Kitty terminal.");
-				return true;
-			}
-		}
-	}
-
-	// Detect WezTerm and Ghostty by the `TERM_PROGRAM` environment variable.
-	if let Ok(term_program) = env::var("TERM_PROGRAM") {
-		if term_program == "WezTerm" {
-			debug!("Detected WezTerm terminal.");
-			return true;
-		} else if term_program == "ghostty" {
-			debug!("Detected Ghostty terminal.");
-			return true;
-		}
-	}
+/// the ID of the 1×1 image sent to ask the terminal whether it supports the
+/// graphics protocol
+const PROBE_ID: u32 = 31;
+/// the time, in milliseconds, to wait for the terminal to answer the probe
+const PROBE_TIMEOUT_MS: u64 = 100;
+
+/// Detect whether the terminal supports Kitty's terminal graphics protocol.
+///
+/// The terminal is asked to validate, but not store, a 1×1 image, followed
+/// by a query for its device attributes (DA1). Terminals that support the
+/// protocol answer the image query first, while all terminals answer DA1, so
+/// the probe does not wait for the timeout in terminals without graphics.
+///
+/// This is only asked of terminals whose support is not known from the
+/// registry of quirks, and only once per run, since the result is kept in
+/// [`Pls`](crate::models::Pls).
+pub fn detect_graphics() -> bool {
+	let query = format!("\x1b_Gi={PROBE_ID},s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\\x1b[c");
+	query_csi(&query, PROBE_TIMEOUT_MS)
+		.map_err(|exc| debug!("Could not probe graphics support: {exc}"))
+		.is_ok_and(|res| graphics_from_response(&res))
+}
 
-	debug!("Graphics not supported.");
-	false
+/// Determine whether the graphics protocol is supported from the response to
+/// the probe, like "\x1b_Gi=31;OK\x1b\\\x1b[?62;22c".
+fn graphics_from_response(res: &str) -> bool {
+	res.find("\x1b_G").is_some_and(|start| {
+		let reply = &res[start + 3..];
+		let reply = reply.split("\x1b\\").next().unwrap_or(reply);
+		reply.starts_with(&format!("i={PROBE_ID};")) && reply.ends_with(";OK")
+	})
 }
 
 /// Send the RGBA data to the terminal and get an ID for the image.
 ///
-/// The image is sent in chunks of 4096 bytes. The last chunk has the
+/// The image is sent in chunks, sized as per the quirks of the terminal. The last chunk has the
 /// `m` parameter set to 0. The terminal then assigns our image an ID,
 /// instead of us determining one.
 ///
@@ -67,8 +63,9 @@ pub fn send_image(hash: u32, size: u8, rgba_data: &[u8]) -> Result<u32, Exc> {
 
 	let encoded = BASE64_STANDARD.encode(rgba_data);
 	let mut iter = encoded.chars().peekable();
+	let chunk_size = PLS.quirks.chunk_size;
 
-	let first_chunk: String = iter.by_ref().take(CHUNK_SIZE).collect();
+	let first_chunk: String = iter.by_ref().take(chunk_size).collect();
 	query.push_str(&format!(
 		"\x1b_G\
 		a=t,I={hash},s={size},v={size},t=d,f=32,m=1;\
@@ -77,7 +74,7 @@ pub fn send_image(hash: u32, size: u8, rgba_data: &[u8]) -> Result<u32, Exc> {
 	));
 
 	while iter.peek().is_some() {
-		let chunk: String = iter.by_ref().take(CHUNK_SIZE).collect();
+		let chunk: String = iter.by_ref().take(chunk_size).collect();
 		query.push_str(&format!("\x1b_Gm=1;{chunk}\x1b\\"));
 	}
 
@@ -104,7 +101,7 @@ pub fn send_image(hash: u32, size: u8, rgba_data: &[u8]) -> Result<u32, Exc> {
 /// * `size` - the size of the image, in pixels
 /// * `count` - the number of times this image has appeared so far
 pub fn render_image(id: u32, size: u8, count: u8) -> String {
-	let cell_height = PLS.window.as_ref().unwrap().cell_height();
+	let cell_height = PLS.window().unwrap().cell_height();
 	let off_y = if cell_height > size {
 		(cell_height - size) / 2
 	} else {
@@ -121,8 +118,8 @@ pub fn render_image(id: u32, size: u8, count: u8) -> String {
 
 /// Strip the image data from the text.
 ///
-/// This function removes the all terminal graphics from the string,
-/// leaving only the text content.
+/// This function removes the all terminal graphics, both Kitty and Sixel
+/// images, from the string, leaving only the text content.
 ///
 /// # Arguments
 ///
@@ -147,7 +144,8 @@ where
 ///
 /// * `query` - the query to perform
 /// * `timeout_ms` - the timeout in milliseconds
-fn query_raw(query: &str, timeout_ms: u64) -> Result<String, Exc> {
+#[cfg(unix)]
+pub(super) fn query_raw(query: &str, timeout_ms: u64) -> Result<String, Exc> {
 	enable_raw_mode().map_err(Exc::Io)?;
 	let res = xterm_query::query_osc(query, timeout_ms).map_err(Exc::Xterm);
 	disable_raw_mode().map_err(Exc::Io)?;
@@ -155,9 +153,62 @@ fn query_raw(query: &str, timeout_ms: u64) -> Result<String, Exc> {
 	res
 }
 
+/// Perform the given CSI query, whose response ends with a letter instead of
+/// a string terminator, in the terminal raw mode.
+///
+/// # Arguments
+///
+/// * `query` - the query to perform
+/// * `timeout_ms` - the timeout in milliseconds
+#[cfg(unix)]
+pub(super) fn query_csi(query: &str, timeout_ms: u64) -> Result<String, Exc> {
+	enable_raw_mode().map_err(Exc::Io)?;
+	let res = xterm_query::query(query, timeout_ms).map_err(Exc::Xterm);
+	disable_raw_mode().map_err(Exc::Io)?;
+
+	res
+}
+
+/// Perform the given query in the terminal raw mode.
+///
+/// Reading the response of the terminal is only supported on Unix, where the
+/// graphics protocol is available.
+#[cfg(not(unix))]
+pub(super) fn query_raw(_query: &str, _timeout_ms: u64) -> Result<String, Exc> {
+	Err(Exc::Other(String::from(
+		"Terminal queries are only supported on Unix.",
+	)))
+}
+
+/// Perform the given CSI query in the terminal raw mode, which is only
+/// supported on Unix.
+#[cfg(not(unix))]
+pub(super) fn query_csi(query: &str, timeout_ms: u64) -> Result<String, Exc> {
+	query_raw(query, timeout_ms)
+}
+
 #[cfg(test)]
 mod tests {
-	use super::strip_image;
+	use super::{graphics_from_response, strip_image};
+
+	macro_rules! make_graphics_test {
+		( $($name:ident: $res:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(graphics_from_response($res), $expected);
+				}
+			)*
+		};
+	}
+
+	make_graphics_test!(
+		test_probe_ok: "\x1b_Gi=31;OK\x1b\\\x1b[?62;22c" => true,
+		test_probe_error: "\x1b_Gi=31;EINVAL:bad format\x1b\\\x1b[?62;22c" => false,
+		test_probe_other_id: "\x1b_Gi=7;OK\x1b\\" => false,
+		test_probe_only_da1: "\x1b[?62;4;22c" => false,
+		test_probe_empty: "" => false,
+	);
 
 	#[test]
 	fn test_remove_image_substrings() {
@@ -0,0 +1,219 @@
+use crate::PLS;
+use std::fmt::Write;
+
+/// the alpha below which a pixel is left transparent, since Sixel graphics
+/// cannot blend pixels with the background
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// the number of levels of each channel in the color palette
+const LEVELS: u8 = 6;
+
+/// Render the RGBA data to the screen as a Sixel image.
+///
+/// Unlike with the Kitty protocol, the image is sent every time it is shown.
+/// The cursor is saved before and restored after drawing the image, since
+/// terminals leave it below the image. Then we move the cursor by as many
+/// cells as the icon width (and a space).
+///
+/// # Arguments
+///
+/// * `rgba_data` - the premultiplied RGBA data of the image
+/// * `size` - the size of the image, in pixels
+pub fn render_sixel(rgba_data: &[u8], size: u8) -> String {
+	let cell_height = PLS.window().unwrap().cell_height();
+	let off_y = if cell_height > size {
+		(cell_height - size) / 2
+	} else {
+		0
+	};
+
+	format!(
+		"\x1b7{}\x1b8\x1b[2C",
+		encode(rgba_data, size.into(), size.into(), off_y.into())
+	)
+}
+
+/// Encode the RGBA data as a Sixel image, wrapped in its DCS sequence.
+///
+/// Colors are reduced to a 6×6×6 palette, which is plenty for icons. The
+/// image is drawn in bands of six rows, one pass per color, with runs of the
+/// same column compressed.
+///
+/// # Arguments
+///
+/// * `rgba_data` - the premultiplied RGBA data of the image
+/// * `width` - the width of the image, in pixels
+/// * `height` - the height of the image, in pixels
+/// * `off_y` - the number of transparent rows to draw above the image, to
+///   center it in the cell
+fn encode(rgba_data: &[u8], width: usize, height: usize, off_y: usize) -> String {
+	let full_height = height + off_y;
+	let pixels: Vec<Option<u8>> = (0..off_y * width)
+		.map(|_| None)
+		.chain(rgba_data.chunks_exact(4).take(width * height).map(register))
+		.collect();
+	let pixel = |x: usize, y: usize| pixels.get(y * width + x).copied().flatten();
+
+	let mut used: Vec<u8> = pixels.iter().flatten().copied().collect();
+	used.sort_unstable();
+	used.dedup();
+
+	// P2 = 1 leaves the pixels that are not drawn transparent.
+	let mut out = format!("\x1bP0;1;0q\"1;1;{width};{full_height}");
+	for reg in &used {
+		let (red, green, blue) = (reg / 36, reg / 6 % 6, reg % 6);
+		let pct = |level: u8| u16::from(level) * 100 / u16::from(LEVELS - 1);
+		let _ = write!(out, "#{reg};2;{};{};{}", pct(red), pct(green), pct(blue));
+	}
+
+	for band in (0..full_height).step_by(6) {
+		let mut first = true;
+		for reg in &used {
+			let mut cols: Vec<u8> = (0..width)
+				.map(|x| {
+					(0..6)
+						.filter(|dy| pixel(x, band + dy) == Some(*reg))
+						.fold(0, |bits, dy| bits | 1 << dy)
+				})
+				.collect();
+			while cols.last() == Some(&0) {
+				cols.pop();
+			}
+			if cols.is_empty() {
+				continue;
+			}
+			if !first {
+				out.push('$'); // Return to the start of the band for the next color.
+			}
+			first = false;
+			let _ = write!(out, "#{reg}");
+			push_runs(&mut out, &cols);
+		}
+		out.push('-');
+	}
+
+	out.push_str("\x1b\\");
+	out
+}
+
+/// Get the palette register for the color of the given pixel, or `None` if
+/// the pixel is transparent.
+///
+/// The channels of the pixel are premultiplied by its alpha, so they must be
+/// divided by it first.
+fn register(pixel: &[u8]) -> Option<u8> {
+	let alpha = pixel[3];
+	if alpha < ALPHA_THRESHOLD {
+		return None;
+	}
+	let level = |channel: u8| {
+		let straight = u16::from(channel) * 255 / u16::from(alpha);
+		let max = u16::from(LEVELS - 1);
+		(straight.min(255) * max + 127) / 255
+	};
+	let idx = level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]);
+	Some(idx as u8)
+}
+
+/// Append the given columns of sixels to the output, compressing runs of four
+/// or more of the same sixel with the repeat introducer.
+fn push_runs(out: &mut String, cols: &[u8]) {
+	let mut iter = cols.iter().peekable();
+	while let Some(&bits) = iter.next() {
+		let mut count = 1;
+		while iter.next_if_eq(&&bits).is_some() {
+			count += 1;
+		}
+		let ch = char::from(0x3f + bits);
+		if count >= 4 {
+			let _ = write!(out, "!{count}{ch}");
+		} else {
+			(0..count).for_each(|_| out.push(ch));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{encode, push_runs, register};
+
+	const RED: [u8; 4] = [255, 0, 0, 255];
+	const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+	macro_rules! make_register_test {
+		( $($name:ident: $pixel:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(register(&$pixel), $expected);
+				}
+			)*
+		};
+	}
+
+	make_register_test!(
+		test_register_red: RED => Some(180),
+		test_register_white: [255, 255, 255, 255] => Some(215),
+		test_register_black: [0, 0, 0, 255] => Some(0),
+		test_register_transparent: CLEAR => None,
+		test_register_faint: [100, 100, 100, 100] => None,
+		test_register_premultiplied: [64, 0, 0, 128] => Some(72),
+	);
+
+	macro_rules! make_runs_test {
+		( $($name:ident: $cols:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let mut out = String::new();
+					push_runs(&mut out, &$cols);
+					assert_eq!(out, $expected);
+				}
+			)*
+		};
+	}
+
+	make_runs_test!(
+		test_runs_short: [1, 1, 1, 2] => "@@@A",
+		test_runs_long: [63, 63, 63, 63, 63, 0] => "!5~?",
+		test_runs_empty: [] => "",
+	);
+
+	#[test]
+	fn test_encode_single_pixel() {
+		assert_eq!(
+			encode(&RED, 1, 1, 0),
+			"\x1bP0;1;0q\"1;1;1;1#180;2;100;0;0#180@-\x1b\\"
+		);
+	}
+
+	#[test]
+	fn test_encode_offsets_rows() {
+		assert_eq!(
+			encode(&RED, 1, 1, 2),
+			"\x1bP0;1;0q\"1;1;1;3#180;2;100;0;0#180C-\x1b\\"
+		);
+	}
+
+	#[test]
+	fn test_encode_transparent() {
+		let rgba = [CLEAR, CLEAR].concat();
+		assert_eq!(encode(&rgba, 2, 1, 0), "\x1bP0;1;0q\"1;1;2;1-\x1b\\");
+	}
+
+	#[test]
+	fn test_encode_colors_and_bands() {
+		let white = [255, 255, 255, 255];
+		let mut rgba = [RED, white].concat();
+		rgba.extend([RED; 12].concat());
+		// 2×7 image: a red and a white pixel, then six rows of red.
+		assert_eq!(
+			encode(&rgba, 2, 7, 0),
+			"\x1bP0;1;0q\"1;1;2;7\
+			#180;2;100;0;0#215;2;100;100;100\
+			#180~}$#215?@-\
+			#180@@-\
+			\x1b\\"
+		);
+	}
+}
@@ -3,7 +3,7 @@ use log::debug;
 use resvg::tiny_skia::{Pixmap, Transform};
 use resvg::usvg::{Options, Tree};
 use std::env;
-use std::fs::{read_to_string, File};
+use std::fs::{read, File};
 use std::io::{Read, Result as IoResult, Write};
 use std::path::Path;
 
@@ -19,6 +19,31 @@ use std::path::Path;
 /// * `path` - the path to the SVG file
 /// * `size` - the size at which to render the icon
 pub fn get_rgba(id: u32, path: &Path, size: u8) -> Option<Vec<u8>> {
+	cached(id, || {
+		let svg_data = read(path).map_err(Exc::Io)?;
+		rasterize(&svg_data, size)
+	})
+}
+
+/// Get the RGBA data for a bundled SVG icon at a given size.
+///
+/// This function caches the RGBA data in the same way as [`get_rgba`].
+///
+/// # Arguments
+///
+/// * `id` - the unique ID of the image
+/// * `svg_data` - the contents of the SVG icon
+/// * `size` - the size at which to render the icon
+pub fn get_builtin_rgba(id: u32, svg_data: &[u8], size: u8) -> Option<Vec<u8>> {
+	cached(id, || rasterize(svg_data, size))
+}
+
+/// Get the RGBA data of the image with the given ID from the cache, computing
+/// and caching it if not present.
+pub(super) fn cached<F>(id: u32, compute: F) -> Option<Vec<u8>>
+where
+	F: FnOnce() -> Result<Vec<u8>, Exc>,
+{
 	let cache_file = env::var("PLS_CACHE")
 		.ok()
 		.map(|cache| Path::new(&cache).join("icons").join(id.to_string()));
@@ -29,7 +54,7 @@ pub fn get_rgba(id: u32, path: &Path, size: u8) -> Option<Vec<u8>> {
 		}
 	}
 
-	let rgba_data = match compute_rgba(path, size) {
+	let rgba_data = match compute() {
 		Ok(rgba_data) => Some(rgba_data),
 		Err(exc) => {
 			debug!("{}", exc);
@@ -46,14 +71,11 @@ pub fn get_rgba(id: u32, path: &Path, size: u8) -> Option<Vec<u8>> {
 	rgba_data
 }
 
-/// Compute the RGBA data for a given SVG file at a given size.
-fn compute_rgba(path: &Path, size: u8) -> Result<Vec<u8>, Exc> {
-	// Read SVG file
-	let svg_data = read_to_string(path).map_err(Exc::Io)?;
-
+/// Compute the RGBA data for the given SVG data at a given size.
+pub(super) fn rasterize(svg_data: &[u8], size: u8) -> Result<Vec<u8>, Exc> {
 	// Create a default options struct with the target dimensions
 	let opt = Options::default();
-	let rtree = Tree::from_str(&svg_data, &opt).map_err(Exc::Svg)?;
+	let rtree = Tree::from_data(svg_data, &opt).map_err(|err| Exc::Svg(Box::new(err)))?;
 
 	// Create a pixmap with the desired dimensions
 	let mut pixmap =
@@ -0,0 +1,409 @@
+use crate::config::ConfMan;
+use crate::enums::Background;
+use crate::gfx::kitty::{query_csi, query_raw};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+
+/// Represents the capabilities and quirks of a terminal emulator.
+///
+/// Terminals differ in which escape sequences they understand and how wide
+/// they draw certain glyphs. Instead of sprinkling checks for each terminal
+/// across the codebase, these are collected here and looked up once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quirks {
+	/// whether the terminal supports Kitty's terminal graphics protocol, or
+	/// `None` if this is not known and must be asked of the terminal
+	pub graphics: Option<bool>,
+	/// whether the terminal supports Sixel graphics, or `None` if this is not
+	/// known and must be asked of the terminal
+	pub sixel: Option<bool>,
+	/// the number of base64 bytes sent in each chunk of graphics data
+	pub chunk_size: usize,
+	/// the number of cells the terminal uses to draw an emoji
+	pub emoji_width: usize,
+	/// whether the terminal supports OSC 8 hyperlinks
+	pub hyperlinks: bool,
+}
+
+impl Default for Quirks {
+	fn default() -> Self {
+		Self {
+			graphics: None,
+			sixel: None,
+			chunk_size: 4096,
+			emoji_width: 2,
+			hyperlinks: false,
+		}
+	}
+}
+
+/// Represents changes to the quirks of a terminal emulator.
+///
+/// These are used both for the built-in registry and for the
+/// `terminal_overrides` in the config files. Fields that are not set retain
+/// their existing values.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuirkOverrides {
+	pub graphics: Option<bool>,
+	pub sixel: Option<bool>,
+	pub chunk_size: Option<usize>,
+	pub emoji_width: Option<usize>,
+	pub hyperlinks: Option<bool>,
+}
+
+impl Quirks {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Get the quirks of the terminal in which `pls` is running.
+	///
+	/// The terminal is detected from the environment, and its entry in the
+	/// built-in registry is overridden by its entry in the `terminal_overrides`
+	/// section of the config files.
+	pub fn detect(conf_man: &ConfMan) -> Self {
+		let Some(term) = detect_term() else {
+			debug!("Could not detect terminal.");
+			return Self::default();
+		};
+		debug!("Detected terminal {term:?}.");
+
+		let overrides = conf_man
+			.base
+			.extract_inner::<HashMap<String, QuirkOverrides>>("terminal_overrides")
+			.unwrap_or_else(|err| {
+				warn!("Could not read terminal overrides: {err}");
+				HashMap::new()
+			});
+		Self::for_term(&term, overrides.get(&term))
+	}
+
+	/// Get the quirks of the given terminal, applying the given overrides on
+	/// top of the built-in registry.
+	fn for_term(term: &str, overrides: Option<&QuirkOverrides>) -> Self {
+		let mut quirks = Self::default();
+		if let Some(builtin) = builtin(term) {
+			quirks.apply(&builtin);
+		}
+		if let Some(overrides) = overrides {
+			quirks.apply(overrides);
+		}
+		quirks
+	}
+
+	// =======
+	// Private
+	// =======
+
+	/// Replace the quirks that are set in the given overrides.
+	fn apply(&mut self, overrides: &QuirkOverrides) {
+		if let Some(graphics) = overrides.graphics {
+			self.graphics = Some(graphics);
+		}
+		if let Some(sixel) = overrides.sixel {
+			self.sixel = Some(sixel);
+		}
+		if let Some(chunk_size) = overrides.chunk_size {
+			self.chunk_size = chunk_size.max(1);
+		}
+		if let Some(emoji_width) = overrides.emoji_width {
+			self.emoji_width = emoji_width;
+		}
+		if let Some(hyperlinks) = overrides.hyperlinks {
+			self.hyperlinks = hyperlinks;
+		}
+	}
+}
+
+/// the time, in milliseconds, to wait for the terminal to report its
+/// background color, after which it is assumed to not support the query
+const BACKGROUND_TIMEOUT_MS: u64 = 100;
+
+/// Detect whether the background of the terminal is dark or light.
+///
+/// The background is determined from these sources, in order:
+///
+/// * the `COLORFGBG` environment variable, set by terminals like rxvt and
+///   Konsole
+/// * the response to the OSC 11 query for the background color
+///
+/// The Linux console, which never answers the query, is not asked, so that
+/// it does not wait for the timeout.
+pub fn detect_background() -> Option<Background> {
+	if let Some(background) = env::var("COLORFGBG")
+		.ok()
+		.and_then(|val| background_from_colorfgbg(&val))
+	{
+		return Some(background);
+	}
+	if matches!(env::var("TERM").as_deref(), Ok("linux" | "dumb")) {
+		return None;
+	}
+	query_raw("\x1b]11;?\x07", BACKGROUND_TIMEOUT_MS)
+		.map_err(|exc| debug!("Could not query background color: {exc}"))
+		.ok()
+		.and_then(|res| background_from_osc(&res))
+}
+
+/// the time, in milliseconds, to wait for the terminal to report its device
+/// attributes, after which it is assumed to not support Sixel graphics
+const ATTRIBUTES_TIMEOUT_MS: u64 = 100;
+
+/// Detect whether the terminal supports Sixel graphics.
+///
+/// Terminals that support Sixel graphics list the attribute 4 in their
+/// response to the primary device attributes (DA1) query. This is only asked
+/// of terminals whose support is not known from the registry of quirks.
+pub fn detect_sixel() -> bool {
+	if matches!(env::var("TERM").as_deref(), Ok("linux" | "dumb")) {
+		return false;
+	}
+	query_csi("\x1b[c", ATTRIBUTES_TIMEOUT_MS)
+		.map_err(|exc| debug!("Could not query device attributes: {exc}"))
+		.is_ok_and(|res| sixel_from_da1(&res))
+}
+
+/// Determine whether Sixel graphics are supported from the response to the
+/// DA1 query, like "\x1b[?62;4;22c".
+///
+/// The first number is the conformance level of the terminal, and the rest
+/// are the attributes that it supports.
+fn sixel_from_da1(res: &str) -> bool {
+	let Some((_, attrs)) = res.split_once("[?") else {
+		return false;
+	};
+	attrs
+		.trim_end_matches('c')
+		.split(';')
+		.skip(1)
+		.any(|attr| attr == "4")
+}
+
+/// Determine the background from the value of `COLORFGBG`, like "15;0".
+///
+/// The last field is the index of the background color in the 16-color
+/// palette, of which 7 (white) and 9 to 15 (the bright colors other than
+/// gray) are light.
+fn background_from_colorfgbg(val: &str) -> Option<Background> {
+	let idx: u8 = val.rsplit(';').next()?.parse().ok()?;
+	let background = match idx {
+		7 | 9..=15 => Background::Light,
+		0..=6 | 8 => Background::Dark,
+		_ => return None,
+	};
+	Some(background)
+}
+
+/// Determine the background from the response to the OSC 11 query, like
+/// "\x1b]11;rgb:ffff/ffff/ffff\x07".
+///
+/// Each channel has 1 to 4 hexadecimal digits, scaled to its own maximum.
+fn background_from_osc(res: &str) -> Option<Background> {
+	let (_, spec) = res.split_once("rgb:")?;
+	let spec = spec.trim_end_matches(['\x07', '\x1b', '\\']);
+	let mut channels = spec.split('/').map(|hex| {
+		let max = 16f32.powi(hex.len() as i32) - 1.0;
+		u16::from_str_radix(hex, 16)
+			.ok()
+			.filter(|_| (1..=4).contains(&hex.len()))
+			.map(|val| f32::from(val) / max)
+	});
+	let rgb = (channels.next()??, channels.next()??, channels.next()??);
+	Some(Background::from_rgb(rgb))
+}
+
+/// Get the built-in quirks of the terminal with the given name.
+///
+/// Support for graphics is recorded for the terminals that are known to have
+/// it or lack it, since asking the terminal is slower and breaks down in some
+/// cases like the macOS Terminal. Others, like Konsole, which gained the Kitty
+/// protocol in recent versions, and xterm, whose Sixel support depends on how
+/// it was built and started, are asked with [`detect_graphics`] and
+/// [`detect_sixel`].
+///
+/// [`detect_graphics`]: crate::gfx::detect_graphics
+fn builtin(term: &str) -> Option<QuirkOverrides> {
+	let (graphics, sixel, hyperlinks, emoji_width) = match term {
+		"kitty" | "ghostty" => (Some(true), Some(false), true, None),
+		"wezterm" => (Some(true), Some(true), true, None),
+		"konsole" => (None, Some(true), true, None), // Older versions lack Kitty graphics.
+		"iterm" | "foot" => (Some(false), Some(true), true, None),
+		"vscode" => (Some(false), None, true, None), // Sixel support is a setting.
+		"alacritty" => (Some(false), Some(false), true, None),
+		"mlterm" => (Some(false), Some(true), false, None),
+		"apple_terminal" => (Some(false), Some(false), false, None),
+		"linux" => (Some(false), Some(false), false, Some(1)), // The Linux console has no wide glyphs.
+		_ => return None,
+	};
+	Some(QuirkOverrides {
+		graphics,
+		sixel,
+		hyperlinks: Some(hyperlinks),
+		emoji_width,
+		..QuirkOverrides::default()
+	})
+}
+
+/// Detect the terminal emulator from the environment.
+///
+/// This returns a short lowercase name for the terminal, which is the key
+/// used for it in the registry and in `terminal_overrides`.
+fn detect_term() -> Option<String> {
+	term_from_env(
+		env::var("TERM_PROGRAM").ok().as_deref(),
+		env::var("TERM").ok().as_deref(),
+		env::var("TERMINAL").ok().as_deref(),
+	)
+}
+
+/// Determine the name of the terminal from the values of the `TERM_PROGRAM`,
+/// `TERM` and `TERMINAL` environment variables.
+fn term_from_env(
+	term_program: Option<&str>,
+	term: Option<&str>,
+	terminal: Option<&str>,
+) -> Option<String> {
+	// Kitty is detected by the `TERM` or `TERMINAL` environment variables.
+	if [term, terminal]
+		.iter()
+		.flatten()
+		.any(|val| val.to_ascii_lowercase().contains("kitty"))
+	{
+		return Some(String::from("kitty"));
+	}
+
+	if let Some(term_program) = term_program.filter(|val| !val.is_empty()) {
+		let name = match term_program {
+			"WezTerm" => "wezterm",
+			"iTerm.app" => "iterm",
+			"Apple_Terminal" => "apple_terminal",
+			other => return Some(other.to_ascii_lowercase()),
+		};
+		return Some(String::from(name));
+	}
+
+	match term? {
+		"alacritty" => Some(String::from("alacritty")),
+		"linux" => Some(String::from("linux")),
+		"mlterm" => Some(String::from("mlterm")),
+		term if term.starts_with("foot") => Some(String::from("foot")),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		background_from_colorfgbg, background_from_osc, sixel_from_da1, term_from_env,
+		QuirkOverrides, Quirks,
+	};
+	use crate::enums::Background;
+
+	macro_rules! make_term_test {
+		( $($name:ident: $term_program:expr, $term:expr, $terminal:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let term = term_from_env($term_program, $term, $terminal);
+					assert_eq!(term.as_deref(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_term_test!(
+		test_detects_kitty_by_term: None, Some("xterm-kitty"), None => Some("kitty"),
+		test_detects_kitty_by_terminal: Some("tmux"), Some("screen"), Some("kitty") => Some("kitty"),
+		test_detects_wezterm: Some("WezTerm"), Some("xterm-256color"), None => Some("wezterm"),
+		test_detects_iterm: Some("iTerm.app"), Some("xterm-256color"), None => Some("iterm"),
+		test_lowercases_unknown_program: Some("Hyper"), None, None => Some("hyper"),
+		test_detects_by_term: None, Some("foot-extra"), None => Some("foot"),
+		test_detects_mlterm: None, Some("mlterm"), None => Some("mlterm"),
+		test_detects_nothing: None, Some("xterm-256color"), None => None,
+	);
+
+	#[test]
+	fn test_unknown_terminal_uses_defaults() {
+		assert_eq!(Quirks::for_term("hyper", None), Quirks::default());
+	}
+
+	#[test]
+	fn test_builtin_quirks_apply() {
+		let quirks = Quirks::for_term("kitty", None);
+		assert_eq!(quirks.graphics, Some(true));
+		assert!(quirks.hyperlinks);
+		assert_eq!(quirks.chunk_size, 4096);
+	}
+
+	#[test]
+	fn test_overrides_replace_builtin_quirks() {
+		let overrides = QuirkOverrides {
+			graphics: Some(false),
+			chunk_size: Some(1024),
+			..QuirkOverrides::default()
+		};
+		let quirks = Quirks::for_term("wezterm", Some(&overrides));
+		assert_eq!(quirks.graphics, Some(false));
+		assert!(quirks.hyperlinks);
+		assert_eq!(quirks.chunk_size, 1024);
+		assert_eq!(quirks.emoji_width, 2);
+	}
+
+	#[test]
+	fn test_overrides_set_unknown_sixel() {
+		let overrides = QuirkOverrides {
+			sixel: Some(true),
+			..QuirkOverrides::default()
+		};
+		assert_eq!(Quirks::for_term("vscode", None).sixel, None);
+		assert_eq!(Quirks::for_term("konsole", None).graphics, None);
+		assert_eq!(
+			Quirks::for_term("vscode", Some(&overrides)).sixel,
+			Some(true)
+		);
+	}
+
+	macro_rules! make_sixel_test {
+		( $($name:ident: $res:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(sixel_from_da1($res), $expected);
+				}
+			)*
+		};
+	}
+
+	make_sixel_test!(
+		test_da1_with_sixel: "\x1b[?62;4;6;22c" => true,
+		test_da1_without_sixel: "\x1b[?62;6;22c" => false,
+		test_da1_level_is_not_attribute: "\x1b[?4;6c" => false,
+		test_da1_vt100: "\x1b[?1;2c" => false,
+		test_da1_garbage: "" => false,
+	);
+
+	macro_rules! make_background_test {
+		( $($name:ident: $parse:ident, $val:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!($parse($val), $expected);
+				}
+			)*
+		};
+	}
+
+	make_background_test!(
+		test_colorfgbg_dark: background_from_colorfgbg, "15;0" => Some(Background::Dark),
+		test_colorfgbg_light: background_from_colorfgbg, "0;15" => Some(Background::Light),
+		test_colorfgbg_three_fields: background_from_colorfgbg, "0;default;7" => Some(Background::Light),
+		test_colorfgbg_default: background_from_colorfgbg, "15;default" => None,
+		test_osc_white: background_from_osc, "\x1b]11;rgb:ffff/ffff/ffff\x07" => Some(Background::Light),
+		test_osc_black_st: background_from_osc, "\x1b]11;rgb:0000/0000/0000\x1b\\" => Some(Background::Dark),
+		test_osc_short_channels: background_from_osc, "\x1b]11;rgb:fd/f6/e3\x07" => Some(Background::Light),
+		test_osc_dark_blue: background_from_osc, "\x1b]11;rgb:0000/2b2b/3636\x07" => Some(Background::Dark),
+		test_osc_garbage: background_from_osc, "\x1b]11;?\x07" => None,
+	);
+}
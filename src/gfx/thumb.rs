@@ -0,0 +1,164 @@
+use crate::exc::Exc;
+use crate::gfx::svg::cached;
+use log::debug;
+use resvg::tiny_skia::{FilterQuality, IntSize, Pixmap, PixmapPaint, Transform};
+use std::fs;
+use std::path::Path;
+
+/// the size, in bytes, above which images are not decoded for thumbnails, to
+/// keep listings of large photos fast
+const MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Get the RGBA data of a thumbnail of the given PNG, JPEG or WebP image.
+///
+/// The image is scaled down, keeping its aspect ratio, to fit in a square of
+/// the given size, and centered in it. Unlike SVG icons, thumbnails are not
+/// cached on disk, since the images they preview change often.
+///
+/// # Arguments
+///
+/// * `path` - the path to the image
+/// * `size` - the size of the square to fit the image in, in pixels
+pub fn get_thumbnail(path: &Path, size: u8) -> Option<Vec<u8>> {
+	match compute_thumbnail(path, size) {
+		Ok(rgba_data) => Some(rgba_data),
+		Err(exc) => {
+			debug!("{}", exc);
+			None
+		}
+	}
+}
+
+/// Get the RGBA data for a PNG, JPEG or WebP icon at a given size.
+///
+/// The icon is fitted in the same way as thumbnails, but its RGBA data is
+/// cached in the same way as that of SVG icons, since icons rarely change.
+///
+/// # Arguments
+///
+/// * `id` - the unique ID of the image
+/// * `path` - the path to the icon
+/// * `size` - the size at which to render the icon
+pub fn get_raster_rgba(id: u32, path: &Path, size: u8) -> Option<Vec<u8>> {
+	cached(id, || compute_thumbnail(path, size))
+}
+
+/// Compute the RGBA data of a thumbnail of the given image.
+fn compute_thumbnail(path: &Path, size: u8) -> Result<Vec<u8>, Exc> {
+	if fs::metadata(path).map_err(Exc::Io)?.len() > MAX_BYTES {
+		return Err(Exc::Other(format!(
+			"{} is too large for a thumbnail.",
+			path.display()
+		)));
+	}
+	let data = fs::read(path).map_err(Exc::Io)?;
+	let image = decode(&data)?;
+
+	let mut pixmap =
+		Pixmap::new(size.into(), size.into()).ok_or(Exc::Other(String::from("Pixmap was None")))?;
+	let (scale, off_x, off_y) = fit(image.width(), image.height(), size);
+	pixmap.draw_pixmap(
+		0,
+		0,
+		image.as_ref(),
+		&PixmapPaint {
+			quality: FilterQuality::Bicubic,
+			..PixmapPaint::default()
+		},
+		Transform::from_scale(scale, scale).post_translate(off_x, off_y),
+		None,
+	);
+	Ok(pixmap.data().to_vec())
+}
+
+/// Decode the given image data into a pixmap.
+///
+/// The format is detected from the data, so images with the wrong extension
+/// are decoded too. The `image` crate yields straight RGBA, which is
+/// premultiplied for drawing with `tiny-skia`.
+fn decode(data: &[u8]) -> Result<Pixmap, Exc> {
+	let image = image::load_from_memory(data)
+		.map_err(|err| Exc::Other(err.to_string()))?
+		.into_rgba8();
+	let size = IntSize::from_wh(image.width(), image.height())
+		.ok_or(Exc::Other(String::from("Image has no pixels")))?;
+
+	let mut rgba = image.into_raw();
+	for pixel in rgba.chunks_exact_mut(4) {
+		let alpha = u16::from(pixel[3]);
+		for channel in &mut pixel[..3] {
+			*channel = ((u16::from(*channel) * alpha + 127) / 255) as u8;
+		}
+	}
+	Pixmap::from_vec(rgba, size).ok_or(Exc::Other(String::from("Pixmap was None")))
+}
+
+/// Get the scale and offsets that fit an image of the given dimensions in a
+/// square of the given size, centered.
+///
+/// Images that are smaller than the square are not scaled up.
+fn fit(width: u32, height: u32, size: u8) -> (f32, f32, f32) {
+	let size = f32::from(size);
+	let scale = (size / width.max(height) as f32).min(1.0);
+	let off_x = (size - width as f32 * scale) / 2.0;
+	let off_y = (size - height as f32 * scale) / 2.0;
+	(scale, off_x.round(), off_y.round())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{compute_thumbnail, decode, fit};
+	use std::path::Path;
+
+	macro_rules! make_fit_test {
+		( $($name:ident: $width:expr, $height:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(fit($width, $height, 16), $expected);
+				}
+			)*
+		};
+	}
+
+	make_fit_test!(
+		test_fit_square: 64, 64 => (0.25, 0.0, 0.0),
+		test_fit_wide: 64, 32 => (0.25, 0.0, 4.0),
+		test_fit_tall: 32, 64 => (0.25, 4.0, 0.0),
+		test_fit_small: 8, 4 => (1.0, 4.0, 6.0),
+	);
+
+	#[test]
+	fn test_decode_jpeg() {
+		let pixmap = decode(include_bytes!("fixtures/red.jpg")).unwrap();
+		assert_eq!((pixmap.width(), pixmap.height()), (32, 16));
+		let pixel = pixmap.pixel(0, 0).unwrap();
+		assert!(pixel.red() > 240 && pixel.green() < 16 && pixel.blue() < 16);
+		assert_eq!(pixel.alpha(), 255);
+	}
+
+	#[test]
+	fn test_thumbnail_of_jpeg() {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/gfx/fixtures/red.jpg");
+		let rgba_data = compute_thumbnail(&path, 16).unwrap();
+		assert_eq!(rgba_data.len(), 16 * 16 * 4);
+		// The wide image is centered vertically, leaving the top rows empty.
+		assert_eq!(rgba_data[3], 0);
+		let center = (8 * 16 + 8) * 4;
+		assert!(rgba_data[center] > 240 && rgba_data[center + 3] == 255);
+	}
+
+	#[test]
+	fn test_decode_webp() {
+		let pixmap = decode(include_bytes!("fixtures/blue.webp")).unwrap();
+		assert_eq!((pixmap.width(), pixmap.height()), (16, 32));
+		let pixel = pixmap.pixel(0, 0).unwrap();
+		assert_eq!((pixel.red(), pixel.green(), pixel.blue()), (0, 0, 255));
+		assert_eq!(pixel.alpha(), 255);
+	}
+
+	#[test]
+	fn test_decode_garbage() {
+		assert!(decode(b"not an image").is_err());
+	}
+}
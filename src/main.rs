@@ -10,35 +10,80 @@ mod output;
 mod traits;
 mod utils;
 
-use crate::gfx::is_supported;
+use crate::config::Args;
+use crate::enums::GfxProtocol;
+use crate::gfx::{detect_graphics, detect_sixel, Quirks};
 use crate::models::Pls;
-use crate::models::Window;
+use crate::models::{View, Window};
+use crate::utils::locale::is_utf8;
 
 use log::debug;
-use std::sync::LazyLock;
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+use std::sync::{LazyLock, RwLock};
 
 static PLS: LazyLock<Pls> = LazyLock::new(|| {
+	let (args, conf_man) = Args::new();
+	let view = RwLock::new(View::new(&args));
+
+	// Deterministic output must not depend on the terminal or the locale.
+	if args.deterministic.is_some() {
+		return Pls {
+			conf_man,
+			args,
+			quirks: Quirks::default(),
+			gfx: None,
+			supports_utf8: true,
+			window: RwLock::new(Some(Window::fixed())),
+			view,
+		};
+	}
+
+	let quirks = Quirks::detect(&conf_man);
 	let window = Window::try_new();
-	let supports_gfx = match &window {
-		Some(win) if win.ws_xpixel > 0 && win.ws_ypixel > 0 => is_supported(),
-		_ => false,
+	// The terminal answers probes on STDIN, so it can only be asked if STDIN
+	// is the terminal.
+	let can_probe = io::stdin().is_terminal();
+	// Images cannot be clipped to fit in a popup, so they are not drawn there,
+	// and they are not text, so they are not drawn with `--ascii` either.
+	let gfx = match &window {
+		Some(win)
+			if args.popup.is_none() && !args.ascii && win.ws_xpixel > 0 && win.ws_ypixel > 0 =>
+		{
+			if quirks
+				.graphics
+				.unwrap_or_else(|| can_probe && detect_graphics())
+			{
+				Some(GfxProtocol::Kitty)
+			} else if quirks.sixel.unwrap_or_else(|| can_probe && detect_sixel()) {
+				Some(GfxProtocol::Sixel)
+			} else {
+				None
+			}
+		}
+		_ => None,
 	};
 
 	Pls {
-		supports_gfx,
-		window,
-		..Pls::default()
+		conf_man,
+		args,
+		quirks,
+		gfx,
+		supports_utf8: is_utf8(),
+		window: RwLock::new(window),
+		view,
 	}
 });
 
 /// Create a `Pls` instance and immediately delegate to it.
 ///
 /// This is the entry point of the application.
-fn main() {
+fn main() -> ExitCode {
 	env_logger::init();
 	debug!("Hello!");
 
-	PLS.cmd();
+	let code = PLS.cmd();
 
 	debug!("Bye!");
+	code
 }
@@ -1,13 +1,59 @@
+mod acl;
+mod archive;
+mod attrs;
+mod code_owners;
+mod daemon;
+mod decorator;
+mod fix_script;
+mod history;
+mod ignores;
+mod ls_colors;
+mod magic;
+mod memo;
 mod node;
 mod owner;
 mod perm;
 mod pls;
+mod popup;
+mod quota;
+mod raw_mode;
+mod renames;
+mod risk;
+mod server;
+mod snapshot;
 mod spec;
+mod stat;
+mod summary;
+mod tags;
+mod view;
 mod window;
 
+pub use acl::Acl;
+pub use archive::{Archive, Archived};
+pub use attrs::Attrs;
+pub use code_owners::CodeOwners;
+pub use daemon::Daemon;
+pub use decorator::{Decoration, Decorator};
+pub use fix_script::FixScript;
+pub use history::History;
+pub use ignores::Ignores;
+pub use ls_colors::LsColors;
+pub use magic::Magic;
+pub use memo::Memo;
 pub use node::Node;
-pub use owner::OwnerMan;
+pub use owner::{Owner, OwnerMan};
 pub use perm::Perm;
 pub use pls::Pls;
-pub use spec::Spec;
+pub use popup::Popup;
+pub use quota::Quota;
+pub use raw_mode::RawMode;
+pub use renames::Renames;
+pub use risk::Risk;
+pub use server::Server;
+pub use snapshot::Snapshot;
+pub use spec::{regex_source, Spec};
+pub use stat::Stat;
+pub use summary::Summary;
+pub use tags::Tags;
+pub use view::View;
 pub use window::Window;
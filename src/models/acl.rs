@@ -0,0 +1,275 @@
+use crate::config::EntryConst;
+use crate::models::OwnerMan;
+use std::path::Path;
+
+/// the version of the POSIX ACL extended attributes written by Linux
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const POSIX_ACL_VERSION: u32 = 2;
+
+// tags of the entries in POSIX ACLs
+const ACL_USER_OBJ: u16 = 0x01;
+const ACL_USER: u16 = 0x02;
+const ACL_GROUP_OBJ: u16 = 0x04;
+const ACL_GROUP: u16 = 0x08;
+const ACL_MASK: u16 = 0x10;
+const ACL_OTHER: u16 = 0x20;
+
+/// Represents one entry of an access control list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AclEntry {
+	/// an entry of a POSIX ACL, as stored by Linux
+	#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+	Posix {
+		/// whether the entry is inherited by new nodes inside a directory
+		is_default: bool,
+		tag: u16,
+		/// the UID or GID, only meaningful for named users and groups
+		id: u32,
+		perm: u16,
+	},
+	/// an entry of an extended ACL, as described by macOS
+	#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+	Text(String),
+}
+
+/// Represents the access control list of a node, that grants permissions
+/// beyond those in the mode bits.
+///
+/// Only ACLs with such extended entries are read, so the presence of an
+/// instance means the node has an ACL worth pointing out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Acl {
+	pub entries: Vec<AclEntry>,
+}
+
+impl Acl {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Read the extended ACL of the node at the given path.
+	///
+	/// On Linux, the access ACL and, for directories, the default ACL are read
+	/// from their extended attributes, which only exist if the ACL has entries
+	/// beyond the mode bits.
+	#[cfg(target_os = "linux")]
+	pub fn read(path: &Path) -> Option<Self> {
+		use crate::ext::Xattr;
+
+		let entries: Vec<_> = [
+			("system.posix_acl_access", false),
+			("system.posix_acl_default", true),
+		]
+		.into_iter()
+		.filter_map(|(name, is_default)| {
+			let value = path.xattr_value(name).ok()?;
+			parse_posix(&value, is_default)
+		})
+		.flatten()
+		.collect();
+		(!entries.is_empty()).then_some(Self { entries })
+	}
+
+	/// Read the extended ACL of the node at the given path.
+	///
+	/// On macOS, the ACL is read with the ACL functions of the system library,
+	/// and each line of its textual form becomes an entry.
+	#[cfg(target_os = "macos")]
+	pub fn read(path: &Path) -> Option<Self> {
+		let text = sys::acl_text(path)?;
+		let entries: Vec<_> = text
+			.lines()
+			.filter(|line| !line.is_empty() && !line.starts_with("!#acl"))
+			.map(|line| AclEntry::Text(line.to_string()))
+			.collect();
+		(!entries.is_empty()).then_some(Self { entries })
+	}
+
+	/// Read the extended ACL of the node at the given path, which is not
+	/// supported on this platform.
+	#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+	pub fn read(_path: &Path) -> Option<Self> {
+		None
+	}
+
+	// ===========
+	// Renderables
+	// ===========
+
+	/// Get the entries of the ACL, separated by commas, with the IDs of named
+	/// users and groups resolved to their names where possible.
+	///
+	/// This function returns a marked-up string.
+	pub fn list(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> String {
+		let directives = &entry_const.acl.style;
+		let entries: Vec<_> = self
+			.entries
+			.iter()
+			.map(|entry| entry.text(owner_man))
+			.collect();
+		format!("<{directives}>{}</>", entries.join(", "))
+	}
+}
+
+impl AclEntry {
+	/// Get the textual form of the entry, like `user:alice:rw-`, as used by
+	/// `getfacl` and `setfacl`.
+	pub fn text(&self, owner_man: &mut OwnerMan) -> String {
+		match self {
+			AclEntry::Posix {
+				is_default,
+				tag,
+				id,
+				perm,
+			} => {
+				let qualifier = match *tag {
+					ACL_USER => owner_man.user(*id).name.unwrap_or_else(|| id.to_string()),
+					ACL_GROUP => owner_man.group(*id).name.unwrap_or_else(|| id.to_string()),
+					_ => String::default(),
+				};
+				let prefix = if *is_default { "default:" } else { "" };
+				format!(
+					"{prefix}{}:{qualifier}:{}",
+					tag_name(*tag),
+					perm_letters(*perm)
+				)
+			}
+			AclEntry::Text(text) => text.clone(),
+		}
+	}
+}
+
+/// Parse the value of a POSIX ACL extended attribute.
+///
+/// The value is a little-endian version number followed by entries of 8 bytes
+/// each, consisting of the tag, the permission bits and the qualifying ID.
+/// This returns `None` if the value is malformed or of an unknown version.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_posix(value: &[u8], is_default: bool) -> Option<Vec<AclEntry>> {
+	let (version, rest) = value.split_first_chunk::<4>()?;
+	if u32::from_le_bytes(*version) != POSIX_ACL_VERSION || rest.len() % 8 != 0 {
+		return None;
+	}
+	let entries = rest
+		.chunks_exact(8)
+		.map(|chunk| AclEntry::Posix {
+			is_default,
+			tag: u16::from_le_bytes([chunk[0], chunk[1]]),
+			perm: u16::from_le_bytes([chunk[2], chunk[3]]),
+			id: u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+		})
+		.collect();
+	Some(entries)
+}
+
+/// Get the name of the given POSIX ACL tag.
+fn tag_name(tag: u16) -> &'static str {
+	match tag {
+		ACL_USER_OBJ | ACL_USER => "user",
+		ACL_GROUP_OBJ | ACL_GROUP => "group",
+		ACL_MASK => "mask",
+		ACL_OTHER => "other",
+		_ => "unknown",
+	}
+}
+
+/// Get the letters of the given POSIX ACL permission bits, like `rw-`.
+fn perm_letters(perm: u16) -> String {
+	[(0o4, 'r'), (0o2, 'w'), (0o1, 'x')]
+		.iter()
+		.map(|(bit, letter)| if perm & bit != 0 { *letter } else { '-' })
+		.collect()
+}
+
+#[cfg(target_os = "macos")]
+mod sys {
+	use libc::{c_char, c_int, c_void, ssize_t};
+	use std::ffi::{CStr, CString};
+	use std::path::Path;
+
+	/// the type of the extended ACLs of macOS, from `<sys/acl.h>`
+	const ACL_TYPE_EXTENDED: c_int = 0x00000100;
+
+	extern "C" {
+		fn acl_get_link_np(path: *const c_char, typ: c_int) -> *mut c_void;
+		fn acl_to_text(acl: *mut c_void, len: *mut ssize_t) -> *mut c_char;
+		fn acl_free(obj: *mut c_void) -> c_int;
+	}
+
+	/// Get the textual form of the extended ACL of the node at the given path,
+	/// without following symlinks.
+	pub fn acl_text(path: &Path) -> Option<String> {
+		let path = CString::new(path.as_os_str().as_encoded_bytes()).ok()?;
+		unsafe {
+			let acl = acl_get_link_np(path.as_ptr(), ACL_TYPE_EXTENDED);
+			if acl.is_null() {
+				return None;
+			}
+			let text = acl_to_text(acl, std::ptr::null_mut());
+			let res = (!text.is_null()).then(|| CStr::from_ptr(text).to_string_lossy().to_string());
+			if !text.is_null() {
+				acl_free(text.cast());
+			}
+			acl_free(acl);
+			res
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{parse_posix, perm_letters, AclEntry, ACL_GROUP, ACL_USER_OBJ};
+
+	/// Encode the given entries, of tag, permission bits and ID, in the format
+	/// of the POSIX ACL extended attributes.
+	fn encode(version: u32, entries: &[(u16, u16, u32)]) -> Vec<u8> {
+		let mut value = version.to_le_bytes().to_vec();
+		for (tag, perm, id) in entries {
+			value.extend_from_slice(&tag.to_le_bytes());
+			value.extend_from_slice(&perm.to_le_bytes());
+			value.extend_from_slice(&id.to_le_bytes());
+		}
+		value
+	}
+
+	macro_rules! make_parse_test {
+		( $($name:ident: $value:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(parse_posix(&$value, false), $expected);
+				}
+			)*
+		};
+	}
+
+	make_parse_test!(
+		test_parses_entries: encode(2, &[(ACL_USER_OBJ, 0o6, u32::MAX), (ACL_GROUP, 0o5, 100)]) => Some(vec![
+			AclEntry::Posix { is_default: false, tag: ACL_USER_OBJ, perm: 0o6, id: u32::MAX },
+			AclEntry::Posix { is_default: false, tag: ACL_GROUP, perm: 0o5, id: 100 },
+		]),
+		test_rejects_unknown_version: encode(1, &[(ACL_USER_OBJ, 0o6, 0)]) => None,
+		test_rejects_partial_entry: encode(2, &[])
+			.into_iter()
+			.chain([1, 0, 6])
+			.collect::<Vec<_>>() => None,
+		test_rejects_empty_value: Vec::<u8>::new() => None,
+	);
+
+	macro_rules! make_perm_test {
+		( $($name:ident: $perm:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(perm_letters($perm), $expected);
+				}
+			)*
+		};
+	}
+
+	make_perm_test!(
+		test_no_perms: 0o0 => "---",
+		test_read_write: 0o6 => "rw-",
+		test_all_perms: 0o7 => "rwx",
+	);
+}
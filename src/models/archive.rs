@@ -0,0 +1,655 @@
+use crate::enums::Typ;
+use crate::exc::Exc;
+use log::debug;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use time::{Date, Month, PrimitiveDateTime, Time};
+
+/// the size of a tar header and the unit in which tar data is padded
+const TAR_BLOCK: u64 = 512;
+/// the largest GNU long path or PAX extended header that is read, so that a
+/// crafted size cannot exhaust the memory
+const TAR_META_LIMIT: u64 = 1 << 20;
+/// the signature of the end of central directory record of a zip archive
+const ZIP_EOCD_SIG: u32 = 0x0605_4b50;
+/// the signature of a central directory file header of a zip archive
+const ZIP_CDFH_SIG: u32 = 0x0201_4b50;
+
+/// Represents the metadata of a node inside an archive, as recorded in the
+/// archive headers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Archived {
+	/// the uncompressed size of the node in bytes
+	pub size: u64,
+	/// the modification time of the node
+	pub mtime: Option<SystemTime>,
+	/// the Unix permission bits of the node, if recorded
+	pub mode: Option<u32>,
+}
+
+/// Represents one node inside an archive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveEntry {
+	/// the path of the node relative to the root of the archive
+	pub path: PathBuf,
+	pub typ: Typ,
+	pub meta: Archived,
+}
+
+/// Represents the contents of a tar or zip archive, read from the archive
+/// headers without extracting anything.
+///
+/// Compressed tarballs are not supported because reading their headers
+/// requires decompressing the entire archive.
+pub struct Archive {
+	/// mapping of directories inside the archive to the nodes in them
+	children: HashMap<PathBuf, Vec<ArchiveEntry>>,
+}
+
+impl Archive {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Read the contents of the archive at the given path.
+	///
+	/// Directories that are not recorded in the archive but contain recorded
+	/// nodes are added, so that every node has a parent.
+	pub fn open(path: &Path) -> Result<Self, Exc> {
+		let file = File::open(path).map_err(Exc::Io)?;
+		let mut reader = BufReader::new(file);
+		let entries = match Self::format(path) {
+			Some(Format::Tar) => read_tar(&mut reader),
+			Some(Format::Zip) => read_zip(&mut reader),
+			None => Err(Exc::Other(format!(
+				"{} is not a tar or zip archive.",
+				path.display()
+			))),
+		}?;
+		debug!("Read {} entries from archive {path:?}.", entries.len());
+		Ok(Self::from_entries(entries))
+	}
+
+	/// Get whether the path can be read as an archive, based on its extension.
+	pub fn is_archive(path: &Path) -> bool {
+		Self::format(path).is_some()
+	}
+
+	// ======
+	// Public
+	// ======
+
+	/// Get the nodes directly inside the given directory of the archive; the
+	/// root of the archive is the empty path.
+	pub fn children(&self, dir: &Path) -> &[ArchiveEntry] {
+		self.children.get(dir).map_or(&[], Vec::as_slice)
+	}
+
+	// =======
+	// Private
+	// =======
+
+	/// Determine the format of the archive from the extension of its path.
+	fn format(path: &Path) -> Option<Format> {
+		let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+		match ext.as_str() {
+			"tar" => Some(Format::Tar),
+			"zip" | "jar" | "whl" => Some(Format::Zip),
+			_ => None,
+		}
+	}
+
+	/// Group the entries by their parent directories, adding any implied
+	/// directories and dropping duplicates.
+	fn from_entries(entries: Vec<ArchiveEntry>) -> Self {
+		let mut by_path: HashMap<PathBuf, ArchiveEntry> = HashMap::new();
+		for entry in entries {
+			let Some(path) = normalise(&entry.path) else {
+				continue;
+			};
+			for ancestor in path.ancestors().skip(1) {
+				if ancestor.as_os_str().is_empty() {
+					break;
+				}
+				by_path
+					.entry(ancestor.to_path_buf())
+					.or_insert_with(|| ArchiveEntry {
+						path: ancestor.to_path_buf(),
+						typ: Typ::Dir,
+						meta: Archived::default(),
+					});
+			}
+			// Later records of the same path replace earlier ones, like when
+			// files are appended to a tarball.
+			by_path.insert(path.clone(), ArchiveEntry { path, ..entry });
+		}
+
+		let mut children: HashMap<PathBuf, Vec<ArchiveEntry>> = HashMap::new();
+		for (path, entry) in by_path {
+			let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+			children.entry(parent).or_default().push(entry);
+		}
+		Self { children }
+	}
+}
+
+/// This enum contains the archive formats that can be read.
+enum Format {
+	Tar,
+	Zip,
+}
+
+/// Normalise a path from an archive into a relative path, dropping `.`
+/// components and rejecting paths that escape the archive.
+fn normalise(path: &Path) -> Option<PathBuf> {
+	let mut normal = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::Normal(part) => normal.push(part),
+			Component::CurDir | Component::RootDir => {}
+			Component::ParentDir | Component::Prefix(_) => return None,
+		}
+	}
+	(!normal.as_os_str().is_empty()).then_some(normal)
+}
+
+// ===
+// Tar
+// ===
+
+/// Read the entries from the headers of a tar archive.
+///
+/// This understands the ustar format, along with the GNU and PAX extensions
+/// for long paths.
+///
+/// Sizes are checked against the length of the archive before any data is
+/// read or skipped, so that a damaged or crafted archive fails with an error
+/// instead of exhausting the memory.
+fn read_tar<R: Read + Seek>(reader: &mut R) -> Result<Vec<ArchiveEntry>, Exc> {
+	let archive_len = reader.seek(SeekFrom::End(0)).map_err(Exc::Io)?;
+	reader.seek(SeekFrom::Start(0)).map_err(Exc::Io)?;
+
+	let mut entries = vec![];
+	let mut long_path: Option<String> = None;
+	let mut header = [0; TAR_BLOCK as usize];
+	loop {
+		if reader.read_exact(&mut header).is_err() || header.iter().all(|byte| *byte == 0) {
+			break;
+		}
+		let size = parse_number(&header[124..136])
+			.and_then(|size| u64::try_from(size).ok())
+			.ok_or_else(|| Exc::Other(String::from("Tar entry has an invalid size.")))?;
+		let padded = size
+			.div_ceil(TAR_BLOCK)
+			.checked_mul(TAR_BLOCK)
+			.ok_or_else(|| Exc::Other(format!("Tar entry of {size} bytes is too large.")))?;
+
+		let typ = match header[156] {
+			// GNU long path, stored as the data of a pseudo-entry
+			b'L' => {
+				let data = read_meta(reader, size, padded)?;
+				long_path = Some(text(&data));
+				continue;
+			}
+			// PAX extended header, which may contain the path
+			b'x' => {
+				let data = read_meta(reader, size, padded)?;
+				long_path = pax_path(&data).or(long_path);
+				continue;
+			}
+			b'0' | b'\0' | b'1' | b'7' => Typ::File,
+			b'2' => Typ::Symlink,
+			b'3' => Typ::CharDevice,
+			b'4' => Typ::BlockDevice,
+			b'5' => Typ::Dir,
+			b'6' => Typ::Fifo,
+			_ => Typ::Unknown,
+		};
+
+		let path = long_path.take().unwrap_or_else(|| {
+			let name = text(&header[0..100]);
+			let prefix = if &header[257..262] == b"ustar" {
+				text(&header[345..500])
+			} else {
+				String::new()
+			};
+			if prefix.is_empty() {
+				name
+			} else {
+				format!("{prefix}/{name}")
+			}
+		});
+		entries.push(ArchiveEntry {
+			path: PathBuf::from(path),
+			typ,
+			meta: Archived {
+				size: if typ == Typ::File { size } else { 0 },
+				mtime: parse_number(&header[136..148]).and_then(epoch_time),
+				mode: parse_number(&header[100..108]).and_then(|mode| u32::try_from(mode).ok()),
+			},
+		});
+
+		// Entries like directories and links can have sizes but no data.
+		if matches!(header[156], b'0' | b'\0' | b'7') {
+			skip(reader, padded, archive_len)?;
+		}
+	}
+	Ok(entries)
+}
+
+/// Read the data of a GNU long path or PAX extended header, and skip the
+/// padding after it.
+///
+/// # Arguments
+///
+/// * `reader` - the archive, positioned after the header
+/// * `size` - the size of the data, as recorded in the header
+/// * `padded` - the size of the data, padded to whole blocks
+fn read_meta<R: Read + Seek>(reader: &mut R, size: u64, padded: u64) -> Result<Vec<u8>, Exc> {
+	if size > TAR_META_LIMIT {
+		return Err(Exc::Other(format!(
+			"Tar metadata of {size} bytes exceeds the limit of {TAR_META_LIMIT} bytes."
+		)));
+	}
+	let mut data = vec![0; size as usize];
+	reader
+		.read_exact(&mut data)
+		.map_err(|_| Exc::Other(String::from("Tar entry is truncated.")))?;
+	reader
+		.seek(SeekFrom::Current((padded - size) as i64))
+		.map_err(Exc::Io)?;
+	Ok(data)
+}
+
+/// Skip the given number of bytes of entry data, failing if the archive ends
+/// before them.
+///
+/// # Arguments
+///
+/// * `reader` - the archive, positioned at the start of the data
+/// * `len` - the number of bytes to skip
+/// * `archive_len` - the length of the whole archive
+fn skip<R: Seek>(reader: &mut R, len: u64, archive_len: u64) -> Result<(), Exc> {
+	let truncated = || Exc::Other(String::from("Tar entry is truncated."));
+	let offset = i64::try_from(len).map_err(|_| truncated())?;
+	let pos = reader.seek(SeekFrom::Current(offset)).map_err(Exc::Io)?;
+	if pos > archive_len {
+		return Err(truncated());
+	}
+	Ok(())
+}
+
+/// Parse a numeric field from a tar header.
+///
+/// Such fields are usually NUL- or space-terminated octal numbers, but large
+/// and negative values, like timestamps before 1970, can be stored in
+/// base-256 instead. In base-256, the field is a big-endian two's complement
+/// number whose first byte has the high bit set, with `0x80` marking positive
+/// and `0xff` marking negative numbers.
+///
+/// This function returns `None` if the value does not fit in an `i64`.
+fn parse_number(field: &[u8]) -> Option<i64> {
+	let first = *field.first()?;
+	if first & 0x80 == 0 {
+		return field
+			.iter()
+			.skip_while(|byte| **byte == b' ')
+			.take_while(|byte| (b'0'..=b'7').contains(*byte))
+			.try_fold(0_i64, |acc, byte| {
+				acc.checked_mul(8)?.checked_add(i64::from(byte - b'0'))
+			});
+	}
+
+	// Header fields are at most 12 bytes long, so they fit in 128 bits.
+	if field.len() >= 16 {
+		return None;
+	}
+	let bits = field.len() as u32 * 8;
+	let raw = field
+		.iter()
+		.fold(0_u128, |acc, byte| (acc << 8) | u128::from(*byte));
+	let value = if first & 0x40 != 0 {
+		raw as i128 - (1_i128 << bits)
+	} else {
+		(raw & !(1 << (bits - 1))) as i128
+	};
+	i64::try_from(value).ok()
+}
+
+/// Convert a number of seconds since the Unix epoch into a time, returning
+/// `None` if the time cannot be represented.
+fn epoch_time(secs: i64) -> Option<SystemTime> {
+	let offset = Duration::from_secs(secs.unsigned_abs());
+	if secs >= 0 {
+		UNIX_EPOCH.checked_add(offset)
+	} else {
+		UNIX_EPOCH.checked_sub(offset)
+	}
+}
+
+/// Get the path from the records of a PAX extended header.
+///
+/// Each record has the form `"<length> <key>=<value>\n"`.
+fn pax_path(data: &[u8]) -> Option<String> {
+	text(data).lines().find_map(|record| {
+		let (_, pair) = record.split_once(' ')?;
+		pair.strip_prefix("path=").map(String::from)
+	})
+}
+
+/// Convert a NUL-terminated field into a string, lossily.
+fn text(field: &[u8]) -> String {
+	let end = field
+		.iter()
+		.position(|byte| *byte == 0)
+		.unwrap_or(field.len());
+	String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+// ===
+// Zip
+// ===
+
+/// Read the entries from the central directory of a zip archive.
+///
+/// The central directory is located through the end of central directory
+/// record at the end of the archive, so the rest of the archive is not read.
+fn read_zip<R: Read + Seek>(reader: &mut R) -> Result<Vec<ArchiveEntry>, Exc> {
+	let invalid = || Exc::Other(String::from("Could not find the zip central directory."));
+
+	// The record is 22 bytes, followed by a comment of up to 65535 bytes.
+	let len = reader.seek(SeekFrom::End(0)).map_err(Exc::Io)?;
+	let tail_len = len.min(22 + 65535);
+	reader
+		.seek(SeekFrom::Start(len - tail_len))
+		.map_err(Exc::Io)?;
+	let mut tail = vec![0; tail_len as usize];
+	reader.read_exact(&mut tail).map_err(Exc::Io)?;
+	let eocd = (0..tail.len().saturating_sub(21))
+		.rev()
+		.find(|idx| le_u32(&tail[*idx..]) == ZIP_EOCD_SIG)
+		.ok_or_else(invalid)?;
+	let eocd = &tail[eocd..];
+	let count = le_u16(&eocd[10..]) as usize;
+	let cd_len = le_u32(&eocd[12..]) as usize;
+	let cd_offset = le_u32(&eocd[16..]) as u64;
+
+	reader.seek(SeekFrom::Start(cd_offset)).map_err(Exc::Io)?;
+	let mut cd = vec![0; cd_len];
+	reader.read_exact(&mut cd).map_err(Exc::Io)?;
+
+	let mut entries = Vec::with_capacity(count);
+	let mut rest = cd.as_slice();
+	while rest.len() >= 46 && le_u32(rest) == ZIP_CDFH_SIG {
+		let made_by = le_u16(&rest[4..]);
+		let dos_time = le_u16(&rest[12..]);
+		let dos_date = le_u16(&rest[14..]);
+		let size = le_u32(&rest[24..]) as u64;
+		let name_len = le_u16(&rest[28..]) as usize;
+		let extra_len = le_u16(&rest[30..]) as usize;
+		let comment_len = le_u16(&rest[32..]) as usize;
+		let attrs = le_u32(&rest[38..]);
+		let name = rest.get(46..46 + name_len).ok_or_else(invalid)?;
+		let name = String::from_utf8_lossy(name).to_string();
+
+		// Archives made on Unix store the mode in the high bits.
+		let mode = (made_by >> 8 == 3).then_some(attrs >> 16);
+		let typ = match mode.map(|mode| mode & 0o170000) {
+			_ if name.ends_with('/') => Typ::Dir,
+			Some(0o040000) => Typ::Dir,
+			Some(0o120000) => Typ::Symlink,
+			_ => Typ::File,
+		};
+		entries.push(ArchiveEntry {
+			path: PathBuf::from(name),
+			typ,
+			meta: Archived {
+				size: if typ == Typ::File { size } else { 0 },
+				mtime: dos_to_system_time(dos_date, dos_time),
+				mode: mode.map(|mode| mode & 0o7777),
+			},
+		});
+
+		let record_len = 46 + name_len + extra_len + comment_len;
+		rest = rest.get(record_len..).unwrap_or_default();
+	}
+	Ok(entries)
+}
+
+/// Convert an MS-DOS date and time, as stored in zip archives, into a time.
+///
+/// Zip archives do not record the time zone, so the time is assumed to be in
+/// UTC.
+fn dos_to_system_time(date: u16, time: u16) -> Option<SystemTime> {
+	let month = Month::try_from((date >> 5 & 0xf) as u8).ok()?;
+	let date =
+		Date::from_calendar_date(1980 + (date >> 9) as i32, month, (date & 0x1f) as u8).ok()?;
+	let time = Time::from_hms(
+		(time >> 11) as u8,
+		(time >> 5 & 0x3f) as u8,
+		((time & 0x1f) * 2) as u8,
+	)
+	.ok()?;
+	Some(PrimitiveDateTime::new(date, time).assume_utc().into())
+}
+
+/// Read a little-endian `u16` from the start of the bytes.
+fn le_u16(bytes: &[u8]) -> u16 {
+	u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+/// Read a little-endian `u32` from the start of the bytes.
+fn le_u32(bytes: &[u8]) -> u32 {
+	u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{dos_to_system_time, normalise, parse_number, read_tar, read_zip, Archive};
+	use crate::enums::Typ;
+	use std::io::Cursor;
+	use std::path::{Path, PathBuf};
+	use std::time::{Duration, UNIX_EPOCH};
+
+	/// Build a tar header for the given path, type flag and data size.
+	fn tar_header(path: &str, flag: u8, size: usize) -> Vec<u8> {
+		let mut header = vec![0; 512];
+		header[..path.len()].copy_from_slice(path.as_bytes());
+		header[100..107].copy_from_slice(b"0000644");
+		header[124..135].copy_from_slice(format!("{size:011o}").as_bytes());
+		header[136..147].copy_from_slice(b"00000000144");
+		header[156] = flag;
+		header[257..262].copy_from_slice(b"ustar");
+		header
+	}
+
+	#[test]
+	fn test_reads_tar_headers() {
+		let mut tar = tar_header("dir/", b'5', 0);
+		tar.extend(tar_header("dir/file.txt", b'0', 600));
+		tar.extend(vec![b'x'; 1024]); // data padded to two blocks
+		tar.extend(tar_header("link", b'2', 0));
+		tar.extend(vec![0; 1024]);
+
+		let entries = read_tar(&mut Cursor::new(tar)).unwrap();
+		let summary: Vec<_> = entries
+			.iter()
+			.map(|entry| (entry.path.to_str().unwrap(), entry.typ, entry.meta.size))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				("dir/", Typ::Dir, 0),
+				("dir/file.txt", Typ::File, 600),
+				("link", Typ::Symlink, 0),
+			]
+		);
+		assert_eq!(entries[1].meta.mode, Some(0o644));
+		assert_eq!(
+			entries[1].meta.mtime,
+			Some(UNIX_EPOCH + Duration::from_secs(100))
+		);
+	}
+
+	#[test]
+	fn test_reads_gnu_long_paths() {
+		let long = "a/".repeat(60) + "file";
+		let mut tar = tar_header("././@LongLink", b'L', long.len());
+		let mut data = long.clone().into_bytes();
+		data.resize(512, 0);
+		tar.extend(data);
+		tar.extend(tar_header("truncated", b'0', 0));
+
+		let entries = read_tar(&mut Cursor::new(tar)).unwrap();
+		assert_eq!(entries[0].path, PathBuf::from(long));
+	}
+
+	#[test]
+	fn test_reads_zip_central_directory() {
+		let name = b"docs/readme.md";
+		let mut zip = vec![];
+		zip.extend(0x0201_4b50_u32.to_le_bytes());
+		zip.extend(((3_u16 << 8) | 20).to_le_bytes()); // made by Unix
+		zip.extend([0; 6]);
+		zip.extend(0x6000_u16.to_le_bytes()); // 12:00:00
+		zip.extend(0x5821_u16.to_le_bytes()); // 2024-01-01
+		zip.extend([0; 8]);
+		zip.extend(42_u32.to_le_bytes());
+		zip.extend((name.len() as u16).to_le_bytes());
+		zip.extend([0; 8]);
+		zip.extend((0o100755_u32 << 16).to_le_bytes());
+		zip.extend([0; 4]);
+		zip.extend(name);
+		let cd_len = zip.len() as u32;
+		zip.extend(0x0605_4b50_u32.to_le_bytes());
+		zip.extend([0; 6]);
+		zip.extend(1_u16.to_le_bytes());
+		zip.extend(cd_len.to_le_bytes());
+		zip.extend(0_u32.to_le_bytes());
+		zip.extend([0; 2]);
+
+		let entries = read_zip(&mut Cursor::new(zip)).unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].path, PathBuf::from("docs/readme.md"));
+		assert_eq!(entries[0].typ, Typ::File);
+		assert_eq!(entries[0].meta.size, 42);
+		assert_eq!(entries[0].meta.mode, Some(0o755));
+		assert_eq!(entries[0].meta.mtime, dos_to_system_time(0x5821, 0x6000));
+	}
+
+	#[test]
+	fn test_adds_implied_directories() {
+		let mut tar = tar_header("a/b/c.txt", b'0', 0);
+		tar.extend(vec![0; 1024]);
+		let archive = Archive::from_entries(read_tar(&mut Cursor::new(tar)).unwrap());
+		assert_eq!(archive.children(Path::new(""))[0].typ, Typ::Dir);
+		assert_eq!(
+			archive.children(Path::new("a/b"))[0].path,
+			PathBuf::from("a/b/c.txt")
+		);
+	}
+
+	#[test]
+	fn test_reads_base_256_mtime_before_epoch() {
+		let mut tar = tar_header("old.txt", b'0', 0);
+		// -100 seconds, as a 12-byte two's complement number
+		tar[136..148].copy_from_slice(&[0xff; 12]);
+		tar[147] = 0x9c;
+		tar.extend(vec![0; 1024]);
+
+		let entries = read_tar(&mut Cursor::new(tar)).unwrap();
+		assert_eq!(
+			entries[0].meta.mtime,
+			Some(UNIX_EPOCH - Duration::from_secs(100))
+		);
+	}
+
+	#[test]
+	fn test_skips_unrepresentable_mtime() {
+		let mut tar = tar_header("far.txt", b'0', 0);
+		tar[136..148].copy_from_slice(&[
+			0x80, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+		]);
+		tar.extend(vec![0; 1024]);
+
+		let entries = read_tar(&mut Cursor::new(tar)).unwrap();
+		assert_eq!(entries[0].meta.mtime, None);
+	}
+
+	#[test]
+	fn test_rejects_huge_size() {
+		let mut tar = tar_header("huge", b'0', 0);
+		tar[124..136].copy_from_slice(&[
+			0x80, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+		]);
+		tar.extend(vec![0; 1024]);
+
+		assert!(read_tar(&mut Cursor::new(tar)).is_err());
+	}
+
+	#[test]
+	fn test_rejects_huge_long_path() {
+		let mut tar = tar_header("././@LongLink", b'L', 0);
+		tar[124..135].copy_from_slice(b"77777777777");
+		tar.extend(vec![0; 1024]);
+
+		assert!(read_tar(&mut Cursor::new(tar)).is_err());
+	}
+
+	#[test]
+	fn test_rejects_truncated_data() {
+		let tar = tar_header("cut.txt", b'0', 600);
+
+		assert!(read_tar(&mut Cursor::new(tar)).is_err());
+	}
+
+	#[test]
+	fn test_rejects_truncated_long_path() {
+		let mut tar = tar_header("././@LongLink", b'L', 300);
+		tar.extend(b"a/b");
+
+		assert!(read_tar(&mut Cursor::new(tar)).is_err());
+	}
+
+	macro_rules! make_parse_number_test {
+		( $($name:ident: $field:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(parse_number($field), $expected);
+				}
+			)*
+		};
+	}
+
+	make_parse_number_test!(
+		test_number_nul_terminated: b"0000644\0" => Some(0o644),
+		test_number_space_padded: b"   755 \0" => Some(0o755),
+		test_number_octal_overflow: b"7777777777777777777777777" => None,
+		test_number_base_256: &[0x80, 0, 0, 1, 0] => Some(256),
+		test_number_base_256_negative: &[0xff, 0xff, 0xff, 0xfe] => Some(-2),
+		test_number_base_256_overflow: &[0x80, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff] => None,
+	);
+
+	macro_rules! make_normalise_test {
+		( $($name:ident: $path:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let expected: Option<&str> = $expected;
+					assert_eq!(normalise(Path::new($path)), expected.map(PathBuf::from));
+				}
+			)*
+		};
+	}
+
+	make_normalise_test!(
+		test_normalise_strips_dot: "./a/b" => Some("a/b"),
+		test_normalise_strips_root: "/a" => Some("a"),
+		test_normalise_rejects_parent: "../a" => None,
+		test_normalise_rejects_empty: "./" => None,
+	);
+}
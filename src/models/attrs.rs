@@ -0,0 +1,70 @@
+use crate::config::EntryConst;
+
+/// the Windows file attributes shown in the attributes column, paired with the
+/// letter that represents each
+const FLAGS: [(u32, char); 7] = [
+	(0x1, 'r'),    // read-only
+	(0x2, 'h'),    // hidden
+	(0x4, 's'),    // system
+	(0x20, 'a'),   // archive
+	(0x800, 'c'),  // compressed
+	(0x1000, 'o'), // offline
+	(0x4000, 'e'), // encrypted
+];
+
+/// Represents the file attributes of a node on Windows, which take the place
+/// of many of the Unix metadata fields.
+///
+/// An instance of this struct can be created using `into` on the node's
+/// attributes, which are type [`u32`].
+pub struct Attrs {
+	pub attrs: u32,
+}
+
+impl From<u32> for Attrs {
+	fn from(attrs: u32) -> Self {
+		Self { attrs }
+	}
+}
+
+impl Attrs {
+	/// Get the letters of the attributes, with `-` in place of each attribute
+	/// that is not set.
+	pub fn letters(&self) -> String {
+		FLAGS
+			.iter()
+			.map(|(bit, letter)| if self.attrs & bit != 0 { *letter } else { '-' })
+			.collect()
+	}
+
+	/// Get the letters of the attributes, styled for the attributes column.
+	///
+	/// This function returns a marked-up string.
+	pub fn flags(&self, entry_const: &EntryConst) -> String {
+		let directives = &entry_const.attrs_style;
+		format!("<{directives}>{}</>", self.letters())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Attrs;
+
+	macro_rules! make_letters_test {
+		( $($name:ident: $attrs:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Attrs::from($attrs).letters(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_letters_test!(
+		test_no_attrs: 0x0 => "-------",
+		test_normal_attr_ignored: 0x80 => "-------",
+		test_read_only_hidden: 0x3 => "rh-----",
+		test_archive_encrypted: 0x4020 => "---a--e",
+	);
+}
@@ -0,0 +1,128 @@
+use crate::utils::globs::{glob_to_regex, haystack};
+use git2::Repository;
+use log::debug;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// These are the locations, relative to the root of the repository, where
+/// GitHub looks for the `CODEOWNERS` file, in the order of precedence.
+const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Represents a single rule from the `CODEOWNERS` file.
+#[derive(Debug)]
+struct Rule {
+	/// the pattern of the rule, compiled from the glob into a regex
+	pat: Regex,
+	/// the owners of the paths matched by the pattern, may be empty
+	owners: Vec<String>,
+}
+
+/// Represents the code owners of the children of a directory, as declared in
+/// the `CODEOWNERS` file of the repository containing the directory.
+///
+/// The file is parsed, and its globs compiled, once when the struct is created
+/// so that the owners of all children can be resolved cheaply.
+#[derive(Debug)]
+pub struct CodeOwners {
+	/// the path of the directory relative to the root of the repository
+	rel_dir: PathBuf,
+	rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Find and parse the `CODEOWNERS` file applicable to the given directory.
+	///
+	/// This returns `None` if the directory is not inside a Git repository or
+	/// if the repository does not have a `CODEOWNERS` file.
+	pub fn discover(dir: &Path) -> Option<Self> {
+		let repo = Repository::discover(dir).ok()?;
+		let workdir = fs::canonicalize(repo.workdir()?).ok()?;
+		let rel_dir = fs::canonicalize(dir)
+			.ok()?
+			.strip_prefix(&workdir)
+			.ok()?
+			.to_path_buf();
+
+		let text = LOCATIONS
+			.iter()
+			.find_map(|loc| fs::read_to_string(workdir.join(loc)).ok())?;
+		debug!("Found CODEOWNERS for {dir:?}.");
+
+		Some(Self {
+			rel_dir,
+			rules: parse(&text),
+		})
+	}
+
+	// ======
+	// Public
+	// ======
+
+	/// Get the owners of the child of the directory with the given name.
+	///
+	/// Whether the child is a directory affects globs with a trailing slash,
+	/// which only match directories.
+	///
+	/// Like on GitHub, the last matching rule takes precedence. This returns
+	/// `None` if no rule matches the child or if the matching rule does not
+	/// declare any owners.
+	pub fn owners_of(&self, name: &str, is_dir: bool) -> Option<&[String]> {
+		let path = haystack(&self.rel_dir.join(name), is_dir);
+		self.rules
+			.iter()
+			.rev()
+			.find(|rule| rule.pat.is_match(&path))
+			.map(|rule| rule.owners.as_slice())
+			.filter(|owners| !owners.is_empty())
+	}
+}
+
+/// Parse the rules from the contents of a `CODEOWNERS` file.
+///
+/// Blank lines, comments and rules with patterns that cannot be compiled are
+/// skipped.
+fn parse(text: &str) -> Vec<Rule> {
+	text.lines()
+		.filter_map(|line| {
+			let line = line.split('#').next().unwrap_or_default();
+			let mut parts = line.split_whitespace();
+			let glob = parts.next()?;
+			let pat = glob_to_regex(glob)?;
+			let owners = parts.map(String::from).collect();
+			Some(Rule { pat, owners })
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{parse, CodeOwners};
+	use std::path::PathBuf;
+
+	#[test]
+	fn test_last_matching_rule_wins() {
+		let code_owners = CodeOwners {
+			rel_dir: PathBuf::from("src"),
+			rules: parse(
+				"# comment\n\
+				 *       @org/all\n\
+				 *.rs    @org/rust @alice # inline comment\n\
+				 /src/gen.rs\n",
+			),
+		};
+		assert_eq!(
+			code_owners.owners_of("main.py", false),
+			Some(&[String::from("@org/all")][..])
+		);
+		assert_eq!(
+			code_owners.owners_of("main.rs", false),
+			Some(&[String::from("@org/rust"), String::from("@alice")][..])
+		);
+		assert_eq!(code_owners.owners_of("gen.rs", false), None);
+	}
+}
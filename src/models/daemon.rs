@@ -0,0 +1,312 @@
+use crate::config::AppConst;
+use crate::exc::Exc;
+use crate::models::Stat;
+use log::warn;
+#[cfg(unix)]
+use log::{debug, info};
+#[cfg(unix)]
+use notify::{Event, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+#[cfg(unix)]
+use std::time::Duration;
+
+/// the time the client waits for the daemon before listing the directory itself
+#[cfg(unix)]
+const CLIENT_TIMEOUT: Duration = Duration::from_millis(200);
+/// the time the daemon waits for a client to send its request or read the
+/// response, so that one stalled client cannot block the others
+#[cfg(unix)]
+const SERVER_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// the names of the children of a directory, paired with their metadata, if
+/// it could be read
+type Listing = Vec<(String, Option<Stat>)>;
+
+/// mapping of directories to the listings of their children
+type Cache = Arc<Mutex<HashMap<PathBuf, Listing>>>;
+
+/// Represents the `pls daemon` process.
+///
+/// The daemon keeps the listings of the directories configured in
+/// [`AppConst::daemon`], along with the metadata of their children, in memory
+/// and serves them over a Unix socket, so that `pls --from-daemon` does not
+/// have to read or stat large directories on slow disks. File system events
+/// invalidate the listings as soon as the directories or their children
+/// change, and invalidated listings are read again on the next request.
+///
+/// The socket is only accessible to the user running the daemon, and clients
+/// only trust sockets owned by themselves, so that other users cannot read or
+/// spoof listings. The daemon is served over Unix sockets, so it is only
+/// available on Unix.
+#[cfg_attr(not(unix), allow(dead_code))]
+pub struct Daemon {
+	/// the directories whose listings are kept warm
+	dirs: Vec<PathBuf>,
+	cache: Cache,
+}
+
+impl Daemon {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Create a new instance of `Daemon` for the directories in the config.
+	///
+	/// The directories can use `~` and environment variables, and ones that
+	/// do not exist are skipped with a warning.
+	pub fn new(app_const: &AppConst) -> Self {
+		let dirs = app_const
+			.daemon
+			.dirs
+			.iter()
+			.filter_map(|dir| {
+				let home_dir = || home::home_dir().map(|home| home.to_string_lossy().to_string());
+				let dir = shellexpand::full_with_context_no_errors(dir, home_dir, |var| {
+					env::var(var).ok()
+				});
+				match fs::canonicalize(dir.as_ref()) {
+					Ok(dir) => Some(dir),
+					Err(err) => {
+						warn!("Skipping daemon directory {dir:?}: {err}");
+						None
+					}
+				}
+			})
+			.collect();
+		Self {
+			dirs,
+			cache: Arc::default(),
+		}
+	}
+
+	// ======
+	// Public
+	// ======
+
+	/// Warm the cache, watch the directories for changes and serve listings
+	/// until the process is terminated.
+	#[cfg(unix)]
+	pub fn serve(&self) -> Result<(), Exc> {
+		let cache = Arc::clone(&self.cache);
+		let mut watcher =
+			notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+				Ok(event) => invalidate(&cache, &event.paths),
+				Err(err) => warn!("Could not watch for changes: {err}"),
+			})
+			.map_err(|err| Exc::Other(err.to_string()))?;
+
+		for dir in &self.dirs {
+			watcher
+				.watch(dir, RecursiveMode::NonRecursive)
+				.map_err(|err| Exc::Other(err.to_string()))?;
+			self.listing(dir);
+		}
+		info!("Warmed listings of {} directories.", self.dirs.len());
+
+		let socket = socket_path()?;
+		if let Ok(meta) = socket.symlink_metadata() {
+			// A socket left behind by a previous daemon would block binding,
+			// but nodes that belong to someone else are left alone.
+			if !is_own_socket(&meta) {
+				return Err(Exc::Other(format!(
+					"{} exists and is not a socket owned by the user.",
+					socket.display()
+				)));
+			}
+			fs::remove_file(&socket).map_err(Exc::Io)?;
+		}
+		let listener = UnixListener::bind(&socket).map_err(Exc::Io)?;
+		info!("Serving listings on {socket:?}.");
+
+		for stream in listener.incoming() {
+			match stream {
+				Ok(stream) => {
+					if let Err(err) = self.respond(stream) {
+						warn!("Could not respond to client: {err}");
+					}
+				}
+				Err(err) => warn!("Could not accept client: {err}"),
+			}
+		}
+		Ok(())
+	}
+
+	/// Get the paths of the children of the given directory, paired with
+	/// their metadata, from the daemon.
+	///
+	/// This returns `None` if the daemon is not running, does not respond in
+	/// time, or does not keep the directory warm, in which case the caller
+	/// should read the directory itself.
+	#[cfg(unix)]
+	pub fn list(dir: &Path) -> Option<Vec<(PathBuf, Option<Stat>)>> {
+		let dir = fs::canonicalize(dir).ok()?;
+		let listing = request(&dir)
+			.map_err(|err| debug!("Could not get listing from daemon: {err}"))
+			.ok()??;
+		debug!("Got listing of {dir:?} from daemon.");
+		Some(
+			listing
+				.into_iter()
+				.map(|(name, stat)| (dir.join(name), stat))
+				.collect(),
+		)
+	}
+
+	/// Serve listings until the process is terminated, which is not supported
+	/// on platforms without Unix sockets.
+	#[cfg(not(unix))]
+	pub fn serve(&self) -> Result<(), Exc> {
+		Err(Exc::Other(String::from(
+			"`pls daemon` is only supported on Unix.",
+		)))
+	}
+
+	/// Get the paths of the children of the given directory from the daemon,
+	/// which never runs on platforms without Unix sockets.
+	#[cfg(not(unix))]
+	pub fn list(_dir: &Path) -> Option<Vec<(PathBuf, Option<Stat>)>> {
+		None
+	}
+
+	// =======
+	// Private
+	// =======
+
+	/// Read the path of a directory from the stream and write its listing,
+	/// as JSON, back to it.
+	///
+	/// Clients are served one at a time, so the stream gives up after
+	/// [`SERVER_TIMEOUT`] instead of waiting on a client that stalls.
+	#[cfg(unix)]
+	fn respond(&self, stream: UnixStream) -> Result<(), Exc> {
+		stream
+			.set_read_timeout(Some(SERVER_TIMEOUT))
+			.map_err(Exc::Io)?;
+		stream
+			.set_write_timeout(Some(SERVER_TIMEOUT))
+			.map_err(Exc::Io)?;
+		let mut reader = BufReader::new(&stream);
+		let mut line = String::new();
+		reader.read_line(&mut line).map_err(Exc::Io)?;
+		let dir = PathBuf::from(line.trim_end_matches('\n'));
+
+		let listing = self.dirs.contains(&dir).then(|| self.listing(&dir));
+		let data = serde_json::to_vec(&listing).map_err(Exc::Json)?;
+		(&stream).write_all(&data).map_err(Exc::Io)
+	}
+
+	/// Get the names and metadata of the children of the directory, reading
+	/// the directory only if its listing is not in the cache.
+	#[cfg(unix)]
+	fn listing(&self, dir: &Path) -> Listing {
+		let mut cache = self.cache.lock().unwrap();
+		if let Some(listing) = cache.get(dir) {
+			return listing.clone();
+		}
+
+		debug!("Reading listing of {dir:?}.");
+		let listing: Listing = fs::read_dir(dir)
+			.map(|entries| {
+				entries
+					.flatten()
+					.map(|entry| {
+						let name = entry.file_name().to_string_lossy().to_string();
+						let stat = entry.path().symlink_metadata().ok();
+						(name, stat.map(|meta| Stat::from(&meta)))
+					})
+					.collect()
+			})
+			.unwrap_or_default();
+		cache.insert(dir.to_path_buf(), listing.clone());
+		listing
+	}
+}
+
+/// Drop the listings affected by changes to the given paths.
+///
+/// Since the directories are watched non-recursively, the changed paths are
+/// either the directories themselves or their children, whose metadata, like
+/// their size or permissions, is part of the listing.
+#[cfg(unix)]
+fn invalidate(cache: &Cache, paths: &[PathBuf]) {
+	let mut cache = cache.lock().unwrap();
+	for path in paths {
+		for dir in [Some(path.as_path()), path.parent()].into_iter().flatten() {
+			if cache.remove(dir).is_some() {
+				debug!("Invalidated listing of {dir:?}.");
+			}
+		}
+	}
+}
+
+/// Request the listing of the given directory from the daemon.
+///
+/// The socket must be owned by the user, so that a daemon started by another
+/// user cannot answer with a spoofed listing.
+#[cfg(unix)]
+fn request(dir: &Path) -> Result<Option<Listing>, Exc> {
+	let socket = socket_path()?;
+	let meta = socket.symlink_metadata().map_err(Exc::Io)?;
+	if !is_own_socket(&meta) {
+		return Err(Exc::Other(format!(
+			"{} is not a socket owned by the user.",
+			socket.display()
+		)));
+	}
+	let mut stream = UnixStream::connect(&socket).map_err(Exc::Io)?;
+	stream
+		.set_read_timeout(Some(CLIENT_TIMEOUT))
+		.map_err(Exc::Io)?;
+	writeln!(stream, "{}", dir.display()).map_err(Exc::Io)?;
+	serde_json::from_reader(stream).map_err(Exc::Json)
+}
+
+/// Get the path of the Unix socket that the daemon listens on.
+///
+/// This is the path in the `PLS_SOCKET` environment variable, if it is set,
+/// and `pls.sock` in the runtime directory of the user otherwise. Without a
+/// runtime directory, the socket is placed in a directory of the temporary
+/// directory that only the user can access, which is created if needed.
+#[cfg(unix)]
+fn socket_path() -> Result<PathBuf, Exc> {
+	if let Some(path) = env::var_os("PLS_SOCKET") {
+		return Ok(PathBuf::from(path));
+	}
+	if let Some(dir) = env::var_os("XDG_RUNTIME_DIR") {
+		return Ok(PathBuf::from(dir).join("pls.sock"));
+	}
+
+	let uid = unsafe { libc::geteuid() };
+	let dir = env::temp_dir().join(format!("pls-{uid}"));
+	match fs::DirBuilder::new().mode(0o700).create(&dir) {
+		Ok(()) => {}
+		Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+		Err(err) => return Err(Exc::Io(err)),
+	}
+	// The directory may have been created in advance by another user.
+	let meta = dir.symlink_metadata().map_err(Exc::Io)?;
+	if !meta.is_dir() || meta.uid() != uid || meta.mode() & 0o077 != 0 {
+		return Err(Exc::Other(format!(
+			"{} must be a directory that only the user can access.",
+			dir.display()
+		)));
+	}
+	Ok(dir.join("pls.sock"))
+}
+
+/// Determine whether the node with the given metadata is a socket owned by
+/// the user.
+#[cfg(unix)]
+fn is_own_socket(meta: &fs::Metadata) -> bool {
+	meta.file_type().is_socket() && meta.uid() == unsafe { libc::geteuid() }
+}
@@ -0,0 +1,117 @@
+use crate::enums::Typ;
+use crate::exc::Exc;
+use crate::models::Node;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Represents an external program that can add styles and labels to nodes.
+///
+/// Decorators allow integrations, like showing ticket numbers or build status
+/// next to node names, without having to modify `pls`. Since they execute
+/// arbitrary programs, decorators are only run when the `--decorate` CLI
+/// argument is set.
+///
+/// The program receives a JSON description of the listed directory and its
+/// nodes on STDIN, and must print a JSON object on STDOUT, mapping node names
+/// to their [`Decoration`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Decorator {
+	/// the program to run followed by its arguments
+	pub command: Vec<String>,
+}
+
+/// Represents the extra information added to a node by a [`Decorator`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Decoration {
+	/// styles to apply to the node name and icon
+	pub style: Option<String>,
+	/// marked-up text to show after the node name
+	pub label: Option<String>,
+}
+
+/// Represents the input sent to the decorator program.
+#[derive(Serialize)]
+struct Batch<'a> {
+	dir: &'a Path,
+	nodes: Vec<NodeInfo<'a>>,
+}
+
+/// Represents one node in the input sent to the decorator program.
+#[derive(Serialize)]
+struct NodeInfo<'a> {
+	name: &'a str,
+	path: &'a Path,
+	typ: Typ,
+}
+
+impl Decorator {
+	/// Run the decorator program for the given nodes, which must all be
+	/// children of the given directory.
+	///
+	/// The program is run with the directory as its working directory.
+	pub fn run(&self, dir: &Path, nodes: &[Node]) -> Result<HashMap<String, Decoration>, Exc> {
+		let (program, args) = self
+			.command
+			.split_first()
+			.ok_or(Exc::Other(String::from("Decorator command is empty.")))?;
+
+		let batch = Batch {
+			dir,
+			nodes: nodes
+				.iter()
+				.map(|node| NodeInfo {
+					name: &node.name,
+					path: &node.path,
+					typ: node.typ,
+				})
+				.collect(),
+		};
+		let input = serde_json::to_vec(&batch).map_err(Exc::Json)?;
+
+		debug!("Running decorator {:?}.", self.command);
+		let mut child = Command::new(program)
+			.args(args)
+			.current_dir(dir)
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.spawn()
+			.map_err(Exc::Io)?;
+		if let Some(mut stdin) = child.stdin.take() {
+			stdin.write_all(&input).map_err(Exc::Io)?;
+		} // `stdin` is dropped here, closing the pipe.
+
+		let output = child.wait_with_output().map_err(Exc::Io)?;
+		if !output.status.success() {
+			return Err(Exc::Other(format!(
+				"Decorator {program:?} exited with {}.",
+				output.status
+			)));
+		}
+		serde_json::from_slice(&output.stdout).map_err(Exc::Json)
+	}
+
+	/// Run all the given decorators and attach the decorations to the nodes.
+	///
+	/// Decorators that fail are skipped with a warning, so that one broken
+	/// integration does not prevent the listing from being rendered.
+	pub fn decorate(decorators: &[Self], dir: &Path, nodes: &mut [Node]) {
+		for decorator in decorators {
+			let mut decorations = match decorator.run(dir, nodes) {
+				Ok(decorations) => decorations,
+				Err(exc) => {
+					warn!("Decorator {:?} failed: {exc:?}", decorator.command);
+					continue;
+				}
+			};
+			for node in nodes.iter_mut() {
+				if let Some(decoration) = decorations.remove(&node.name) {
+					node.decorations.push(decoration);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,107 @@
+use crate::enums::Quoting;
+use crate::exc::Exc;
+use std::fs;
+use std::path::Path;
+
+/// the lines at the top of the script, before the commands
+const PREAMBLE: &str = "#!/bin/sh
+# Suggested fixes for the nodes flagged by `pls --audit`.
+# pls has not run any of these commands; review them before running this script.
+set -e
+";
+
+/// Represents the shell script written with `--fix-script`, holding the
+/// command that would fix each risk flagged by `--audit`.
+///
+/// `pls` never changes the permissions or the owners of nodes itself. The
+/// script is only written, so that the commands can be reviewed, edited and
+/// then run by the user.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FixScript {
+	/// the commands, in the order in which the nodes are listed
+	pub commands: Vec<String>,
+}
+
+impl FixScript {
+	// ======
+	// Public
+	// ======
+
+	/// Add the commands that would fix the risks of the node at the given path.
+	///
+	/// Each fix, like `chmod o-w`, is followed by the quoted path. Repeated
+	/// commands are only added once.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the path of the flagged node
+	/// * `fixes` - the fixes suggested by the risk rules that flagged the node
+	pub fn push<'a>(&mut self, path: &Path, fixes: impl IntoIterator<Item = &'a str>) {
+		let path = Quoting::Posix.quote(&path.to_string_lossy());
+		for fix in fixes {
+			let command = format!("{fix} -- {path}");
+			if !self.commands.contains(&command) {
+				self.commands.push(command);
+			}
+		}
+	}
+
+	/// Get the text of the script.
+	pub fn text(&self) -> String {
+		let mut text = String::from(PREAMBLE);
+		if !self.commands.is_empty() {
+			text.push('\n');
+		}
+		for command in &self.commands {
+			text.push_str(command);
+			text.push('\n');
+		}
+		text
+	}
+
+	/// Write the script to the given file, replacing its contents.
+	pub fn write(&self, path: &Path) -> Result<(), Exc> {
+		fs::write(path, self.text()).map_err(|err| {
+			Exc::Other(format!(
+				"Could not write the fix script to {}: {err}",
+				path.display()
+			))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{FixScript, PREAMBLE};
+	use std::path::Path;
+
+	#[test]
+	fn test_commands_are_quoted() {
+		let mut script = FixScript::default();
+		script.push(Path::new("my dir/deploy.sh"), ["chmod o-w"]);
+		script.push(Path::new("-x.pem"), ["chmod go-rwx", "chown root:"]);
+		assert_eq!(
+			script.commands,
+			vec![
+				"chmod o-w -- 'my dir/deploy.sh'",
+				"chmod go-rwx -- -x.pem",
+				"chown root: -- -x.pem",
+			]
+		);
+	}
+
+	#[test]
+	fn test_repeated_commands_are_dropped() {
+		let mut script = FixScript::default();
+		script.push(Path::new(".env"), ["chmod go-rwx", "chmod go-rwx"]);
+		assert_eq!(script.commands, vec!["chmod go-rwx -- .env"]);
+	}
+
+	#[test]
+	fn test_text() {
+		let mut script = FixScript::default();
+		assert_eq!(script.text(), PREAMBLE);
+		script.push(Path::new("a.sh"), ["chmod o-w"]);
+		assert_eq!(script.text(), format!("{PREAMBLE}\nchmod o-w -- a.sh\n"));
+	}
+}
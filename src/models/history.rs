@@ -0,0 +1,215 @@
+use crate::exc::Exc;
+use git2::{DiffFindOptions, DiffOptions, Repository, Sort};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Represents the Git history of a node.
+///
+/// For directories, the history is the combined history of all the nodes
+/// inside the directory.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct History {
+	/// the number of commits that touched the node
+	pub commits: usize,
+	/// the name of the author of the latest commit that touched the node
+	pub author: String,
+	/// the time of the latest commit that touched the node, in seconds since
+	/// the Unix epoch
+	pub time: i64,
+}
+
+impl History {
+	/// Get the histories of all the children of the given directory.
+	///
+	/// The histories are computed with a single walk over the commits that
+	/// touched the directory, following renames similar to `git log --follow`.
+	/// Merge commits are skipped, like they are in `git log` with a path.
+	///
+	/// The result maps the names of the children to their histories. Children
+	/// that are not tracked by Git do not have an entry in the map. If the
+	/// directory is not inside a Git repository, the map is empty.
+	///
+	/// Histories can be cached across invocations, keyed by the commit at
+	/// `HEAD`, if the `PLS_CACHE` environment variable is set.
+	pub fn of_children(dir: &Path) -> HashMap<String, History> {
+		match Self::compute(dir) {
+			Ok(histories) => histories,
+			Err(exc) => {
+				debug!("Could not compute Git history of {dir:?}: {exc:?}");
+				HashMap::new()
+			}
+		}
+	}
+
+	// =======
+	// Private
+	// =======
+
+	fn compute(dir: &Path) -> Result<HashMap<String, History>, Exc> {
+		let repo = Repository::discover(dir).map_err(Exc::Git)?;
+		let Some(workdir) = repo.workdir() else {
+			return Ok(HashMap::new()); // Bare repositories have no files.
+		};
+		let workdir = fs::canonicalize(workdir).map_err(Exc::Io)?;
+		let dir = fs::canonicalize(dir).map_err(Exc::Io)?;
+		let Ok(rel_dir) = dir.strip_prefix(&workdir).map(Path::to_path_buf) else {
+			return Ok(HashMap::new());
+		};
+
+		let head = repo.head().map_err(Exc::Git)?;
+		let head_id = head.peel_to_commit().map_err(Exc::Git)?.id().to_string();
+		let cache_file = cache_file(&head_id, &rel_dir);
+		if let Some(histories) = cache_file.as_deref().and_then(load_from_cache) {
+			return Ok(histories);
+		}
+
+		let histories = walk(&repo, &rel_dir)?;
+
+		if let Some(cache_file) = &cache_file {
+			if let Err(err) = save_to_cache(cache_file, &histories) {
+				warn!("Could not cache Git history: {err}");
+			}
+		}
+		Ok(histories)
+	}
+}
+
+/// Walk the commits from `HEAD` and attribute each commit to the children of
+/// the given directory that it touched.
+///
+/// # Arguments
+///
+/// * `repo` - the repository to walk
+/// * `rel_dir` - the directory, relative to the root of the repository
+fn walk(repo: &Repository, rel_dir: &Path) -> Result<HashMap<String, History>, Exc> {
+	let mut revwalk = repo.revwalk().map_err(Exc::Git)?;
+	revwalk.push_head().map_err(Exc::Git)?;
+	revwalk.set_sorting(Sort::TIME).map_err(Exc::Git)?;
+
+	let mut histories: HashMap<String, History> = HashMap::new();
+	// mapping of past paths to the current paths of renamed nodes
+	let mut aliases: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+	for oid in revwalk {
+		let commit = repo.find_commit(oid.map_err(Exc::Git)?).map_err(Exc::Git)?;
+		if commit.parent_count() > 1 {
+			continue;
+		}
+
+		let tree = commit.tree().map_err(Exc::Git)?;
+		let parent_tree = match commit.parents().next() {
+			Some(parent) => Some(parent.tree().map_err(Exc::Git)?),
+			None => None,
+		};
+
+		let mut opts = DiffOptions::new();
+		if !rel_dir.as_os_str().is_empty() {
+			opts.pathspec(rel_dir);
+		}
+		let mut diff = repo
+			.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))
+			.map_err(Exc::Git)?;
+		diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+			.map_err(Exc::Git)?;
+
+		let mut touched = vec![];
+		for delta in diff.deltas() {
+			let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+				continue;
+			};
+			let path = aliases.get(path).cloned().unwrap_or(path.to_path_buf());
+			if let Some(old_path) = delta.old_file().path() {
+				if old_path != path {
+					aliases.insert(old_path.to_path_buf(), path.clone());
+				}
+			}
+			if let Some(child) = child_name(rel_dir, &path) {
+				if !touched.contains(&child) {
+					touched.push(child);
+				}
+			}
+		}
+
+		for child in touched {
+			let history = histories.entry(child).or_insert_with(|| History {
+				// Commits are walked from newest to oldest, so the first
+				// commit to touch a node is the latest one.
+				author: String::from_utf8_lossy(commit.author().name_bytes()).to_string(),
+				time: commit.time().seconds(),
+				..History::default()
+			});
+			history.commits += 1;
+		}
+	}
+
+	Ok(histories)
+}
+
+/// Get the name of the child of the given directory that contains the given
+/// path, or is the given path itself.
+///
+/// Returns `None` if the path is not inside the directory.
+fn child_name(dir: &Path, path: &Path) -> Option<String> {
+	path.strip_prefix(dir)
+		.ok()?
+		.components()
+		.next()
+		.map(|comp| comp.as_os_str().to_string_lossy().to_string())
+}
+
+/// Get the path of the cache file for the given commit and directory.
+///
+/// Caching is only enabled if the `PLS_CACHE` environment variable is set.
+fn cache_file(head_id: &str, rel_dir: &Path) -> Option<PathBuf> {
+	let cache = env::var("PLS_CACHE").ok()?;
+	let dir_key = rel_dir.to_string_lossy().replace('/', "%");
+	Some(
+		Path::new(&cache)
+			.join("git")
+			.join(format!("{head_id}-{dir_key}.json")),
+	)
+}
+
+/// Load the histories from the cache, if present and valid.
+fn load_from_cache(cache_file: &Path) -> Option<HashMap<String, History>> {
+	let data = fs::read(cache_file).ok()?;
+	serde_json::from_slice(&data).ok()
+}
+
+/// Save the histories to the cache, creating the necessary directories.
+fn save_to_cache(cache_file: &Path, histories: &HashMap<String, History>) -> Result<(), Exc> {
+	let data = serde_json::to_vec(histories).map_err(Exc::Json)?;
+	fs::create_dir_all(cache_file.parent().unwrap()).map_err(Exc::Io)?;
+	fs::write(cache_file, data).map_err(Exc::Io)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::child_name;
+	use std::path::Path;
+
+	macro_rules! make_child_name_test {
+		( $($name:ident: $dir:expr, $path:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let child = child_name(Path::new($dir), Path::new($path));
+					assert_eq!(child.as_deref(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_child_name_test!(
+		test_root_file: "", "Cargo.toml" => Some("Cargo.toml"),
+		test_root_nested: "", "src/main.rs" => Some("src"),
+		test_dir_file: "src", "src/main.rs" => Some("main.rs"),
+		test_dir_nested: "src", "src/models/node.rs" => Some("models"),
+		test_outside_dir: "src", "docs/index.mdx" => None,
+		test_prefix_is_not_parent: "src", "srcs/main.rs" => None,
+	);
+}
@@ -0,0 +1,188 @@
+use crate::utils::globs::{glob_to_regex, haystack};
+use git2::Repository;
+use log::debug;
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Represents a single rule from an ignore file.
+struct Rule {
+	/// the 1-based line number of the rule in the ignore file
+	line: usize,
+	/// the pattern of the rule, compiled from the glob into a regex
+	pat: Regex,
+	/// whether the rule re-includes paths instead of ignoring them
+	negated: bool,
+}
+
+/// Represents an ignore file, like `.gitignore`, and the rules in it.
+struct Source {
+	/// the path of the ignore file, as it should be shown to the user
+	name: String,
+	/// the path of the directory the rules apply to, relative to the root of
+	/// the repository
+	base: PathBuf,
+	rules: Vec<Rule>,
+}
+
+/// Represents the ignore files applicable to the children of a directory.
+///
+/// Git decides whether a child is ignored, and these ignore files are only
+/// used to find the rule responsible for it, similar to `git check-ignore -v`.
+pub struct Ignores {
+	repo: Repository,
+	/// the path of the directory relative to the root of the repository
+	rel_dir: PathBuf,
+	/// the ignore files, in increasing order of precedence
+	sources: Vec<Source>,
+}
+
+impl Ignores {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Find and parse the ignore files applicable to the given directory.
+	///
+	/// These are, in increasing order of precedence, the global excludes file,
+	/// the repository's `.git/info/exclude` and the `.gitignore` files in the
+	/// directory and all its ancestors up to the root of the repository.
+	///
+	/// This returns `None` if the directory is not inside a Git repository.
+	pub fn discover(dir: &Path) -> Option<Self> {
+		let repo = Repository::discover(dir).ok()?;
+		let workdir = fs::canonicalize(repo.workdir()?).ok()?;
+		let rel_dir = fs::canonicalize(dir)
+			.ok()?
+			.strip_prefix(&workdir)
+			.ok()?
+			.to_path_buf();
+
+		let mut files = vec![];
+		if let Some(global) = global_excludes_file(&repo) {
+			files.push((global.to_string_lossy().to_string(), global, PathBuf::new()));
+		}
+		files.push((
+			String::from(".git/info/exclude"),
+			repo.path().join("info").join("exclude"),
+			PathBuf::new(),
+		));
+		let mut ancestors: Vec<_> = rel_dir.ancestors().collect();
+		ancestors.reverse();
+		for base in ancestors {
+			let name = base.join(".gitignore");
+			files.push((
+				name.to_string_lossy().to_string(),
+				workdir.join(&name),
+				base.to_path_buf(),
+			));
+		}
+
+		let sources = files
+			.into_iter()
+			.filter_map(|(name, path, base)| {
+				let text = fs::read_to_string(path).ok()?;
+				debug!("Found ignore file {name:?}.");
+				Some(Source {
+					name,
+					base,
+					rules: parse(&text),
+				})
+			})
+			.collect();
+
+		Some(Self {
+			repo,
+			rel_dir,
+			sources,
+		})
+	}
+
+	// ======
+	// Public
+	// ======
+
+	/// Get the source of the rule that causes Git to ignore the child of the
+	/// directory with the given name.
+	///
+	/// The source is formatted as the path of the ignore file followed by the
+	/// line number of the rule. This returns `None` if the child is not
+	/// ignored, or if the responsible rule could not be found.
+	pub fn source_of(&self, name: &str, is_dir: bool) -> Option<String> {
+		let path = self.rel_dir.join(name);
+		if !self.repo.is_path_ignored(&path).unwrap_or(false) {
+			return None;
+		}
+
+		self.sources.iter().rev().find_map(|source| {
+			let rel_path = path.strip_prefix(&source.base).ok()?;
+			let rel_path = haystack(rel_path, is_dir);
+			source
+				.rules
+				.iter()
+				.rev()
+				.find(|rule| rule.pat.is_match(&rel_path))
+				.filter(|rule| !rule.negated)
+				.map(|rule| format!("{}:{}", source.name, rule.line))
+		})
+	}
+}
+
+/// Get the path of the global excludes file.
+///
+/// This is the file set by the `core.excludesFile` Git configuration, or the
+/// default `$XDG_CONFIG_HOME/git/ignore` if it is not set.
+fn global_excludes_file(repo: &Repository) -> Option<PathBuf> {
+	if let Some(path) = repo
+		.config()
+		.ok()
+		.and_then(|config| config.get_path("core.excludesFile").ok())
+	{
+		return Some(path);
+	}
+	env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.or_else(|| home::home_dir().map(|home| home.join(".config")))
+		.map(|config_dir| config_dir.join("git").join("ignore"))
+}
+
+/// Parse the rules from the contents of an ignore file.
+///
+/// Blank lines, comments and rules with patterns that cannot be compiled are
+/// skipped.
+fn parse(text: &str) -> Vec<Rule> {
+	text.lines()
+		.enumerate()
+		.filter_map(|(idx, line)| {
+			let line = line.trim_end();
+			if line.is_empty() || line.starts_with('#') {
+				return None;
+			}
+			let (negated, glob) = match line.strip_prefix('!') {
+				Some(glob) => (true, glob),
+				None => (false, line),
+			};
+			let glob = glob.strip_prefix('\\').unwrap_or(glob);
+			let pat = glob_to_regex(glob)?;
+			Some(Rule {
+				line: idx + 1,
+				pat,
+				negated,
+			})
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::parse;
+
+	#[test]
+	fn test_parse_skips_blanks_and_comments() {
+		let rules = parse("# comment\n\n*.log\n!keep.log\n\\#hash\n");
+		let lines: Vec<_> = rules.iter().map(|rule| (rule.line, rule.negated)).collect();
+		assert_eq!(lines, vec![(3, false), (4, true), (5, false)]);
+		assert!(rules[2].pat.is_match("#hash"));
+	}
+}
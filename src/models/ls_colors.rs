@@ -0,0 +1,270 @@
+use crate::config::EntryConst;
+use crate::enums::{PatternSyntax, SymState, Typ};
+use crate::models::{regex_source, Spec};
+use regex::bytes::Regex;
+use std::env;
+
+/// the names of the 8 ANSI colors, in the order of their SGR codes
+const COLORS: [&str; 8] = [
+	"black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// the two-letter codes of the node types, with the type they style
+const TYPS: [(&str, Typ); 7] = [
+	("di", Typ::Dir),
+	("ln", Typ::Symlink),
+	("pi", Typ::Fifo),
+	("so", Typ::Socket),
+	("bd", Typ::BlockDevice),
+	("cd", Typ::CharDevice),
+	("fi", Typ::File),
+];
+
+/// Represents the styles read from the `LS_COLORS` environment variable, as
+/// set by `dircolors` for GNU `ls`.
+///
+/// With `--ls-colors`, these styles are applied on top of the config, so that
+/// users migrating from `ls` or `eza` can keep their color schemes. The SGR
+/// codes of each style are translated into style directives, the codes for
+/// node types into type styles, and the glob patterns into specs.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LsColors {
+	/// the styles of node types and states, keyed by their two-letter code
+	pub kinds: Vec<(String, String)>,
+	/// the styles of glob patterns, like `*.tar`, in the order they are defined
+	pub globs: Vec<(String, String)>,
+}
+
+impl LsColors {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Read the styles from the `LS_COLORS` environment variable, if it is set.
+	pub fn from_env() -> Option<Self> {
+		env::var("LS_COLORS").ok().map(|value| Self::parse(&value))
+	}
+
+	/// Parse the styles from the value of `LS_COLORS`.
+	///
+	/// The value is a colon-separated list of `key=codes` entries. Malformed
+	/// entries are ignored, and for repeated keys the last entry wins, like in
+	/// GNU `ls`.
+	pub fn parse(value: &str) -> Self {
+		let mut ls_colors = Self::default();
+		for (key, codes) in value.split(':').filter_map(|entry| entry.split_once('=')) {
+			let list = if key.contains('*') || key.contains('.') {
+				&mut ls_colors.globs
+			} else {
+				&mut ls_colors.kinds
+			};
+			list.retain(|(existing, _)| existing != key);
+			list.push((key.to_string(), directives(codes)));
+		}
+		ls_colors
+	}
+
+	// =========
+	// Appliers
+	// =========
+
+	/// Apply the styles of node types and states to the given entry constants.
+	///
+	/// Codes that have no counterpart in `pls`, like `su` for setuid files,
+	/// are ignored.
+	pub fn apply(&self, entry_const: &mut EntryConst) {
+		for (key, style) in &self.kinds {
+			let style = style.clone();
+			if let Some((_, typ)) = TYPS.iter().find(|(code, _)| code == key) {
+				if let Some(info) = entry_const.typ.get_mut(typ) {
+					info.style = style;
+				}
+				continue;
+			}
+			match key.as_str() {
+				"ex" => entry_const.exec_style = style,
+				"mi" => entry_const.missing_style = style,
+				"or" => {
+					if let Some(info) = entry_const.symlink.get_mut(&SymState::Broken) {
+						info.style = style;
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	/// Get the specs that apply the styles of the glob patterns.
+	///
+	/// Patterns that share a style are combined into a single spec, which
+	/// keeps the number of specs small for the long lists that `dircolors`
+	/// generates.
+	pub fn specs(&self) -> Vec<Spec> {
+		let mut grouped: Vec<(&str, Vec<String>)> = vec![];
+		for (glob, style) in &self.globs {
+			let source = regex_source(glob, PatternSyntax::Glob).into_owned();
+			match grouped.iter_mut().find(|(existing, _)| existing == style) {
+				Some((_, sources)) => sources.push(source),
+				None => grouped.push((style, vec![source])),
+			}
+		}
+		grouped
+			.into_iter()
+			.filter_map(|(style, sources)| {
+				let pattern = Regex::new(&sources.join("|")).ok()?;
+				Some(Spec {
+					pattern,
+					icons: None,
+					style: Some(style.to_string()),
+					importance: None,
+					collapse: None,
+					opener: None,
+					summarize: None,
+					pin: None,
+				})
+			})
+			.collect()
+	}
+}
+
+/// Translate the given SGR codes, like `01;34`, into style directives.
+///
+/// Both the 8 ANSI colors, with their bright versions, and the 256-color and
+/// true-color forms are supported. Unknown codes are ignored.
+fn directives(codes: &str) -> String {
+	let codes: Vec<u8> = codes
+		.split(';')
+		.filter_map(|code| code.parse().ok())
+		.collect();
+	let mut directives = vec![];
+	let mut iter = codes.into_iter();
+	while let Some(code) = iter.next() {
+		let directive = match code {
+			0 => String::from("clear"),
+			1 => String::from("bold"),
+			2 => String::from("dimmed"),
+			3 => String::from("italic"),
+			4 => String::from("underline"),
+			5 => String::from("blink"),
+			7 => String::from("reversed"),
+			8 => String::from("hidden"),
+			9 => String::from("strikethrough"),
+			30..=37 => COLORS[(code - 30) as usize].to_string(),
+			40..=47 => format!("bg:{}", COLORS[(code - 40) as usize]),
+			90..=97 => format!("bright_{}", COLORS[(code - 90) as usize]),
+			100..=107 => format!("bg:bright_{}", COLORS[(code - 100) as usize]),
+			38 | 48 => {
+				let prefix = if code == 48 { "bg:" } else { "" };
+				let color = match iter.next() {
+					Some(5) => iter.next().map(xterm),
+					Some(2) => match (iter.next(), iter.next(), iter.next()) {
+						(Some(r), Some(g), Some(b)) => Some(format!("rgb({r},{g},{b})")),
+						_ => None,
+					},
+					_ => None,
+				};
+				match color {
+					Some(color) => format!("{prefix}{color}"),
+					None => continue,
+				}
+			}
+			_ => continue,
+		};
+		directives.push(directive);
+	}
+	directives.join(" ")
+}
+
+/// Get the directive for the given color of the 256-color palette of xterm.
+///
+/// The first 16 colors are the ANSI colors and their bright versions, and the
+/// rest are a 6×6×6 color cube followed by a ramp of 24 grays.
+fn xterm(idx: u8) -> String {
+	match idx {
+		0..=7 => COLORS[idx as usize].to_string(),
+		8..=15 => format!("bright_{}", COLORS[(idx - 8) as usize]),
+		16..=231 => {
+			let level = |val: u8| if val == 0 { 0 } else { 55 + val * 40 };
+			let idx = idx - 16;
+			format!(
+				"rgb({},{},{})",
+				level(idx / 36),
+				level(idx / 6 % 6),
+				level(idx % 6)
+			)
+		}
+		_ => {
+			let gray = 8 + (idx - 232) * 10;
+			format!("rgb({gray},{gray},{gray})")
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{directives, LsColors};
+
+	macro_rules! make_directives_test {
+		( $($name:ident: $codes:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(directives($codes), $expected);
+				}
+			)*
+		};
+	}
+
+	make_directives_test!(
+		test_bold_blue: "01;34" => "bold blue",
+		test_reset: "0" => "clear",
+		test_background: "37;41" => "white bg:red",
+		test_bright: "92;100" => "bright_green bg:bright_black",
+		test_256_ansi: "38;5;9" => "bright_red",
+		test_256_cube: "38;5;208" => "rgb(255,135,0)",
+		test_256_gray: "48;5;244" => "bg:rgb(128,128,128)",
+		test_true_color: "38;2;10;20;30" => "rgb(10,20,30)",
+		test_truncated_color: "38;5" => "",
+		test_unknown_code: "1;66;31" => "bold red",
+	);
+
+	#[test]
+	fn test_parse_splits_kinds_and_globs() {
+		let ls_colors = LsColors::parse("di=01;34:*.tar=31:ln=36:README=4:bad");
+		assert_eq!(
+			ls_colors.kinds,
+			vec![
+				(String::from("di"), String::from("bold blue")),
+				(String::from("ln"), String::from("cyan")),
+				(String::from("README"), String::from("underline")),
+			]
+		);
+		assert_eq!(
+			ls_colors.globs,
+			vec![(String::from("*.tar"), String::from("red"))]
+		);
+	}
+
+	#[test]
+	fn test_parse_last_entry_wins() {
+		let ls_colors = LsColors::parse("*.tar=31:*.gz=32:*.tar=33");
+		assert_eq!(
+			ls_colors.globs,
+			vec![
+				(String::from("*.gz"), String::from("green")),
+				(String::from("*.tar"), String::from("yellow")),
+			]
+		);
+	}
+
+	#[test]
+	fn test_specs_group_by_style() {
+		let ls_colors = LsColors::parse("*.tar=31:*.gz=32:*.zip=31");
+		let specs = ls_colors.specs();
+		assert_eq!(specs.len(), 2);
+		assert!(specs[0].pattern.is_match(b"a.zip"));
+		assert!(specs[0].pattern.is_match(b"a.tar"));
+		assert!(!specs[0].pattern.is_match(b"a.gz"));
+		assert_eq!(specs[1].style.as_deref(), Some("green"));
+	}
+}
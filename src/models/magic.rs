@@ -0,0 +1,155 @@
+use std::fs::File;
+use std::io::{Read, Result as IoResult};
+use std::path::Path;
+
+/// the number of bytes read from the start of a file to sniff its contents,
+/// enough for the signatures below and most shebang lines
+const HEAD_LEN: u64 = 128;
+
+/// the signatures of the binary formats that can be sniffed, each with its
+/// offset from the start of the file and the kind of contents it identifies
+const SIGNATURES: &[(usize, &[u8], Magic)] = &[
+	(0, b"\x7fELF", Magic::Executable),
+	(0, b"\xcf\xfa\xed\xfe", Magic::Executable), // 64-bit Mach-O
+	(0, b"\xce\xfa\xed\xfe", Magic::Executable), // 32-bit Mach-O
+	(0, b"\xca\xfe\xba\xbe", Magic::Executable), // universal Mach-O
+	(0, b"MZ", Magic::Executable),               // PE, for Windows
+	(0, b"\x89PNG\r\n\x1a\n", Magic::Png),
+	(0, b"\xff\xd8\xff", Magic::Jpeg),
+	(0, b"GIF8", Magic::Gif),
+	(8, b"WEBP", Magic::Webp),
+	(0, b"%PDF-", Magic::Pdf),
+	(0, b"PK\x03\x04", Magic::Zip),
+	(0, b"\x1f\x8b", Magic::Gzip),
+];
+
+/// the interpreters of scripts, as pairs of the prefix of the name of the
+/// interpreter and the extension of its scripts
+const INTERPRETERS: &[(&str, &str)] = &[
+	("bash", "sh"),
+	("dash", "sh"),
+	("fish", "fish"),
+	("ksh", "sh"),
+	("node", "js"),
+	("perl", "pl"),
+	("python", "py"),
+	("ruby", "rb"),
+	("sh", "sh"),
+	("zsh", "sh"),
+];
+
+/// Represents the kind of contents of a file, sniffed from its first bytes.
+///
+/// Sniffing lets files without an extension, like scripts and binaries in a
+/// `bin` directory, be matched with the specs of the extension that their
+/// contents usually have. Since it costs an extra read per file, it is only
+/// done with `--sniff`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Magic {
+	Script(&'static str), // script, with the extension for its interpreter
+	Executable,           // compiled binary, like ELF, Mach-O or PE
+	Png,                  // PNG image
+	Jpeg,                 // JPEG image
+	Gif,                  // GIF image
+	Webp,                 // WebP image
+	Pdf,                  // PDF document
+	Zip,                  // ZIP archive
+	Gzip,                 // gzip archive
+}
+
+impl Magic {
+	/// Sniff the kind of contents of the file at the given path.
+	///
+	/// Only the first few bytes of the file are read.
+	pub fn sniff(path: &Path) -> IoResult<Option<Self>> {
+		let mut head = vec![];
+		File::open(path)?.take(HEAD_LEN).read_to_end(&mut head)?;
+		Ok(Self::detect(&head))
+	}
+
+	/// Detect the kind of contents from the first bytes of a file.
+	pub fn detect(head: &[u8]) -> Option<Self> {
+		if let Some(line) = head.strip_prefix(b"#!") {
+			return interpreter(line).map(Magic::Script);
+		}
+		SIGNATURES
+			.iter()
+			.find(|(offset, signature, _)| {
+				head.get(*offset..)
+					.is_some_and(|rest| rest.starts_with(signature))
+			})
+			.map(|(_, _, magic)| *magic)
+	}
+
+	/// Get the extension that files with these contents usually have.
+	///
+	/// This extension is appended to the name of the node to find the specs
+	/// that would apply to it if it had the extension.
+	pub fn ext(&self) -> &'static str {
+		match self {
+			Magic::Script(ext) => ext,
+			Magic::Executable => "exe",
+			Magic::Png => "png",
+			Magic::Jpeg => "jpg",
+			Magic::Gif => "gif",
+			Magic::Webp => "webp",
+			Magic::Pdf => "pdf",
+			Magic::Zip => "zip",
+			Magic::Gzip => "gz",
+		}
+	}
+}
+
+/// Get the extension of scripts for the interpreter named in the given
+/// shebang line, which follows the `#!`.
+///
+/// With `/usr/bin/env`, the interpreter is the first argument that is not an
+/// option. Version suffixes, like in `python3`, are ignored.
+fn interpreter(line: &[u8]) -> Option<&'static str> {
+	let line = line.split(|&byte| byte == b'\n').next()?;
+	let line = String::from_utf8_lossy(line);
+	let mut words = line.split_whitespace();
+	let mut program = words.next()?.rsplit('/').next()?;
+	if program == "env" {
+		program = words.find(|word| !word.starts_with('-'))?;
+	}
+	INTERPRETERS
+		.iter()
+		.find(|(name, _)| {
+			program
+				.strip_prefix(name)
+				.is_some_and(|rest| rest.chars().all(|ch| ch.is_ascii_digit() || ch == '.'))
+		})
+		.map(|(_, ext)| *ext)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Magic;
+
+	macro_rules! make_detect_test {
+		( $($name:ident: $head:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Magic::detect($head), $expected);
+				}
+			)*
+		};
+	}
+
+	make_detect_test!(
+		test_empty: b"" => None,
+		test_plain_text: b"hello, world\n" => None,
+		test_bash_shebang: b"#!/bin/bash\necho hi\n" => Some(Magic::Script("sh")),
+		test_env_shebang: b"#!/usr/bin/env python3\n" => Some(Magic::Script("py")),
+		test_env_option_shebang: b"#!/usr/bin/env -S node --harmony\n" => Some(Magic::Script("js")),
+		test_unknown_shebang: b"#!/usr/bin/awk -f\n" => None,
+		test_prefix_is_not_version: b"#!/usr/bin/shellcheck\n" => None,
+		test_elf: b"\x7fELF\x02\x01\x01" => Some(Magic::Executable),
+		test_png: b"\x89PNG\r\n\x1a\n\0\0" => Some(Magic::Png),
+		test_webp: b"RIFF\0\0\0\0WEBPVP8 " => Some(Magic::Webp),
+		test_short_webp: b"RIFF" => None,
+		test_pdf: b"%PDF-1.7" => Some(Magic::Pdf),
+	);
+}
@@ -0,0 +1,27 @@
+use crate::models::{Acl, Owner, Tags};
+use std::cell::OnceCell;
+
+/// Holds intermediate values of a node that are shared by several columns of
+/// its row, or by several comparisons while sorting.
+///
+/// Each value is computed on first use and reused thereafter, so that wide
+/// tables and sorts do not repeat lookups for every column or comparison.
+/// Values are only computed once the node is fully set up, so they never have
+/// to be invalidated.
+#[derive(Default)]
+pub struct Memo {
+	/// the styling directives of the node name
+	pub directives: OnceCell<String>,
+	/// the user that owns the node, if known
+	pub user: OnceCell<Option<Owner>>,
+	/// the group that owns the node, if known
+	pub group: OnceCell<Option<Owner>>,
+	/// the size of the node, `None` for directories
+	pub size: OnceCell<Option<u64>>,
+	/// the names of the extended attributes of the node
+	pub xattrs: OnceCell<Vec<String>>,
+	/// the extended ACL of the node, if it has one
+	pub acl: OnceCell<Option<Acl>>,
+	/// the tags of the node, read from an extended attribute
+	pub tags: OnceCell<Tags>,
+}
@@ -1,15 +1,34 @@
 use crate::config::{AppConst, Conf, EntryConst};
-use crate::enums::{Appearance, Collapse, DetailField, Icon, Typ};
-use crate::models::{OwnerMan, Spec};
+use crate::enums::{
+	Appearance, Collapse, DetailField, ExportIcons, Icon, IconSet, IconSource, Typ,
+};
+use crate::ext::{Abs, PlatformMeta, Xattr};
+use crate::fmt::{is_styled, link, truncate};
+use crate::gfx::builtin_icon;
+use crate::models::{
+	Acl, Archived, Decoration, History, Magic, Memo, Owner, OwnerMan, Risk, Snapshot, Spec, Stat,
+	Summary, Tags,
+};
 use crate::traits::{Detail, Imp, Name, Sym};
+use crate::utils::mounts;
+use crate::utils::templates::fill;
+use crate::utils::times::{epoch, relative};
+use crate::utils::urls::file_url;
 use crate::PLS;
+use clap::ValueEnum;
+use log::debug;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::fmt::{Display, Formatter, Result as FmtResult};
-use std::fs::Metadata;
 use std::io::Result as IoResult;
 use std::iter::once;
 use std::path::{Path, PathBuf};
+use std::ptr;
+use std::time::Duration;
+
+/// the maximum width of the values of extended attributes listed with
+/// `--xattrs`
+const XATTR_WIDTH: usize = 64;
 
 pub struct Node<'pls> {
 	/// the name of the node on the file system, determined from the path and
@@ -20,12 +39,33 @@ pub struct Node<'pls> {
 	pub display_name: String,
 
 	pub path: PathBuf,
-	meta: IoResult<Metadata>,
+	meta: IoResult<Stat>,
 	pub typ: Typ, // `Typ::Unknown` if `meta` is `Err`
 
 	pub appearances: HashSet<Appearance>,
 
 	pub specs: Vec<&'pls Spec>,
+	/// the risk rules that flag the node
+	pub risks: Vec<&'pls Risk>,
+	/// whether the details of the node are hidden with `--redact`
+	pub is_redacted: bool,
+	/// extra styles and labels provided by external decorators
+	pub decorations: Vec<Decoration>,
+	/// the Git history of the node, only computed if needed
+	pub history: Option<History>,
+	/// the owners of the node declared in `CODEOWNERS`, only computed if needed
+	pub code_owners: Option<Vec<String>>,
+	/// the ignore rule that causes Git to ignore the node, only computed if
+	/// needed
+	pub ignored_by: Option<String>,
+	/// the summary of the contents of the directory, only computed if a spec
+	/// asks for it
+	pub summary: Option<Summary>,
+	/// the metadata recorded in the archive headers, only present for nodes
+	/// listed from inside an archive with `--as-dir`
+	pub archived: Option<Archived>,
+	/// intermediate values shared by several columns, computed on first use
+	pub memo: Memo,
 
 	pub collapse_name: Option<String>,
 	pub children: Vec<Node<'pls>>,
@@ -37,6 +77,43 @@ impl<'pls> Node<'pls> {
 	// ===========
 
 	pub fn new(path: &Path) -> Self {
+		// With `--dereference`, symlinks are described by their targets, but
+		// broken symlinks have no target and are still described by the link.
+		let meta = {
+			// Only nodes on network file systems are read on a thread of their
+			// own, so that a dead mount cannot hang the listing.
+			let dir = match path.parent() {
+				Some(dir) if !dir.as_os_str().is_empty() => dir,
+				_ => Path::new("."),
+			};
+			let timeout = mounts::timeout_in(dir, PLS.args.stat_timeout.map(Duration::from_millis));
+			let path = path.to_owned();
+			mounts::within(timeout, move || {
+				if PLS.args.dereference {
+					path.metadata().or_else(|_| path.symlink_metadata())
+				} else {
+					path.symlink_metadata()
+				}
+				.map(|meta| Stat::from(&meta))
+			})
+		};
+		Self::with_meta(path, meta)
+	}
+
+	/// Create a new instance of `Node` from metadata that was already read,
+	/// like that cached by `pls daemon`, without reading it again.
+	///
+	/// The cached metadata describes symlinks themselves, so with
+	/// `--dereference`, the metadata of symlinks is read again.
+	pub fn cached(path: &Path, stat: Stat) -> Self {
+		if PLS.args.dereference && stat.typ.is_link() {
+			return Self::new(path);
+		}
+		Self::with_meta(path, Ok(stat))
+	}
+
+	/// Create a new instance of `Node` with the given metadata.
+	fn with_meta(path: &Path, meta: IoResult<Stat>) -> Self {
 		let name = path
 			.file_name()
 			.unwrap_or_default()
@@ -45,8 +122,7 @@ impl<'pls> Node<'pls> {
 		let display_name = name.clone();
 
 		let path = path.to_owned();
-		let meta = path.symlink_metadata();
-		let typ = path.as_path().try_into().unwrap_or(Typ::Unknown);
+		let typ = meta.as_ref().map(|stat| stat.typ).unwrap_or(Typ::Unknown);
 
 		Self {
 			name,
@@ -56,11 +132,33 @@ impl<'pls> Node<'pls> {
 			typ,
 			appearances: HashSet::new(),
 			specs: vec![],
+			risks: vec![],
+			is_redacted: false,
+			decorations: vec![],
+			history: None,
+			code_owners: None,
+			ignored_by: None,
+			summary: None,
+			archived: None,
+			memo: Memo::default(),
 			collapse_name: None,
 			children: vec![],
 		}
 	}
 
+	/// Create a new instance of `Node` for a node inside an archive.
+	///
+	/// The path is the virtual path of the node, i.e. the path of the archive
+	/// joined with the path of the node inside it. Since this path does not
+	/// exist on the file system, the type and metadata are taken from the
+	/// archive headers instead.
+	pub fn archived(path: &Path, typ: Typ, archived: Archived) -> Self {
+		let mut node = Self::new(path);
+		node.typ = typ;
+		node.archived = Some(archived);
+		node
+	}
+
 	// ===========
 	// Appearances
 	// ===========
@@ -109,23 +207,212 @@ impl<'pls> Node<'pls> {
 	// =======
 
 	/// Get the metadata of the node if it was successfully retrieved.
-	pub fn meta_ok(&self) -> Option<&Metadata> {
+	pub fn meta_ok(&self) -> Option<&Stat> {
 		self.meta.as_ref().ok()
 	}
 
+	/// Get whether slow lookups, like those of owners, block counts and
+	/// symlink targets, are skipped for this node.
+	///
+	/// With `--fast`, these are skipped for nodes on network file systems,
+	/// where every lookup can stall on an unresponsive server.
+	pub fn is_on_slow_fs(&self) -> bool {
+		PLS.args.fast
+			&& self
+				.meta_ok()
+				.and_then(|meta| meta.dev_id())
+				.is_some_and(|dev| {
+					let timeout = PLS.args.stat_timeout.map(Duration::from_millis);
+					mounts::is_network(&self.path, dev, timeout)
+				})
+	}
+
+	/// Get whether the size of the node is within all the bounds given with
+	/// `--size`.
+	///
+	/// Nodes without a size, like directories without `--du`, are only
+	/// included when there are no bounds.
+	pub fn is_size_included(&self) -> bool {
+		if PLS.args.size.is_empty() {
+			return true;
+		}
+		self.size_val()
+			.is_some_and(|size| PLS.args.size.iter().all(|filter| filter.matches(size)))
+	}
+
+	/// Get whether the modification time of the node is within the window given
+	/// with `--newer-than` and `--older-than`.
+	///
+	/// Nodes without a modification time are only included when there is no
+	/// window.
+	pub fn is_mtime_included(&self) -> bool {
+		let args = &PLS.args;
+		if args.newer_than.is_none() && args.older_than.is_none() {
+			return true;
+		}
+		let Some(mtime) = self.time_val(DetailField::Mtime) else {
+			return false;
+		};
+		let (now, offset) = (PLS.now(), PLS.utc_offset());
+		args.newer_than
+			.map_or(true, |bound| mtime > bound.instant(now, offset))
+			&& args
+				.older_than
+				.map_or(true, |bound| mtime < bound.instant(now, offset))
+	}
+
+	/// Get whether the node is a regular file that can be executed, because
+	/// any of its executable bits is set.
+	///
+	/// Windows has no executable bits, so no node is executable there.
+	pub fn is_exec(&self) -> bool {
+		self.typ == Typ::File
+			&& self
+				.meta_ok()
+				.and_then(|meta| meta.mode_bits())
+				.is_some_and(|mode| mode & 0o111 != 0)
+	}
+
+	/// Get whether the node is lifted to the top of the listing, because its
+	/// name matches `--pin` or because the last spec that defines `pin`
+	/// enables it.
+	pub fn is_pinned(&self) -> bool {
+		PLS.args
+			.pin
+			.as_ref()
+			.is_some_and(|pat| pat.is_match(self.name.as_bytes()))
+			|| self
+				.specs
+				.iter()
+				.rev()
+				.find_map(|spec| spec.pin)
+				.unwrap_or(false)
+	}
+
+	/// Get whether the node does not exist, either on the file system or in an
+	/// archive.
+	pub fn is_missing(&self) -> bool {
+		self.meta.is_err() && self.archived.is_none()
+	}
+
+	/// Get whether the node is a cloud placeholder, i.e. a file synced by a
+	/// service like OneDrive, iCloud or Dropbox whose content is not stored
+	/// locally.
+	///
+	/// The detection depends on the platform:
+	///
+	/// * On Windows, the file has the offline or recall attributes.
+	/// * On macOS, the file has the dataless flag.
+	/// * Elsewhere, the file is larger than any inline data but occupies no
+	///   blocks on the disk.
+	pub fn is_cloud_placeholder(&self) -> bool {
+		if !matches!(self.typ, Typ::File | Typ::Reparse) {
+			return false;
+		}
+		self.meta_ok().is_some_and(|stat| stat.is_dataless)
+	}
+
 	// =========
 	// Mutations
 	// =========
 
-	/// Link the current node with all the specs that apply to it, based on
-	/// whether the spec's `pattern` matches with this node's name.
-	pub fn match_specs(&mut self, all_specs: &'pls [Spec]) {
-		self.specs = all_specs
+	/// Link the current node with all the specs from the config that apply to
+	/// it, based on whether the spec's `pattern` matches with this node's name.
+	///
+	/// With `--sniff`, the specs that would apply to the node if it had the
+	/// extension that its contents usually have are placed before the others,
+	/// so that the specs matching the actual name take precedence.
+	pub fn match_specs(&mut self, conf: &'pls Conf) {
+		self.specs = conf.matching_specs(self.name.as_bytes());
+		if let Some(magic) = self.sniff() {
+			let name = format!("{}.{}", self.name, magic.ext());
+			let mut specs: Vec<_> = conf
+				.matching_specs(name.as_bytes())
+				.into_iter()
+				.filter(|spec| !self.specs.iter().any(|own| ptr::eq(*own, *spec)))
+				.collect();
+			specs.append(&mut self.specs);
+			self.specs = specs;
+		}
+	}
+
+	/// Sniff the kind of contents of the node, with `--sniff`.
+	///
+	/// Only regular files without an extension are sniffed, since an extension
+	/// already identifies the contents. Nodes inside archives, and nodes
+	/// skipped as slow with `--fast`, are not sniffed.
+	fn sniff(&self) -> Option<Magic> {
+		if !PLS.args.sniff
+			|| self.typ != Typ::File
+			|| self.archived.is_some()
+			|| !self.ext().is_empty()
+			|| self.is_on_slow_fs()
+		{
+			return None;
+		}
+		Magic::sniff(&self.path)
+			.map_err(|err| debug!("Could not sniff {:?}: {err}", self.name))
+			.ok()
+			.flatten()
+	}
+
+	/// Find the risk rules from the config that flag this node.
+	pub fn match_risks(&mut self, conf: &'pls Conf) {
+		self.risks = conf
+			.risks
 			.iter()
-			.filter(|spec| spec.pattern.is_match(self.name.as_bytes()))
+			.filter(|risk| risk.matches(self))
 			.collect();
 	}
 
+	/// Get the fixes suggested by the risk rules that flag this node, each
+	/// with the rule that suggests it.
+	///
+	/// Nodes inside archives cannot be fixed in place, so they have no fixes.
+	pub fn fixes(&self) -> impl Iterator<Item = (&Risk, &str)> {
+		let risks = if self.archived.is_some() {
+			&[][..]
+		} else {
+			&self.risks[..]
+		};
+		risks
+			.iter()
+			.filter_map(|risk| risk.fix.as_deref().map(|fix| (*risk, fix)))
+	}
+
+	/// Call the given function with this node and, recursively, each of its
+	/// children.
+	pub fn walk(&self, f: &mut dyn FnMut(&Node)) {
+		f(self);
+		for child in &self.children {
+			child.walk(f);
+		}
+	}
+
+	/// Mark the node as redacted, with `--redact`, if its name matches any of
+	/// the sensitive patterns from the config.
+	///
+	/// Redacted nodes hide their sizes and timestamps, which can hint at the
+	/// contents of credentials, and are dimmed.
+	pub fn redact(&mut self, conf: &Conf) {
+		self.is_redacted = PLS.args.redact && conf.is_sensitive(self.name.as_bytes());
+	}
+
+	/// Compute the summary of the contents of the node, if it is a directory
+	/// and the last spec that defines `summarize` enables it.
+	pub fn summarize(&mut self) {
+		let summarize = self
+			.specs
+			.iter()
+			.rev()
+			.find_map(|spec| spec.summarize)
+			.unwrap_or(false);
+		// Directories inside archives cannot be read from the file system.
+		if summarize && self.typ == Typ::Dir && self.archived.is_none() {
+			self.summary = Some(Summary::of(&self.path));
+		}
+	}
+
 	/// Find the name of the node against which this node will collapse.
 	///
 	/// If the collapse uses a name, use that name.
@@ -150,13 +437,36 @@ impl<'pls> Node<'pls> {
 
 	/// Get all styling directives applicable to the node.
 	///
-	/// A node can get its style directives from two sources:
+	/// A node can get its style directives from these sources:
 	///
-	/// * the node's type
+	/// * the node's type, or its absence if the node is missing
 	/// * specs associated with the node
-	fn directives(&self, app_const: &AppConst, entry_const: &EntryConst) -> String {
+	/// * the age of the node's latest commit, based on the `--age` CLI argument
+	/// * decorations provided by external decorators
+	/// * risk rules that flag the node
+	/// * redaction of sensitive nodes, based on the `--redact` CLI argument
+	///
+	/// The directives are computed once and reused for the icon and the name.
+	fn directives(&self, app_const: &AppConst, entry_const: &EntryConst) -> &str {
+		self.memo
+			.directives
+			.get_or_init(|| self.compute_directives(app_const, entry_const))
+	}
+
+	/// Compute the styling directives for [`directives`](Node::directives).
+	fn compute_directives(&self, app_const: &AppConst, entry_const: &EntryConst) -> String {
 		let mut directives = String::from(self.typ.directives(entry_const));
 
+		if self.is_missing() {
+			directives.push(' ');
+			directives.push_str(&entry_const.missing_style);
+		}
+
+		if self.is_exec() {
+			directives.push(' ');
+			directives.push_str(&entry_const.exec_style);
+		}
+
 		if !self.appearances.contains(&Appearance::Symlink) {
 			let imp_dir = Imp::directives(self, app_const);
 			if let Some(directive) = imp_dir {
@@ -172,9 +482,162 @@ impl<'pls> Node<'pls> {
 			}
 		}
 
+		if PLS.args.age {
+			if let Some(style) = self.age_directives(entry_const) {
+				directives.push(' ');
+				directives.push_str(style);
+			}
+		}
+
+		for decoration in &self.decorations {
+			if let Some(style) = &decoration.style {
+				directives.push(' ');
+				directives.push_str(style);
+			}
+		}
+
+		for risk in &self.risks {
+			directives.push(' ');
+			directives.push_str(risk.directives(entry_const));
+		}
+
+		if self.is_redacted {
+			directives.push(' ');
+			directives.push_str(&entry_const.redacted_style);
+		}
+
 		directives
 	}
 
+	/// Get the directives associated with the age of the node's latest commit.
+	///
+	/// Nodes that are not tracked by Git do not have any age directives.
+	fn age_directives<'conf>(&self, entry_const: &'conf EntryConst) -> Option<&'conf String> {
+		let time = self.time_val(DetailField::GitDate)?;
+		let age = PLS.now().duration_since(time).unwrap_or_default();
+		entry_const.age_style(age.as_secs() / 86_400)
+	}
+
+	// ======
+	// Owners
+	// ======
+
+	/// Get the user that owns the node, looking it up only once per node.
+	pub fn owner_user(&self, owner_man: &mut OwnerMan) -> Option<&Owner> {
+		self.memo
+			.user
+			.get_or_init(|| {
+				if self.is_on_slow_fs() {
+					return None;
+				}
+				self.meta_ok()
+					.and_then(|meta| meta.owner_uid())
+					.map(|uid| owner_man.user(uid))
+			})
+			.as_ref()
+	}
+
+	/// Get the group that owns the node, looking it up only once per node.
+	pub fn owner_group(&self, owner_man: &mut OwnerMan) -> Option<&Owner> {
+		self.memo
+			.group
+			.get_or_init(|| {
+				if self.is_on_slow_fs() {
+					return None;
+				}
+				self.meta_ok()
+					.and_then(|meta| meta.owner_gid())
+					.map(|gid| owner_man.group(gid))
+			})
+			.as_ref()
+	}
+
+	// ===================
+	// Extended attributes
+	// ===================
+
+	/// Get the names of the extended attributes of the node, reading them
+	/// only once per node.
+	///
+	/// Nodes inside archives, and nodes whose attributes cannot be read, are
+	/// treated as having no extended attributes.
+	pub fn xattr_names(&self) -> &[String] {
+		self.memo.xattrs.get_or_init(|| {
+			if self.archived.is_some() {
+				return vec![];
+			}
+			self.path.xattr_names().unwrap_or_default()
+		})
+	}
+
+	/// Get the tags of the node, reading them only once per node.
+	///
+	/// Nodes inside archives, and nodes skipped as slow with `--fast`, have no
+	/// tags.
+	pub fn tags(&self, entry_const: &EntryConst) -> &Tags {
+		self.memo.tags.get_or_init(|| {
+			if self.archived.is_some() || self.is_on_slow_fs() {
+				return Tags::default();
+			}
+			Tags::read(&self.path, entry_const)
+		})
+	}
+
+	/// Get the extended ACL of the node, reading it only once per node.
+	///
+	/// Nodes inside archives have no ACLs.
+	pub fn acl(&self) -> Option<&Acl> {
+		self.memo
+			.acl
+			.get_or_init(|| {
+				if self.archived.is_some() {
+					return None;
+				}
+				Acl::read(&self.path)
+			})
+			.as_ref()
+	}
+
+	/// Get the rows that list the extended attributes of the node, with their
+	/// values, beneath the node with `--xattrs`.
+	///
+	/// Only the name column is filled, indented past the tree shapes so that
+	/// the attributes line up under the name of the node.
+	fn xattr_rows(
+		&self,
+		entry_const: &EntryConst,
+		tree_shapes: &[&str],
+	) -> Vec<HashMap<DetailField, String>> {
+		let offset = " ".repeat(if PLS.args.align { 3 } else { 2 });
+		let indent = tree_shapes.iter().fold(String::new(), |mut acc, shape| {
+			let _ = write!(acc, "{offset}{shape}"); // `write!`-ing into a `String` can never fail.
+			acc
+		});
+		let info = &entry_const.xattr;
+
+		self.xattr_names()
+			.iter()
+			.map(|name| {
+				let value = match self.path.xattr_value(name) {
+					Ok(value) => xattr_text(&value),
+					Err(err) => err.to_string(),
+				};
+				let text = format!(
+					"{indent}{offset}<{}>{name}</> <{}>{value}</>",
+					info.name_style, info.value_style
+				);
+				PLS.args
+					.details
+					.iter()
+					.map(|&detail| match detail {
+						DetailField::Name => (detail, text.clone()),
+						_ => (detail, String::default()),
+					})
+					.collect()
+			})
+			.collect()
+	}
+
 	// ===============
 	// Name components
 	// ===============
@@ -182,34 +645,106 @@ impl<'pls> Node<'pls> {
 	/// Get the icons associated with the node, filtered by the
 	/// capabilities of the current terminal.
 	///
-	/// A node can get its icon from two sources:
+	/// A node can get its icon from three sources:
 	///
 	/// * specs associated with the node
+	/// * the "missing" icon, if the node is missing
 	/// * the node's type
-	fn icon(&self, conf: &Conf, entry_const: &EntryConst) -> Icon {
-		let icon = self
+	///
+	/// With `--thumbs`, PNG images are previewed in place of their icon. With
+	/// the built-in icon source, icons bundled with `pls` are drawn in place of
+	/// glyphs in terminals that support graphics.
+	pub fn icon(&self, conf: &Conf, entry_const: &EntryConst) -> Icon {
+		if self.has_thumbnail() {
+			return Icon::Thumbnail(self.path.clone());
+		}
+		match self.icon_name(conf, entry_const) {
+			Some(icon_name) => {
+				let icon = conf.icons[icon_name].clone();
+				if Icon::is_image(&icon) {
+					Icon::Image(icon)
+				} else if conf.icon_source == IconSource::Builtin
+					&& PLS.gfx.is_some()
+					&& builtin_icon(icon_name).is_some()
+				{
+					Icon::Builtin(icon_name.to_string())
+				} else {
+					Icon::Text(icon)
+				}
+			}
+			None => Icon::Text(String::default()),
+		}
+	}
+
+	/// Check whether the node is previewed with a thumbnail, with `--thumbs`.
+	///
+	/// Only PNG, JPEG and WebP images that are regular files are previewed,
+	/// and only in the grid view of terminals that support graphics. Nodes inside
+	/// archives, and nodes skipped as slow with `--fast`, are not previewed.
+	fn has_thumbnail(&self) -> bool {
+		PLS.args.thumbs
+			&& PLS.args.grid
+			&& PLS.gfx.is_some()
+			&& self.typ == Typ::File
+			&& self.archived.is_none()
+			&& ["png", "jpg", "jpeg", "webp"]
+				.iter()
+				.any(|ext| self.ext().eq_ignore_ascii_case(ext))
+			&& !self.is_on_slow_fs()
+	}
+
+	/// Get the name, in the `icons` section of the config, of the icon
+	/// associated with the node.
+	///
+	/// The name is the first one, in the order of precedence, that maps to an
+	/// icon that can be shown, i.e. that is not an image icon when the terminal
+	/// does not support graphics.
+	pub fn icon_name<'conf>(
+		&'conf self,
+		conf: &'conf Conf,
+		entry_const: &'conf EntryConst,
+	) -> Option<&'conf str> {
+		let spec_names = self
 			.specs
 			.iter()
 			.rev()
 			.filter_map(|spec| spec.icons.as_ref())
-			.chain(self.typ.icons(entry_const))
 			.flatten()
-			.find_map(|icon_name| {
+			.map(String::as_str);
+		let missing_name = self.is_missing().then_some("missing");
+		let exec_name = self.is_exec().then_some("exec");
+		let typ_names = self
+			.typ
+			.icons(entry_const)
+			.iter()
+			.flatten()
+			.map(String::as_str);
+		spec_names
+			.chain(missing_name)
+			.chain(exec_name)
+			.chain(typ_names)
+			.find(|icon_name| {
 				conf.icons
-					.get(icon_name.as_str())
-					.filter(|icon| !icon.ends_with(".svg") || PLS.supports_gfx)
-			});
+					.get(*icon_name)
+					.is_some_and(|icon| !Icon::is_image(icon) || PLS.gfx.is_some())
+			})
+	}
 
-		match icon {
-			Some(icon) => {
-				let icon = String::from(icon);
-				if icon.ends_with(".svg") {
-					Icon::Image(icon)
-				} else {
-					Icon::Text(icon)
-				}
-			}
-			None => Icon::Text(String::default()),
+	/// Get the icon of the node as it should be exported, based on the
+	/// `--export-icons` CLI argument.
+	///
+	/// Image icons have no glyph, so they are exported as blank glyphs.
+	fn export_icon(&self, conf: &Conf, entry_const: &EntryConst) -> String {
+		match PLS.args.export_icons {
+			ExportIcons::Glyph => match self.icon(conf, entry_const) {
+				Icon::Text(text) => text,
+				Icon::Image(_) | Icon::Builtin(_) | Icon::Thumbnail(_) => String::default(),
+			},
+			ExportIcons::Name => self
+				.icon_name(conf, entry_const)
+				.map(String::from)
+				.unwrap_or_default(),
+			ExportIcons::None => String::default(),
 		}
 	}
 
@@ -217,6 +752,23 @@ impl<'pls> Node<'pls> {
 	// Renderables
 	// ===========
 
+	/// Get the `file://` URL that the name of the node links to, with
+	/// `--hyperlinks`.
+	///
+	/// Nodes inside archives and symlink targets are not linked, since their
+	/// paths are not paths on the file system. Nothing is linked when the
+	/// output is plain text.
+	fn url(&self) -> Option<String> {
+		if !PLS.args.hyperlinks
+			|| !is_styled()
+			|| self.archived.is_some()
+			|| self.appearances.contains(&Appearance::Symlink)
+		{
+			return None;
+		}
+		Some(file_url(&self.path.abs()))
+	}
+
 	/// Get the display name of the node.
 	///
 	/// The display name of a node consists of the following parts:
@@ -224,6 +776,10 @@ impl<'pls> Node<'pls> {
 	/// * icon, based on the `--icons` CLI argument
 	/// * actual name, aligned based on the `--align` CLI argument
 	/// * suffix, based on the `--suffix` CLI argument
+	/// * summary of the contents, based on the `summarize` field of specs
+	/// * cloud badge, if the node is a cloud placeholder
+	/// * decoration labels, based on the `--decorate` CLI argument
+	/// * risk badges, based on the risk rules in the config
 	/// * symlink target, based on the `--symlink` CLI argument
 	///
 	/// Additionally, the display name is marked up with the appropriate
@@ -249,27 +805,75 @@ impl<'pls> Node<'pls> {
 		}
 
 		// Icon
-		if PLS.args.icon && !self.appearances.contains(&Appearance::Symlink) {
+		if PLS.view().icon
+			&& conf.icon_set != IconSet::None
+			&& !self.appearances.contains(&Appearance::Symlink)
+		{
 			let icon = self.icon(conf, entry_const);
-			parts.push_str(&icon.render(&text_directives));
+			parts.push_str(&icon.render(text_directives));
 		}
 
 		// Name and suffix
 		parts.push_str(&format!("<{text_directives}>"));
+		let mut name = String::default();
 		if !PLS.args.align
 			|| self.appearances.contains(&Appearance::Symlink)
 			|| self.appearances.contains(&Appearance::SoloFile)
 		{
-			parts.push_str(&self.display_name)
+			if self.appearances.contains(&Appearance::Symlink) {
+				name.push_str(&self.display_name)
+			} else {
+				name.push_str(&self.highlighted(&self.display_name, entry_const))
+			}
 		} else {
-			parts.push_str(&self.aligned_name())
+			name.push_str(&self.aligned_name(entry_const))
 		}
 		if PLS.args.suffix && !self.appearances.contains(&Appearance::Symlink) {
 			// Symlink should not have suffix because it should show the path reference without modifications
-			parts.push_str(self.typ.suffix(entry_const))
+			name.push_str(self.typ.suffix(entry_const))
 		};
+		match self.url() {
+			Some(url) => parts.push_str(&link(&url, &name)),
+			None => parts.push_str(&name),
+		}
 		parts.push_str("</>");
 
+		// The badge is an icon, so it is hidden along with them.
+		if self.is_pinned() && PLS.view().icon && conf.icon_set != IconSet::None {
+			parts.push(' ');
+			parts.push_str(&entry_const.pin_badge);
+		}
+
+		let tags = self.tags(entry_const);
+		if !tags.0.is_empty() {
+			parts.push(' ');
+			parts.push_str(&tags.chips(entry_const));
+		}
+
+		if let Some(summary) = &self.summary {
+			parts.push(' ');
+			parts.push_str(&summary.label(entry_const));
+		}
+
+		if self.is_cloud_placeholder() {
+			parts.push(' ');
+			parts.push_str(&entry_const.cloud.badge);
+		}
+
+		for label in self
+			.decorations
+			.iter()
+			.filter_map(|deco| deco.label.as_ref())
+		{
+			parts.push(' ');
+			parts.push_str(label);
+		}
+
+		for risk in &self.risks {
+			parts.push(' ');
+			parts.push_str(&risk.badge(entry_const));
+		}
+
 		if PLS.args.sym {
 			if let Some(target) = self.target() {
 				parts.push_str(&target.print(conf));
@@ -283,19 +887,66 @@ impl<'pls> Node<'pls> {
 	// Printer entry
 	// =============
 
+	/// Get the values of the details recorded with `--snapshot`.
+	///
+	/// This function returns marked-up strings.
+	pub fn snapshot_values(
+		&self,
+		owner_man: &mut OwnerMan,
+		entry_const: &EntryConst,
+	) -> Vec<(DetailField, String)> {
+		Snapshot::FIELDS
+			.into_iter()
+			.map(|field| (field, self.value(field, owner_man, entry_const)))
+			.collect()
+	}
+
+	/// Get the value of the given detail, as shown in the output.
+	///
+	/// With `--compare`, the permissions and owners that changed since the
+	/// snapshot are shown as `old → new`.
+	///
+	/// This function returns a marked-up string.
 	fn get_value(
 		&self,
 		detail: DetailField,
 		owner_man: &mut OwnerMan,
 		entry_const: &EntryConst,
+	) -> String {
+		if PLS.args.raw_values && detail.raw_unit().is_some() {
+			return self.raw_value(detail, owner_man).unwrap_or_default();
+		}
+
+		let val = self.value(detail, owner_man, entry_const);
+		let old = Snapshot::baseline()
+			.filter(|_| Snapshot::FIELDS.contains(&detail) && self.archived.is_none())
+			.and_then(|baseline| baseline.change(&self.path.abs(), detail, &val));
+		match old {
+			Some(old) => format!("<{}>{old}</> → {val}", entry_const.changed_style),
+			None => val,
+		}
+	}
+
+	/// Get the value of the given detail, without comparing it to a snapshot.
+	///
+	/// This function returns a marked-up string.
+	fn value(
+		&self,
+		detail: DetailField,
+		owner_man: &mut OwnerMan,
+		entry_const: &EntryConst,
 	) -> String {
 		let val = match detail {
 			// `Detail` trait
 			DetailField::Dev => self.dev(entry_const),
+			DetailField::Rdev => self.rdev(entry_const),
 			DetailField::Ino => self.ino(entry_const),
 			DetailField::Nlink => self.nlink(entry_const),
 			DetailField::Perm => self.perm(entry_const),
 			DetailField::Oct => self.oct(entry_const),
+			DetailField::Attrs => self.attrs(entry_const),
+			DetailField::Xattr => self.xattr(entry_const),
+			DetailField::Acl => self.acl_entries(owner_man, entry_const),
 			DetailField::User => self.user(owner_man, entry_const),
 			DetailField::Uid => self.uid(owner_man, entry_const),
 			DetailField::Group => self.group(owner_man, entry_const),
@@ -305,7 +956,18 @@ impl<'pls> Node<'pls> {
 			DetailField::Ctime => self.time(detail, entry_const),
 			DetailField::Atime => self.time(detail, entry_const),
 			DetailField::Size => self.size(entry_const),
+			DetailField::SizeRaw => self.size_raw(entry_const),
 			DetailField::Blocks => self.blocks(entry_const),
+			DetailField::Commits => self.commits(entry_const),
+			DetailField::GitAuthor => self.git_author(entry_const),
+			DetailField::GitDate => self.time(detail, entry_const),
+			DetailField::CodeOwners => self.code_owners(entry_const),
+			DetailField::IgnoredBy => self.ignored_by(entry_const),
+			DetailField::Marker => self.marker(entry_const),
+			DetailField::Comm => self.comm(entry_const),
+			DetailField::Role => self.role(entry_const),
+			DetailField::Fix => self.fix(entry_const),
+			DetailField::Derived(idx) => Some(self.derived(idx, owner_man, entry_const)),
 			// `Typ` enum
 			DetailField::Typ => Some(self.typ.ch(entry_const)),
 			_ => Some(String::default()),
@@ -313,9 +975,49 @@ impl<'pls> Node<'pls> {
 		val.unwrap_or_default()
 	}
 
+	/// Get the value of a column derived from other fields, by filling each
+	/// placeholder in its template with the value of the field it names.
+	///
+	/// Besides the fields, the `{age}` placeholder is filled with the time
+	/// elapsed since the node was modified.
+	///
+	/// This function returns a marked-up string.
+	fn derived(&self, idx: usize, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> String {
+		fill(&PLS.args.columns[idx].1, |key| {
+			if key == "age" {
+				let mtime = self.time_val(DetailField::Mtime)?;
+				let age = relative(epoch(PLS.now()) - epoch(mtime));
+				return Some(age.trim_start().to_string());
+			}
+			let field = DetailField::from_str(key, false).ok()?;
+			Some(self.get_value(field, owner_man, entry_const))
+		})
+	}
+
+	/// Get the raw value of the given detail field, as a plain number without
+	/// formatting, for exporting with `--raw-values`.
+	///
+	/// Sizes are in bytes and timestamps are in seconds since the Unix epoch,
+	/// as declared by [`DetailField::raw_unit`].
+	fn raw_value(&self, detail: DetailField, owner_man: &mut OwnerMan) -> Option<String> {
+		let val = match detail {
+			DetailField::Ino => self.meta_ok()?.ino_num()?.to_string(),
+			DetailField::Nlink => self.meta_ok()?.nlink_count()?.to_string(),
+			DetailField::Size | DetailField::SizeRaw => self.size_val()?.to_string(),
+			DetailField::Blocks => self.blocks_val()?.to_string(),
+			DetailField::Uid => self.owner_user(owner_man)?.id.to_string(),
+			DetailField::Gid => self.owner_group(owner_man)?.id.to_string(),
+			DetailField::Commits => self.history.as_ref()?.commits.to_string(),
+			_ => epoch(self.time_val(detail)?).to_string(),
+		};
+		Some(val)
+	}
+
 	/// Get a mapping of detail fields to their values.
 	///
-	/// This information is used to render the table row for a node.
+	/// This information is used to render the table row for a node. In the
+	/// grid view, the `grid_details` from the config are also included, to be
+	/// shown next to the name.
 	pub fn row(
 		&self,
 		owner_man: &mut OwnerMan,
@@ -324,15 +1026,25 @@ impl<'pls> Node<'pls> {
 		entry_const: &EntryConst,
 		tree_shape: &[&str],
 	) -> HashMap<DetailField, String> {
+		let grid_details = if PLS.args.grid {
+			app_const.grid_details.as_slice()
+		} else {
+			&[]
+		};
 		PLS.args
 			.details
 			.iter()
+			.chain(grid_details)
 			.map(|&detail| {
 				if detail == DetailField::Name {
 					(
 						detail,
 						self.display_name(conf, app_const, entry_const, tree_shape),
 					)
+				} else if detail == DetailField::Icon {
+					(detail, self.export_icon(conf, entry_const))
+				} else if detail == DetailField::Style {
+					(detail, self.directives(app_const, entry_const).to_string())
 				} else {
 					(detail, self.get_value(detail, owner_man, entry_const))
 				}
@@ -360,25 +1072,34 @@ impl<'pls> Node<'pls> {
 		// the complete set of shapes to print for the current node
 		let mut all_shapes = parent_shapes.to_vec();
 
+		let tree = app_const.tree_shapes();
+
 		if let Some(more_shape) = own_shape {
-			child_parent_shapes.push(if more_shape == app_const.tree.tee_dash {
+			child_parent_shapes.push(if more_shape == tree.tee_dash {
 				// Current node is not the last of its parent, so child nodes
 				// will have a pipe for continuity.
-				&app_const.tree.pipe_space
+				&tree.pipe_space
 			} else {
 				// Current node is the last of its parent, so child nodes will
 				// not have a pipe, but rather spaces for padding.
-				&app_const.tree.space_space
+				&tree.space_space
 			});
 			all_shapes.push(more_shape);
 		}
 
+		let xattr_rows = if PLS.args.xattrs {
+			self.xattr_rows(entry_const, &child_parent_shapes)
+		} else {
+			vec![]
+		};
+
 		once(self.row(owner_man, conf, app_const, entry_const, &all_shapes))
+			.chain(xattr_rows)
 			.chain(self.children.iter().enumerate().flat_map(|(idx, child)| {
 				let child_own_shape = if idx == self.children.len() - 1 {
-					&app_const.tree.bend_dash
+					&tree.bend_dash
 				} else {
-					&app_const.tree.tee_dash
+					&tree.tee_dash
 				};
 
 				child.entries(
@@ -399,3 +1120,22 @@ impl Display for Node<'_> {
 		write!(f, "{}", self.name)
 	}
 }
+
+/// Convert the value of an extended attribute into text that can be shown.
+///
+/// Values that are valid UTF-8 without control characters, ignoring the
+/// trailing null byte of C strings, are shown as-is, up to a maximum width.
+/// Other values are summarised by their length.
+fn xattr_text(value: &[u8]) -> String {
+	let text = std::str::from_utf8(value.strip_suffix(&[0]).unwrap_or(value))
+		.ok()
+		.filter(|text| !text.chars().any(char::is_control));
+	match text {
+		Some(text) => truncate(
+			text.replace('<', "\\<"),
+			XATTR_WIDTH,
+			PLS.quirks.emoji_width,
+		),
+		None => format!("({} bytes)", value.len()),
+	}
+}
@@ -1,20 +1,29 @@
 use crate::config::EntryConst;
 use crate::enums::Entity;
 use std::collections::HashMap;
+#[cfg(unix)]
 use std::sync::Arc;
 #[cfg(unix)]
 use uzers::os::unix::GroupExt;
+#[cfg(unix)]
 use uzers::{Groups, User, Users, UsersCache};
 
 /// Manages owner information. This manager provides access to the user and
 /// group that own a node. It also implements caching.
+///
+/// Windows has no UIDs or GIDs, so there the manager only produces nameless
+/// owners and is never consulted for real nodes.
+#[cfg_attr(not(unix), derive(Default))]
 pub struct OwnerMan {
 	/// the cache of users and groups at the library level
+	#[cfg(unix)]
 	pub cache: UsersCache,
 
 	/// the UID of the current user
+	#[cfg(unix)]
 	pub curr_uid: u32,
 	/// the `User` instance pointing to the current user
+	#[cfg(unix)]
 	pub curr_user: Option<Arc<User>>,
 
 	/// mapping of UIDs to `Owner` instances representing users
@@ -23,6 +32,7 @@ pub struct OwnerMan {
 	pub groups: HashMap<u32, Owner>,
 }
 
+#[cfg(unix)]
 impl Default for OwnerMan {
 	fn default() -> Self {
 		let cache = UsersCache::new();
@@ -40,6 +50,7 @@ impl Default for OwnerMan {
 }
 
 impl OwnerMan {
+	#[cfg(unix)]
 	fn lookup_user(&mut self, uid: u32) -> Owner {
 		Owner {
 			entity: Entity::User,
@@ -52,6 +63,7 @@ impl OwnerMan {
 		}
 	}
 
+	#[cfg(unix)]
 	fn lookup_group(&mut self, gid: u32) -> Owner {
 		if let Some(group) = self.cache.get_group_by_gid(gid) {
 			Owner {
@@ -75,6 +87,26 @@ impl OwnerMan {
 		}
 	}
 
+	#[cfg(not(unix))]
+	fn lookup_user(&mut self, uid: u32) -> Owner {
+		Owner {
+			entity: Entity::User,
+			id: uid,
+			name: None,
+			is_curr: false,
+		}
+	}
+
+	#[cfg(not(unix))]
+	fn lookup_group(&mut self, gid: u32) -> Owner {
+		Owner {
+			entity: Entity::Group,
+			id: gid,
+			name: None,
+			is_curr: false,
+		}
+	}
+
 	/// Get the [`Owner`] instance of the user corresponding to the given UID.
 	pub fn user(&mut self, uid: u32) -> Owner {
 		self.users.get(&uid).cloned().unwrap_or_else(|| {
@@ -104,12 +136,21 @@ pub struct Owner {
 }
 
 impl Owner {
+	/// Wrap the text in the style for the owner.
+	///
+	/// Owners that are the current user, or groups the current user belongs
+	/// to, get the current style, even if they are system accounts.
 	fn format(&self, text: &String, constants: &EntryConst) -> String {
-		let directives = match (&self.entity, self.is_curr) {
-			(Entity::User, true) => &constants.user_styles.curr,
-			(Entity::User, false) => &constants.user_styles.other,
-			(Entity::Group, true) => &constants.group_styles.curr,
-			(Entity::Group, false) => &constants.group_styles.other,
+		let styles = match self.entity {
+			Entity::User => &constants.user_styles,
+			Entity::Group => &constants.group_styles,
+		};
+		let directives = if self.is_curr {
+			&styles.curr
+		} else if styles.is_system(self.id) {
+			&styles.system
+		} else {
+			&styles.other
 		};
 		format!("<{}>{}</>", directives, text)
 	}
@@ -162,12 +203,16 @@ mod tests {
     }
 
 	make_renderables_test!(
-		test_current_user: Entity::User, 420, Some(String::from("user")), true => "<blue bold>420</>", "<blue bold>user</>",
-		test_other_user: Entity::User, 420, Some(String::from("user")), false => "<dimmed>420</>", "<dimmed>user</>",
-		test_nameless_user: Entity::User, 420, None, false => "<dimmed>420</>", "<dimmed>420</>",
-
-		test_current_group: Entity::Group, 69, Some(String::from("group")), true => "<blue>69</>", "<blue>group</>",
-		test_other_group: Entity::Group, 69, Some(String::from("group")), false => "<dimmed>69</>", "<dimmed>group</>",
-		test_nameless_group: Entity::Group, 69, None, false => "<dimmed>69</>", "<dimmed>69</>",
+		test_current_user: Entity::User, 1420, Some(String::from("user")), true => "<blue bold>1420</>", "<blue bold>user</>",
+		test_other_user: Entity::User, 1420, Some(String::from("user")), false => "<dimmed>1420</>", "<dimmed>user</>",
+		test_nameless_user: Entity::User, 1420, None, false => "<dimmed>1420</>", "<dimmed>1420</>",
+		test_system_user: Entity::User, 420, Some(String::from("daemon")), false => "<yellow>420</>", "<yellow>daemon</>",
+		test_nobody_user: Entity::User, 65534, Some(String::from("nobody")), false => "<yellow>65534</>", "<yellow>nobody</>",
+		test_current_system_user: Entity::User, 0, Some(String::from("root")), true => "<blue bold>0</>", "<blue bold>root</>",
+
+		test_current_group: Entity::Group, 1069, Some(String::from("group")), true => "<blue>1069</>", "<blue>group</>",
+		test_other_group: Entity::Group, 1069, Some(String::from("group")), false => "<dimmed>1069</>", "<dimmed>group</>",
+		test_nameless_group: Entity::Group, 1069, None, false => "<dimmed>1069</>", "<dimmed>1069</>",
+		test_system_group: Entity::Group, 69, Some(String::from("wheel")), false => "<yellow>69</>", "<yellow>wheel</>",
 	);
 }
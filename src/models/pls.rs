@@ -1,7 +1,46 @@
-use crate::args::{Group, Input};
-use crate::config::{Args, ConfMan};
-use crate::fmt::render;
-use crate::models::{OwnerMan, Window};
+use crate::args::{FilesGroup, Group, Input};
+use crate::config::InteractiveInfo;
+use crate::config::{Args, Bookmarks, ConfMan};
+use crate::enums::{Cmd, Export, GfxProtocol, Key, KeyAction, Quoting, TagAction, Typ};
+use crate::exc::Exc;
+use crate::ext::Abs;
+use crate::fmt::{configure_color, configure_widths, render};
+use crate::gfx::Quirks;
+use crate::models::{
+	Daemon, FixScript, Node, OwnerMan, RawMode, Renames, Server, Snapshot, Tags, View, Window,
+};
+use crate::output::{Delimited, Nuon, PowerShell, Prompt, Sink};
+use log::warn;
+use notify::{Event, RecursiveMode, Watcher};
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::sync::{mpsc, RwLock, RwLockReadGuard};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use time::UtcOffset;
+
+/// the program used to open files when no other opener is configured
+#[cfg(target_os = "macos")]
+const PLATFORM_OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const PLATFORM_OPENER: &str = "xdg-open";
+
+/// the time to wait after a change, with `--watch`, for related changes, like
+/// those of a build writing many files, to arrive before repainting
+const WATCH_SETTLE: Duration = Duration::from_millis(100);
+
+/// the escape sequences that move the cursor home and clear the screen and
+/// its scrollback, so that each repaint starts on a blank terminal
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J\x1b[3J";
+
+/// This enum contains the events that interactive mode responds to.
+enum Interaction {
+	Key(Key), // a key was pressed
+	Resize,   // the terminal was resized
+}
 
 /// Represents the entire application state.
 ///
@@ -13,20 +52,172 @@ pub struct Pls {
 	pub conf_man: ConfMan,
 	/// command-line arguments
 	pub args: Args,
-	/// whether the terminal supports Kitty's terminal graphics protocol
-	pub supports_gfx: bool,
-	/// the width and height of a terminal cell in pixels
-	pub window: Option<Window>,
+	/// capabilities and quirks of the terminal emulator
+	pub quirks: Quirks,
+	/// the graphics protocol that the terminal supports, if any
+	pub gfx: Option<GfxProtocol>,
+	/// whether the locale of the environment supports UTF-8
+	pub supports_utf8: bool,
+	/// the width and height of a terminal cell in pixels, measured again when
+	/// the terminal is resized with `--watch`
+	pub window: RwLock<Option<Window>>,
+	/// the parts of the listing that the keys of `--interactive` can change
+	pub view: RwLock<View>,
 }
 
 impl Pls {
+	/// Get the current time, against which the ages of nodes are measured.
+	///
+	/// With `--deterministic`, this is the given reference time, so that
+	/// relative timestamps and age styles do not change between runs.
+	pub fn now(&self) -> SystemTime {
+		match self.args.deterministic {
+			Some(secs) if secs >= 0 => UNIX_EPOCH + Duration::from_secs(secs as u64),
+			Some(secs) => UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs()),
+			None => SystemTime::now(),
+		}
+	}
+
+	/// Get the offset of the local time zone, in which dates are read.
+	///
+	/// With `--deterministic`, or if the offset cannot be determined, this is
+	/// UTC, so that the output does not depend on the time zone.
+	pub fn utc_offset(&self) -> UtcOffset {
+		if self.args.deterministic.is_some() {
+			return UtcOffset::UTC;
+		}
+		UtcOffset::current_local_offset().unwrap_or_else(|_| {
+			warn!("Could not determine UTC offset");
+			UtcOffset::UTC
+		})
+	}
+
+	/// Get the latest measurements of the terminal window.
+	pub fn window(&self) -> Option<Window> {
+		*self.window.read().unwrap()
+	}
+
+	/// Get the parts of the listing that the keys of `--interactive` can
+	/// change.
+	pub fn view(&self) -> RwLockReadGuard<'_, View> {
+		self.view.read().unwrap()
+	}
+
+	/// Measure the terminal window again, after it was resized.
+	///
+	/// With `--deterministic`, the window keeps its fixed measurements.
+	fn remeasure(&self) {
+		if self.args.deterministic.is_none() {
+			*self.window.write().unwrap() = Window::try_new();
+		}
+	}
+
+	/// Get the terminal width.
+	///
+	/// The terminal width is determined from these sources, in order:
+	///
+	/// * the `--width` CLI argument, if it is given
+	/// * the width of the `--popup`, if it is given
+	/// * the `PLS_COLUMNS` environment variable, if it is set
+	/// * the result of an ioctl call, if it succeeds
+	/// * the `COLUMNS` environment variable, if it is set
+	///
+	/// Since the ioctl call only fails when the output is not a terminal,
+	/// `COLUMNS` only sets the width of output sent to pipes and files.
+	pub fn term_width(&self) -> Option<usize> {
+		self.args
+			.width
+			.or_else(|| self.args.popup.map(|popup| popup.width))
+			.or_else(|| env_width("PLS_COLUMNS")) // development hack
+			.or_else(|| self.window().map(|win| win.ws_col))
+			.or_else(|| env_width("COLUMNS"))
+			.map(usize::from)
+	}
+
 	/// Handle the `pls` command and its subcommands.
 	///
 	/// This is the entrypoint of the application that takes over the
-	/// control from `main`.
-	pub fn cmd(&self) {
-		// TODO: Handle subcommands.
-		self.run();
+	/// control from `main`, returning the exit code for the process.
+	///
+	/// With `--render-to`, all output is sent to the given file instead of
+	/// STDOUT, which is how the snapshot tests capture styled output.
+	pub fn cmd(&self) -> ExitCode {
+		if let Some(path) = &self.args.render_to {
+			if let Err(err) = Sink::redirect(path) {
+				println!("{}", Exc::Io(err));
+				return ExitCode::FAILURE;
+			}
+		}
+		configure_color(self.args.color, self.args.render_to.is_some());
+		// Glyph widths depend on the font, so they come from the base config.
+		match self.conf_man.get(None::<&Path>) {
+			Ok(mut conf) => {
+				conf.apply_icon_set();
+				conf.apply_ascii();
+				configure_widths(conf.glyph_widths());
+			}
+			Err(exc) => warn!("Could not read the widths of icons: {exc}"),
+		}
+		if let Some(popup) = self.args.popup {
+			Sink::clip(
+				popup.width.into(),
+				popup.height.into(),
+				self.quirks.emoji_width,
+			);
+		}
+
+		let code = match &self.args.cmd {
+			Some(Cmd::Daemon) => match self.daemon() {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					ExitCode::FAILURE
+				}
+			},
+			Some(Cmd::Tag { action }) => self.tag(action),
+			None => self.run(),
+		};
+		// With `--popup`, the last line may still be held back by the sink.
+		Sink::finish();
+		code
+	}
+
+	/// Run `pls daemon`, serving warm listings of the configured directories
+	/// until the process is terminated.
+	fn daemon(&self) -> Result<(), Exc> {
+		let conf = self.conf_man.get(None::<&Path>)?;
+		Daemon::new(&conf.app_const).serve()
+	}
+
+	/// Run `pls tag`, adding the tag to or removing it from the given paths.
+	///
+	/// Each path is tagged independently, so that one failure does not stop
+	/// the others. The failures are reported, and fail the run.
+	fn tag(&self, action: &TagAction) -> ExitCode {
+		let (TagAction::Add { name, paths } | TagAction::Remove { name, paths }) = action;
+		if !Tags::is_valid(name) {
+			let exc = Exc::Other(format!(
+				"Tag {name:?} cannot be blank, have surrounding spaces or contain commas."
+			));
+			Sink::println(exc.to_string());
+			return ExitCode::FAILURE;
+		}
+
+		let mut code = ExitCode::SUCCESS;
+		for path in paths {
+			let res = self.conf_man.get(Some(path)).and_then(|conf| {
+				let mut tags = Tags::read(path, &conf.entry_const);
+				match action {
+					TagAction::Add { .. } => tags.add(name),
+					TagAction::Remove { .. } => tags.remove(name),
+				}
+				tags.write(path, &conf.entry_const).map_err(Exc::Io)
+			});
+			if Self::report(path, res).is_none() {
+				code = ExitCode::FAILURE;
+			}
+		}
+		code
 	}
 
 	/// Run `pls`.
@@ -34,35 +225,618 @@ impl Pls {
 	/// This is the entrypoint of the `Pls` class, and once control is passed
 	/// to it from `main`, it handles everything.
 	///
+	/// Besides listing the given paths, `pls` can open a file, rename the
+	/// listed paths in an editor, print a prompt line, keep the listing up to
+	/// date with `--watch`, change the listing with keys with
+	/// `--interactive` or answer requests from other programs with `--serve`.
+	fn run(&self) -> ExitCode {
+		if self.args.open {
+			return match self.open() {
+				Ok(code) => code,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					ExitCode::FAILURE
+				}
+			};
+		}
+
+		if self.args.rename_edit {
+			return match self.rename_edit() {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					ExitCode::FAILURE
+				}
+			};
+		}
+
+		if self.args.prompt {
+			return match self.prompt() {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					ExitCode::FAILURE
+				}
+			};
+		}
+
+		if self.args.watch {
+			return match self.watch() {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					ExitCode::FAILURE
+				}
+			};
+		}
+
+		if self.args.interactive {
+			return match self.interactive() {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					ExitCode::FAILURE
+				}
+			};
+		}
+
+		if self.args.serve {
+			return match Server::new(&self.conf_man).serve() {
+				Ok(()) => ExitCode::SUCCESS,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					ExitCode::FAILURE
+				}
+			};
+		}
+
+		self.list()
+	}
+
+	/// List the given paths.
+	///
 	/// The primary function of this method is to organise the input list of
 	/// paths into groups and then delegate to each group the job of listing
 	/// their entries and rendering the layout.
-	fn run(&self) {
-		let inputs: Vec<_> = self
-			.args
-			.paths
-			.iter()
-			.filter_map(|path| {
-				let input = Input::new(path, &self.conf_man);
-				match input {
-					Ok(input) => Some(input),
+	///
+	/// When `--find` is used, the number of matching and hidden entries is
+	/// reported at the end, unless `--quiet` is used, and the run fails if
+	/// nothing matched.
+	///
+	/// With `--compare`, the listing is compared against the given snapshot.
+	/// With `--fix-script` and `--snapshot`, the commands that would fix the
+	/// flagged nodes and the permissions and owners of the listed nodes are
+	/// written to their files after the listing.
+	fn list(&self) -> ExitCode {
+		if let Some(path) = &self.args.compare {
+			match Snapshot::load(path) {
+				Ok(snapshot) => Snapshot::compare_to(snapshot),
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					return ExitCode::FAILURE;
+				}
+			}
+		}
+
+		let groups = if self.args.bookmarks {
+			match self.bookmark_groups() {
+				Ok(groups) => groups,
+				Err(exc) => {
+					Sink::println(exc.to_string());
+					return ExitCode::FAILURE;
+				}
+			}
+		} else {
+			let inputs = self
+				.args
+				.paths
+				.iter()
+				.filter_map(|path| Self::report(path, Input::new(path, &self.conf_man)))
+				.collect();
+			Group::partition(inputs, &self.conf_man)
+		};
+		let show_title = !self.args.quiet
+			&& !self.args.bookmarks
+			&& (self.args.paths.len() > 1 || self.args.recurse);
+
+		let (shown, hidden) = if let Some(export) = self.args.export {
+			self.export(&groups, export)
+		} else {
+			groups
+				.iter()
+				.map(|group| group.render(show_title, &mut OwnerMan::default()))
+				.filter_map(|res| match res {
+					Ok(counts) => Some(counts),
 					Err(exc) => {
-						let loc = render(format!("<bold>{}</>", path.display()));
-						println!("{loc}:");
-						println!("\t{exc}");
+						Sink::println(exc.to_string());
 						None
 					}
+				})
+				.fold((0, 0), |acc, counts| (acc.0 + counts.0, acc.1 + counts.1))
+		};
+
+		if let Some(path) = &self.args.fix_script {
+			if let Err(exc) = Self::write_fix_script(&groups, path) {
+				Sink::println(exc.to_string());
+				return ExitCode::FAILURE;
+			}
+		}
+		if let Some(path) = &self.args.snapshot {
+			if let Err(exc) = Self::write_snapshot(&groups, path) {
+				Sink::println(exc.to_string());
+				return ExitCode::FAILURE;
+			}
+		}
+
+		if self.args.find.is_none() {
+			return ExitCode::SUCCESS;
+		}
+
+		// The report would corrupt the exported records.
+		if !self.args.quiet && self.args.export.is_none() {
+			Sink::println(format!(
+				"\n{}",
+				render(format!(
+					"<bold>{shown}</> matched, <dimmed>{hidden} hidden</>"
+				))
+			));
+		}
+		if shown == 0 {
+			ExitCode::FAILURE
+		} else {
+			ExitCode::SUCCESS
+		}
+	}
+
+	/// Write the commands that would fix the nodes flagged in the given
+	/// groups to the script at the given path.
+	///
+	/// Groups that cannot be read are skipped, as they are in the listing.
+	fn write_fix_script(groups: &[Group], path: &Path) -> Result<(), Exc> {
+		let mut script = FixScript::default();
+		let mut owner_man = OwnerMan::default();
+		for group in groups {
+			let res = group.visit(&mut owner_man, &mut |node, _, _| {
+				script.push(&node.path, node.fixes().map(|(_, fix)| fix));
+			});
+			if let Err(exc) = res {
+				warn!("Could not find the fixes of a group: {exc}");
+			}
+		}
+		script.write(path)
+	}
+
+	/// Write the permissions and owners of the nodes listed in the given
+	/// groups to the snapshot at the given path.
+	///
+	/// Nodes inside archives are not recorded, since their paths do not
+	/// exist on disk. Groups that cannot be read are skipped, as they are in
+	/// the listing.
+	fn write_snapshot(groups: &[Group], path: &Path) -> Result<(), Exc> {
+		let mut snapshot = Snapshot::default();
+		let mut owner_man = OwnerMan::default();
+		for group in groups {
+			let res = group.visit(&mut owner_man, &mut |node, conf, owner_man| {
+				if node.archived.is_none() {
+					let values = node.snapshot_values(owner_man, &conf.entry_const);
+					snapshot.record(&node.path.abs(), values);
+				}
+			});
+			if let Err(exc) = res {
+				warn!("Could not record the nodes of a group: {exc}");
+			}
+		}
+		snapshot.write(path)
+	}
+
+	/// List the given paths, and list them again whenever their contents
+	/// change or the terminal is resized, until the process is terminated.
+	///
+	/// The screen is cleared before each listing, so that the listing appears
+	/// to update in place. Changes are watched for recursively with
+	/// `--recurse` and `--tree`, since the nested listings show them.
+	///
+	/// The window is measured again before each listing, so that the grid and
+	/// the table are laid out for the new size, and SVG icons are rasterized
+	/// again for the new cell size.
+	fn watch(&self) -> Result<(), Exc> {
+		let (tx, rx) = mpsc::channel();
+		let resize_tx = tx.clone();
+		if let Err(err) = Window::on_resize(move || {
+			let _ = resize_tx.send(());
+		}) {
+			warn!("Could not watch for resizes: {err}");
+		}
+		let mut watcher =
+			notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+				// Listing the paths accesses them, which must not trigger
+				// another listing. The receiver is only gone when the process
+				// is exiting.
+				Ok(event) if !event.kind.is_access() => {
+					let _ = tx.send(());
+				}
+				Ok(_) => {}
+				Err(err) => warn!("Could not watch for changes: {err}"),
+			})
+			.map_err(|err| Exc::Other(err.to_string()))?;
+
+		let mode = if self.args.recurse || self.args.tree {
+			RecursiveMode::Recursive
+		} else {
+			RecursiveMode::NonRecursive
+		};
+		for path in &self.args.paths {
+			watcher
+				.watch(path, mode)
+				.map_err(|err| Exc::Other(format!("Could not watch {}: {err}", path.display())))?;
+		}
+
+		loop {
+			{
+				let mut sink = Sink::lock();
+				write!(sink, "{CLEAR_SCREEN}").map_err(Exc::Io)?;
+			}
+			self.remeasure();
+			self.list();
+
+			// Wait for a change, and then for the changes that follow it.
+			if rx.recv().is_err() {
+				return Ok(());
+			}
+			while rx.recv_timeout(WATCH_SETTLE).is_ok() {}
+		}
+	}
+
+	/// List the given paths on the alternate screen, and list them again
+	/// whenever a key changes the listing or the terminal is resized, until
+	/// the quit key or Ctrl-C is pressed.
+	///
+	/// The keys and the actions they perform come from the `interactive` map
+	/// of the base config. After the filter key, the keys that are typed form
+	/// a pattern, in the syntax chosen with `--filter-mode`, that filters the
+	/// listing as it is typed. Enter keeps the filter and Escape clears it.
+	fn interactive(&self) -> Result<(), Exc> {
+		if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+			return Err(Exc::Other(String::from(
+				"Interactive mode needs a terminal for both input and output.",
+			)));
+		}
+		let info = self.conf_man.get(None::<&Path>)?.app_const.interactive;
+
+		let (tx, rx) = mpsc::channel();
+		let resize_tx = tx.clone();
+		if let Err(err) = Window::on_resize(move || {
+			let _ = resize_tx.send(Interaction::Resize);
+		}) {
+			warn!("Could not watch for resizes: {err}");
+		}
+		thread::spawn(move || {
+			let mut stdin = io::stdin();
+			let mut buf = [0; 64];
+			// The receiver is only gone when interactive mode has ended.
+			while let Ok(len @ 1..) = stdin.read(&mut buf) {
+				for key in Key::parse(&buf[..len]) {
+					if tx.send(Interaction::Key(key)).is_err() {
+						return;
+					}
+				}
+			}
+		});
+
+		let _raw_mode = RawMode::enter()?;
+		// the pattern being typed after the filter key, and whether it is valid
+		let mut typing: Option<(String, bool)> = None;
+		loop {
+			self.paint(
+				&info,
+				typing.as_ref().map(|(pat, valid)| (pat.as_str(), *valid)),
+			)?;
+
+			// STDIN is only closed when the terminal is gone.
+			let Ok(interaction) = rx.recv() else {
+				return Ok(());
+			};
+			let key = match interaction {
+				Interaction::Resize => {
+					self.remeasure();
+					continue;
+				}
+				Interaction::Key(Key::Ctrl('c')) => return Ok(()),
+				Interaction::Key(key) => key,
+			};
+
+			if let Some((pattern, is_valid)) = &mut typing {
+				match key {
+					Key::Char(ch) => {
+						pattern.push(ch);
+						*is_valid = self.refilter(pattern);
+					}
+					Key::Backspace => {
+						pattern.pop();
+						*is_valid = self.refilter(pattern);
+					}
+					Key::Enter => typing = None,
+					Key::Esc => {
+						self.view.write().unwrap().filter = None;
+						typing = None;
+					}
+					_ => {}
+				}
+				continue;
+			}
+
+			match info.keys.get(&key.name()) {
+				Some(KeyAction::Quit) => return Ok(()),
+				Some(KeyAction::Filter) => typing = Some((String::new(), true)),
+				Some(&action) => {
+					self.view
+						.write()
+						.unwrap()
+						.apply(action, &info, &self.args.sort_bases)
+				}
+				None => {}
+			}
+		}
+	}
+
+	/// Clear the screen, and then show the status line of interactive mode
+	/// above the listing.
+	///
+	/// # Arguments
+	///
+	/// * `info` - the configuration of interactive mode
+	/// * `typing` - the pattern being typed, if any, and whether it is valid
+	fn paint(&self, info: &InteractiveInfo, typing: Option<(&str, bool)>) -> Result<(), Exc> {
+		let status = self.view().status(info, typing);
+		{
+			let mut sink = Sink::lock();
+			write!(sink, "{CLEAR_SCREEN}{}\n\n", render(status)).map_err(Exc::Io)?;
+		}
+		self.list();
+		Sink::lock().flush().map_err(Exc::Io)
+	}
+
+	/// Filter the listing of interactive mode by the given pattern, or stop
+	/// filtering it if the pattern is empty.
+	///
+	/// A pattern that is not valid, which it often is while being typed, keeps
+	/// the previous filter. This function returns whether the pattern is
+	/// valid.
+	fn refilter(&self, pattern: &str) -> bool {
+		let filter = if pattern.is_empty() {
+			None
+		} else {
+			match self.args.filter_regex(pattern) {
+				Ok(filter) => Some(filter),
+				Err(_) => return false,
+			}
+		};
+		self.view.write().unwrap().filter = filter;
+		true
+	}
+
+	/// Export the entries of all groups as delimited records, a NUON table or
+	/// PowerShell objects.
+	///
+	/// Unlike the other views, all groups are written as one set of records
+	/// with a single header, so that the output can be read by spreadsheets.
+	/// The column names of delimited records are taken from the config of the
+	/// first group.
+	///
+	/// This function returns the number of entries that were exported and the
+	/// number of entries that were hidden by `--find`.
+	fn export(&self, groups: &[Group], export: Export) -> (usize, usize) {
+		let mut owner_man = OwnerMan::default();
+		let mut entries = vec![];
+		let mut hidden = 0;
+		for group in groups {
+			match group.entries(&mut owner_man) {
+				Ok((group_entries, group_hidden)) => {
+					entries.extend(group_entries);
+					hidden += group_hidden;
 				}
+				Err(exc) => Sink::println(exc.to_string()),
+			}
+		}
+
+		let shown = entries.len();
+		if let Some(group) = groups.first() {
+			let res = match export {
+				Export::Nuon => Nuon::new(entries).render(),
+				Export::Powershell => PowerShell::new(entries).render(),
+				_ => Delimited::new(entries, export).render(&group.conf().app_const),
+			};
+			if let Err(exc) = res {
+				Sink::println(exc.to_string());
+			}
+		}
+		(shown, hidden)
+	}
+
+	/// Get the group of bookmarked paths.
+	///
+	/// All bookmarks, including directories, are listed as individual entries
+	/// in a single group. Bookmarks that do not exist are retained so that
+	/// they can be shown as missing.
+	fn bookmark_groups(&self) -> Result<Vec<Group>, Exc> {
+		let inputs: Vec<_> = Bookmarks::load()?
+			.paths()
+			.iter()
+			.filter_map(|path| {
+				let input = Input::new(path, &self.conf_man)
+					.or_else(|_| Input::missing(path, &self.conf_man));
+				Self::report(path, input)
 			})
 			.collect();
+		if inputs.is_empty() {
+			return Ok(vec![]);
+		}
+		Ok(vec![Group::Files(FilesGroup::new(inputs, &self.conf_man))])
+	}
+
+	/// Print the prompt line for the first given path.
+	///
+	/// The prompt line is meant to be embedded in shell prompts, so it only
+	/// describes one directory.
+	fn prompt(&self) -> Result<(), Exc> {
+		let path = self
+			.args
+			.paths
+			.first()
+			.map_or(Path::new("."), PathBuf::as_path);
+		let input = Input::new(path, &self.conf_man)?;
+		if input.typ != Typ::Dir {
+			return Err(Exc::Other(format!(
+				"`--prompt` needs a directory but {} is not one.",
+				path.display()
+			)));
+		}
+		Prompt::new(&input)?.render(&input.conf.app_const);
+		Ok(())
+	}
+
+	/// Open the only given path with its opener.
+	///
+	/// The opener is the first one found from these sources:
+	///
+	/// * the `opener` of the last matching spec that defines one
+	/// * the `$VISUAL` or `$EDITOR` environment variables
+	/// * the platform opener, `open` on macOS and `xdg-open` elsewhere
+	///
+	/// The opener is split on whitespace, so it can contain arguments, and the
+	/// path is appended as the last argument.
+	fn open(&self) -> Result<ExitCode, Exc> {
+		let [path] = self.args.paths.as_slice() else {
+			return Err(Exc::Other(String::from("`--open` needs exactly one path.")));
+		};
+		let input = Input::new(path, &self.conf_man)?;
+		if input.typ == Typ::Dir {
+			return Err(Exc::Other(format!(
+				"`--open` needs a file but {} is a directory.",
+				path.display()
+			)));
+		}
 
-		let show_title = self.args.paths.len() > 1;
-		let groups = Group::partition(inputs, &self.conf_man);
+		let mut node = Node::new(&input.path);
+		node.match_specs(&input.conf);
+		let opener = node
+			.specs
+			.iter()
+			.rev()
+			.find_map(|spec| spec.opener.clone())
+			.or_else(|| env::var("VISUAL").ok())
+			.or_else(|| env::var("EDITOR").ok())
+			.filter(|opener| !opener.trim().is_empty())
+			.unwrap_or_else(|| String::from(PLATFORM_OPENER));
 
-		groups
+		let mut parts = opener.split_whitespace();
+		let program = parts.next().unwrap_or(PLATFORM_OPENER);
+		let status = Command::new(program)
+			.args(parts)
+			.arg(&input.path)
+			.status()
+			.map_err(Exc::Io)?;
+		Ok(if status.success() {
+			ExitCode::SUCCESS
+		} else {
+			ExitCode::FAILURE
+		})
+	}
+
+	/// Rename the listed paths by editing them in a text editor, like `vidir`.
+	///
+	/// The paths are written to a temporary file, one per line, which is
+	/// opened in `$VISUAL` or `$EDITOR`, or `vi` if neither is set. Once the
+	/// editor exits, the `mv` commands for the changed lines are printed, or
+	/// with `--apply`, performed. Nothing is renamed if any of the renames
+	/// conflict.
+	fn rename_edit(&self) -> Result<(), Exc> {
+		let inputs = self
+			.args
+			.paths
+			.iter()
+			.filter_map(|path| Self::report(path, Input::new(path, &self.conf_man)))
+			.collect();
+		let mut owner_man = OwnerMan::default();
+		let mut paths = vec![];
+		for group in Group::partition(inputs, &self.conf_man) {
+			paths.extend(group.paths(&mut owner_man)?.0);
+		}
+		if let Some(path) = paths
 			.iter()
-			.map(|group| group.render(show_title, &mut OwnerMan::default()))
-			.filter_map(|res| res.err())
-			.for_each(|res| println!("{res}"));
+			.find(|path| path.to_string_lossy().contains('\n'))
+		{
+			return Err(Exc::Other(format!(
+				"{} cannot be edited because its name has a line break.",
+				path.display()
+			)));
+		}
+
+		// The file gets a random name and is created exclusively, readable and
+		// writable only by the user, so that other users cannot plant a
+		// symlink in its place or change the paths before they are renamed.
+		let mut file = tempfile::Builder::new()
+			.prefix("pls-rename-")
+			.suffix(".txt")
+			.tempfile()
+			.map_err(Exc::Io)?;
+		for path in &paths {
+			writeln!(file, "{}", path.to_string_lossy()).map_err(Exc::Io)?;
+		}
+		file.flush().map_err(Exc::Io)?;
+
+		let editor = env::var("VISUAL")
+			.ok()
+			.or_else(|| env::var("EDITOR").ok())
+			.filter(|editor| !editor.trim().is_empty())
+			.unwrap_or_else(|| String::from("vi"));
+		let mut parts = editor.split_whitespace();
+		let program = parts.next().unwrap_or("vi");
+		let status = Command::new(program).args(parts).arg(file.path()).status();
+		let edited = fs::read_to_string(file.path());
+		drop(file); // The file is deleted when dropped.
+		if !status.map_err(Exc::Io)?.success() {
+			return Err(Exc::Other(String::from(
+				"The editor failed, so nothing was renamed.",
+			)));
+		}
+
+		let renames = Renames::plan(&paths, &edited.map_err(Exc::Io)?, |path| {
+			path.symlink_metadata().is_ok()
+		})?;
+		let quoting = self.args.shell_quote.unwrap_or(Quoting::Posix);
+		for command in renames.commands(quoting) {
+			Sink::println(command);
+		}
+		if self.args.apply {
+			renames.apply()?;
+		}
+		Ok(())
+	}
+
+	/// Print the error for an operation on the given path that failed, such
+	/// as creating an input.
+	///
+	/// This function converts the result into an option, so that it can be
+	/// used to filter out failed inputs.
+	fn report<T>(path: &Path, res: Result<T, Exc>) -> Option<T> {
+		match res {
+			Ok(val) => Some(val),
+			Err(exc) => {
+				let loc = render(format!("<bold>{}</>", path.display()));
+				Sink::println(format!("{loc}:"));
+				Sink::println(format!("\t{exc}"));
+				None
+			}
+		}
 	}
 }
+
+/// Read a width from the given environment variable, ignoring values that
+/// are not positive numbers.
+fn env_width(var: &str) -> Option<u16> {
+	env::var(var)
+		.ok()
+		.and_then(|width_str| width_str.trim().parse::<u16>().ok())
+		.filter(|&width| width > 0)
+}
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+/// Represents the size of the fixed-size overlay that `--popup` fits the
+/// output in, like a `tmux display-popup`.
+///
+/// The size is parsed from strings like "80x24", giving the number of columns
+/// followed by the number of rows.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Popup {
+	/// the number of columns in the popup
+	pub width: u16,
+	/// the number of rows in the popup
+	pub height: u16,
+}
+
+impl FromStr for Popup {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (width, height) = s
+			.trim()
+			.split_once(['x', 'X'])
+			.ok_or_else(|| String::from("size must be WIDTHxHEIGHT, like '80x24'"))?;
+		let dimension = |val: &str| {
+			val.trim()
+				.parse::<u16>()
+				.ok()
+				.filter(|&val| val > 0)
+				.ok_or_else(|| format!("'{val}' is not a positive number"))
+		};
+		Ok(Self {
+			width: dimension(width)?,
+			height: dimension(height)?,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Popup;
+	use std::str::FromStr;
+
+	macro_rules! make_parse_test {
+		( $($name:ident: $input:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Popup::from_str($input).ok(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_parse_test!(
+		test_parse_size: "80x24" => Some(Popup { width: 80, height: 24 }),
+		test_parse_upper_x: "120X40" => Some(Popup { width: 120, height: 40 }),
+		test_parse_spaces: " 80 x 24 " => Some(Popup { width: 80, height: 24 }),
+		test_parse_zero: "0x24" => None,
+		test_parse_missing_height: "80" => None,
+		test_parse_garbage: "wide x tall" => None,
+	);
+}
@@ -0,0 +1,225 @@
+use crate::config::{AppConst, EntryConst};
+use crate::models::OwnerMan;
+use crate::utils::numbers::group_digits;
+use crate::PLS;
+use std::path::Path;
+
+/// Represents the disk quota of a user on one file system.
+///
+/// Quotas are mostly found on shared servers, where each user is allotted a
+/// share of the file system holding the home directories. Only quotas with
+/// at least one limit are read, so the presence of an instance means the
+/// user is restricted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quota {
+	/// the UID of the user whose quota this is
+	pub uid: u32,
+	/// the number of bytes used by the user
+	pub bytes: u64,
+	/// the number of bytes the user may use, if limited
+	pub bytes_limit: Option<u64>,
+	/// the number of files used by the user
+	pub files: u64,
+	/// the number of files the user may create, if limited
+	pub files_limit: Option<u64>,
+}
+
+impl Quota {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Read the quota of the owner of the node at the given path, on the file
+	/// system containing that node.
+	///
+	/// On Linux, the quota is read with `quotactl` from the block device on
+	/// which the file system is mounted. Network file systems like NFS, whose
+	/// quotas are only known to the server, are not supported.
+	#[cfg(target_os = "linux")]
+	pub fn of(path: &Path) -> Option<Self> {
+		use crate::ext::PlatformMeta;
+		use crate::utils::procfs;
+
+		let meta = path.metadata().ok()?;
+		let uid = meta.owner_uid()?;
+		let dev = meta.dev_id()?;
+		let source = procfs::mount_source(libc::major(dev), libc::minor(dev))?;
+		let dqblk = sys::user_quota(&source, uid)?;
+
+		// Soft limits are the quota proper, while hard limits only bound how
+		// far the quota may be exceeded during the grace period.
+		let limit = |soft: u64, hard: u64| [soft, hard].into_iter().find(|&limit| limit != 0);
+		let quota = Self {
+			uid,
+			bytes: dqblk.dqb_curspace,
+			bytes_limit: limit(dqblk.dqb_bsoftlimit, dqblk.dqb_bhardlimit)
+				.map(|blocks| blocks * sys::QUOTA_BLOCK_SIZE),
+			files: dqblk.dqb_curinodes,
+			files_limit: limit(dqblk.dqb_isoftlimit, dqblk.dqb_ihardlimit),
+		};
+		quota.is_limited().then_some(quota)
+	}
+
+	/// Read the quota of the owner of the node at the given path, which is not
+	/// supported on this platform.
+	#[cfg(not(target_os = "linux"))]
+	pub fn of(_path: &Path) -> Option<Self> {
+		None
+	}
+
+	// ===========
+	// Renderables
+	// ===========
+
+	/// Get the quota as a line to show above the listing, naming the user and
+	/// their usage against each limit.
+	///
+	/// This function returns a marked-up string.
+	pub fn label(
+		&self,
+		owner_man: &mut OwnerMan,
+		app_const: &AppConst,
+		entry_const: &EntryConst,
+	) -> String {
+		let user = owner_man
+			.user(self.uid)
+			.name
+			.unwrap_or_else(|| self.uid.to_string());
+		let mut parts = vec![];
+		if let Some(limit) = self.bytes_limit {
+			parts.push(format!(
+				"{} of {} ({}%)",
+				PLS.args.unit.size(self.bytes, entry_const),
+				PLS.args.unit.size(limit, entry_const),
+				percent(self.bytes, limit),
+			));
+		}
+		if let Some(limit) = self.files_limit {
+			parts.push(format!(
+				"{} of {} files ({}%)",
+				group_digits(self.files),
+				group_digits(limit),
+				percent(self.files, limit),
+			));
+		}
+
+		let directives = if self.is_exceeded() {
+			&app_const.quota.exceeded_style
+		} else {
+			&app_const.quota.style
+		};
+		format!("<{directives}>Quota of {user}:</> {}", parts.join(", "))
+	}
+
+	// =======
+	// Private
+	// =======
+
+	/// Determine whether the quota has any limits.
+	#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+	fn is_limited(&self) -> bool {
+		self.bytes_limit.is_some() || self.files_limit.is_some()
+	}
+
+	/// Determine whether the usage is beyond any of the limits.
+	fn is_exceeded(&self) -> bool {
+		self.bytes_limit.is_some_and(|limit| self.bytes > limit)
+			|| self.files_limit.is_some_and(|limit| self.files > limit)
+	}
+}
+
+/// Get the given usage as a whole percentage of the given limit.
+fn percent(used: u64, limit: u64) -> u64 {
+	if limit == 0 {
+		return 0;
+	}
+	(used as u128 * 100 / limit as u128) as u64
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+	use libc::{c_char, c_int, dqblk};
+	use std::ffi::CString;
+
+	/// the size of the blocks in which quota limits are expressed, from
+	/// `<sys/quota.h>`
+	pub const QUOTA_BLOCK_SIZE: u64 = 1024;
+
+	/// the command to get the quota of a user, from `<sys/quota.h>`
+	const Q_GETQUOTA: u32 = 0x800007;
+	/// the type of user quotas, from `<sys/quota.h>`
+	const USRQUOTA: u32 = 0;
+	/// the shift that combines a command with a quota type, from
+	/// `<sys/quota.h>`
+	const SUBCMDSHIFT: u32 = 8;
+
+	/// Get the quota of the user with the given UID on the file system mounted
+	/// from the given source.
+	///
+	/// This is `None` if the source is not a block device, quotas are not
+	/// enabled on the file system, or the quota cannot be read, such as when
+	/// reading the quota of another user without privileges.
+	pub fn user_quota(source: &str, uid: u32) -> Option<dqblk> {
+		let source = CString::new(source).ok()?;
+		let cmd = ((Q_GETQUOTA << SUBCMDSHIFT) | USRQUOTA) as c_int;
+		let mut dqblk: dqblk = unsafe { std::mem::zeroed() };
+		let res = unsafe {
+			libc::quotactl(
+				cmd,
+				source.as_ptr(),
+				uid as c_int,
+				(&mut dqblk as *mut dqblk).cast::<c_char>(),
+			)
+		};
+		(res == 0).then_some(dqblk)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{percent, Quota};
+
+	macro_rules! make_percent_test {
+		( $($name:ident: $used:expr, $limit:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(percent($used, $limit), $expected);
+				}
+			)*
+		};
+	}
+
+	make_percent_test!(
+		test_percent_partial: 1, 4 => 25,
+		test_percent_rounds_down: 2, 3 => 66,
+		test_percent_exceeded: 6, 4 => 150,
+		test_percent_huge: u64::MAX, u64::MAX => 100,
+		test_percent_zero_limit: 5, 0 => 0,
+	);
+
+	macro_rules! make_exceeded_test {
+		( $($name:ident: $bytes:expr, $bytes_limit:expr, $files:expr, $files_limit:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let quota = Quota {
+						uid: 1000,
+						bytes: $bytes,
+						bytes_limit: $bytes_limit,
+						files: $files,
+						files_limit: $files_limit,
+					};
+					assert_eq!(quota.is_exceeded(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_exceeded_test!(
+		test_within_limits: 10, Some(20), 5, Some(10) => false,
+		test_bytes_exceeded: 30, Some(20), 5, Some(10) => true,
+		test_files_exceeded: 10, Some(20), 15, Some(10) => true,
+		test_unlimited: 30, None, 15, None => false,
+	);
+}
@@ -0,0 +1,76 @@
+use crate::exc::Exc;
+use crate::output::Sink;
+#[cfg(unix)]
+use libc::{tcgetattr, tcsetattr, termios, STDIN_FILENO, TCSANOW};
+use std::io::Write;
+
+/// the escape sequences that switch to the alternate screen and hide the
+/// cursor
+const ENTER: &str = "\x1b[?1049h\x1b[?25l";
+/// the escape sequences that show the cursor and switch back to the main
+/// screen
+const LEAVE: &str = "\x1b[?25h\x1b[?1049l";
+
+/// Represents the terminal in raw mode, on the alternate screen, for as long
+/// as it is alive.
+///
+/// In raw mode, keys are read as they are pressed, without being echoed, and
+/// Ctrl-C arrives as a key instead of a signal. Unlike the raw mode of
+/// `crossterm`, output processing is kept, so that the listing can still
+/// break lines with `\n`.
+///
+/// The terminal is restored when the value is dropped, so that it is also
+/// restored on early returns and panics.
+pub struct RawMode {
+	/// the settings of the terminal before raw mode was entered
+	#[cfg(unix)]
+	saved: termios,
+}
+
+impl RawMode {
+	/// Put the terminal in raw mode and switch to the alternate screen.
+	#[cfg(unix)]
+	pub fn enter() -> Result<Self, Exc> {
+		let mut saved = unsafe { std::mem::zeroed::<termios>() };
+		if unsafe { tcgetattr(STDIN_FILENO, &mut saved) } != 0 {
+			return Err(Exc::Io(std::io::Error::last_os_error()));
+		}
+		let mut raw = saved;
+		raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+		raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+		raw.c_cc[libc::VMIN] = 1;
+		raw.c_cc[libc::VTIME] = 0;
+		if unsafe { tcsetattr(STDIN_FILENO, TCSANOW, &raw) } != 0 {
+			return Err(Exc::Io(std::io::Error::last_os_error()));
+		}
+
+		let mode = Self { saved };
+		let mut sink = Sink::lock();
+		write!(sink, "{ENTER}").map_err(Exc::Io)?;
+		sink.flush().map_err(Exc::Io)?;
+		Ok(mode)
+	}
+
+	/// Put the terminal in raw mode and switch to the alternate screen.
+	///
+	/// Raw mode is only implemented with the terminal interface of Unix, so
+	/// this function always fails on other platforms.
+	#[cfg(not(unix))]
+	pub fn enter() -> Result<Self, Exc> {
+		Err(Exc::Other(String::from(
+			"Interactive mode is only available on Unix.",
+		)))
+	}
+}
+
+impl Drop for RawMode {
+	fn drop(&mut self) {
+		let mut sink = Sink::lock();
+		let _ = write!(sink, "{LEAVE}");
+		let _ = sink.flush();
+		#[cfg(unix)]
+		unsafe {
+			tcsetattr(STDIN_FILENO, TCSANOW, &self.saved);
+		}
+	}
+}
@@ -0,0 +1,219 @@
+use crate::enums::Quoting;
+use crate::exc::Exc;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Represents the renames made by editing the listed paths with
+/// `--rename-edit`, in the order in which they can be performed safely.
+///
+/// Each line of the edited text replaces the path on the same line of the
+/// listing, so lines must not be added, removed or reordered. Renames in
+/// which one path takes the place of another, like swaps, are ordered so that
+/// no path is overwritten, moving a path aside to a temporary name if the
+/// renames form a cycle.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Renames {
+	/// the pairs of the current path and the new path of each rename
+	pub moves: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Renames {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Plan the renames from the listed paths and their edited text.
+	///
+	/// Renames are refused, as a whole, if two paths would be renamed to the
+	/// same path or if a path would be renamed to one that already exists and
+	/// is not itself being renamed.
+	///
+	/// # Arguments
+	///
+	/// * `paths` - the listed paths, one for each line of the text
+	/// * `text` - the text of the paths after editing
+	/// * `exists` - the function that checks whether a path exists
+	pub fn plan(
+		paths: &[PathBuf],
+		text: &str,
+		exists: impl Fn(&Path) -> bool,
+	) -> Result<Self, Exc> {
+		let lines: Vec<_> = text.lines().collect();
+		if lines.len() != paths.len() {
+			return Err(Exc::Other(format!(
+				"Expected {} lines but found {}; lines must not be added or removed.",
+				paths.len(),
+				lines.len()
+			)));
+		}
+
+		let mut pending = vec![];
+		for (path, line) in paths.iter().zip(lines) {
+			if line.trim().is_empty() {
+				return Err(Exc::Other(format!(
+					"The line for {} is empty.",
+					path.display()
+				)));
+			}
+			let target = PathBuf::from(line);
+			if &target != path {
+				pending.push((path.clone(), target));
+			}
+		}
+
+		let sources: HashSet<_> = pending.iter().map(|(from, _)| from.clone()).collect();
+		let mut targets = HashSet::new();
+		for (_, to) in &pending {
+			if !targets.insert(to.clone()) {
+				return Err(Exc::Other(format!(
+					"{} would be the target of more than one rename.",
+					to.display()
+				)));
+			}
+			if exists(to) && !sources.contains(to) {
+				return Err(Exc::Other(format!("{} already exists.", to.display())));
+			}
+		}
+
+		let mut moves = vec![];
+		while !pending.is_empty() {
+			// A rename is safe once no other rename still needs its target.
+			let safe = pending
+				.iter()
+				.position(|(_, to)| !pending.iter().any(|(from, _)| from == to));
+			if let Some(idx) = safe {
+				moves.push(pending.remove(idx));
+				continue;
+			}
+			// All remaining renames form cycles, one of which is broken by
+			// moving its first path aside.
+			let (from, to) = pending.remove(0);
+			let aside = aside(&from, |path| {
+				exists(path) || sources.contains(path) || targets.contains(path)
+			});
+			moves.push((from, aside.clone()));
+			pending.push((aside, to));
+		}
+		Ok(Self { moves })
+	}
+
+	// ======
+	// Public
+	// ======
+
+	/// Get the `mv` command for each rename, quoted for the given shell.
+	pub fn commands(&self, quoting: Quoting) -> Vec<String> {
+		self.moves
+			.iter()
+			.map(|(from, to)| {
+				format!(
+					"mv -- {} {}",
+					quoting.quote(&from.to_string_lossy()),
+					quoting.quote(&to.to_string_lossy())
+				)
+			})
+			.collect()
+	}
+
+	/// Perform the renames, stopping at the first one that fails.
+	pub fn apply(&self) -> Result<(), Exc> {
+		for (from, to) in &self.moves {
+			fs::rename(from, to).map_err(|err| {
+				Exc::Other(format!(
+					"Could not rename {} to {}: {err}",
+					from.display(),
+					to.display()
+				))
+			})?;
+		}
+		Ok(())
+	}
+}
+
+/// Get a free temporary name, next to the given path, to move it aside to.
+///
+/// # Arguments
+///
+/// * `path` - the path to move aside
+/// * `is_taken` - the function that checks whether a name is already in use
+fn aside(path: &Path, is_taken: impl Fn(&Path) -> bool) -> PathBuf {
+	let name = path.file_name().unwrap_or_default().to_string_lossy();
+	(0..)
+		.map(|idx| path.with_file_name(format!("{name}.pls-rename-{idx}")))
+		.find(|candidate| !is_taken(candidate))
+		.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Renames;
+	use crate::enums::Quoting;
+	use std::path::PathBuf;
+
+	/// Plan the renames of the paths `a`, `b` and `c` into the given text.
+	fn plan(text: &str, existing: &[&str]) -> Result<Vec<(String, String)>, String> {
+		let paths: Vec<_> = ["a", "b", "c"].iter().map(PathBuf::from).collect();
+		let renames = Renames::plan(&paths, text, |path| {
+			existing.contains(&path.to_string_lossy().as_ref())
+		})
+		.map_err(|exc| exc.message())?;
+		Ok(renames
+			.moves
+			.iter()
+			.map(|(from, to)| (from.display().to_string(), to.display().to_string()))
+			.collect())
+	}
+
+	macro_rules! make_plan_test {
+		( $($name:ident: $text:expr, $existing:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let expected: Vec<(&str, &str)> = $expected;
+					let expected = expected
+						.into_iter()
+						.map(|(from, to)| (from.to_string(), to.to_string()))
+						.collect();
+					assert_eq!(plan($text, &$existing), Ok(expected));
+				}
+			)*
+		};
+	}
+
+	make_plan_test!(
+		test_plan_unchanged: "a\nb\nc\n", ["a", "b", "c"] => vec![],
+		test_plan_rename: "a\nd\nc\n", ["a", "b", "c"] => vec![("b", "d")],
+		test_plan_chain: "b\nc\nd\n", ["a", "b", "c"] => vec![("c", "d"), ("b", "c"), ("a", "b")],
+		test_plan_swap: "b\na\nc\n", ["a", "b", "c"]
+			=> vec![("a", "a.pls-rename-0"), ("b", "a"), ("a.pls-rename-0", "b")],
+	);
+
+	macro_rules! make_refusal_test {
+		( $($name:ident: $text:expr, $existing:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(plan($text, &$existing), Err(String::from($expected)));
+				}
+			)*
+		};
+	}
+
+	make_refusal_test!(
+		test_refuse_removed_line: "a\nb\n", ["a", "b", "c"]
+			=> "Expected 3 lines but found 2; lines must not be added or removed.",
+		test_refuse_empty_line: "a\n\nc\n", ["a", "b", "c"] => "The line for b is empty.",
+		test_refuse_same_target: "d\nd\nc\n", ["a", "b", "c"]
+			=> "d would be the target of more than one rename.",
+		test_refuse_existing_target: "a\nb\nd\n", ["a", "b", "c", "d"] => "d already exists.",
+	);
+
+	#[test]
+	fn test_commands_are_quoted() {
+		let renames = Renames {
+			moves: vec![(PathBuf::from("my file"), PathBuf::from("-x"))],
+		};
+		assert_eq!(renames.commands(Quoting::Posix), vec!["mv -- 'my file' -x"]);
+	}
+}
@@ -0,0 +1,135 @@
+use crate::config::EntryConst;
+use crate::ext::PlatformMeta;
+use crate::models::Node;
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Represents a rule for flagging nodes that pose a risk, like private keys
+/// or scripts that any user can modify.
+///
+/// A node is flagged if it meets every condition of the rule. Flagged nodes
+/// are shown with a badge naming the risk, and `--audit` lists only them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Risk {
+	/// a short description of the risk, shown next to the badge
+	pub label: String,
+	/// a regex pattern to match against the node's name
+	#[serde(with = "serde_regex")]
+	pub pattern: Regex,
+	/// whether the node must be writable by all users to be flagged
+	#[serde(default)]
+	pub world_writable: bool,
+	/// styles to apply to the node name and badge, instead of the default
+	pub style: Option<String>,
+	/// the command that would fix the risk, like `chmod o-w`, suggested in the
+	/// `fix` column and written to the `--fix-script`
+	pub fix: Option<String>,
+}
+
+impl Risk {
+	/// Create a basic `Risk` instance with only a label and a pattern.
+	///
+	/// `Risk` follows a builder pattern, so you can chain the following method
+	/// to define the remaining fields.
+	///
+	/// - [`world_writable`](Risk::world_writable)
+	/// - [`fix`](Risk::fix)
+	pub fn new(label: &str, pattern: &str) -> Self {
+		Self {
+			label: String::from(label),
+			pattern: Regex::new(pattern).unwrap(),
+			world_writable: false,
+			style: None,
+			fix: None,
+		}
+	}
+
+	/// Consume the current `Risk` instance and return a new one that only
+	/// flags nodes writable by all users.
+	pub fn world_writable(self) -> Self {
+		Self {
+			world_writable: true,
+			..self
+		}
+	}
+
+	/// Consume the current `Risk` instance and return a new one that suggests
+	/// the given command to fix the risk.
+	pub fn fix(self, fix: &str) -> Self {
+		Self {
+			fix: Some(String::from(fix)),
+			..self
+		}
+	}
+
+	/// Determine whether the given node meets every condition of the rule.
+	///
+	/// Symlinks are never world-writable, since their permission bits are not
+	/// used. Windows has no permission bits for other users, so no node is
+	/// world-writable there.
+	pub fn matches(&self, node: &Node) -> bool {
+		if !self.pattern.is_match(node.name.as_bytes()) {
+			return false;
+		}
+		if self.world_writable {
+			let is_world_writable = cfg!(unix)
+				&& !node.typ.is_link()
+				&& node
+					.meta_ok()
+					.and_then(|meta| meta.mode_bits())
+					.is_some_and(|mode| mode & 0o002 != 0);
+			if !is_world_writable {
+				return false;
+			}
+		}
+		true
+	}
+
+	/// Get the style of the rule, falling back to the default risk style.
+	pub fn directives<'a>(&'a self, entry_const: &'a EntryConst) -> &'a str {
+		self.style.as_deref().unwrap_or(&entry_const.risk.style)
+	}
+
+	/// Get the badge to show after the name of a flagged node.
+	///
+	/// This function returns a marked-up string.
+	pub fn badge(&self, entry_const: &EntryConst) -> String {
+		let directives = self.directives(entry_const);
+		format!("<{directives}>{} {}</>", entry_const.risk.badge, self.label)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::config::Conf;
+
+	macro_rules! make_pattern_test {
+		( $($name:ident: $node_name:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let conf = Conf::default();
+					let labels: Vec<_> = conf
+						.risks
+						.iter()
+						.filter(|risk| risk.pattern.is_match($node_name.as_bytes()))
+						.map(|risk| risk.label.as_str())
+						.collect();
+					assert_eq!(labels, $expected as Vec<&str>);
+				}
+			)*
+		};
+	}
+
+	make_pattern_test!(
+		test_plain_file: "README.md" => vec![],
+		test_env: ".env" => vec!["secrets"],
+		test_env_local: ".env.local" => vec!["secrets"],
+		test_env_example: ".env.example" => vec![],
+		test_secrets_file: "Secrets.yaml" => vec!["secrets"],
+		test_ssh_key: "id_ed25519" => vec!["private key"],
+		test_ssh_public_key: "id_ed25519.pub" => vec![],
+		test_pem: "server.pem" => vec!["private key"],
+		test_script: "deploy.sh" => vec!["world-writable"],
+	);
+}
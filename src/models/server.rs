@@ -0,0 +1,244 @@
+use crate::args::{FilesGroup, Group, Input};
+use crate::config::ConfMan;
+use crate::enums::DetailField;
+use crate::exc::Exc;
+use crate::fmt::plain;
+use crate::models::{Node, OwnerMan};
+use crate::output::Sink;
+use crate::PLS;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+/// the error code for requests that are not valid JSON
+const PARSE_ERROR: i64 = -32700;
+/// the error code for JSON that is not a valid request
+const INVALID_REQUEST: i64 = -32600;
+/// the error code for requests for a method that does not exist
+const METHOD_NOT_FOUND: i64 = -32601;
+/// the error code for requests with missing or malformed parameters
+const INVALID_PARAMS: i64 = -32602;
+/// the error code for requests that were valid but could not be answered,
+/// such as for paths that do not exist
+const SERVER_ERROR: i64 = -32000;
+
+/// Represents one request read from the client.
+#[derive(Debug, Deserialize)]
+struct Request {
+	/// the version of the protocol, which must be "2.0"
+	jsonrpc: String,
+	/// the ID that the response must carry, absent for notifications
+	#[serde(default)]
+	id: Option<Value>,
+	/// the name of the method to call
+	method: String,
+	/// the named parameters of the method
+	#[serde(default)]
+	params: Value,
+}
+
+/// Represents the parameters shared by all methods.
+#[derive(Debug, Deserialize)]
+struct Params {
+	/// the path to describe, relative to the working directory of the server
+	path: PathBuf,
+}
+
+/// Represents the methods that the server responds to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Method {
+	List,    // the rows of the children of a directory
+	Details, // the row of a single node
+	Explain, // the specs that match a node
+}
+
+/// Represents an error to report to the client, with its JSON-RPC code.
+#[derive(Debug, PartialEq, Eq)]
+struct Failure {
+	code: i64,
+	message: String,
+}
+
+impl Failure {
+	fn new(code: i64, message: impl Into<String>) -> Self {
+		Self {
+			code,
+			message: message.into(),
+		}
+	}
+}
+
+/// Represents the `pls --serve` process.
+///
+/// The server speaks JSON-RPC 2.0 over STDIN and STDOUT, one request or
+/// response per line, so that editor plugins and file-manager frontends can
+/// reuse the config-aware metadata of `pls` without parsing its output. The
+/// rows are the same as those of `--export`, keyed by the names of the
+/// details, and are shaped by the same arguments, like `--det` and `--sort`.
+pub struct Server<'conf> {
+	conf_man: &'conf ConfMan,
+}
+
+impl<'conf> Server<'conf> {
+	// ===========
+	// Constructor
+	// ===========
+
+	pub fn new(conf_man: &'conf ConfMan) -> Self {
+		Self { conf_man }
+	}
+
+	// ======
+	// Public
+	// ======
+
+	/// Answer requests from STDIN until it is closed.
+	///
+	/// Notifications, which are requests without an ID, are processed but not
+	/// answered, as the protocol requires.
+	pub fn serve(&self) -> Result<(), Exc> {
+		for line in io::stdin().lock().lines() {
+			let line = line.map_err(Exc::Io)?;
+			if line.trim().is_empty() {
+				continue;
+			}
+			let Some(response) = self.respond(&line) else {
+				continue;
+			};
+			let mut sink = Sink::lock();
+			writeln!(sink, "{response}").map_err(Exc::Io)?;
+			sink.flush().map_err(Exc::Io)?;
+		}
+		Ok(())
+	}
+
+	// =======
+	// Private
+	// =======
+
+	/// Get the response to one line of input, if it needs one.
+	fn respond(&self, line: &str) -> Option<Value> {
+		let (id, res) = match parse(line) {
+			Ok((request, method, params)) => {
+				let id = request.id?;
+				(id, self.call(method, &params))
+			}
+			// Errors in requests that cannot be read are always answered.
+			Err((id, failure)) => (id, Err(failure)),
+		};
+		Some(match res {
+			Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+			Err(failure) => json!({
+				"jsonrpc": "2.0",
+				"id": id,
+				"error": { "code": failure.code, "message": failure.message },
+			}),
+		})
+	}
+
+	/// Call the given method with the given parameters.
+	fn call(&self, method: Method, params: &Params) -> Result<Value, Failure> {
+		let failed = |exc: Exc| Failure::new(SERVER_ERROR, exc.message());
+		let input = Input::new(&params.path, self.conf_man).map_err(failed)?;
+		match method {
+			Method::List => {
+				let groups = Group::partition(vec![input], self.conf_man);
+				let mut owner_man = OwnerMan::default();
+				let mut rows = vec![];
+				let mut hidden = 0;
+				for group in groups {
+					let (entries, group_hidden) = group.entries(&mut owner_man).map_err(failed)?;
+					rows.extend(entries.iter().map(row));
+					hidden += group_hidden;
+				}
+				Ok(json!({ "entries": rows, "hidden": hidden }))
+			}
+			Method::Details => {
+				let group = Group::Files(FilesGroup::new(vec![input], self.conf_man));
+				let (entries, _) = group.entries(&mut OwnerMan::default()).map_err(failed)?;
+				Ok(entries.first().map(row).unwrap_or(Value::Null))
+			}
+			Method::Explain => {
+				let mut node = Node::new(&input.path);
+				node.match_specs(&input.conf);
+				let specs = serde_json::to_value(&node.specs)
+					.map_err(|err| Failure::new(SERVER_ERROR, err.to_string()))?;
+				Ok(json!({ "specs": specs }))
+			}
+		}
+	}
+}
+
+/// Parse one line of input into a request, its method and its parameters.
+///
+/// If the line is not a valid request, this returns the ID of the request, if
+/// it could be read, along with the failure to report.
+fn parse(line: &str) -> Result<(Request, Method, Params), (Value, Failure)> {
+	let value: Value = serde_json::from_str(line)
+		.map_err(|err| (Value::Null, Failure::new(PARSE_ERROR, err.to_string())))?;
+	let id = value.get("id").cloned().unwrap_or(Value::Null);
+	let request: Request = serde_json::from_value(value)
+		.map_err(|err| (id.clone(), Failure::new(INVALID_REQUEST, err.to_string())))?;
+	if request.jsonrpc != "2.0" {
+		let message = format!("unsupported protocol version {:?}", request.jsonrpc);
+		return Err((id, Failure::new(INVALID_REQUEST, message)));
+	}
+	let method = match request.method.as_str() {
+		"list" => Method::List,
+		"details" => Method::Details,
+		"explain" => Method::Explain,
+		other => {
+			let message = format!("unknown method {other:?}");
+			return Err((id, Failure::new(METHOD_NOT_FOUND, message)));
+		}
+	};
+	let params: Params = serde_json::from_value(request.params.clone())
+		.map_err(|err| (id.clone(), Failure::new(INVALID_PARAMS, err.to_string())))?;
+	Ok((request, method, params))
+}
+
+/// Convert the cells of one entry into a JSON object keyed by the names of
+/// the details, stripped of all formatting.
+fn row(entry: &HashMap<DetailField, String>) -> Value {
+	let cells: Map<_, _> = entry
+		.iter()
+		.filter(|(det, _)| PLS.args.details.contains(det))
+		.map(|(det, value)| (det.key(), Value::String(plain(value).trim().to_string())))
+		.collect();
+	Value::Object(cells)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{parse, Method, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR};
+	use serde_json::{json, Value};
+	use std::path::PathBuf;
+
+	macro_rules! make_parse_test {
+		( $($name:ident: $line:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let res = parse($line)
+						.map(|(request, method, params)| (request.id, method, params.path))
+						.map_err(|(id, failure)| (id, failure.code));
+					assert_eq!(res, $expected);
+				}
+			)*
+		};
+	}
+
+	make_parse_test!(
+		test_list: r#"{"jsonrpc":"2.0","id":1,"method":"list","params":{"path":"src"}}"#
+			=> Ok((Some(json!(1)), Method::List, PathBuf::from("src"))),
+		test_notification: r#"{"jsonrpc":"2.0","method":"explain","params":{"path":"a.rs"}}"#
+			=> Ok((None, Method::Explain, PathBuf::from("a.rs"))),
+		test_not_json: "{list" => Err((Value::Null, PARSE_ERROR)),
+		test_missing_method: r#"{"jsonrpc":"2.0","id":"a"}"# => Err((json!("a"), INVALID_REQUEST)),
+		test_wrong_version: r#"{"jsonrpc":"1.0","id":2,"method":"list"}"# => Err((json!(2), INVALID_REQUEST)),
+		test_unknown_method: r#"{"jsonrpc":"2.0","id":3,"method":"rm"}"# => Err((json!(3), METHOD_NOT_FOUND)),
+		test_missing_params: r#"{"jsonrpc":"2.0","id":4,"method":"details"}"# => Err((json!(4), INVALID_PARAMS)),
+	);
+}
@@ -0,0 +1,178 @@
+use crate::enums::DetailField;
+use crate::exc::Exc;
+use crate::fmt::plain;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// the snapshot that the listing is compared against, if `--compare` is used
+static BASELINE: OnceLock<Snapshot> = OnceLock::new();
+
+/// Represents the permissions and owners of the listed nodes at one point in
+/// time, as written with `--snapshot`.
+///
+/// Listing the same paths later with `--compare` shows the permission and
+/// owner columns of the nodes that changed since the snapshot as `old → new`,
+/// which helps audit the changes made to a server.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+	/// the recorded details, keyed by the absolute path of each node
+	pub nodes: BTreeMap<String, BTreeMap<DetailField, String>>,
+}
+
+impl Snapshot {
+	/// the details recorded in a snapshot, which are those that change audits
+	/// are concerned with
+	pub const FIELDS: [DetailField; 6] = [
+		DetailField::Perm,
+		DetailField::Oct,
+		DetailField::User,
+		DetailField::Uid,
+		DetailField::Group,
+		DetailField::Gid,
+	];
+
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Read the snapshot from the given file.
+	pub fn load(path: &Path) -> Result<Self, Exc> {
+		let failed = |err: String| {
+			Exc::Other(format!(
+				"Could not read the snapshot from {}: {err}",
+				path.display()
+			))
+		};
+		let text = fs::read_to_string(path).map_err(|err| failed(err.to_string()))?;
+		serde_json::from_str(&text).map_err(|err| failed(err.to_string()))
+	}
+
+	// ======
+	// Static
+	// ======
+
+	/// Use the given snapshot as the baseline that the listing is compared
+	/// against.
+	pub fn compare_to(snapshot: Self) {
+		// The baseline is only set once, at startup.
+		let _ = BASELINE.set(snapshot);
+	}
+
+	/// Get the snapshot that the listing is compared against, if any.
+	pub fn baseline() -> Option<&'static Self> {
+		BASELINE.get()
+	}
+
+	// ======
+	// Public
+	// ======
+
+	/// Record the details of the node at the given path.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the absolute path of the node
+	/// * `values` - the marked-up values of the details, which are stored
+	///   without their formatting
+	pub fn record(&mut self, path: &Path, values: Vec<(DetailField, String)>) {
+		let values = values
+			.into_iter()
+			.map(|(field, value)| (field, plain(&value).trim().to_string()))
+			.collect();
+		self.nodes.insert(key(path), values);
+	}
+
+	/// Get the recorded value of the given detail of the node at the given
+	/// path, if it differs from the current value.
+	///
+	/// Nodes that were not recorded, like those created since the snapshot,
+	/// have no changes.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the absolute path of the node
+	/// * `field` - the detail to compare
+	/// * `value` - the current marked-up value of the detail
+	pub fn change(&self, path: &Path, field: DetailField, value: &str) -> Option<&str> {
+		let old = self.nodes.get(&key(path))?.get(&field)?;
+		(old != plain(value).trim()).then_some(old.as_str())
+	}
+
+	/// Write the snapshot to the given file, replacing its contents.
+	pub fn write(&self, path: &Path) -> Result<(), Exc> {
+		let failed = |err: String| {
+			Exc::Other(format!(
+				"Could not write the snapshot to {}: {err}",
+				path.display()
+			))
+		};
+		let text = serde_json::to_string_pretty(self).map_err(|err| failed(err.to_string()))?;
+		fs::write(path, text).map_err(|err| failed(err.to_string()))
+	}
+}
+
+/// Get the key of the node at the given absolute path.
+///
+/// The `.` components are dropped, so that a node has the same key whether
+/// its directory was listed as `.` or by its full path.
+fn key(path: &Path) -> String {
+	path.components()
+		.filter(|component| component != &Component::CurDir)
+		.collect::<PathBuf>()
+		.to_string_lossy()
+		.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Snapshot;
+	use crate::enums::DetailField;
+	use std::path::Path;
+
+	fn snapshot() -> Snapshot {
+		let mut snapshot = Snapshot::default();
+		snapshot.record(
+			Path::new("/srv/a.sh"),
+			vec![
+				(DetailField::Perm, String::from("<green>rw-</> r-- r--")),
+				(DetailField::User, String::from("<bold>root</>")),
+			],
+		);
+		snapshot
+	}
+
+	macro_rules! make_change_test {
+		( $($name:ident: $path:expr, $field:expr, $value:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let snapshot = snapshot();
+					assert_eq!(snapshot.change(Path::new($path), $field, $value), $expected);
+				}
+			)*
+		};
+	}
+
+	make_change_test!(
+		test_unchanged: "/srv/a.sh", DetailField::Perm, "<red>rw-</> r-- r--" => None,
+		test_changed: "/srv/a.sh", DetailField::Perm, "rw- r-- rw-" => Some("rw- r-- r--"),
+		test_changed_owner: "/srv/a.sh", DetailField::User, "www" => Some("root"),
+		test_unrecorded_field: "/srv/a.sh", DetailField::Group, "wheel" => None,
+		test_unrecorded_node: "/srv/b.sh", DetailField::Perm, "rw- r-- rw-" => None,
+		test_current_dir: "/srv/./a.sh", DetailField::User, "www" => Some("root"),
+	);
+
+	#[test]
+	fn test_round_trip() {
+		let snapshot = snapshot();
+		let text = serde_json::to_string(&snapshot).unwrap();
+		assert_eq!(
+			text,
+			r#"{"nodes":{"/srv/a.sh":{"perm":"rw- r-- r--","user":"root"}}}"#
+		);
+		assert_eq!(serde_json::from_str::<Snapshot>(&text).unwrap(), snapshot);
+	}
+}
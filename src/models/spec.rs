@@ -1,12 +1,19 @@
-use crate::enums::Collapse;
-use regex::bytes::{Regex, RegexBuilder};
+use crate::enums::{Collapse, PatternSyntax};
+use crate::utils::globs::shell_glob_to_regex;
+use regex::bytes::Regex;
+use regex::Error as RegexError;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// Represents the specification for identifying and styling a node.
 ///
 /// Specs are the ideological core of `pls` and the key differentiating factor
 /// from other tools.
+///
+/// In the config, the pattern may also be written as a glob, by setting
+/// `syntax` to `glob`. It is translated into a regex when the config is read.
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(try_from = "SpecDef")]
 pub struct Spec {
 	/// a regex pattern to match against the node's name
 	#[serde(with = "serde_regex")]
@@ -19,6 +26,12 @@ pub struct Spec {
 	pub importance: Option<i8>,
 	/// the rule for determining the parent node, if any, for this node
 	pub collapse: Option<Collapse>,
+	/// the command to open the node with, used by `--open`
+	pub opener: Option<String>,
+	/// whether to show a summary of the contents of the directory
+	pub summarize: Option<bool>,
+	/// whether to lift the node to the top of the listing
+	pub pin: Option<bool>,
 }
 
 impl Spec {
@@ -30,13 +43,17 @@ impl Spec {
 	/// - [`importance`](Spec::importance)
 	/// - [`style`](Spec::style)
 	/// - [`collapse`](Spec::collapse)
+	/// - [`summarize`](Spec::summarize)
 	pub fn new(pattern: &str, icon: &str) -> Self {
 		Self {
-			pattern: RegexBuilder::new(pattern).unicode(false).build().unwrap(),
+			pattern: compile(pattern).unwrap(),
 			icons: Some(vec![String::from(icon)]),
 			style: None,
 			importance: None,
 			collapse: None,
+			opener: None,
+			summarize: None,
+			pin: None,
 		}
 	}
 
@@ -66,4 +83,110 @@ impl Spec {
 			..self
 		}
 	}
+
+	/// Consume the current `Spec` instance and return a new one that shows a
+	/// summary of the contents of matching directories.
+	pub fn summarize(self) -> Self {
+		Self {
+			summarize: Some(true),
+			..self
+		}
+	}
+}
+
+/// Represents a spec as written in the config, before its pattern has been
+/// compiled.
+#[derive(Deserialize)]
+struct SpecDef {
+	pattern: String,
+	#[serde(default)]
+	syntax: PatternSyntax,
+	icons: Option<Vec<String>>,
+	style: Option<String>,
+	importance: Option<i8>,
+	collapse: Option<Collapse>,
+	opener: Option<String>,
+	summarize: Option<bool>,
+	pin: Option<bool>,
+}
+
+impl TryFrom<SpecDef> for Spec {
+	type Error = RegexError;
+
+	fn try_from(def: SpecDef) -> Result<Self, Self::Error> {
+		Ok(Self {
+			pattern: compile(&regex_source(&def.pattern, def.syntax))?,
+			icons: def.icons,
+			style: def.style,
+			importance: def.importance,
+			collapse: def.collapse,
+			opener: def.opener,
+			summarize: def.summarize,
+			pin: def.pin,
+		})
+	}
+}
+
+/// Compile the pattern of a spec.
+///
+/// Patterns keep Unicode mode, so that classes like `\p{Lu}` can be used in
+/// the config. Names that are not valid UTF-8 are matched in their lossy form
+/// by [`Conf::matching_specs`](crate::config::Conf::matching_specs). The same
+/// flags are used for the [`RegexSet`](regex::bytes::RegexSet) of all specs,
+/// so that the set and the individual patterns always agree.
+fn compile(pattern: &str) -> Result<Regex, RegexError> {
+	Regex::new(pattern)
+}
+
+/// Get the source of a regex that matches the same names as the given
+/// pattern, written in the given syntax.
+///
+/// This is shared by the patterns of specs and the name filters, like
+/// `--only`, so that both understand globs the same way.
+pub fn regex_source(pattern: &str, syntax: PatternSyntax) -> Cow<'_, str> {
+	match syntax {
+		PatternSyntax::Regex => Cow::Borrowed(pattern),
+		PatternSyntax::Glob => Cow::Owned(shell_glob_to_regex(pattern)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Spec;
+	use figment::providers::{Format, Yaml};
+	use figment::Figment;
+
+	#[test]
+	fn test_spec_with_glob() {
+		let spec: Spec = Figment::from(Yaml::string("pattern: '*.rs'\nsyntax: glob\n"))
+			.extract()
+			.unwrap();
+		assert!(spec.pattern.is_match(b"main.rs"));
+		assert!(!spec.pattern.is_match(b"main.rsx"));
+	}
+
+	#[test]
+	fn test_spec_with_unicode_class() {
+		let spec: Spec = Figment::from(Yaml::string("pattern: '^\\p{Lu}'\n"))
+			.extract()
+			.unwrap();
+		assert!(spec.pattern.is_match("Éclair".as_bytes()));
+		assert!(!spec.pattern.is_match("éclair".as_bytes()));
+	}
+
+	#[test]
+	fn test_spec_with_pin() {
+		let spec: Spec = Figment::from(Yaml::string("pattern: '^README'\npin: true\n"))
+			.extract()
+			.unwrap();
+		assert_eq!(spec.pin, Some(true));
+	}
+
+	#[test]
+	fn test_spec_with_regex() {
+		let spec: Spec = Figment::from(Yaml::string("pattern: '\\.rs$'\n"))
+			.extract()
+			.unwrap();
+		assert!(spec.pattern.is_match(b"main.rs"));
+	}
 }
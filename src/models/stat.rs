@@ -0,0 +1,164 @@
+use crate::enums::Typ;
+use crate::ext::{Ctime, PlatformMeta};
+use serde::{Deserialize, Serialize};
+use std::fs::Metadata;
+use std::io::{Error, ErrorKind, Result as IoResult};
+use std::time::SystemTime;
+
+/// Represents the metadata of a node, as read from the file system.
+///
+/// Unlike [`Metadata`], this can be serialized, so that `pls daemon` can keep
+/// the metadata of the nodes in the directories it keeps warm and send it to
+/// `pls --from-daemon` without the client reading the disk again. It provides
+/// the same accessors as `Metadata`, so that it can stand in for it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stat {
+	pub typ: Typ,
+	pub len: u64,
+	pub dev: Option<u64>,
+	pub ino: Option<u64>,
+	pub nlink: Option<u64>,
+	pub mode: Option<u32>,
+	pub uid: Option<u32>,
+	pub gid: Option<u32>,
+	pub blocks: Option<u64>,
+	pub rdev: Option<(u32, u32)>,
+	pub attributes: Option<u32>,
+	pub atime: Option<SystemTime>,
+	pub btime: Option<SystemTime>,
+	pub ctime: Option<SystemTime>,
+	pub mtime: Option<SystemTime>,
+	/// whether the node is a file without local content, like a cloud
+	/// placeholder
+	pub is_dataless: bool,
+}
+
+impl From<&Metadata> for Stat {
+	fn from(meta: &Metadata) -> Self {
+		Self {
+			typ: meta.into(),
+			len: meta.len(),
+			dev: meta.dev_id(),
+			ino: meta.ino_num(),
+			nlink: meta.nlink_count(),
+			mode: meta.mode_bits(),
+			uid: meta.owner_uid(),
+			gid: meta.owner_gid(),
+			blocks: meta.block_count(),
+			rdev: meta.rdev_num(),
+			attributes: meta.attributes(),
+			atime: meta.accessed().ok(),
+			btime: meta.created().ok(),
+			ctime: meta.c_time().ok(),
+			mtime: meta.modified().ok(),
+			is_dataless: is_dataless(meta),
+		}
+	}
+}
+
+impl Stat {
+	/// Get the size of the node in bytes.
+	pub fn len(&self) -> u64 {
+		self.len
+	}
+
+	/// Get the last access time of the node.
+	pub fn accessed(&self) -> IoResult<SystemTime> {
+		time(self.atime)
+	}
+
+	/// Get the creation time of the node.
+	pub fn created(&self) -> IoResult<SystemTime> {
+		time(self.btime)
+	}
+
+	/// Get the last modification time of the node.
+	pub fn modified(&self) -> IoResult<SystemTime> {
+		time(self.mtime)
+	}
+}
+
+impl Ctime for Stat {
+	fn c_time(&self) -> IoResult<SystemTime> {
+		time(self.ctime)
+	}
+}
+
+impl PlatformMeta for Stat {
+	fn dev_id(&self) -> Option<u64> {
+		self.dev
+	}
+
+	fn ino_num(&self) -> Option<u64> {
+		self.ino
+	}
+
+	fn nlink_count(&self) -> Option<u64> {
+		self.nlink
+	}
+
+	fn mode_bits(&self) -> Option<u32> {
+		self.mode
+	}
+
+	fn owner_uid(&self) -> Option<u32> {
+		self.uid
+	}
+
+	fn owner_gid(&self) -> Option<u32> {
+		self.gid
+	}
+
+	fn block_count(&self) -> Option<u64> {
+		self.blocks
+	}
+
+	fn rdev_num(&self) -> Option<(u32, u32)> {
+		self.rdev
+	}
+
+	fn attributes(&self) -> Option<u32> {
+		self.attributes
+	}
+}
+
+/// Convert a timestamp that may not be available on the platform into the
+/// result returned by the accessors of [`Metadata`].
+fn time(time: Option<SystemTime>) -> IoResult<SystemTime> {
+	time.ok_or_else(|| Error::new(ErrorKind::Unsupported, "timestamp is not available"))
+}
+
+/// Determine whether the metadata belongs to a file without local content.
+#[cfg(windows)]
+fn is_dataless(meta: &Metadata) -> bool {
+	use std::os::windows::fs::MetadataExt;
+
+	const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+	const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+	const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+	meta.file_attributes()
+		& (FILE_ATTRIBUTE_OFFLINE
+			| FILE_ATTRIBUTE_RECALL_ON_OPEN
+			| FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+		!= 0
+}
+
+/// Determine whether the metadata belongs to a file without local content.
+#[cfg(target_os = "macos")]
+fn is_dataless(meta: &Metadata) -> bool {
+	use std::os::macos::fs::MetadataExt;
+
+	const SF_DATALESS: u32 = 0x40000000;
+	meta.st_flags() & SF_DATALESS != 0
+}
+
+/// Determine whether the metadata belongs to a file without local content.
+///
+/// Small files can be stored inline in the inode without occupying any
+/// blocks, so only files larger than a typical block are considered.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn is_dataless(meta: &Metadata) -> bool {
+	use std::os::unix::fs::MetadataExt;
+
+	meta.blocks() == 0 && meta.len() > 4096
+}
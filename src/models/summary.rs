@@ -0,0 +1,145 @@
+use crate::config::EntryConst;
+use crate::exc::Exc;
+use crate::utils::numbers::group_digits;
+use crate::PLS;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Represents a summary of the contents of a directory.
+///
+/// Summaries are shown for well-known bulky directories, like `node_modules`,
+/// in place of listing their contents.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Summary {
+	/// the number of items in the directory; for scoped directories, like
+	/// `@types` in `node_modules`, each child counts as a separate item
+	pub items: usize,
+	/// the total size of all files inside the directory, recursively
+	pub bytes: u64,
+}
+
+impl Summary {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Get the summary of the given directory.
+	///
+	/// Summaries can be cached across invocations, keyed by the modification
+	/// time of the directory, if the `PLS_CACHE` environment variable is set.
+	/// Since changes deep inside the directory do not update its modification
+	/// time, cached summaries can be slightly stale.
+	pub fn of(dir: &Path) -> Self {
+		let cache_file = cache_file(dir);
+		if let Some(summary) = cache_file.as_deref().and_then(load_from_cache) {
+			return summary;
+		}
+
+		let summary = Self::compute(dir);
+
+		if let Some(cache_file) = &cache_file {
+			if let Err(err) = save_to_cache(cache_file, &summary) {
+				warn!("Could not cache directory summary: {err}");
+			}
+		}
+		summary
+	}
+
+	// ===========
+	// Renderables
+	// ===========
+
+	/// Get the summary as a label to show next to the name of the directory.
+	///
+	/// This function returns a marked-up string.
+	pub fn label(&self, entry_const: &EntryConst) -> String {
+		let directives = &entry_const.summary_style;
+		format!(
+			"<{directives}>({} items, {})</>",
+			group_digits(self.items as u64),
+			PLS.args.unit.size(self.bytes, entry_const)
+		)
+	}
+
+	// =======
+	// Private
+	// =======
+
+	/// Compute the summary by walking the directory.
+	///
+	/// Symlinks are not followed, so that the size of linked content is not
+	/// counted and cycles are not possible. Unreadable entries are skipped.
+	fn compute(dir: &Path) -> Self {
+		debug!("Summarising {dir:?}.");
+		let mut summary = Self::default();
+
+		let Ok(children) = fs::read_dir(dir) else {
+			return summary;
+		};
+		let mut stack = vec![];
+		for child in children.flatten() {
+			let path = child.path();
+			let is_scope = child.file_name().to_string_lossy().starts_with('@')
+				&& child.file_type().is_ok_and(|typ| typ.is_dir());
+			summary.items += if is_scope {
+				fs::read_dir(&path).map_or(0, |scope| scope.count())
+			} else {
+				1
+			};
+			stack.push(path);
+		}
+
+		while let Some(path) = stack.pop() {
+			let Ok(meta) = path.symlink_metadata() else {
+				continue;
+			};
+			if meta.is_dir() {
+				if let Ok(children) = fs::read_dir(&path) {
+					stack.extend(children.flatten().map(|child| child.path()));
+				}
+			} else if meta.is_file() {
+				summary.bytes += meta.len();
+			}
+		}
+		summary
+	}
+}
+
+/// Get the path to the cache file for the summary of the given directory.
+///
+/// This returns `None` if caching is disabled or the directory's modification
+/// time cannot be determined.
+fn cache_file(dir: &Path) -> Option<PathBuf> {
+	let cache = env::var("PLS_CACHE").ok()?;
+	let dir = fs::canonicalize(dir).ok()?;
+	let mtime = dir
+		.metadata()
+		.and_then(|meta| meta.modified())
+		.ok()?
+		.duration_since(UNIX_EPOCH)
+		.ok()?
+		.as_secs();
+	let dir_key = dir.to_string_lossy().replace('/', "%");
+	Some(
+		Path::new(&cache)
+			.join("summaries")
+			.join(format!("{mtime}-{dir_key}.json")),
+	)
+}
+
+/// Load the summary from the cache, if present and valid.
+fn load_from_cache(cache_file: &Path) -> Option<Summary> {
+	let data = fs::read(cache_file).ok()?;
+	serde_json::from_slice(&data).ok()
+}
+
+/// Save the summary to the cache, creating the necessary directories.
+fn save_to_cache(cache_file: &Path, summary: &Summary) -> Result<(), Exc> {
+	let data = serde_json::to_vec(summary).map_err(Exc::Json)?;
+	fs::create_dir_all(cache_file.parent().unwrap()).map_err(Exc::Io)?;
+	fs::write(cache_file, data).map_err(Exc::Io)
+}
@@ -0,0 +1,200 @@
+use crate::config::EntryConst;
+use crate::ext::Xattr;
+use std::io::Result as IoResult;
+use std::path::Path;
+
+/// Represents the tags of a node, read from an extended attribute.
+///
+/// Tags let users label nodes on the file system itself, for example by
+/// project or by client, so that the labels follow the nodes when they are
+/// moved. The attribute holds a comma-separated list of tag names, read from
+/// the attribute named by `entry_const.tag.attr`.
+///
+/// Tags are written with `pls tag add` and `pls tag remove`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Tags(pub Vec<String>);
+
+impl Tags {
+	// ===========
+	// Constructor
+	// ===========
+
+	/// Read the tags of the node at the given path.
+	///
+	/// Nodes without the attribute, and nodes whose attributes cannot be read,
+	/// have no tags.
+	pub fn read(path: &Path, entry_const: &EntryConst) -> Self {
+		path.xattr_value(&entry_const.tag.attr)
+			.map(|value| Self::parse(&value))
+			.unwrap_or_default()
+	}
+
+	/// Parse the tags from the value of the attribute.
+	///
+	/// Tags are separated by commas, and surrounding whitespace is ignored,
+	/// as are empty and repeated tags. A trailing null byte, written by tools
+	/// that store C strings, is also ignored.
+	pub fn parse(value: &[u8]) -> Self {
+		let value = value.strip_suffix(&[0]).unwrap_or(value);
+		let mut tags: Vec<String> = vec![];
+		for tag in String::from_utf8_lossy(value).split(',') {
+			let tag = tag.trim();
+			if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+				tags.push(tag.to_string());
+			}
+		}
+		Self(tags)
+	}
+
+	// =======
+	// Getters
+	// =======
+
+	/// Determine whether the node has the given tag.
+	pub fn contains(&self, tag: &str) -> bool {
+		self.0.iter().any(|existing| existing == tag)
+	}
+
+	/// Determine whether the given name can be used as a tag.
+	///
+	/// Since the tags are separated by commas, a tag cannot contain commas.
+	/// It also cannot be blank, or have surrounding whitespace, as these would
+	/// be lost when the tags are read back.
+	pub fn is_valid(tag: &str) -> bool {
+		!tag.is_empty() && tag.trim() == tag && !tag.contains(',')
+	}
+
+	// =========
+	// Mutations
+	// =========
+
+	/// Add the given tag, if the node does not already have it.
+	pub fn add(&mut self, tag: &str) {
+		if !self.contains(tag) {
+			self.0.push(tag.to_string());
+		}
+	}
+
+	/// Remove the given tag, if the node has it.
+	pub fn remove(&mut self, tag: &str) {
+		self.0.retain(|existing| existing != tag);
+	}
+
+	/// Write the tags to the node at the given path.
+	///
+	/// If there are no tags, the attribute is removed instead of being left
+	/// empty.
+	pub fn write(&self, path: &Path, entry_const: &EntryConst) -> IoResult<()> {
+		let attr = &entry_const.tag.attr;
+		if !self.0.is_empty() {
+			return path.set_xattr(attr, self.0.join(",").as_bytes());
+		}
+		if path.xattr_value(attr).is_err() {
+			// There is no attribute to remove.
+			return Ok(());
+		}
+		path.remove_xattr(attr)
+	}
+
+	// ===========
+	// Renderables
+	// ===========
+
+	/// Get the tags as chips to show after the name of the node.
+	///
+	/// Each tag uses its own style from `entry_const.tag.styles`, if set, and
+	/// otherwise a style from `entry_const.tag.palette` picked by its name, so
+	/// that a tag has the same color everywhere.
+	///
+	/// This function returns a marked-up string.
+	pub fn chips(&self, entry_const: &EntryConst) -> String {
+		let info = &entry_const.tag;
+		self.0
+			.iter()
+			.map(|tag| {
+				let directives = info
+					.styles
+					.get(tag)
+					.map(String::as_str)
+					.or_else(|| {
+						(!info.palette.is_empty())
+							.then(|| info.palette[palette_index(tag, info.palette.len())].as_str())
+					})
+					.unwrap_or_default();
+				format!("<{directives}> {} </>", tag.replace('<', "\\<"))
+			})
+			.collect::<Vec<_>>()
+			.join(" ")
+	}
+}
+
+/// Pick the index of the palette entry for the given tag.
+///
+/// The index is derived from the bytes of the name with the FNV-1a hash,
+/// which, unlike the hasher of the standard library, is stable across runs
+/// and versions.
+fn palette_index(tag: &str, len: usize) -> usize {
+	let hash = tag.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+		(hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+	});
+	(hash % len as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{palette_index, Tags};
+
+	macro_rules! make_parse_test {
+		( $($name:ident: $value:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Tags::parse($value).0, $expected as Vec<&str>);
+				}
+			)*
+		};
+	}
+
+	make_parse_test!(
+		test_parse_empty: b"" => vec![],
+		test_parse_single: b"work" => vec!["work"],
+		test_parse_many: b"work, urgent,client-a" => vec!["work", "urgent", "client-a"],
+		test_parse_blank_and_repeated: b"work,, ,work" => vec!["work"],
+		test_parse_null_terminated: b"work\0" => vec!["work"],
+	);
+
+	macro_rules! make_valid_test {
+		( $($name:ident: $tag:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Tags::is_valid($tag), $expected);
+				}
+			)*
+		};
+	}
+
+	make_valid_test!(
+		test_valid_tag: "client-a" => true,
+		test_valid_tag_with_space: "to do" => true,
+		test_invalid_empty_tag: "" => false,
+		test_invalid_padded_tag: " work" => false,
+		test_invalid_tag_with_comma: "a,b" => false,
+	);
+
+	#[test]
+	fn test_add_and_remove() {
+		let mut tags = Tags::parse(b"work");
+		tags.add("urgent");
+		tags.add("work");
+		assert_eq!(tags.0, vec!["work", "urgent"]);
+		tags.remove("work");
+		assert_eq!(tags.0, vec!["urgent"]);
+	}
+
+	#[test]
+	fn test_palette_index_is_stable() {
+		assert_eq!(palette_index("work", 6), palette_index("work", 6));
+		assert!(palette_index("urgent", 6) < 6);
+	}
+}
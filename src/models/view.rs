@@ -0,0 +1,208 @@
+use crate::config::{Args, InteractiveInfo};
+use crate::enums::{KeyAction, SortField};
+use clap::ValueEnum;
+use regex::bytes::Regex;
+
+/// Represents the parts of the listing that can be changed while it is shown,
+/// in interactive mode.
+///
+/// Outside interactive mode, the view mirrors the CLI arguments and never
+/// changes. The listing reads these values from the view, instead of from
+/// [`Args`], so that the keys of interactive mode can change them between
+/// repaints.
+#[derive(Debug, Default)]
+pub struct View {
+	/// the pattern typed after the filter key, which names must match
+	pub filter: Option<Regex>,
+	/// the fields to sort by, the first of which takes precedence
+	pub sort_bases: Vec<SortField>,
+	/// the position of the current field in the sort cycle, `None` until the
+	/// sort is first cycled
+	pub sort_idx: Option<usize>,
+	/// whether nodes below the importance cutoff are shown
+	pub show_hidden: bool,
+	/// whether icons are shown next to names
+	pub icon: bool,
+}
+
+impl View {
+	/// Create a new `View` that mirrors the given CLI arguments.
+	pub fn new(args: &Args) -> Self {
+		Self {
+			filter: None,
+			sort_bases: args.sort_bases.clone(),
+			sort_idx: None,
+			show_hidden: false,
+			icon: args.icon,
+		}
+	}
+
+	/// Sort by the next field in the sort cycle.
+	///
+	/// The field replaces the sort bases, except for a leading `cat` or
+	/// `cat_`, so that directories stay where `--dirs` placed them.
+	///
+	/// # Arguments
+	///
+	/// * `cycle` - the fields to step through
+	/// * `bases` - the sort bases given in the CLI
+	pub fn cycle_sort(&mut self, cycle: &[SortField], bases: &[SortField]) {
+		if cycle.is_empty() {
+			return;
+		}
+		let idx = self.sort_idx.map_or(0, |idx| (idx + 1) % cycle.len());
+		self.sort_idx = Some(idx);
+		self.sort_bases = bases
+			.iter()
+			.take_while(|field| matches!(field, SortField::Cat | SortField::Cat_))
+			.copied()
+			.chain([cycle[idx]])
+			.collect();
+	}
+
+	/// Get the status line shown above the listing, as markup.
+	///
+	/// While a pattern is being typed, the line shows the pattern, in red if
+	/// it is not valid. Otherwise, it shows the state of the view and the keys
+	/// that change it.
+	///
+	/// # Arguments
+	///
+	/// * `info` - the configuration of interactive mode
+	/// * `typing` - the pattern being typed, if any, and whether it is valid
+	pub fn status(&self, info: &InteractiveInfo, typing: Option<(&str, bool)>) -> String {
+		let directives = &info.status_style;
+		if let Some((pattern, is_valid)) = typing {
+			// The cursor keeps a trailing backslash from escaping the tag after it.
+			let mut prompt = format!("/{}_", pattern.replace('<', "\\<"));
+			if !is_valid {
+				prompt = format!("<red>{prompt}</>");
+			}
+			return format!("<{directives}> {prompt} </>");
+		}
+
+		let filter = self
+			.filter
+			.as_ref()
+			.map_or(String::from("-"), |pat| pat.as_str().replace('<', "\\<"));
+		let sort = self
+			.sort_bases
+			.iter()
+			.filter_map(|field| field.to_possible_value())
+			.map(|value| value.get_name().to_string())
+			.collect::<Vec<_>>()
+			.join(",");
+		let on_off = |is_on| if is_on { "on" } else { "off" };
+
+		let mut keys: Vec<_> = info.keys.iter().collect();
+		keys.sort_by_key(|(key, action)| (**action, key.as_str()));
+		let hints = keys
+			.iter()
+			.map(|(key, action)| format!("{} {}", key.replace('<', "\\<"), action.label()))
+			.collect::<Vec<_>>()
+			.join("  ");
+
+		format!(
+			"<{directives}> filter: {filter}  sort: {sort}  hidden: {}  icons: {} </>  <dimmed>{hints}</>",
+			on_off(self.show_hidden),
+			on_off(self.icon),
+		)
+	}
+
+	/// Perform the action of a key, other than filtering and quitting, which
+	/// need the event loop.
+	///
+	/// # Arguments
+	///
+	/// * `action` - the action bound to the key that was pressed
+	/// * `info` - the configuration of interactive mode
+	/// * `bases` - the sort bases given in the CLI
+	pub fn apply(&mut self, action: KeyAction, info: &InteractiveInfo, bases: &[SortField]) {
+		match action {
+			KeyAction::CycleSort => self.cycle_sort(&info.sort_cycle, bases),
+			KeyAction::ToggleHidden => self.show_hidden = !self.show_hidden,
+			KeyAction::ToggleIcons => self.icon = !self.icon,
+			KeyAction::Filter | KeyAction::Quit => {}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::View;
+	use crate::config::AppConst;
+	use crate::enums::{KeyAction, SortField};
+
+	macro_rules! make_cycle_sort_test {
+		( $($name:ident: $bases:expr, $presses:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let cycle = [SortField::Cname, SortField::Size_, SortField::Mtime_];
+					let mut view = View::default();
+					for _ in 0..$presses {
+						view.cycle_sort(&cycle, &$bases);
+					}
+					assert_eq!(view.sort_bases, $expected);
+				}
+			)*
+		};
+	}
+
+	make_cycle_sort_test!(
+		test_cycle_first: [SortField::Name], 1 => vec![SortField::Cname],
+		test_cycle_second: [SortField::Name], 2 => vec![SortField::Size_],
+		test_cycle_wraps: [SortField::Name], 4 => vec![SortField::Cname],
+		test_cycle_keeps_cat: [SortField::Cat, SortField::Cname], 2 => vec![SortField::Cat, SortField::Size_],
+		test_cycle_keeps_cat_rev: [SortField::Cat_, SortField::Name], 3 => vec![SortField::Cat_, SortField::Mtime_],
+	);
+
+	#[test]
+	fn test_cycle_empty() {
+		let mut view = View::default();
+		view.cycle_sort(&[], &[SortField::Name]);
+		assert!(view.sort_bases.is_empty());
+		assert_eq!(view.sort_idx, None);
+	}
+
+	#[test]
+	fn test_apply_toggles() {
+		let info = AppConst::default().interactive;
+		let mut view = View::default();
+		view.apply(KeyAction::ToggleHidden, &info, &[]);
+		view.apply(KeyAction::ToggleIcons, &info, &[]);
+		assert!(view.show_hidden);
+		assert!(view.icon);
+		view.apply(KeyAction::ToggleIcons, &info, &[]);
+		assert!(!view.icon);
+	}
+
+	#[test]
+	fn test_status_shows_state_and_keys() {
+		let info = AppConst::default().interactive;
+		let view = View {
+			sort_bases: vec![SortField::Cat, SortField::Size_],
+			icon: true,
+			..View::default()
+		};
+		assert_eq!(
+			view.status(&info, None),
+			"<reversed> filter: -  sort: cat,size_  hidden: off  icons: on </>  \
+			<dimmed>/ filter  s sort  . hidden  i icons  q quit</>"
+		);
+	}
+
+	#[test]
+	fn test_status_while_typing() {
+		let info = AppConst::default().interactive;
+		let view = View::default();
+		assert_eq!(
+			view.status(&info, Some(("a<b", false))),
+			"<reversed> <red>/a\\<b_</> </>"
+		);
+		assert_eq!(
+			view.status(&info, Some(("\\", false))),
+			"<reversed> <red>/\\_</> </>"
+		);
+	}
+}
@@ -1,9 +1,30 @@
-use libc::{c_ushort, ioctl, STDOUT_FILENO, TIOCGWINSZ};
+#[cfg(unix)]
+use libc::{c_int, c_ushort, c_void, ioctl, STDOUT_FILENO, TIOCGWINSZ};
 use log::warn;
+use std::io::Result as IoResult;
+#[cfg(unix)]
+use std::io::{Error, ErrorKind};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+#[cfg(unix)]
+use std::thread;
+
+#[cfg(not(unix))]
+#[allow(non_camel_case_types)]
+type c_ushort = u16;
+
+/// the number of rows in a window with fixed measurements
+const FIXED_ROWS: c_ushort = 24;
+/// the number of columns in a window with fixed measurements
+const FIXED_COLS: c_ushort = 80;
+
+/// the write end of the pipe that the `SIGWINCH` handler signals resizes on
+#[cfg(unix)]
+static RESIZE_FD: AtomicI32 = AtomicI32::new(-1);
 
 /// See http://www.delorie.com/djgpp/doc/libc/libc_495.html
 #[repr(C)]
-#[derive(Default)]
+#[derive(Copy, Clone, Default)]
 pub struct Window {
 	pub ws_row: c_ushort,    /* rows, in characters */
 	pub ws_col: c_ushort,    /* columns, in characters */
@@ -15,6 +36,7 @@ impl Window {
 	/// Get a new `Window` instance with the terminal measurements.
 	///
 	/// This function returns `None` if the ioctl call fails.
+	#[cfg(unix)]
 	pub fn try_new() -> Option<Self> {
 		let mut win = Self::default();
 		#[allow(clippy::useless_conversion)]
@@ -26,6 +48,131 @@ impl Window {
 		None
 	}
 
+	/// Get a new `Window` instance with the terminal measurements.
+	///
+	/// The cell dimensions are only used for terminal graphics, which are not
+	/// supported on Windows, so this function always returns `None` there.
+	#[cfg(not(unix))]
+	pub fn try_new() -> Option<Self> {
+		warn!("Could not determine cell dimensions.");
+		None
+	}
+
+	/// Get a `Window` instance with fixed measurements, for output that must
+	/// not depend on the terminal.
+	///
+	/// The pixel dimensions are unknown, so graphics are not supported.
+	pub fn fixed() -> Self {
+		Self {
+			ws_row: FIXED_ROWS,
+			ws_col: FIXED_COLS,
+			..Self::default()
+		}
+	}
+
+	/// Call the given function, from a background thread, each time the
+	/// terminal is resized.
+	///
+	/// The `SIGWINCH` handler writes a byte to a pipe that the thread waits on,
+	/// so that no busy-waiting is needed. Signal handlers may only make
+	/// async-signal-safe calls, and `write` is one of them.
+	///
+	/// Both ends of the pipe are non-blocking, so that the handler does not
+	/// hang when the pipe is full, and close-on-exec, so that they are not
+	/// inherited by the processes that `pls` spawns.
+	#[cfg(unix)]
+	pub fn on_resize<F>(callback: F) -> IoResult<()>
+	where
+		F: Fn() + Send + 'static,
+	{
+		extern "C" fn handle(_: c_int) {
+			let fd = RESIZE_FD.load(Ordering::Relaxed);
+			let byte = 0u8;
+			unsafe { libc::write(fd, &byte as *const u8 as *const c_void, 1) };
+		}
+
+		let mut fds = [0; 2];
+		if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+			return Err(Error::last_os_error());
+		}
+		let [read_fd, write_fd] = fds;
+		let close = || unsafe {
+			libc::close(read_fd);
+			libc::close(write_fd);
+		};
+		for fd in fds {
+			if let Err(err) = set_flags(fd) {
+				close();
+				return Err(err);
+			}
+		}
+		if RESIZE_FD
+			.compare_exchange(-1, write_fd, Ordering::Relaxed, Ordering::Relaxed)
+			.is_err()
+		{
+			close();
+			return Err(Error::new(
+				ErrorKind::AlreadyExists,
+				"resizes are already handled",
+			));
+		}
+		let handler = handle as extern "C" fn(c_int) as libc::sighandler_t;
+		if unsafe { libc::signal(libc::SIGWINCH, handler) } == libc::SIG_ERR {
+			return Err(Error::last_os_error());
+		}
+
+		thread::spawn(move || {
+			let mut buf = [0u8; 64];
+			let mut pollfd = libc::pollfd {
+				fd: read_fd,
+				events: libc::POLLIN,
+				revents: 0,
+			};
+			loop {
+				if unsafe { libc::poll(&mut pollfd, 1, -1) } < 0 {
+					if Error::last_os_error().kind() == ErrorKind::Interrupted {
+						continue;
+					}
+					break;
+				}
+				// Drain the pipe, so that a burst of resizes calls back once.
+				let mut resized = false;
+				loop {
+					let res =
+						unsafe { libc::read(read_fd, buf.as_mut_ptr() as *mut c_void, buf.len()) };
+					if res > 0 {
+						resized = true;
+						continue;
+					}
+					if res == 0 {
+						return;
+					}
+					match Error::last_os_error().kind() {
+						ErrorKind::Interrupted => continue,
+						ErrorKind::WouldBlock => break,
+						_ => return,
+					}
+				}
+				if resized {
+					callback();
+				}
+			}
+		});
+		Ok(())
+	}
+
+	/// Call the given function each time the terminal is resized.
+	///
+	/// Windows has no `SIGWINCH` signal, so resizes are not detected there.
+	#[cfg(not(unix))]
+	pub fn on_resize<F>(_callback: F) -> IoResult<()>
+	where
+		F: Fn() + Send + 'static,
+	{
+		warn!("Could not watch for resizes.");
+		Ok(())
+	}
+
 	pub fn cell_width(&self) -> u8 {
 		(self.ws_xpixel / self.ws_col) as u8
 	}
@@ -34,3 +181,38 @@ impl Window {
 		(self.ws_ypixel / self.ws_row) as u8
 	}
 }
+
+/// Make the file descriptor non-blocking and close-on-exec.
+#[cfg(unix)]
+fn set_flags(fd: c_int) -> IoResult<()> {
+	unsafe {
+		let flags = libc::fcntl(fd, libc::F_GETFL);
+		if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+			return Err(Error::last_os_error());
+		}
+		let flags = libc::fcntl(fd, libc::F_GETFD);
+		if flags < 0 || libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC) < 0 {
+			return Err(Error::last_os_error());
+		}
+	}
+	Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::set_flags;
+
+	#[test]
+	fn test_set_flags() {
+		let mut fds = [0; 2];
+		assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+		for fd in fds {
+			set_flags(fd).unwrap();
+			let status = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+			assert_ne!(status & libc::O_NONBLOCK, 0);
+			let descriptor = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+			assert_ne!(descriptor & libc::FD_CLOEXEC, 0);
+			unsafe { libc::close(fd) };
+		}
+	}
+}
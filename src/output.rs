@@ -1,7 +1,20 @@
 mod cell;
+mod delimited;
 mod grid;
+mod nuon;
+mod powershell;
+mod prompt;
+mod record;
+mod sink;
 mod table;
+mod typed;
 
 pub use cell::Cell;
+pub use delimited::Delimited;
 pub use grid::Grid;
+pub use nuon::Nuon;
+pub use powershell::PowerShell;
+pub use prompt::Prompt;
+pub use record::Record;
+pub use sink::Sink;
 pub use table::Table;
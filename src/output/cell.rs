@@ -1,6 +1,8 @@
-use crate::fmt::{len, render};
+use crate::fmt::{len, render_into};
 use crate::gfx::strip_image;
+use crate::PLS;
 use std::fmt::Alignment;
+use std::iter::repeat;
 
 /// Represents one cell in the rendered output.
 ///
@@ -13,6 +15,8 @@ use std::fmt::Alignment;
 pub struct Cell {
 	pub alignment: Alignment,
 	pub padding: (usize, usize),
+	/// the number of cells the terminal uses to draw an emoji
+	pub emoji_width: usize,
 }
 
 impl Default for Cell {
@@ -20,14 +24,21 @@ impl Default for Cell {
 		Self {
 			alignment: Alignment::Left,
 			padding: (0, 1),
+			emoji_width: 2,
 		}
 	}
 }
 
 impl Cell {
 	/// Create a `Cell` instance with the given alignment and padding.
+	///
+	/// The width of emoji is taken from the quirks of the terminal.
 	pub fn new(alignment: Alignment, padding: (usize, usize)) -> Self {
-		Self { alignment, padding }
+		Self {
+			alignment,
+			padding,
+			emoji_width: PLS.quirks.emoji_width,
+		}
 	}
 
 	/// Return the content of the cell, padded to the given width and aligned
@@ -44,9 +55,35 @@ impl Cell {
 	pub fn print<S>(&self, text: S, width: &Option<usize>, directives: Option<String>) -> String
 	where
 		S: AsRef<str>,
+	{
+		let mut out = String::default();
+		self.print_into(text, width, directives.as_deref(), &mut out);
+		out
+	}
+
+	/// Append the content of the cell to the given buffer, padded and aligned
+	/// in the same way as [`print`](Cell::print).
+	///
+	/// Unstyled padding is written directly to the buffer, so that cells
+	/// without directives do not allocate any intermediate strings.
+	///
+	/// # Arguments
+	///
+	/// * `text` - the text to print in the cell
+	/// * `width` - the width that the cell should span
+	/// * `directives` - styles to apply to the entire cell, including padding
+	/// * `out` - the buffer to which the rendered cell is appended
+	pub fn print_into<S>(
+		&self,
+		text: S,
+		width: &Option<usize>,
+		directives: Option<&str>,
+		out: &mut String,
+	) where
+		S: AsRef<str>,
 	{
 		let text = text.as_ref();
-		let text_len = len(strip_image(text)); // This `len` can understand markup.
+		let text_len = len(strip_image(text), self.emoji_width); // This `len` can understand markup.
 
 		let (left, right): (usize, usize) = match width {
 			Some(width) if *width > text_len => {
@@ -59,20 +96,26 @@ impl Cell {
 			}
 			_ => (0, 0),
 		};
-		let (left, right) = (
-			" ".repeat(left + self.padding.0),
-			" ".repeat(right + self.padding.1),
-		);
-
-		let mut content = format!("{left}{text}{right}");
-
-		if let Some(directives) = directives {
-			content.insert_str(0, "<>");
-			content.insert_str(1, &directives);
-			content.push_str("</>");
+		let (left, right) = (left + self.padding.0, right + self.padding.1);
+
+		match directives {
+			Some(directives) => {
+				let mut content = String::with_capacity(text.len() + left + right + 8);
+				content.push('<');
+				content.push_str(directives);
+				content.push('>');
+				content.extend(repeat(' ').take(left));
+				content.push_str(text);
+				content.extend(repeat(' ').take(right));
+				content.push_str("</>");
+				render_into(content, out);
+			}
+			None => {
+				out.extend(repeat(' ').take(left));
+				render_into(text, out);
+				out.extend(repeat(' ').take(right));
+			}
 		}
-
-		render(content)
 	}
 }
 
@@ -125,4 +168,17 @@ mod tests {
 
 		test_handles_missing_width: "A", Alignment::Center, None => "A ",
 	);
+
+	#[test]
+	fn test_print_into_appends_to_buffer() {
+		colored::control::set_override(true); // needed when running tests in CLion
+		let cell = Cell {
+			alignment: Alignment::Right,
+			..Cell::default()
+		};
+		let mut out = String::from("|");
+		cell.print_into("A", &Some(3), None, &mut out);
+		cell.print_into("B", &None, Some("bold"), &mut out);
+		assert_eq!(out, format!("|  A {}", render("<bold>B </>")));
+	}
 }
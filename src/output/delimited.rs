@@ -0,0 +1,88 @@
+use crate::config::AppConst;
+use crate::enums::{DetailField, Export};
+use crate::exc::Exc;
+use crate::fmt::plain;
+use crate::output::Sink;
+use crate::PLS;
+use std::collections::HashMap;
+use std::io::{self, BufWriter, ErrorKind, Write};
+
+/// The delimited view writes the same rows as the [detailed view](crate::output::Table)
+/// as plain CSV or TSV records, meant to be read by spreadsheets and other
+/// programs instead of people.
+///
+/// Cells are stripped of all formatting and alignment. Numeric fields are
+/// exported as plain numbers, and their units are noted in the header.
+pub struct Delimited {
+	pub entries: Vec<HashMap<DetailField, String>>,
+	pub export: Export,
+}
+
+impl Delimited {
+	/// Create a new instance of `Delimited`, taking ownership of the given
+	/// entries.
+	pub fn new(entries: Vec<HashMap<DetailField, String>>, export: Export) -> Self {
+		Self { entries, export }
+	}
+
+	/// Render the records to the output.
+	///
+	/// If the reader closes the pipe early, such as `head`, rendering stops
+	/// silently.
+	pub fn render(&self, app_const: &AppConst) -> Result<(), Exc> {
+		match self.write(app_const) {
+			Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+			res => res.map_err(Exc::Io),
+		}
+	}
+
+	/// Write the records to the output.
+	fn write(&self, app_const: &AppConst) -> io::Result<()> {
+		let mut out = BufWriter::new(Sink::lock());
+
+		if PLS.args.header {
+			let names: Vec<_> = PLS
+				.args
+				.details
+				.iter()
+				.map(|det| {
+					let name = plain(det.name(app_const));
+					match det.raw_unit() {
+						Some(unit) if !unit.is_empty() => {
+							format!("{name} ({unit})")
+						}
+						_ => name,
+					}
+				})
+				.collect();
+			self.write_record(&mut out, names.iter().map(String::as_str))?;
+		}
+
+		for entry in &self.entries {
+			let cells: Vec<_> = PLS
+				.args
+				.details
+				.iter()
+				.map(|det| plain(entry.get(det).map_or("", String::as_str)))
+				.collect();
+			self.write_record(&mut out, cells.iter().map(|cell| cell.trim()))?;
+		}
+
+		out.flush()
+	}
+
+	/// Write one record, escaping each field and joining them with the
+	/// separator of the export format.
+	fn write_record<'a, W, I>(&self, out: &mut W, fields: I) -> io::Result<()>
+	where
+		W: Write,
+		I: Iterator<Item = &'a str>,
+	{
+		let sep = self.export.sep().to_string();
+		let record = fields
+			.map(|field| self.export.escape(field))
+			.collect::<Vec<_>>()
+			.join(&sep);
+		writeln!(out, "{record}")
+	}
+}
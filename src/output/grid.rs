@@ -1,14 +1,18 @@
 use crate::config::AppConst;
-use crate::enums::DetailField;
+use crate::enums::{DetailField, Direction};
 use crate::fmt::len;
 use crate::gfx::strip_image;
-use crate::output::Cell;
+use crate::output::{Cell, Sink};
 use crate::PLS;
 use std::collections::HashMap;
 use std::fmt::Alignment;
 
+/// the number of spaces between two columns of the grid
+const GAP: usize = 2;
+
 /// The grid view renders the node names in a two dimensional layout to minimise
-/// scrolling. It does not support rendering of node metadata.
+/// scrolling. Only compact details, chosen with `grid_details` in the config,
+/// can be shown next to each name.
 ///
 /// The grid view is one of two views supported by `pls`, the other being the
 /// [detailed view](crate::output::Table).
@@ -16,104 +20,235 @@ use std::fmt::Alignment;
 /// The grid view tries to render all elements in as few lines as possible. Once
 /// the number of lines has been minimised, it minimises the column count by
 /// making each column take the maximum number of rows.
+///
+/// With `--balance`, all columns are as wide as the widest entry. Otherwise,
+/// each column is only as wide as its own widest entry, so that one long name
+/// does not force every column to be wide.
 pub struct Grid {
 	pub entries: Vec<String>,
+	/// the background directives of each entry, that fill its whole cell
+	pub fills: Vec<Option<String>>,
+}
+
+/// Represents the arrangement of entries in the grid.
+#[derive(Debug, PartialEq, Eq)]
+struct Layout {
+	/// the indices of the entries in each row, from left to right
+	rows: Vec<Vec<usize>>,
+	/// the width of each column, or `None` if the entries need no padding
+	widths: Vec<Option<usize>>,
 }
 
 impl Grid {
 	/// Create a new instance of `Grid`, taking ownership of the given entries.
-	pub fn new(entries: Vec<HashMap<DetailField, String>>) -> Self {
-		Self {
-			entries: entries
-				.into_iter()
-				.map(|mut entry| entry.remove(&DetailField::Name).unwrap_or_default())
-				.collect(),
-		}
+	///
+	/// The styles of each node are read from the [`DetailField::Style`] field,
+	/// of which only the background directives are kept, because the names
+	/// are already styled.
+	///
+	/// The values of the given details follow the name in each cell, separated
+	/// by spaces. Details without a value for a node, like the size of a
+	/// directory, are left out of its cell.
+	pub fn new(entries: Vec<HashMap<DetailField, String>>, details: &[DetailField]) -> Self {
+		let (entries, fills) = entries
+			.into_iter()
+			.map(|mut entry| {
+				let fill = entry
+					.remove(&DetailField::Style)
+					.and_then(|style| fill_directives(&style));
+				let mut text = entry.remove(&DetailField::Name).unwrap_or_default();
+				for detail in details {
+					if let Some(value) = entry.get(detail).filter(|value| !value.is_empty()) {
+						text.push(' ');
+						text.push_str(value);
+					}
+				}
+				(text, fill)
+			})
+			.unzip();
+		Self { entries, fills }
 	}
 
-	/// Render the grid to STDOUT.
+	/// Render the grid to the output.
 	pub fn render(&self, _app_const: &AppConst) {
-		let mut max_width = self.entries.iter().map(strip_image).map(len).max();
-		let max_cols = self.columns(max_width);
-
-		let entry_len = self.entries.len();
-		if entry_len == 0 {
+		if self.entries.is_empty() {
 			// Nothing to render, so we exit.
 			return;
 		}
 
-		let rows = (entry_len as f64 / max_cols as f64).ceil() as usize;
-		let cols = (entry_len as f64 / rows as f64).ceil() as usize;
-
-		if cols == 1 {
-			// If there is only one column, we don't need to equalise width.
-			max_width = None;
-		}
-
-		if cols > 1 && PLS.args.down {
-			self.print(&self.down(rows), cols, max_width);
-		} else {
-			self.print(&self.entries, cols, max_width);
-		};
+		let emoji_width = PLS.quirks.emoji_width;
+		let widths: Vec<_> = self
+			.entries
+			.iter()
+			.map(|entry| len(strip_image(entry), emoji_width))
+			.collect();
+		let down = PLS.args.direction == Direction::Column;
+		let layout = layout(&widths, PLS.term_width(), PLS.args.balance, down);
+		self.print(&layout);
 	}
 
-	/// Print the entries to the screen.
+	/// Print the entries to the output.
 	///
-	/// This prints the entries in the specified number of columns, each cell
-	/// padded to span the given max-width.
-	fn print<S>(&self, entries: &[S], cols: usize, max_width: Option<usize>)
-	where
-		S: AsRef<str>,
-	{
-		let entry_len = self.entries.len();
-
-		let cell = Cell::new(Alignment::Left, (0, 2));
-		let end_cell = Cell::new(Alignment::Left, (0, 0));
-		for (idx, text) in entries.iter().enumerate() {
-			if idx % cols == cols - 1 || idx == entry_len - 1 {
-				println!("{}", &end_cell.print(text, &max_width, None));
-			} else {
-				print!("{}", &cell.print(text, &max_width, None));
+	/// This prints the entries row by row, each cell padded to span the width
+	/// of its column. Cells with background directives are filled up to that
+	/// width, so that the colors form even blocks. Each row is assembled
+	/// before being written, so that it reaches the output in one piece.
+	fn print(&self, layout: &Layout) {
+		let cell = Cell::new(Alignment::Left, (0, 0));
+		let mut line = String::new();
+		for row in &layout.rows {
+			line.clear();
+			for (col, &idx) in row.iter().enumerate() {
+				if col > 0 {
+					line.push_str(&" ".repeat(GAP));
+				}
+				let fill = self.fills[idx].clone();
+				line.push_str(&cell.print(&self.entries[idx], &layout.widths[col], fill));
 			}
+			Sink::println(&line);
 		}
 	}
+}
 
-	/// Shuffle the entries to enable printing down instead of across.
-	///
-	/// Since terminals can only print row-by-row, we split the entries into
-	/// columns and then pick one cell per column, going in cycles till all
-	/// cells are exhausted.
-	fn down(&self, rows: usize) -> Vec<&String> {
-		let chunks: Vec<_> = self.entries.chunks(rows).collect();
-		(0..rows)
-			.flat_map(|row_idx| chunks.iter().filter_map(move |chunk| chunk.get(row_idx)))
+/// Get the directives from the given style that paint the background of a
+/// cell, namely background colors and reversal.
+fn fill_directives(style: &str) -> Option<String> {
+	let fill: Vec<_> = style
+		.split_whitespace()
+		.filter(|directive| directive.starts_with("bg:") || *directive == "reversed")
+		.collect();
+	(!fill.is_empty()).then(|| fill.join(" "))
+}
+
+/// Arrange entries of the given widths into the fewest rows that fit within
+/// the terminal width.
+///
+/// If the terminal width cannot be determined, such as when piping to a
+/// file, the output will be laid out in a single column.
+///
+/// # Arguments
+///
+/// * `widths` - the printed width of each entry
+/// * `term_width` - the number of columns in the terminal
+/// * `balance` - whether all columns must be equally wide
+/// * `down` - whether entries are placed down columns instead of across rows
+fn layout(widths: &[usize], term_width: Option<usize>, balance: bool, down: bool) -> Layout {
+	let count = widths.len();
+	let max_width = widths.iter().copied().max().unwrap_or_default();
+
+	let rows = match term_width {
+		Some(term_width) if balance => {
+			let max_cols = ((term_width + GAP) / (max_width + GAP)).max(1);
+			count.div_ceil(max_cols)
+		}
+		Some(term_width) => {
+			// No layout can have more columns than if every entry were as
+			// narrow as the narrowest one.
+			let min_width = widths.iter().copied().min().unwrap_or_default();
+			let max_cols = ((term_width + GAP) / (min_width + GAP)).max(1);
+			(count.div_ceil(max_cols)..count)
+				.find(|&rows| {
+					let col_widths = col_widths(widths, rows, down);
+					let total: usize = col_widths.iter().sum();
+					total + GAP * (col_widths.len() - 1) <= term_width
+				})
+				.unwrap_or(count)
+		}
+		None => count,
+	};
+	let cols = count.div_ceil(rows);
+
+	let rows: Vec<Vec<usize>> = (0..rows)
+		.map(|row| {
+			(0..cols)
+				.map(|col| {
+					if down {
+						col * rows + row
+					} else {
+						row * cols + col
+					}
+				})
+				.filter(|&idx| idx < count)
+				.collect()
+		})
+		.collect();
+
+	// If there is only one column, we don't need to equalise width.
+	let widths = if cols == 1 {
+		vec![None]
+	} else if balance {
+		vec![Some(max_width); cols]
+	} else {
+		col_widths(widths, rows.len(), down)
+			.into_iter()
+			.map(Some)
 			.collect()
+	};
+	Layout { rows, widths }
+}
+
+/// Get the width of each column when entries of the given widths are
+/// arranged in the given number of rows.
+fn col_widths(widths: &[usize], rows: usize, down: bool) -> Vec<usize> {
+	let cols = widths.len().div_ceil(rows);
+	let mut col_widths = vec![0; cols];
+	for (idx, &width) in widths.iter().enumerate() {
+		let col = if down { idx / rows } else { idx % cols };
+		col_widths[col] = col_widths[col].max(width);
 	}
+	col_widths
+}
 
-	/// Get the number of columns that can be accommodated on the screen.
-	///
-	/// If the terminal width cannot be determined, such as when piping to a
-	/// file, the output will be laid out in a single column.
-	fn columns(&self, max_width: Option<usize>) -> u16 {
-		match (Self::term_width(), max_width) {
-			(Some(term_width), Some(item_width)) => {
-				let cols = (term_width + 2) / (item_width as u16 + 2);
-				cols.max(1)
-			}
-			_ => 1,
-		}
+#[cfg(test)]
+mod tests {
+	use super::{fill_directives, layout, Layout};
+
+	macro_rules! make_fill_test {
+		( $($name:ident: $style:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(fill_directives($style).as_deref(), $expected);
+				}
+			)*
+		};
 	}
 
-	/// Get the terminal width.
-	///
-	/// The terminal width is determined from two sources:
-	///
-	/// * the `PLS_COLUMNS` environment variable, if it is set
-	/// * the result of an ioctl call, if it succeeds
-	fn term_width() -> Option<u16> {
-		std::env::var("PLS_COLUMNS") // development hack
-			.ok()
-			.and_then(|width_str| width_str.parse::<u16>().ok())
-			.or_else(|| PLS.window.as_ref().map(|win| win.ws_col))
+	make_fill_test!(
+		test_fill_none: "blue bold" => None,
+		test_fill_empty: "" => None,
+		test_fill_bg: "blue bg:red bold" => Some("bg:red"),
+		test_fill_bg_and_reversed: "bg:rgb(0,0,0) italic reversed" => Some("bg:rgb(0,0,0) reversed"),
+	);
+
+	macro_rules! make_layout_test {
+		( $($name:ident: $widths:expr, $term_width:expr, $balance:expr, $down:expr => $rows:expr, $col_widths:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let expected = Layout { rows: $rows, widths: $col_widths };
+					assert_eq!(layout(&$widths, $term_width, $balance, $down), expected);
+				}
+			)*
+		};
 	}
+
+	make_layout_test!(
+		test_unknown_width: [3, 4, 5], None, true, false
+			=> vec![vec![0], vec![1], vec![2]], vec![None],
+		test_single_row: [3, 4, 5], Some(80), true, false
+			=> vec![vec![0, 1, 2]], vec![Some(5); 3],
+		test_balanced_across: [10, 2, 2, 2, 2], Some(26), true, false
+			=> vec![vec![0, 1], vec![2, 3], vec![4]], vec![Some(10); 2],
+		test_packed_across: [10, 2, 2, 2, 2], Some(26), false, false
+			=> vec![vec![0, 1, 2, 3, 4]], vec![Some(10), Some(2), Some(2), Some(2), Some(2)],
+		test_packed_across_wraps: [10, 2, 2, 2, 9], Some(26), false, false
+			=> vec![vec![0, 1, 2], vec![3, 4]], vec![Some(10), Some(9), Some(2)],
+		test_balanced_down: [1, 1, 1, 1, 1, 1, 1], Some(7), true, true
+			=> vec![vec![0, 3, 6], vec![1, 4], vec![2, 5]], vec![Some(1); 3],
+		test_packed_down: [8, 8, 1, 1, 1, 1], Some(14), false, true
+			=> vec![vec![0, 2, 4], vec![1, 3, 5]], vec![Some(8), Some(1), Some(1)],
+		test_too_wide: [90, 2], Some(80), false, false
+			=> vec![vec![0], vec![1]], vec![None],
+	);
 }
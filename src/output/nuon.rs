@@ -0,0 +1,131 @@
+use crate::enums::{DetailField, Export};
+use crate::exc::Exc;
+use crate::output::typed::Typed;
+use crate::output::Sink;
+use crate::PLS;
+use std::collections::HashMap;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use time::format_description::well_known::Rfc3339;
+
+/// The NUON view writes the same rows as the [detailed view](crate::output::Table)
+/// as a table in Nushell Object Notation, so that the output of `pls` can be
+/// piped into `nu` pipelines with `from nuon`.
+///
+/// Unlike CSV and TSV, NUON values are typed. Sizes are written as file sizes,
+/// timestamps as dates, IDs and counts as integers, and missing values as
+/// `null`, which are all read from the raw values. All other values are
+/// written as strings stripped of formatting, and the columns are keyed like
+/// the fields in the config.
+pub struct Nuon {
+	pub entries: Vec<HashMap<DetailField, String>>,
+}
+
+impl Nuon {
+	/// Create a new instance of `Nuon`, taking ownership of the given entries.
+	pub fn new(entries: Vec<HashMap<DetailField, String>>) -> Self {
+		Self { entries }
+	}
+
+	/// Render the table to the output.
+	///
+	/// If the reader closes the pipe early, such as `head`, rendering stops
+	/// silently.
+	pub fn render(&self) -> Result<(), Exc> {
+		match self.write() {
+			Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+			res => res.map_err(Exc::Io),
+		}
+	}
+
+	/// Write the table to the output, with one row per line.
+	fn write(&self) -> io::Result<()> {
+		let mut out = BufWriter::new(Sink::lock());
+
+		if self.entries.is_empty() {
+			writeln!(out, "[]")?;
+			return out.flush();
+		}
+
+		let keys: Vec<_> = PLS.args.details.iter().map(|det| key(&det.key())).collect();
+		write!(out, "[[{}];", keys.join(", "))?;
+		for (idx, entry) in self.entries.iter().enumerate() {
+			let cells: Vec<_> = PLS
+				.args
+				.details
+				.iter()
+				.map(|det| literal(&Typed::new(*det, entry.get(det).map_or("", String::as_str))))
+				.collect();
+			let sep = if idx == 0 { "" } else { "," };
+			write!(out, "{sep}\n [{}]", cells.join(", "))?;
+		}
+		writeln!(out, "]")?;
+
+		out.flush()
+	}
+}
+
+/// Get the column name for the given key, quoted unless it is a bare word.
+fn key(key: &str) -> String {
+	let mut chars = key.chars();
+	let is_bare = chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+		&& chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+	if is_bare {
+		key.to_string()
+	} else {
+		Export::Nuon.escape(key)
+	}
+}
+
+/// Get the NUON literal for the given typed value.
+fn literal(typed: &Typed) -> String {
+	match typed {
+		Typed::Null => String::from("null"),
+		Typed::Bytes(bytes) => format!("{bytes}b"),
+		Typed::Date(dt) => dt.format(&Rfc3339).unwrap_or_default(),
+		Typed::Int(num) => num.to_string(),
+		Typed::Text(text) => Export::Nuon.escape(text),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{key, literal};
+	use crate::output::typed::Typed;
+	use time::OffsetDateTime;
+
+	macro_rules! make_literal_test {
+		( $($name:ident: $typed:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(literal(&$typed), $expected);
+				}
+			)*
+		};
+	}
+
+	make_literal_test!(
+		test_null: Typed::Null => "null",
+		test_bytes: Typed::Bytes(2048) => "2048b",
+		test_date: Typed::Date(OffsetDateTime::from_unix_timestamp(1_706_725_800).unwrap()) => "2024-01-31T18:30:00Z",
+		test_int: Typed::Int(-1) => "-1",
+		test_text: Typed::Text(String::from("a \"b\"")) => "\"a \\\"b\\\"\"",
+	);
+
+	macro_rules! make_key_test {
+		( $($name:ident: $key:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(key($key), $expected);
+				}
+			)*
+		};
+	}
+
+	make_key_test!(
+		test_bare_key: "size_raw" => "size_raw",
+		test_spaced_key: "last commit" => "\"last commit\"",
+		test_digit_key: "2fa" => "\"2fa\"",
+	);
+}
@@ -0,0 +1,148 @@
+use crate::enums::DetailField;
+use crate::exc::Exc;
+use crate::output::typed::Typed;
+use crate::output::Sink;
+use crate::PLS;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use time::format_description::well_known::Rfc3339;
+
+/// The PowerShell view writes the same rows as the [detailed view](crate::output::Table)
+/// as a JSON array of objects, shaped for `ConvertFrom-Json`, so that the
+/// output of `pls` can be used in PowerShell pipelines.
+///
+/// Properties are named in PascalCase, like `SizeRaw`, following the
+/// conventions of PowerShell. Like the [NUON view](crate::output::Nuon), values
+/// are typed. Sizes are numbers of bytes, timestamps are ISO 8601 dates, which
+/// `ConvertFrom-Json` reads as `DateTime` objects, and missing values are
+/// `null`.
+pub struct PowerShell {
+	pub entries: Vec<HashMap<DetailField, String>>,
+}
+
+impl PowerShell {
+	/// Create a new instance of `PowerShell`, taking ownership of the given
+	/// entries.
+	pub fn new(entries: Vec<HashMap<DetailField, String>>) -> Self {
+		Self { entries }
+	}
+
+	/// Render the objects to the output.
+	///
+	/// If the reader closes the pipe early, such as `head`, rendering stops
+	/// silently.
+	pub fn render(&self) -> Result<(), Exc> {
+		match self.write() {
+			Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+			res => res.map_err(Exc::Io),
+		}
+	}
+
+	/// Write the objects to the output, with one object per line.
+	///
+	/// The properties are written in the order of the columns, which is the
+	/// order in which PowerShell shows them.
+	fn write(&self) -> io::Result<()> {
+		let mut out = BufWriter::new(Sink::lock());
+
+		let keys: Vec<_> = PLS
+			.args
+			.details
+			.iter()
+			.map(|det| Value::String(pascal(&det.key())).to_string())
+			.collect();
+		write!(out, "[")?;
+		for (idx, entry) in self.entries.iter().enumerate() {
+			let props: Vec<_> = PLS
+				.args
+				.details
+				.iter()
+				.zip(&keys)
+				.map(|(det, key)| {
+					let typed = Typed::new(*det, entry.get(det).map_or("", String::as_str));
+					format!("{key}:{}", json(typed))
+				})
+				.collect();
+			let sep = if idx == 0 { "" } else { "," };
+			write!(out, "{sep}\n{{{}}}", props.join(","))?;
+		}
+		writeln!(out, "\n]")?;
+
+		out.flush()
+	}
+}
+
+/// Convert the given key, like `size_raw`, into PascalCase, like `SizeRaw`.
+///
+/// Any character that is not a letter or a digit separates words, so that
+/// the names of derived columns, like `last commit`, become valid property
+/// names too.
+fn pascal(key: &str) -> String {
+	key.split(|ch: char| !ch.is_alphanumeric())
+		.flat_map(|word| {
+			let mut chars = word.chars();
+			chars
+				.next()
+				.into_iter()
+				.flat_map(char::to_uppercase)
+				.chain(chars)
+		})
+		.collect()
+}
+
+/// Get the JSON value for the given typed value.
+fn json(typed: Typed) -> Value {
+	match typed {
+		Typed::Null => Value::Null,
+		Typed::Bytes(bytes) => Value::from(bytes),
+		Typed::Date(dt) => dt.format(&Rfc3339).map_or(Value::Null, Value::String),
+		Typed::Int(num) => Value::from(num),
+		Typed::Text(text) => Value::String(text),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{json, pascal};
+	use crate::output::typed::Typed;
+	use serde_json::json;
+	use time::OffsetDateTime;
+
+	macro_rules! make_pascal_test {
+		( $($name:ident: $key:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(pascal($key), $expected);
+				}
+			)*
+		};
+	}
+
+	make_pascal_test!(
+		test_pascal_word: "size" => "Size",
+		test_pascal_snake: "size_raw" => "SizeRaw",
+		test_pascal_spaces: "last commit" => "LastCommit",
+		test_pascal_repeated_separators: "a__b" => "AB",
+	);
+
+	macro_rules! make_json_test {
+		( $($name:ident: $typed:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(json($typed), $expected);
+				}
+			)*
+		};
+	}
+
+	make_json_test!(
+		test_json_null: Typed::Null => json!(null),
+		test_json_bytes: Typed::Bytes(2048) => json!(2048),
+		test_json_date: Typed::Date(OffsetDateTime::from_unix_timestamp(1_706_725_800).unwrap()) => json!("2024-01-31T18:30:00Z"),
+		test_json_int: Typed::Int(1000) => json!(1000),
+		test_json_text: Typed::Text(String::from("rw-")) => json!("rw-"),
+	);
+}
@@ -0,0 +1,209 @@
+use crate::args::Input;
+use crate::config::AppConst;
+use crate::enums::Typ;
+use crate::exc::Exc;
+use crate::fmt::{render, truncate};
+use crate::models::{Daemon, Node};
+use crate::output::Sink;
+use crate::traits::Imp;
+use crate::PLS;
+use git2::{Repository, StatusOptions};
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// The prompt view renders a single compact line describing a directory,
+/// meant to be embedded in shell prompts or status bars.
+///
+/// The line consists of the number of directories and files, the Git branch
+/// and whether the working tree is dirty, and the names of the most important
+/// nodes. Since prompts are rendered very frequently, expensive probes are
+/// bounded by the time budget in [`AppConst::prompt`].
+pub struct Prompt {
+	/// the number of visible directories
+	pub dirs: usize,
+	/// the number of visible nodes that are not directories
+	pub files: usize,
+	/// the current Git branch, if the directory is inside a repository
+	pub branch: Option<String>,
+	/// whether the working tree has changes, `None` if the probe timed out
+	pub dirty: Option<bool>,
+	/// the names of the most important nodes, paired with their styles
+	pub top: Vec<(String, String)>,
+}
+
+impl Prompt {
+	/// Create a new instance of `Prompt` by inspecting the given directory.
+	pub fn new(input: &Input) -> Result<Self, Exc> {
+		let conf = &input.conf;
+		let app_const = &conf.app_const;
+
+		let children = match PLS
+			.args
+			.from_daemon
+			.then(|| Daemon::list(&input.path))
+			.flatten()
+		{
+			Some(children) => children,
+			None => fs::read_dir(&input.path)
+				.map_err(Exc::Io)?
+				.flatten()
+				.map(|entry| (entry.path(), None))
+				.collect(),
+		};
+		let mut nodes: Vec<_> = children
+			.into_iter()
+			.map(|(path, stat)| {
+				let mut node = match stat {
+					Some(stat) => Node::cached(&path, stat),
+					None => Node::new(&path),
+				};
+				node.match_specs(conf);
+				node
+			})
+			.filter(|node| node.is_visible(conf))
+			.collect();
+
+		let dirs = nodes.iter().filter(|node| node.typ == Typ::Dir).count();
+		let files = nodes.len() - dirs;
+
+		nodes.retain(|node| node.imp_val() > 0);
+		nodes.sort_by(|a, b| b.imp_val().cmp(&a.imp_val()).then(a.name.cmp(&b.name)));
+		let top = nodes
+			.iter()
+			.take(app_const.prompt.top_nodes)
+			.map(|node| {
+				let directives = Imp::directives(node, app_const).unwrap_or_default();
+				// The name is escaped before it is truncated, because the
+				// truncation treats it as markup and keeps escapes whole.
+				let name = truncate(
+					node.name.replace('<', "\\<"),
+					app_const.prompt.name_width,
+					PLS.quirks.emoji_width,
+				);
+				(name, directives)
+			})
+			.collect();
+
+		let branch = branch(&input.path);
+		let dirty = if branch.is_some() {
+			let budget = Duration::from_millis(app_const.prompt.budget_ms);
+			is_dirty(input.path.clone(), budget)
+		} else {
+			None
+		};
+
+		Ok(Self {
+			dirs,
+			files,
+			branch,
+			dirty,
+			top,
+		})
+	}
+
+	/// Get the markup for the number of directories and files, with the nouns
+	/// in the singular for counts of one.
+	fn counts(&self) -> String {
+		let dirs = if self.dirs == 1 { "dir" } else { "dirs" };
+		let files = if self.files == 1 { "file" } else { "files" };
+		format!(
+			"<bold>{}</> {dirs} <bold>{}</> {files}",
+			self.dirs, self.files
+		)
+	}
+
+	/// Render the prompt line to the output.
+	pub fn render(&self, app_const: &AppConst) {
+		let mut parts = vec![self.counts()];
+
+		if let Some(branch) = &self.branch {
+			let state = match self.dirty {
+				Some(true) => "*",
+				Some(false) => "",
+				None => "?",
+			};
+			let directives = &app_const.prompt.branch_style;
+			parts.push(format!("<{directives}>{branch}{state}</>"));
+		}
+
+		if !self.top.is_empty() {
+			parts.push(
+				self.top
+					.iter()
+					.map(|(name, directives)| format!("<{directives}>{name}</>"))
+					.collect::<Vec<_>>()
+					.join(" "),
+			);
+		}
+
+		Sink::println(render(parts.join("  ")));
+	}
+}
+
+/// Get the name of the current Git branch of the repository containing the
+/// given directory.
+///
+/// Reading the branch is cheap, so it is not bounded by the time budget.
+fn branch(dir: &Path) -> Option<String> {
+	let repo = Repository::discover(dir).ok()?;
+	let head = repo.head().ok()?;
+	head.shorthand().map(String::from)
+}
+
+/// Determine whether the working tree of the repository containing the given
+/// directory has changes.
+///
+/// The probe runs in a separate thread and is abandoned if it does not finish
+/// within the budget, in which case `None` is returned.
+fn is_dirty(dir: PathBuf, budget: Duration) -> Option<bool> {
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn(move || {
+		let dirty = Repository::discover(&dir).ok().and_then(|repo| {
+			let mut opts = StatusOptions::new();
+			opts.include_untracked(true).exclude_submodules(true);
+			let statuses = repo.statuses(Some(&mut opts)).ok()?;
+			Some(!statuses.is_empty())
+		});
+		let _ = sender.send(dirty); // The receiver may have timed out.
+	});
+	match receiver.recv_timeout(budget) {
+		Ok(dirty) => dirty,
+		Err(_) => {
+			debug!("Git status probe exceeded the budget of {budget:?}.");
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Prompt;
+
+	macro_rules! make_counts_test {
+		( $($name:ident: $dirs:expr, $files:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let prompt = Prompt {
+						dirs: $dirs,
+						files: $files,
+						branch: None,
+						dirty: None,
+						top: vec![],
+					};
+					assert_eq!(prompt.counts(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_counts_test!(
+		test_counts_singular: 1, 1 => "<bold>1</> dir <bold>1</> file",
+		test_counts_plural: 2, 3 => "<bold>2</> dirs <bold>3</> files",
+		test_counts_none: 0, 0 => "<bold>0</> dirs <bold>0</> files",
+	);
+}
@@ -0,0 +1,133 @@
+use crate::config::AppConst;
+use crate::enums::DetailField;
+use crate::exc::Exc;
+use crate::fmt::{len, plain, render_into};
+use crate::output::Sink;
+use crate::PLS;
+use std::collections::HashMap;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::iter::once;
+
+/// the indentation of the detail lines below the name of a node
+const INDENT: &str = "  ";
+
+/// The record view renders each node as a block of lines, with the name of the
+/// node on the first line and each chosen detail on an indented line of its
+/// own, labelled with the name of the column.
+///
+/// The record view shows the same details as the
+/// [detailed view](crate::output::Table), but it does not need a wide
+/// terminal, and it reads better with screen readers, which announce the
+/// label of every value instead of a row of unlabelled cells.
+pub struct Record {
+	pub entries: Vec<HashMap<DetailField, String>>,
+}
+
+impl Record {
+	/// Create a new instance of `Record`, taking ownership of the given
+	/// entries.
+	pub fn new(entries: Vec<HashMap<DetailField, String>>) -> Self {
+		Self { entries }
+	}
+
+	/// Render the records to the output.
+	///
+	/// If the reader closes the pipe early, such as `head`, rendering stops
+	/// silently.
+	pub fn render(&self, app_const: &AppConst) -> Result<(), Exc> {
+		match self.write(app_const) {
+			Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+			res => res.map_err(Exc::Io),
+		}
+	}
+
+	/// Write the records to the output, separated by blank lines.
+	///
+	/// Details without a value for a node, like the size of a directory, are
+	/// left out of its record.
+	fn write(&self, app_const: &AppConst) -> io::Result<()> {
+		let emoji_width = PLS.quirks.emoji_width;
+		let labelled: Vec<_> = PLS
+			.args
+			.details
+			.iter()
+			.filter(|det| **det != DetailField::Name)
+			.map(|det| (det, det.name(app_const)))
+			.collect();
+		let label_width = labelled
+			.iter()
+			.map(|(_, label)| len(label, emoji_width))
+			.max()
+			.unwrap_or_default();
+		let directives = &app_const.table.header_style;
+
+		let mut out = BufWriter::new(Sink::lock());
+		let mut line = String::new();
+		for (idx, entry) in self.entries.iter().enumerate() {
+			if idx > 0 {
+				out.write_all(b"\n")?;
+			}
+			let name = entry.get(&DetailField::Name).map_or("", String::as_str);
+			let pairs: Vec<_> = labelled
+				.iter()
+				.filter_map(|(det, label)| {
+					let value = entry
+						.get(det)
+						.filter(|value| !plain(value).trim().is_empty())?;
+					let pad = label_width - len(label, emoji_width);
+					Some((label.as_str(), pad, value.as_str()))
+				})
+				.collect();
+			for markup in block(name, &pairs, directives) {
+				line.clear();
+				render_into(markup, &mut line);
+				line.push('\n');
+				out.write_all(line.as_bytes())?;
+			}
+		}
+
+		out.flush()
+	}
+}
+
+/// Get the marked-up lines of the record of one node.
+///
+/// # Arguments
+///
+/// * `name` - the name of the node, shown on the first line
+/// * `pairs` - the label of each detail, the padding that aligns its value
+///   with the others, and the value
+/// * `directives` - the styles to apply to the labels
+fn block(name: &str, pairs: &[(&str, usize, &str)], directives: &str) -> Vec<String> {
+	once(name.to_string())
+		.chain(pairs.iter().map(|(label, pad, value)| {
+			format!(
+				"{INDENT}<{directives}>{label}:</>{} {value}",
+				" ".repeat(*pad)
+			)
+		}))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::block;
+
+	macro_rules! make_block_test {
+		( $($name:ident: $pairs:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(block("a.txt", &$pairs, "bold"), $expected as Vec<&str>);
+				}
+			)*
+		};
+	}
+
+	make_block_test!(
+		test_block_name_only: [] => vec!["a.txt"],
+		test_block_one_detail: [("Size", 0, "1 KiB")] => vec!["a.txt", "  <bold>Size:</> 1 KiB"],
+		test_block_aligns_values: [("Size", 4, "1 KiB"), ("Modified", 0, "today")]
+			=> vec!["a.txt", "  <bold>Size:</>     1 KiB", "  <bold>Modified:</> today"],
+	);
+}
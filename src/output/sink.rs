@@ -0,0 +1,153 @@
+use crate::fmt::clip;
+use std::fs::File;
+use std::io::{self, StdoutLock, Write};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// the file that output is rendered to, if `--render-to` is used
+static FILE: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// the bounds that output is clipped to, if `--popup` is used
+static BOUNDS: OnceLock<Mutex<Bounds>> = OnceLock::new();
+
+/// Represents the space left in a fixed-size overlay, along with the start of
+/// the line that is being written to it.
+struct Bounds {
+	/// the number of columns that each line is clipped to
+	width: usize,
+	/// the number of rows that can still be written
+	rows_left: usize,
+	/// the number of cells the terminal uses to draw an emoji
+	emoji_width: usize,
+	/// the bytes written since the last line break
+	pending: Vec<u8>,
+}
+
+/// Represents the destination of all rendered output.
+///
+/// Output goes to STDOUT, unless [`Sink::redirect`] has been called, in which
+/// case it goes to the given file instead. Every view writes through a sink,
+/// so the file contains exactly what would have been shown in the terminal,
+/// escape sequences included.
+pub enum Sink {
+	Stdout(StdoutLock<'static>),
+	File(MutexGuard<'static, File>),
+}
+
+impl Sink {
+	/// Get a lock on the destination of the output.
+	pub fn lock() -> Self {
+		match FILE.get() {
+			Some(file) => Sink::File(file.lock().unwrap_or_else(|err| err.into_inner())),
+			None => Sink::Stdout(io::stdout().lock()),
+		}
+	}
+
+	/// Send all subsequent output to the file at the given path, truncating it
+	/// if it exists.
+	///
+	/// Since the file is meant to be compared with the terminal output, it is
+	/// styled like a terminal by [`configure_color`](crate::fmt::configure_color).
+	pub fn redirect(path: &Path) -> io::Result<()> {
+		let file = File::create(path)?;
+		// The sink is only redirected once, at startup.
+		let _ = FILE.set(Mutex::new(file));
+		Ok(())
+	}
+
+	/// Clip all subsequent output to the given number of columns and rows.
+	///
+	/// Output is clipped as each line is completed. Lines that are too wide
+	/// are cut, escape sequences included, and rows that do not fit are
+	/// dropped. The last row is written without a line break, so that the
+	/// overlay does not scroll its first row out of view.
+	///
+	/// # Arguments
+	///
+	/// * `width` - the number of columns in the overlay
+	/// * `height` - the number of rows in the overlay
+	/// * `emoji_width` - the number of cells the terminal uses to draw an emoji
+	pub fn clip(width: usize, height: usize, emoji_width: usize) {
+		// The sink is only clipped once, at startup.
+		let _ = BOUNDS.set(Mutex::new(Bounds {
+			width,
+			rows_left: height,
+			emoji_width,
+			pending: vec![],
+		}));
+	}
+
+	/// Write the line that is still pending because the output did not end
+	/// with a line break.
+	///
+	/// This only has an effect if the output is clipped, and is called once
+	/// all output has been written.
+	pub fn finish() {
+		let Some(bounds) = BOUNDS.get() else {
+			return;
+		};
+		let mut sink = Self::lock();
+		let mut bounds = bounds.lock().unwrap_or_else(|err| err.into_inner());
+		let line = std::mem::take(&mut bounds.pending);
+		if line.is_empty() || bounds.rows_left == 0 {
+			return;
+		}
+		bounds.rows_left -= 1;
+		let text = String::from_utf8_lossy(&line);
+		let clipped = clip(&text, bounds.width, bounds.emoji_width);
+		// Like `println`, errors from a reader that went away are ignored.
+		let _ = sink.inner().write_all(clipped.as_bytes());
+		let _ = sink.inner().flush();
+	}
+
+	/// Get the destination of the output, to write to without clipping.
+	fn inner(&mut self) -> &mut dyn Write {
+		match self {
+			Sink::Stdout(out) => out,
+			Sink::File(file) => &mut **file,
+		}
+	}
+
+	/// Write the given text, followed by a line break, like `println!`.
+	///
+	/// Errors are ignored if the reader closes the pipe early, such as `head`.
+	pub fn println<S>(text: S)
+	where
+		S: AsRef<str>,
+	{
+		let mut sink = Self::lock();
+		if let Err(err) = writeln!(sink, "{}", text.as_ref()) {
+			if err.kind() != io::ErrorKind::BrokenPipe {
+				panic!("failed printing to output: {err}");
+			}
+		}
+	}
+}
+
+impl Write for Sink {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let Some(bounds) = BOUNDS.get() else {
+			return self.inner().write(buf);
+		};
+		let mut bounds = bounds.lock().unwrap_or_else(|err| err.into_inner());
+		bounds.pending.extend_from_slice(buf);
+		while let Some(idx) = bounds.pending.iter().position(|byte| *byte == b'\n') {
+			let line: Vec<u8> = bounds.pending.drain(..=idx).collect();
+			if bounds.rows_left == 0 {
+				continue;
+			}
+			bounds.rows_left -= 1;
+			let text = String::from_utf8_lossy(&line[..idx]);
+			let mut clipped = clip(&text, bounds.width, bounds.emoji_width);
+			if bounds.rows_left > 0 {
+				clipped.push('\n');
+			}
+			self.inner().write_all(clipped.as_bytes())?;
+		}
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner().flush()
+	}
+}
@@ -1,9 +1,19 @@
 use crate::config::AppConst;
-use crate::enums::DetailField;
-use crate::fmt::len;
+use crate::enums::{DetailField, Direction};
+use crate::exc::Exc;
+use crate::fmt::{len, render_into, wrap};
+use crate::gfx::strip_image;
+use crate::output::{Cell, Sink};
 use crate::PLS;
 use std::collections::HashMap;
-use std::iter::once;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::iter::{once, repeat};
+
+/// the narrowest that the name column can be squeezed to by wrapping
+const MIN_NAME_WIDTH: usize = 16;
+
+/// the number of spaces between two tiles of a tiled table
+const TILE_GAP: usize = 2;
 
 /// The detailed renders node names, and optionally, chosen node metadata in
 /// a tabular layout with one row per node.
@@ -22,50 +32,239 @@ impl Table {
 		Self { entries, is_solo }
 	}
 
-	/// Render the table to STDOUT.
-	pub fn render(&self, app_const: &AppConst) {
-		let max_widths = self.max_widths(app_const);
+	/// Render the table to the output.
+	///
+	/// Rows are rendered into one reusable buffer and written through a single
+	/// buffered lock on the [sink](Sink), which keeps large listings fast. If
+	/// the reader closes the pipe early, such as `head`, rendering stops
+	/// silently.
+	pub fn render(&self, app_const: &AppConst) -> Result<(), Exc> {
+		match self.write(app_const) {
+			Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+			res => res.map_err(Exc::Io),
+		}
+	}
 
-		let iter_basis: Vec<_> = PLS
+	/// Write the rendered table to the output.
+	///
+	/// With `--elide-uniform`, columns in which every row has the same value
+	/// are left out, and their values are noted once above the table. With
+	/// `--tile`, short tables are split into tiles placed side by side.
+	fn write(&self, app_const: &AppConst) -> io::Result<()> {
+		let elided = if PLS.args.elide_uniform {
+			elided(&self.entries, &PLS.args.details)
+		} else {
+			vec![]
+		};
+		let fields: Vec<_> = PLS
 			.args
 			.details
+			.iter()
+			.filter(|det| !elided.iter().any(|(field, _)| field == *det))
+			.collect();
+		let max_widths = self.max_widths(&fields, app_const);
+
+		let iter_basis: Vec<_> = fields
 			.iter()
 			.enumerate()
 			.map(|(idx, det)| {
 				let mut cell = det.cell();
-				if idx == PLS.args.details.len() - 1 {
+				if idx == fields.len() - 1 {
 					cell.padding = (0, 0); // Remove right padding from the last column.
 				}
 				(max_widths[idx], det, cell)
 			})
 			.collect();
 
+		let mut out = BufWriter::new(Sink::lock());
+		let mut line = String::new();
+
+		let notes: Vec<_> = elided
+			.iter()
+			.filter(|(_, text)| !text.is_empty())
+			.map(|(det, text)| {
+				let directives = &app_const.table.elided_style;
+				format!("<{directives}>{}:</> {text}", det.name(app_const))
+			})
+			.collect();
+		if !notes.is_empty() {
+			render_into(notes.join("  "), &mut line);
+			line.push('\n');
+			out.write_all(line.as_bytes())?;
+		}
+
+		// The name column is always present, and always last.
+		let (name_basis, rest_basis) = iter_basis.split_last().unwrap();
+		let prefix_width: usize = rest_basis
+			.iter()
+			.map(|(width, _, cell)| width.unwrap_or_default() + cell.padding.0 + cell.padding.1)
+			.sum();
+
+		if PLS.args.tile {
+			let tile_width = prefix_width + self.max_name_width(name_basis.1, app_const);
+			let tile_count = PLS.term_width().map_or(1, |term_width| {
+				tile_count(self.entries.len(), tile_width, term_width)
+			});
+			if tile_count > 1 {
+				return self.write_tiles(&mut out, &iter_basis, tile_width, tile_count, app_const);
+			}
+		}
+
 		if PLS.args.header {
+			line.clear();
+			let directives = &app_const.table.header_style;
 			for (width, det, cell) in &iter_basis {
-				let name = det.name(app_const);
-				let directives = app_const.table.header_style.clone();
-				print!("{}", &cell.print(name, width, Some(directives)));
+				cell.print_into(det.name(app_const), width, Some(directives), &mut line);
 			}
-			println!();
+			line.push('\n');
+			out.write_all(line.as_bytes())?;
 		}
 
+		let name_width = self.name_width(prefix_width, app_const);
+
 		for entry in &self.entries {
-			for (width, det, cell) in &iter_basis {
-				print!("{}", &cell.print(entry.get(det).unwrap(), width, None));
+			line.clear();
+			for (width, det, cell) in rest_basis {
+				cell.print_into(entry.get(det).unwrap(), width, None, &mut line);
 			}
-			println!();
+			let (width, det, cell) = name_basis;
+			let name = entry.get(det).unwrap();
+			match name_width {
+				Some(name_width) => {
+					let emoji_width = PLS.quirks.emoji_width;
+					for (idx, part) in wrap(name, name_width, emoji_width).iter().enumerate() {
+						if idx > 0 {
+							// Continuation lines leave the other cells blank.
+							line.push('\n');
+							line.extend(repeat(' ').take(prefix_width));
+						}
+						render_into(part, &mut line);
+					}
+				}
+				None => cell.print_into(name, width, None, &mut line),
+			}
+			line.push('\n');
+			out.write_all(line.as_bytes())?;
 		}
+
+		out.flush()
 	}
 
-	/// Get mapping of detail field to the maximum width of the cells in that
-	/// column.
-	fn max_widths(&self, app_const: &AppConst) -> Vec<Option<usize>> {
-		PLS.args
-			.details
+	/// Write the rows of the table in the given number of tiles, placed side
+	/// by side, each with its own header.
+	///
+	/// Like the grid view, rows fill the tiles across or down, as per
+	/// `--direction`. Names are padded to the same width, so that the tiles
+	/// line up, and are never wrapped.
+	fn write_tiles<W: Write>(
+		&self,
+		out: &mut W,
+		iter_basis: &[(Option<usize>, &&DetailField, Cell)],
+		tile_width: usize,
+		tile_count: usize,
+		app_const: &AppConst,
+	) -> io::Result<()> {
+		let emoji_width = PLS.quirks.emoji_width;
+		let (name_basis, rest_basis) = iter_basis.split_last().unwrap();
+		let prefix_width: usize = rest_basis
+			.iter()
+			.map(|(width, _, cell)| width.unwrap_or_default() + cell.padding.0 + cell.padding.1)
+			.sum();
+		// Each row is paired with the padding that lines up the next tile.
+		let print_row = |texts: &dyn Fn(&DetailField) -> String, directives: Option<&str>| {
+			let mut row = String::new();
+			for (width, det, cell) in rest_basis {
+				cell.print_into(texts(det), width, directives, &mut row);
+			}
+			let (width, det, cell) = name_basis;
+			let name = texts(det);
+			cell.print_into(&name, width, directives, &mut row);
+			let name_len = len(strip_image(&name), emoji_width);
+			let pad = tile_width.saturating_sub(prefix_width + name_len);
+			(row, pad)
+		};
+
+		let rows: Vec<_> = self
+			.entries
+			.iter()
+			.map(|entry| print_row(&|det| entry.get(det).cloned().unwrap_or_default(), None))
+			.collect();
+		let row_count = rows.len().div_ceil(tile_count);
+		let tile_count = rows.len().div_ceil(row_count);
+		let join = |tiles: Vec<&(String, usize)>| {
+			let mut line = String::new();
+			for (idx, (row, pad)) in tiles.iter().enumerate() {
+				if idx > 0 {
+					line.extend(repeat(' ').take(TILE_GAP));
+				}
+				line.push_str(row);
+				if idx < tiles.len() - 1 {
+					line.extend(repeat(' ').take(*pad));
+				}
+			}
+			line
+		};
+
+		if PLS.args.header {
+			let directives = &app_const.table.header_style;
+			let header = print_row(&|det| det.name(app_const).to_string(), Some(directives));
+			writeln!(out, "{}", join(vec![&header; tile_count]))?;
+		}
+
+		for row in 0..row_count {
+			let tiles: Vec<_> = (0..tile_count)
+				.map(|tile| match PLS.args.direction {
+					Direction::Row => row * tile_count + tile,
+					Direction::Column => tile * row_count + row,
+				})
+				.filter_map(|idx| rows.get(idx))
+				.collect();
+			writeln!(out, "{}", join(tiles))?;
+		}
+		out.flush()
+	}
+
+	/// Get the width of the widest name, or of the name column header.
+	fn max_name_width(&self, det: &DetailField, app_const: &AppConst) -> usize {
+		let emoji_width = PLS.quirks.emoji_width;
+		self.entries
+			.iter()
+			.filter_map(|entry| {
+				entry
+					.get(det)
+					.map(|text| len(strip_image(text), emoji_width))
+			})
+			.chain(once(if PLS.args.header {
+				len(det.name(app_const), emoji_width)
+			} else {
+				0
+			}))
+			.max()
+			.unwrap_or_default()
+	}
+
+	/// Get the width available to the name column, if long names are to be
+	/// wrapped onto continuation lines.
+	///
+	/// Names are only wrapped if enabled in the config, if the terminal width
+	/// is known and if the other columns leave enough room for the names.
+	fn name_width(&self, prefix_width: usize, app_const: &AppConst) -> Option<usize> {
+		if !app_const.table.wrap_names {
+			return None;
+		}
+		PLS.term_width()
+			.map(|term_width| term_width.saturating_sub(prefix_width))
+			.filter(|name_width| *name_width >= MIN_NAME_WIDTH)
+	}
+
+	/// Get the maximum width of the cells in each of the given columns.
+	fn max_widths(&self, fields: &[&DetailField], app_const: &AppConst) -> Vec<Option<usize>> {
+		let emoji_width = PLS.quirks.emoji_width;
+		fields
 			.iter()
 			.enumerate()
 			.map(|(det_idx, det)| {
-				if det_idx == PLS.args.details.len() - 1 {
+				if det_idx == fields.len() - 1 {
 					return None;
 				}
 				let end_lim = if self.entries.is_empty() {
@@ -81,9 +280,9 @@ impl Table {
 				};
 				self.entries[0..end_lim]
 					.iter()
-					.filter_map(|entry| entry.get(det).map(len))
+					.filter_map(|entry| entry.get(det).map(|text| len(text, emoji_width)))
 					.chain(once(if PLS.args.header {
-						len(det.name(app_const))
+						len(det.name(app_const), emoji_width)
 					} else {
 						0
 					}))
@@ -92,3 +291,107 @@ impl Table {
 			.collect()
 	}
 }
+
+/// Get the number of tiles, each of the given width, into which a table with
+/// the given number of rows can be split to fit within the terminal width.
+///
+/// The rows are spread evenly, so that no more tiles are used than needed
+/// for the fewest rows per tile.
+fn tile_count(row_count: usize, tile_width: usize, term_width: usize) -> usize {
+	if row_count == 0 {
+		return 1;
+	}
+	let max_tiles = ((term_width + TILE_GAP) / (tile_width + TILE_GAP)).clamp(1, row_count);
+	row_count.div_ceil(row_count.div_ceil(max_tiles))
+}
+
+/// Get the columns in which every entry has the same value, paired with that
+/// value.
+///
+/// The name column is never elided, and neither is any column of a table
+/// with fewer than two entries, where every column is trivially uniform.
+fn elided<'a>(
+	entries: &'a [HashMap<DetailField, String>],
+	details: &[DetailField],
+) -> Vec<(DetailField, &'a str)> {
+	let Some((first, rest)) = entries.split_first() else {
+		return vec![];
+	};
+	if rest.is_empty() {
+		return vec![];
+	}
+	details
+		.iter()
+		.filter(|&&det| det != DetailField::Name)
+		.filter_map(|det| {
+			let text = first.get(det)?;
+			rest.iter()
+				.all(|entry| entry.get(det) == Some(text))
+				.then_some((*det, text.as_str()))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{elided, tile_count};
+	use crate::enums::DetailField;
+	use std::collections::HashMap;
+
+	/// Create entries with the given users and groups.
+	fn entries(rows: &[(&str, &str)]) -> Vec<HashMap<DetailField, String>> {
+		rows.iter()
+			.enumerate()
+			.map(|(idx, (user, group))| {
+				HashMap::from([
+					(DetailField::User, user.to_string()),
+					(DetailField::Group, group.to_string()),
+					(DetailField::Name, format!("file{idx}")),
+				])
+			})
+			.collect()
+	}
+
+	macro_rules! make_elided_test {
+		( $($name:ident: $rows:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let entries = entries(&$rows);
+					let details = [DetailField::User, DetailField::Group, DetailField::Name];
+					assert_eq!(elided(&entries, &details), $expected);
+				}
+			)*
+		};
+	}
+
+	make_elided_test!(
+		test_no_entries: [] => vec![],
+		test_single_entry: [("root", "wheel")] => vec![],
+		test_all_uniform: [("root", "wheel"), ("root", "wheel")] => vec![
+			(DetailField::User, "root"),
+			(DetailField::Group, "wheel"),
+		],
+		test_some_uniform: [("root", "wheel"), ("dhruv", "wheel")] => vec![(DetailField::Group, "wheel")],
+		test_none_uniform: [("root", "wheel"), ("dhruv", "staff")] => vec![],
+	);
+
+	macro_rules! make_tile_count_test {
+		( $($name:ident: $row_count:expr, $tile_width:expr, $term_width:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(tile_count($row_count, $tile_width, $term_width), $expected);
+				}
+			)*
+		};
+	}
+
+	make_tile_count_test!(
+		test_tiles_fit: 6, 20, 80 => 3,
+		test_tiles_too_wide: 6, 50, 80 => 1,
+		test_tiles_fewer_rows: 2, 10, 80 => 2,
+		test_tiles_spread_evenly: 5, 10, 50 => 3, // 4 tiles would also need 2 rows
+		test_tiles_no_rows: 0, 10, 80 => 1,
+	);
+}
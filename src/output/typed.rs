@@ -0,0 +1,68 @@
+use crate::enums::DetailField;
+use crate::fmt::plain;
+use time::OffsetDateTime;
+
+/// Represents the value of a cell, typed for structured exports.
+///
+/// Raw values are typed according to their [unit](DetailField::raw_unit), and
+/// values that cannot be read as their type fall back to text.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Typed {
+	Null,                 // a value that the node does not have
+	Bytes(u64),           // a size, in bytes
+	Date(OffsetDateTime), // a timestamp, in UTC
+	Int(i64),             // a plain number, like an ID
+	Text(String),         // any other value, stripped of formatting
+}
+
+impl Typed {
+	/// Get the typed value of the given cell of the given detail field.
+	pub fn new(det: DetailField, cell: &str) -> Self {
+		let text = plain(cell);
+		let text = text.trim();
+		if text.is_empty() {
+			return Typed::Null;
+		}
+		let typed = match det.raw_unit() {
+			Some("bytes") => text.parse().ok().map(Typed::Bytes),
+			Some("epoch seconds") => text
+				.parse()
+				.ok()
+				.and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+				.map(Typed::Date),
+			Some(_) => text.parse().ok().map(Typed::Int),
+			None => None,
+		};
+		typed.unwrap_or_else(|| Typed::Text(text.to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Typed;
+	use crate::enums::DetailField;
+	use time::OffsetDateTime;
+
+	macro_rules! make_new_test {
+		( $($name:ident: $det:expr, $cell:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Typed::new($det, $cell), $expected);
+				}
+			)*
+		};
+	}
+
+	make_new_test!(
+		test_text: DetailField::User, "root" => Typed::Text(String::from("root")),
+		test_markup: DetailField::Perm, "<blue>rw-</>" => Typed::Text(String::from("rw-")),
+		test_empty: DetailField::Size, "" => Typed::Null,
+		test_padded_empty: DetailField::Git, "  " => Typed::Null,
+		test_size: DetailField::Size, "2048" => Typed::Bytes(2048),
+		test_blocks: DetailField::Blocks, "8" => Typed::Int(8),
+		test_uid: DetailField::Uid, "1000" => Typed::Int(1000),
+		test_mtime: DetailField::Mtime, "1706725800" => Typed::Date(OffsetDateTime::from_unix_timestamp(1_706_725_800).unwrap()),
+		test_formatted_size: DetailField::Size, "2 KiB" => Typed::Text(String::from("2 KiB")),
+	);
+}
@@ -1,33 +1,53 @@
 use crate::config::EntryConst;
-use crate::enums::{DetailField, Typ};
-use crate::ext::Ctime;
-use crate::models::{Node, OwnerMan, Perm};
+use crate::enums::{DetailField, Role, TimeStyle, Typ};
+use crate::ext::{Ctime, PlatformMeta};
+use crate::models::{Attrs, Node, OwnerMan, Perm};
+use crate::traits::Imp;
+use crate::utils::du::du;
+use crate::utils::numbers::group_digits;
+use crate::utils::procfs;
+use crate::utils::times::{epoch, relative};
 use crate::PLS;
-use log::warn;
-#[cfg(unix)]
-use std::os::unix::fs::MetadataExt;
-use std::time::SystemTime;
-use time::{format_description, OffsetDateTime, UtcOffset};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use time::{format_description, OffsetDateTime};
+
+/// the placeholder in timestamp formats that is replaced by the time elapsed
+const RELATIVE: &str = "[relative]";
 
 pub trait Detail {
 	fn size_val(&self) -> Option<u64>;
+	fn mode_val(&self) -> Option<u32>;
 	fn blocks_val(&self) -> Option<u64>;
+	fn rdev_val(&self) -> Option<(u32, u32)>;
 	fn time_val(&self, field: DetailField) -> Option<SystemTime>;
 	fn user_val(&self, owner_man: &mut OwnerMan) -> Option<String>;
 	fn group_val(&self, owner_man: &mut OwnerMan) -> Option<String>;
 
 	fn dev(&self, entry_const: &EntryConst) -> Option<String>;
+	fn rdev(&self, entry_const: &EntryConst) -> Option<String>;
 	fn ino(&self, entry_const: &EntryConst) -> Option<String>;
 	fn nlink(&self, entry_const: &EntryConst) -> Option<String>;
 	fn perm(&self, entry_const: &EntryConst) -> Option<String>;
 	fn oct(&self, entry_const: &EntryConst) -> Option<String>;
+	fn attrs(&self, entry_const: &EntryConst) -> Option<String>;
+	fn xattr(&self, entry_const: &EntryConst) -> Option<String>;
+	fn acl_entries(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String>;
 	fn user(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String>;
 	fn uid(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String>;
 	fn group(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String>;
 	fn gid(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String>;
 	fn size(&self, entry_const: &EntryConst) -> Option<String>;
+	fn size_raw(&self, entry_const: &EntryConst) -> Option<String>;
 	fn blocks(&self, entry_const: &EntryConst) -> Option<String>;
 	fn time(&self, field: DetailField, entry_const: &EntryConst) -> Option<String>;
+	fn commits(&self, entry_const: &EntryConst) -> Option<String>;
+	fn git_author(&self, entry_const: &EntryConst) -> Option<String>;
+	fn code_owners(&self, entry_const: &EntryConst) -> Option<String>;
+	fn ignored_by(&self, entry_const: &EntryConst) -> Option<String>;
+	fn marker(&self, entry_const: &EntryConst) -> Option<String>;
+	fn comm(&self, entry_const: &EntryConst) -> Option<String>;
+	fn role(&self, entry_const: &EntryConst) -> Option<String>;
+	fn fix(&self, entry_const: &EntryConst) -> Option<String>;
 }
 
 impl Detail for Node<'_> {
@@ -35,22 +55,71 @@ impl Detail for Node<'_> {
 	// Sort fields
 	// ===========
 
-	/// Compute the size of the node, returning `None` for directories.
+	/// Compute the size of the node, returning `None` for directories and
+	/// redacted nodes.
+	///
+	/// With the `--du` CLI argument, the size of a directory is the total size
+	/// of the files inside it, recursively. For nodes inside archives, this is
+	/// the uncompressed size recorded in the archive headers. The size is
+	/// computed once per node, since both the size columns and sorting use it.
 	fn size_val(&self) -> Option<u64> {
+		if self.is_redacted {
+			return None;
+		}
+		*self.memo.size.get_or_init(|| {
+			if self.typ == Typ::Dir {
+				// Directories inside archives cannot be read from the file system.
+				return (PLS.args.du && self.archived.is_none()).then(|| du(&self.path));
+			}
+			self.meta_ok()
+				.map(|meta| meta.len())
+				.or_else(|| self.archived.as_ref().map(|archived| archived.size))
+		})
+	}
+
+	/// Get the permission bits of the node, from the metadata or, for nodes
+	/// inside archives, from the archive headers.
+	fn mode_val(&self) -> Option<u32> {
 		self.meta_ok()
-			.filter(|_| self.typ != Typ::Dir)
-			.map(|meta| meta.len())
+			.and_then(|meta| meta.mode_bits())
+			.or_else(|| self.archived.as_ref().and_then(|archived| archived.mode))
 	}
 
-	/// Compute the block count for the node, returning `None` for directories.
+	/// Compute the block count for the node, returning `None` for directories
+	/// and redacted nodes.
 	fn blocks_val(&self) -> Option<u64> {
 		self.meta_ok()
-			.filter(|_| self.typ != Typ::Dir)
-			.map(|meta| meta.blocks())
+			.filter(|_| self.typ != Typ::Dir && !self.is_redacted && !self.is_on_slow_fs())
+			.and_then(|meta| meta.block_count())
+	}
+
+	/// Get the major and minor numbers of the device, returning `None` for
+	/// nodes that are not device nodes.
+	fn rdev_val(&self) -> Option<(u32, u32)> {
+		if !matches!(self.typ, Typ::BlockDevice | Typ::CharDevice) {
+			return None;
+		}
+		self.meta_ok().and_then(|meta| meta.rdev_num())
 	}
 
 	/// Get the value of the system time field specified by `field`.
+	///
+	/// For [`DetailField::GitDate`], the time is read from the Git history of
+	/// the node instead of the metadata. Redacted nodes have no times.
 	fn time_val(&self, field: DetailField) -> Option<SystemTime> {
+		if self.is_redacted {
+			return None;
+		}
+		if field == DetailField::GitDate {
+			return self.history.as_ref().map(|history| {
+				let secs = history.time.max(0) as u64;
+				UNIX_EPOCH + Duration::from_secs(secs)
+			});
+		}
+		if let Some(archived) = &self.archived {
+			// Archives only record the modification time.
+			return archived.mtime.filter(|_| field == DetailField::Mtime);
+		}
 		self.meta_ok().and_then(|meta| {
 			match field {
 				DetailField::Atime => meta.accessed(),
@@ -65,14 +134,14 @@ impl Detail for Node<'_> {
 
 	/// Get the name of the user that owns this node, if known.
 	fn user_val(&self, owner_man: &mut OwnerMan) -> Option<String> {
-		self.meta_ok()
-			.and_then(|meta| owner_man.user(meta.uid()).name)
+		self.owner_user(owner_man)
+			.and_then(|owner| owner.name.clone())
 	}
 
 	/// Get the name of the group that owns this node, if known.
 	fn group_val(&self, owner_man: &mut OwnerMan) -> Option<String> {
-		self.meta_ok()
-			.and_then(|meta| owner_man.group(meta.gid()).name)
+		self.owner_group(owner_man)
+			.and_then(|owner| owner.name.clone())
 	}
 
 	// ===========
@@ -83,19 +152,36 @@ impl Detail for Node<'_> {
 	///
 	/// This function returns a marked-up string.
 	fn dev(&self, entry_const: &EntryConst) -> Option<String> {
-		self.meta_ok().map(|meta| {
-			let dev = meta.dev().to_string();
+		self.meta_ok().and_then(|meta| meta.dev_id()).map(|dev| {
+			let dev = dev.to_string();
 			let directives = &entry_const.dev_style;
 			format!("<{directives}>{dev}</>")
 		})
 	}
 
+	/// Get the major and minor numbers of the device, followed by the name of
+	/// its driver, which identifies the subsystem.
+	///
+	/// This is blank for nodes that are not device nodes.
+	///
+	/// This function returns a marked-up string.
+	fn rdev(&self, entry_const: &EntryConst) -> Option<String> {
+		let (major, minor) = self.rdev_val()?;
+		let directives = &entry_const.rdev_style;
+		let mut rdev = format!("<{directives}>{major}:{minor}</>");
+		if let Some(driver) = procfs::driver(self.typ == Typ::BlockDevice, major) {
+			let directives = &entry_const.driver_style;
+			rdev.push_str(&format!(" <{directives}>{driver}</>"));
+		}
+		Some(rdev)
+	}
+
 	/// Get the inode number of the node.
 	///
 	/// This function returns a marked-up string.
 	fn ino(&self, entry_const: &EntryConst) -> Option<String> {
-		self.meta_ok().map(|meta| {
-			let ino = meta.ino().to_string();
+		self.meta_ok().and_then(|meta| meta.ino_num()).map(|ino| {
+			let ino = ino.to_string();
 			let directives = &entry_const.ino_style;
 			format!("<{directives}>{ino}</>")
 		})
@@ -110,23 +196,62 @@ impl Detail for Node<'_> {
 	/// This function returns a marked-up string.
 	fn nlink(&self, entry_const: &EntryConst) -> Option<String> {
 		self.meta_ok()
-			.map(|meta| entry_const.nlink_styles.format(meta.nlink(), &self.typ))
+			.and_then(|meta| meta.nlink_count())
+			.map(|nlink| entry_const.nlink_styles.format(nlink, &self.typ))
 	}
 
 	/// Get the symbolic representation of the permissions of the node.
 	///
+	/// Nodes with an extended ACL have a marker appended, like the `+` shown
+	/// by `ls`, since the permission bits do not tell the whole story.
+	///
 	/// This function returns a marked-up string.
 	fn perm(&self, entry_const: &EntryConst) -> Option<String> {
-		self.meta_ok()
-			.map(|meta| Perm::from(meta.mode()).sym(entry_const))
+		self.mode_val().map(|mode| {
+			let sym = Perm::from(mode).sym(entry_const);
+			match self.acl() {
+				Some(_) => format!("{sym}{}", entry_const.acl.marker),
+				None => sym,
+			}
+		})
 	}
 
 	/// Get the octal representation of the permissions of a node.
 	///
 	/// This function returns a marked-up string.
 	fn oct(&self, entry_const: &EntryConst) -> Option<String> {
+		self.mode_val()
+			.map(|mode| Perm::from(mode).oct(entry_const))
+	}
+
+	/// Get the letters of the Windows file attributes of the node.
+	///
+	/// This is blank on other platforms.
+	///
+	/// This function returns a marked-up string.
+	fn attrs(&self, entry_const: &EntryConst) -> Option<String> {
 		self.meta_ok()
-			.map(|meta| Perm::from(meta.mode()).oct(entry_const))
+			.and_then(|meta| meta.attributes())
+			.map(|attrs| Attrs::from(attrs).flags(entry_const))
+	}
+
+	/// Get the marker for nodes that have extended attributes, like the `@`
+	/// shown by BSD `ls`.
+	///
+	/// This is blank for nodes without extended attributes.
+	///
+	/// This function returns a marked-up string.
+	fn xattr(&self, entry_const: &EntryConst) -> Option<String> {
+		(!self.xattr_names().is_empty()).then(|| entry_const.xattr.marker.clone())
+	}
+
+	/// Get the entries of the extended ACL of the node.
+	///
+	/// This is blank for nodes without an extended ACL.
+	///
+	/// This function returns a marked-up string.
+	fn acl_entries(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String> {
+		self.acl().map(|acl| acl.list(owner_man, entry_const))
 	}
 
 	/// Get the name of the user that owns this node. The name is highlighted if
@@ -134,8 +259,8 @@ impl Detail for Node<'_> {
 	///
 	/// This function returns a marked-up string.
 	fn user(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String> {
-		self.meta_ok()
-			.map(|meta| owner_man.user(meta.uid()).name(entry_const))
+		self.owner_user(owner_man)
+			.map(|owner| owner.name(entry_const))
 	}
 
 	/// Get the UID of the user that owns this node. The UID is highlighted if
@@ -143,8 +268,8 @@ impl Detail for Node<'_> {
 	///
 	/// This function returns a marked-up string.
 	fn uid(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String> {
-		self.meta_ok()
-			.map(|meta| owner_man.user(meta.uid()).id(entry_const))
+		self.owner_user(owner_man)
+			.map(|owner| owner.id(entry_const))
 	}
 
 	/// Get the name of the group that owns this node. The name is highlighted
@@ -152,8 +277,8 @@ impl Detail for Node<'_> {
 	///
 	/// This function returns a marked-up string.
 	fn group(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String> {
-		self.meta_ok()
-			.map(|meta| owner_man.group(meta.gid()).name(entry_const))
+		self.owner_group(owner_man)
+			.map(|owner| owner.name(entry_const))
 	}
 
 	/// Get the GID of the group that owns this node. The GID is highlighted
@@ -161,17 +286,37 @@ impl Detail for Node<'_> {
 	///
 	/// This function returns a marked-up string.
 	fn gid(&self, owner_man: &mut OwnerMan, entry_const: &EntryConst) -> Option<String> {
-		self.meta_ok()
-			.map(|meta| owner_man.group(meta.gid()).id(entry_const))
+		self.owner_group(owner_man)
+			.map(|owner| owner.id(entry_const))
 	}
 
 	/// Get the size of the file in bytes, optionally with higher units in
 	/// powers of 2^10 or 10^3.
 	///
+	/// For cloud placeholders, the size is followed by a note because it is the
+	/// logical size of the content and not the space used locally.
+	///
 	/// This function returns a marked-up string.
 	fn size(&self, entry_const: &EntryConst) -> Option<String> {
-		self.size_val()
-			.map(|size| PLS.args.unit.size(size, entry_const))
+		self.size_val().map(|size| {
+			let size = PLS.args.unit.size(size, entry_const);
+			if self.is_cloud_placeholder() {
+				format!("{size} {}", entry_const.cloud.size_note)
+			} else {
+				size
+			}
+		})
+	}
+
+	/// Get the exact size of the file in bytes, with commas separating groups
+	/// of thousands.
+	///
+	/// This function returns a marked-up string.
+	fn size_raw(&self, entry_const: &EntryConst) -> Option<String> {
+		self.size_val().map(|size| {
+			let directives = &entry_const.size_raw_style;
+			format!("<{directives}>{}</>", group_digits(size))
+		})
 	}
 
 	/// Get the number of blocks occupied by the file.
@@ -186,21 +331,145 @@ impl Detail for Node<'_> {
 
 	/// Get the chosen timestamp field.
 	///
+	/// The format is picked based on the `--time-style` CLI argument, and any
+	/// `[relative]` in it is replaced by the time elapsed, so relative times
+	/// can also be chosen per field through the `timestamp_formats`.
+	///
 	/// This function returns a marked-up string.
 	fn time(&self, field: DetailField, entry_const: &EntryConst) -> Option<String> {
 		self.time_val(field).map(|time| {
-			let mut dt: OffsetDateTime = time.into();
-			match UtcOffset::current_local_offset() {
-				Ok(offset) => dt = dt.to_offset(offset),
-				Err(_) => {
-					warn!("Could not determine UTC offset")
-				}
+			let formats = match PLS.args.time_style {
+				TimeStyle::Absolute => &entry_const.timestamp_formats,
+				TimeStyle::Relative => &entry_const.relative_timestamp_formats,
+			};
+			let mut format = formats.get(&field).cloned().unwrap_or_default();
+			if format.contains(RELATIVE) {
+				let age = epoch(PLS.now()) - epoch(time);
+				format = format.replace(RELATIVE, &relative(age));
 			}
-			let format = format_description::parse_borrowed::<2>(
-				entry_const.timestamp_formats.get(&field).unwrap(),
-			)
-			.unwrap();
+
+			let dt = OffsetDateTime::from(time).to_offset(PLS.utc_offset());
+			let format = format_description::parse_borrowed::<2>(&format).unwrap();
 			dt.format(&format).unwrap()
 		})
 	}
+
+	/// Get the number of commits that touched the node.
+	///
+	/// This is only available if the Git history of the node was computed, and
+	/// blank for nodes that are not tracked by Git.
+	///
+	/// This function returns a marked-up string.
+	fn commits(&self, entry_const: &EntryConst) -> Option<String> {
+		self.history.as_ref().map(|history| {
+			let directives = &entry_const.commits_style;
+			format!("<{directives}>{}</>", history.commits)
+		})
+	}
+
+	/// Get the name of the author of the latest commit that touched the node.
+	///
+	/// This is only available if the Git history of the node was computed, and
+	/// blank for nodes that are not tracked by Git.
+	///
+	/// This function returns a marked-up string.
+	fn git_author(&self, entry_const: &EntryConst) -> Option<String> {
+		self.history.as_ref().map(|history| {
+			let directives = &entry_const.git_author_style;
+			format!("<{directives}>{}</>", history.author)
+		})
+	}
+
+	/// Get the owners of the node declared in the `CODEOWNERS` file.
+	///
+	/// This is blank for nodes without owners, and for nodes that are not in
+	/// a repository with a `CODEOWNERS` file.
+	///
+	/// This function returns a marked-up string.
+	fn code_owners(&self, entry_const: &EntryConst) -> Option<String> {
+		self.code_owners.as_ref().map(|owners| {
+			let directives = &entry_const.code_owners_style;
+			format!("<{directives}>{}</>", owners.join(" "))
+		})
+	}
+
+	/// Get the ignore file and line of the rule that causes Git to ignore the
+	/// node.
+	///
+	/// This is blank for nodes that are not ignored, and for nodes that are not
+	/// in a Git repository.
+	///
+	/// This function returns a marked-up string.
+	fn ignored_by(&self, entry_const: &EntryConst) -> Option<String> {
+		self.ignored_by.as_ref().map(|source| {
+			let directives = &entry_const.ignored_by_style;
+			format!("<{directives}>{source}</>")
+		})
+	}
+
+	/// Get the glyph that marks the relative importance of the node.
+	///
+	/// This conveys importance without relying on colors, for users who have
+	/// disabled them.
+	///
+	/// This function returns a marked-up string.
+	fn marker(&self, entry_const: &EntryConst) -> Option<String> {
+		entry_const
+			.imp_marker(self.imp_val())
+			.filter(|(glyph, _)| !glyph.is_empty())
+			.map(|(glyph, directives)| format!("<{directives}>{glyph}</>"))
+	}
+
+	/// Get the command name of the process, for process directories in
+	/// `/proc`.
+	///
+	/// This is blank for all other nodes.
+	///
+	/// This function returns a marked-up string.
+	fn comm(&self, entry_const: &EntryConst) -> Option<String> {
+		if self.typ != Typ::Dir {
+			return None;
+		}
+		procfs::comm(&self.path).map(|comm| {
+			let directives = &entry_const.comm_style;
+			format!("<{directives}>{comm}</>")
+		})
+	}
+
+	/// Get the role of the node in the project, like tests or docs, inferred
+	/// from conventional directory names.
+	///
+	/// Directories take the role of their own name, and other nodes take the
+	/// role of their parent directory. This is blank for nodes whose names do
+	/// not follow the conventions.
+	///
+	/// This function returns a marked-up string.
+	fn role(&self, entry_const: &EntryConst) -> Option<String> {
+		let dir = if self.typ == Typ::Dir {
+			Some(self.path.as_path())
+		} else {
+			self.path.parent()
+		};
+		let role = dir
+			.and_then(|dir| dir.file_name())
+			.and_then(|name| Role::from_dir_name(&name.to_string_lossy()))?;
+		let directives = &entry_const.role_style;
+		Some(format!("<{directives}>{role}</>"))
+	}
+
+	/// Get the commands that would fix the risks flagged on the node, like
+	/// `chmod o-w`, separated by semicolons.
+	///
+	/// This is blank for nodes that are not flagged, for nodes whose risks
+	/// suggest no fix, and for nodes inside archives, which cannot be fixed in
+	/// place.
+	///
+	/// This function returns a marked-up string.
+	fn fix(&self, entry_const: &EntryConst) -> Option<String> {
+		let fixes: Vec<_> = self
+			.fixes()
+			.map(|(risk, fix)| format!("<{}>{fix}</>", risk.directives(entry_const)))
+			.collect();
+		(!fixes.is_empty()).then(|| fixes.join("; "))
+	}
 }
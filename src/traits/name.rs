@@ -1,4 +1,7 @@
+use crate::config::EntryConst;
 use crate::models::Node;
+use crate::PLS;
+use regex::bytes::Regex;
 use std::path::PathBuf;
 
 pub trait Name {
@@ -6,7 +9,8 @@ pub trait Name {
 	fn stem(&self) -> String;
 	fn cname(&self) -> String;
 
-	fn aligned_name(&self) -> String;
+	fn highlighted(&self, text: &str, entry_const: &EntryConst) -> String;
+	fn aligned_name(&self, entry_const: &EntryConst) -> String;
 }
 
 impl Name for Node<'_> {
@@ -51,20 +55,37 @@ impl Name for Node<'_> {
 	// Name components
 	// ===============
 
+	/// Get the given text with the parts matching the `--highlight` pattern
+	/// wrapped in the highlight style.
+	///
+	/// If no pattern was provided, the text is returned as-is.
+	///
+	/// This function returns a marked-up string.
+	fn highlighted(&self, text: &str, entry_const: &EntryConst) -> String {
+		match &PLS.args.highlight {
+			Some(pat) => highlight(text, pat, &entry_const.highlight_style),
+			None => text.to_string(),
+		}
+	}
+
 	/// Get the name of the node when aligning for leading dots.
 	///
 	/// If the node name starts with a dot, the dot is dimmed. If not, the name
 	/// is left-padded with a space to line up the alphabetic characters.
-	fn aligned_name(&self) -> String {
+	///
+	/// A leading dot that is part of a highlighted match is not dimmed, so
+	/// that the highlight is not broken up.
+	fn aligned_name(&self, entry_const: &EntryConst) -> String {
 		let path = PathBuf::from(&self.display_name);
 		if let Some(name) = path.file_name() {
 			let name = name.to_string_lossy();
+			let highlighted = self.highlighted(&name, entry_const);
 
 			// 'clear' ensures that the dot and padding spaces are not formatted.
-			let aligned_name = if name.starts_with('.') {
-				format!("<clear dimmed>.</>{}", name.strip_prefix('.').unwrap())
-			} else {
-				format!("<clear> </>{}", name)
+			let aligned_name = match highlighted.strip_prefix('.') {
+				Some(rest) => format!("<clear dimmed>.</>{rest}"),
+				None if name.starts_with('.') => highlighted,
+				None => format!("<clear> </>{highlighted}"),
 			};
 
 			if let Some(parent) = path.parent() {
@@ -74,3 +95,56 @@ impl Name for Node<'_> {
 		self.display_name.clone()
 	}
 }
+
+/// Wrap all the parts of the text that match the given pattern with the given
+/// directives.
+///
+/// Since the pattern operates on bytes, matches that split a multi-byte
+/// character are lossily converted.
+///
+/// # Arguments
+///
+/// * `text` - the text in which to find the matches
+/// * `pat` - the pattern to match against the text
+/// * `directives` - the directives to apply to the matching parts
+fn highlight(text: &str, pat: &Regex, directives: &str) -> String {
+	let haystack = text.as_bytes();
+	let mut marked = Vec::with_capacity(haystack.len());
+	let mut last = 0;
+	for mat in pat.find_iter(haystack).filter(|mat| !mat.is_empty()) {
+		marked.extend_from_slice(&haystack[last..mat.start()]);
+		marked.extend_from_slice(format!("<{directives}>").as_bytes());
+		marked.extend_from_slice(mat.as_bytes());
+		marked.extend_from_slice(b"</>");
+		last = mat.end();
+	}
+	marked.extend_from_slice(&haystack[last..]);
+	String::from_utf8_lossy(&marked).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::highlight;
+	use regex::bytes::RegexBuilder;
+
+	macro_rules! make_highlight_test {
+		( $($name:ident: $text:expr, $pat:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let pat = RegexBuilder::new($pat).unicode(false).build().unwrap();
+					assert_eq!(highlight($text, &pat, "bold"), $expected);
+				}
+			)*
+		};
+	}
+
+	make_highlight_test!(
+		test_no_match: "README.md", "rs$" => "README.md",
+		test_single_match: "main.rs", "rs$" => "main.<bold>rs</>",
+		test_multiple_matches: "a_b_c", "_" => "a<bold>_</>b<bold>_</>c",
+		test_whole_match: "main.rs", ".*" => "<bold>main.rs</>",
+		test_skips_empty_matches: "main.rs", "x*" => "main.rs",
+		test_leading_dot: ".git", r"^\." => "<bold>.</>git",
+	);
+}
@@ -1,19 +1,21 @@
-use crate::enums::{SymTarget, Typ};
+use crate::enums::SymTarget;
 use crate::exc::Exc;
 use crate::models::Node;
 use std::fs;
 
 pub trait Sym {
-	fn target(&self) -> Option<SymTarget>;
+	fn target(&self) -> Option<SymTarget<'_>>;
 }
 
 impl Sym for Node<'_> {
 	/// Get the target destination of the node.
 	///
-	/// If the node is not a symlink, the target is `None`. If the node is a
-	/// symlink, the target is a variant of [`SymTarget`], wrapped in `Some`.
-	fn target(&self) -> Option<SymTarget> {
-		if self.typ != Typ::Symlink {
+	/// If the node is not a symlink or junction, the target is `None`. If the
+	/// node is one, the target is a variant of [`SymTarget`], wrapped in `Some`.
+	fn target(&self) -> Option<SymTarget<'_>> {
+		// Link targets inside archives cannot be resolved on the file system,
+		// and those on network file systems are skipped with `--fast`.
+		if !self.typ.is_link() || self.archived.is_some() || self.is_on_slow_fs() {
 			return None;
 		}
 
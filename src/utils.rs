@@ -3,10 +3,26 @@
 //! The public interface of the module consists of sub-modules, each of which
 //! can contain any number of utility functions.
 //!
+//! * [`du`]
+//! * [`globs`]
+//! * [`locale`]
+//! * [`mounts`]
+//! * [`numbers`]
 //! * [`paths`]
+//! * [`procfs`]
+//! * [`templates`]
+//! * [`times`]
 //! * [`urls`]
 //! * [`vectors`]
 
+pub mod du;
+pub mod globs;
+pub mod locale;
+pub mod mounts;
+pub mod numbers;
 pub mod paths;
+pub mod procfs;
+pub mod templates;
+pub mod times;
 pub mod urls;
 pub mod vectors;
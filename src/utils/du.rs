@@ -0,0 +1,98 @@
+//! This module contains code for computing the disk usage of directories.
+//!
+//! The public interface of the module consists of one function:
+//!
+//! * [`du`]
+
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+
+/// mapping of directories to the total size of the files inside them
+static CACHE: LazyLock<Mutex<HashMap<PathBuf, u64>>> = LazyLock::new(Mutex::default);
+
+/// Get the total size of all files inside the given directory, recursively.
+///
+/// The subdirectories are walked in parallel, and the size of every directory
+/// visited along the way is cached, so that listing nested directories, like
+/// with `--tree`, does not walk any directory twice.
+///
+/// Symlinks are not followed, so that the size of linked content is not
+/// counted and cycles are not possible. Unreadable entries are skipped.
+///
+/// # Arguments
+///
+/// * `dir` - the directory whose disk usage to compute
+pub fn du(dir: &Path) -> u64 {
+	if let Some(bytes) = cached(dir) {
+		return bytes;
+	}
+	debug!("Computing disk usage of {dir:?}.");
+
+	let (mut bytes, subdirs) = read(dir);
+	let workers = thread::available_parallelism()
+		.map_or(1, |count| count.get())
+		.min(subdirs.len());
+	let next = AtomicUsize::new(0);
+	bytes += thread::scope(|scope| {
+		let handles: Vec<_> = (0..workers)
+			.map(|_| {
+				scope.spawn(|| {
+					let mut bytes = 0;
+					while let Some(subdir) = subdirs.get(next.fetch_add(1, Ordering::Relaxed)) {
+						bytes += walk(subdir);
+					}
+					bytes
+				})
+			})
+			.collect();
+		handles
+			.into_iter()
+			.map(|handle| handle.join().unwrap_or_default())
+			.sum::<u64>()
+	});
+
+	CACHE.lock().unwrap().insert(dir.to_path_buf(), bytes);
+	bytes
+}
+
+/// Get the cached size of the given directory, if it has been computed.
+fn cached(dir: &Path) -> Option<u64> {
+	CACHE.lock().unwrap().get(dir).copied()
+}
+
+/// Compute the size of the given directory on the current thread.
+fn walk(dir: &Path) -> u64 {
+	if let Some(bytes) = cached(dir) {
+		return bytes;
+	}
+	let (mut bytes, subdirs) = read(dir);
+	bytes += subdirs.iter().map(|subdir| walk(subdir)).sum::<u64>();
+	CACHE.lock().unwrap().insert(dir.to_path_buf(), bytes);
+	bytes
+}
+
+/// Read the given directory, returning the total size of the files directly
+/// inside it and the paths of its subdirectories.
+fn read(dir: &Path) -> (u64, Vec<PathBuf>) {
+	let mut bytes = 0;
+	let mut subdirs = vec![];
+	let Ok(children) = fs::read_dir(dir) else {
+		return (bytes, subdirs);
+	};
+	for child in children.flatten() {
+		let Ok(meta) = child.metadata() else {
+			continue;
+		};
+		if meta.is_dir() {
+			subdirs.push(child.path());
+		} else if meta.is_file() {
+			bytes += meta.len();
+		}
+	}
+	(bytes, subdirs)
+}
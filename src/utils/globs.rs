@@ -0,0 +1,231 @@
+//! This module contains code for working with globs.
+//!
+//! There are two flavours of globs. The globs used in `.gitignore` files and
+//! the files that follow the same syntax, like `CODEOWNERS`, match paths. The
+//! shell-style globs used in the config and name filters match names.
+//!
+//! The public interface of the module consists of three functions:
+//!
+//! * [`glob_to_regex`]
+//! * [`shell_glob_to_regex`]
+//! * [`haystack`]
+
+use regex::Regex;
+use std::path::Path;
+
+/// Convert a `.gitignore`-style glob into a regex.
+///
+/// The regex matches paths relative to the directory that the glob is
+/// declared for, which must be prepared with [`haystack`]. The glob follows
+/// `.gitignore` semantics:
+///
+/// * globs with a leading or inner slash are anchored to the directory
+/// * other globs can match at any depth
+/// * globs with a trailing slash only match directories
+/// * globs also match everything inside the directories they match, except
+///   those ending in `/*`, which only match the direct children
+///
+/// This returns `None` if the glob is empty.
+///
+/// # Arguments
+///
+/// * `glob` - the glob to convert into a regex
+pub fn glob_to_regex(glob: &str) -> Option<Regex> {
+	let dir_only = glob.ends_with('/');
+	let trimmed = glob.trim_end_matches('/');
+	let anchored = trimmed.contains('/');
+	let trimmed = trimmed.trim_start_matches('/');
+	if trimmed.is_empty() {
+		return None;
+	}
+
+	let mut pat = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+	let mut rest = trimmed;
+	while let Some(ch) = rest.chars().next() {
+		if let Some(after) = rest.strip_prefix("**/") {
+			pat.push_str("(?:.*/)?");
+			rest = after;
+			continue;
+		}
+		if let Some(after) = rest.strip_prefix("**") {
+			pat.push_str(".*");
+			rest = after;
+			continue;
+		}
+		match ch {
+			'*' => pat.push_str("[^/]*"),
+			'?' => pat.push_str("[^/]"),
+			_ => push_literal(&mut pat, ch),
+		}
+		rest = &rest[ch.len_utf8()..];
+	}
+	pat.push_str(match (trimmed.ends_with("/*"), dir_only) {
+		(true, _) => "/?$",
+		(false, true) => "/.*$",
+		(false, false) => "(?:/.*)?$",
+	});
+
+	Regex::new(&pat).ok()
+}
+
+/// Translate a shell-style glob into a regex that matches the whole name.
+///
+/// Globs support `*` for any run of characters, `?` for any one character,
+/// `[abc]` and `[!abc]` for sets of characters, `{a,b}` for alternatives, and
+/// `\` to match the next character literally. Unlike in shells, `*` also
+/// matches a leading dot.
+///
+/// # Arguments
+///
+/// * `glob` - the glob to convert into the source of a regex
+pub fn shell_glob_to_regex(glob: &str) -> String {
+	let chars: Vec<_> = glob.chars().collect();
+	let mut regex = String::from("(?s)^");
+	let mut depth = 0;
+	let mut idx = 0;
+	while idx < chars.len() {
+		let ch = chars[idx];
+		idx += 1;
+		match ch {
+			'*' => regex.push_str(".*"),
+			'?' => regex.push('.'),
+			'\\' if idx < chars.len() => {
+				push_literal(&mut regex, chars[idx]);
+				idx += 1;
+			}
+			'[' => {
+				// A `]` right after the opening bracket is part of the set.
+				let start = idx + usize::from(chars.get(idx) == Some(&'!'));
+				let end = chars
+					.iter()
+					.skip(start + 1)
+					.position(|&ch| ch == ']')
+					.map(|pos| start + 1 + pos);
+				match end {
+					Some(end) => {
+						regex.push('[');
+						if start > idx {
+							regex.push('^');
+						}
+						for &ch in &chars[start..end] {
+							if matches!(ch, '\\' | '[' | ']' | '^' | '&' | '~') {
+								regex.push('\\');
+							}
+							regex.push(ch);
+						}
+						regex.push(']');
+						idx = end + 1;
+					}
+					None => push_literal(&mut regex, '['),
+				}
+			}
+			'{' => {
+				depth += 1;
+				regex.push_str("(?:");
+			}
+			'}' if depth > 0 => {
+				depth -= 1;
+				regex.push(')');
+			}
+			',' if depth > 0 => regex.push('|'),
+			_ => push_literal(&mut regex, ch),
+		}
+	}
+	// Unclosed alternatives are closed at the end of the glob.
+	regex.push_str(&")".repeat(depth));
+	regex.push('$');
+	regex
+}
+
+/// Append the character to the regex, escaped so that it matches literally.
+///
+/// This is shared by both flavours of globs.
+fn push_literal(regex: &mut String, ch: char) {
+	let mut buf = [0; 4];
+	regex.push_str(&regex::escape(ch.encode_utf8(&mut buf)));
+}
+
+/// Prepare a path to be matched against regexes from [`glob_to_regex`].
+///
+/// The path is lossily converted into a string, and a trailing slash is added
+/// to directories so that globs can tell them apart from files.
+///
+/// # Arguments
+///
+/// * `path` - the path, relative to the directory that globs are declared for
+/// * `is_dir` - whether the path points to a directory
+pub fn haystack(path: &Path, is_dir: bool) -> String {
+	let mut haystack = path.to_string_lossy().to_string();
+	if is_dir {
+		haystack.push('/');
+	}
+	haystack
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{glob_to_regex, haystack, shell_glob_to_regex};
+	use regex::bytes::Regex;
+	use std::path::Path;
+
+	macro_rules! make_glob_test {
+		( $($name:ident: $glob:expr, $path:expr, $is_dir:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let pat = glob_to_regex($glob).unwrap();
+					assert_eq!(pat.is_match(&haystack(Path::new($path), $is_dir)), $expected);
+				}
+			)*
+		};
+	}
+
+	make_glob_test!(
+		test_star_matches_everything: "*", "src/main.rs", false => true,
+		test_extension_at_any_depth: "*.rs", "src/models/node.rs", false => true,
+		test_extension_mismatch: "*.rs", "src/main.py", false => false,
+		test_extension_not_dir: "*.rs", "src", true => false,
+		test_unanchored_dir_at_any_depth: "docs/", "site/docs/index.md", false => true,
+		test_dir_only_matches_dir: "docs/", "docs", true => true,
+		test_dir_only_skips_file: "docs/", "docs", false => false,
+		test_bare_name_at_any_depth: "apps", "web/apps/index.js", false => true,
+		test_anchored_dir_contents: "/build/logs/", "build/logs/a/b.log", false => true,
+		test_anchored_dir_elsewhere: "/build/logs/", "src/build/logs/a.log", false => false,
+		test_direct_children_only: "docs/*", "docs/a/b.md", false => false,
+		test_direct_child: "docs/*", "docs/a.md", false => true,
+		test_direct_child_dir: "docs/*", "docs/a", true => true,
+		test_double_star_prefix: "**/logs", "a/b/logs/c.log", false => true,
+		test_double_star_inner: "src/**/mod.rs", "src/mod.rs", false => true,
+		test_question_mark: "v?.txt", "v1.txt", false => true,
+		test_escapes_dots: "a.b", "axb", false => false,
+	);
+
+	macro_rules! make_shell_glob_test {
+		( $($name:ident: $glob:expr, $node_name:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					let regex = Regex::new(&shell_glob_to_regex($glob)).unwrap();
+					assert_eq!(regex.is_match($node_name.as_bytes()), $expected);
+				}
+			)*
+		};
+	}
+
+	make_shell_glob_test!(
+		test_shell_glob_star: "*.rs", "main.rs" => true,
+		test_shell_glob_is_anchored: "*.rs", "main.rs.bak" => false,
+		test_shell_glob_dot_is_literal: "*.rs", "main_rs" => false,
+		test_shell_glob_star_matches_dotfile: "*rc", ".bashrc" => true,
+		test_shell_glob_question: "?.md", "a.md" => true,
+		test_shell_glob_question_is_one_char: "?.md", "ab.md" => false,
+		test_shell_glob_set: "[ab].txt", "b.txt" => true,
+		test_shell_glob_negated_set: "[!ab].txt", "b.txt" => false,
+		test_shell_glob_set_with_bracket: "[]a].txt", "].txt" => true,
+		test_shell_glob_unclosed_set: "[a.txt", "[a.txt" => true,
+		test_shell_glob_alternatives: "*.{yml,yaml}", "conf.yaml" => true,
+		test_shell_glob_comma_is_literal: "a,b", "a,b" => true,
+		test_shell_glob_escape: r"\*.rs", "*.rs" => true,
+		test_shell_glob_escaped_star_is_literal: r"\*.rs", "main.rs" => false,
+	);
+}
@@ -0,0 +1,114 @@
+//! This module contains code for working with the locale of the environment.
+//!
+//! The public interface of the module consists of two functions:
+//!
+//! * [`collation_locale`]
+//! * [`is_utf8`]
+
+use crate::PLS;
+use std::env;
+
+/// Determine whether the locale of the environment uses UTF-8 encoding.
+///
+/// The locale is determined from the first non-empty value among the `LC_ALL`,
+/// `LC_CTYPE` and `LANG` environment variables, in that order of precedence.
+/// If none of them are set, the locale is assumed to support UTF-8 because
+/// most modern terminals do.
+pub fn is_utf8() -> bool {
+	let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+		.into_iter()
+		.filter_map(|key| env::var(key).ok())
+		.find(|val| !val.is_empty());
+	locale.map_or(true, |locale| is_utf8_locale(&locale))
+}
+
+/// Get the locale of the environment that determines the order of text, as a
+/// BCP-47 language tag like "de-DE".
+///
+/// The locale is determined from the first non-empty value among the `LC_ALL`,
+/// `LC_COLLATE` and `LANG` environment variables, in that order of precedence.
+/// With `--deterministic`, and for the "C" and "POSIX" locales, this is `None`
+/// so that the language-neutral order is used.
+pub fn collation_locale() -> Option<String> {
+	if PLS.args.deterministic.is_some() {
+		return None;
+	}
+	["LC_ALL", "LC_COLLATE", "LANG"]
+		.into_iter()
+		.filter_map(|key| env::var(key).ok())
+		.find(|val| !val.is_empty())
+		.and_then(|locale| to_language_tag(&locale))
+}
+
+// =======
+// Private
+// =======
+
+/// Convert the given POSIX locale string into a BCP-47 language tag.
+///
+/// The encoding and modifier of the locale are dropped, and the separator
+/// between the language and the territory becomes a hyphen.
+///
+/// # Arguments
+///
+/// * `locale` - the locale string, like "en_US.UTF-8"
+fn to_language_tag(locale: &str) -> Option<String> {
+	let locale = locale.split(['.', '@']).next().unwrap_or_default();
+	if locale.is_empty() || locale == "C" || locale == "POSIX" {
+		return None;
+	}
+	Some(locale.replace('_', "-"))
+}
+
+/// Determine whether the given locale string names a UTF-8 encoding.
+///
+/// # Arguments
+///
+/// * `locale` - the locale string, like "en_US.UTF-8"
+fn is_utf8_locale(locale: &str) -> bool {
+	let locale = locale.to_ascii_lowercase();
+	locale.contains("utf-8") || locale.contains("utf8")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{is_utf8_locale, to_language_tag};
+
+	macro_rules! make_test {
+		( $($name:ident: $locale:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(is_utf8_locale($locale), $expected);
+				}
+			)*
+		};
+	}
+
+	make_test!(
+		test_upper_dashed: "en_US.UTF-8" => true,
+		test_lower_undashed: "de_DE.utf8" => true,
+		test_posix: "POSIX" => false,
+		test_c: "C" => false,
+		test_latin: "en_US.ISO-8859-1" => false,
+	);
+
+	macro_rules! make_tag_test {
+		( $($name:ident: $locale:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(to_language_tag($locale).as_deref(), $expected);
+				}
+			)*
+		};
+	}
+
+	make_tag_test!(
+		test_tag_full: "de_DE.UTF-8" => Some("de-DE"),
+		test_tag_modifier: "sr_RS@latin" => Some("sr-RS"),
+		test_tag_language: "fr" => Some("fr"),
+		test_tag_posix: "POSIX" => None,
+		test_tag_c: "C.UTF-8" => None,
+	);
+}
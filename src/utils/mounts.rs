@@ -0,0 +1,287 @@
+//! This module contains code for guarding against slow file systems, like
+//! network mounts, that can stall a listing.
+//!
+//! The public interface of the module consists of four functions:
+//!
+//! * [`is_network`]
+//! * [`read_dir`]
+//! * [`timeout_in`]
+//! * [`within`]
+
+use log::warn;
+use std::collections::HashMap;
+use std::fs::DirEntry;
+use std::io::{Error, ErrorKind, Result as IoResult};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// mapping of device IDs to whether the file system on the device is backed
+/// by the network
+static CACHE: LazyLock<Mutex<HashMap<u64, bool>>> = LazyLock::new(Mutex::default);
+
+/// mapping of directories to whether the file system containing them is
+/// backed by the network
+static DIR_CACHE: LazyLock<Mutex<HashMap<PathBuf, bool>>> = LazyLock::new(Mutex::default);
+
+/// the magic numbers of network and FUSE file systems on Linux, from
+/// `<linux/magic.h>` and the sources of the file systems
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const NETWORK_MAGICS: &[i64] = &[
+	0x6969,     // NFS
+	0x517b,     // SMB
+	0xff534d42, // CIFS
+	0xfe534d42, // SMB2
+	0x5346414f, // AFS
+	0x6b414653, // AFS, as mounted by kAFS
+	0x00c36400, // Ceph
+	0x65735546, // FUSE, which includes SSHFS and most cloud drives
+	0x01021997, // 9P
+	0x47504653, // GPFS
+	0x0bd00bd0, // Lustre
+];
+
+/// the name prefixes of network and FUSE file systems on macOS, as reported
+/// by `statfs`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const NETWORK_NAMES: &[&str] = &[
+	"nfs", "smbfs", "afpfs", "webdav", "ftp", "osxfuse", "macfuse",
+];
+
+/// Determine whether the node at the given path, on the device with the given
+/// ID, is on a file system backed by the network, like NFS, SMB or FUSE.
+///
+/// Network file systems can stall on every request when the server is slow
+/// or unreachable. The result is cached per device, so that the file system
+/// is only queried once.
+///
+/// # Arguments
+///
+/// * `path` - the path of the node
+/// * `dev` - the ID of the device that the node is on
+/// * `timeout` - the time that querying the file system may take, if limited
+pub fn is_network(path: &Path, dev: u64, timeout: Option<Duration>) -> bool {
+	if let Some(is_network) = CACHE.lock().unwrap().get(&dev) {
+		return *is_network;
+	}
+	let is_network = query(path, timeout);
+	CACHE.lock().unwrap().insert(dev, is_network);
+	is_network
+}
+
+/// Get the timeout for operations on the nodes in the given directory.
+///
+/// The timeout is only kept if the directory is on a network file system, so
+/// that nodes on local disks are read on the current thread without paying
+/// for a thread each. The result is cached per directory.
+///
+/// # Arguments
+///
+/// * `dir` - the directory containing the nodes
+/// * `timeout` - the time that each operation may take, if limited
+pub fn timeout_in(dir: &Path, timeout: Option<Duration>) -> Option<Duration> {
+	timeout?;
+	if let Some(is_network) = DIR_CACHE.lock().unwrap().get(dir) {
+		return timeout.filter(|_| *is_network);
+	}
+	let is_network = query(dir, timeout);
+	DIR_CACHE.lock().unwrap().insert(dir.to_owned(), is_network);
+	timeout.filter(|_| is_network)
+}
+
+/// Read the entries of the given directory, abandoning the read if the
+/// directory is on a network file system and the read does not finish within
+/// the given timeout.
+///
+/// Entries that cannot be read are left out.
+///
+/// # Arguments
+///
+/// * `dir` - the directory to read
+/// * `timeout` - the time the read may take, if limited
+pub fn read_dir(dir: &Path, timeout: Option<Duration>) -> IoResult<Vec<DirEntry>> {
+	let timeout = timeout_in(dir, timeout);
+	let dir = dir.to_owned();
+	within(timeout, move || Ok(dir.read_dir()?.flatten().collect()))
+}
+
+/// Run the given file system operation, abandoning it if it does not finish
+/// within the given timeout.
+///
+/// Without a timeout, the operation runs on the current thread. Otherwise, it
+/// runs on its own thread, which is left behind if it times out, since system
+/// calls stuck on a dead mount cannot be cancelled.
+///
+/// # Arguments
+///
+/// * `timeout` - the time the operation may take, if limited
+/// * `op` - the operation to run
+pub fn within<T, F>(timeout: Option<Duration>, op: F) -> IoResult<T>
+where
+	T: Send + 'static,
+	F: FnOnce() -> IoResult<T> + Send + 'static,
+{
+	let Some(timeout) = timeout else {
+		return op();
+	};
+	let (tx, rx) = mpsc::channel();
+	thread::spawn(move || {
+		// The receiver is gone if the operation timed out.
+		let _ = tx.send(op());
+	});
+	rx.recv_timeout(timeout).unwrap_or_else(|_| {
+		warn!("File system operation timed out after {timeout:?}.");
+		Err(Error::from(ErrorKind::TimedOut))
+	})
+}
+
+/// Query the file system containing the given path for whether it is backed
+/// by the network.
+///
+/// The query itself can stall on a dead mount, so it is abandoned after the
+/// given timeout, in which case the file system is treated as a network one.
+fn query(path: &Path, timeout: Option<Duration>) -> bool {
+	let path = path.to_owned();
+	within(timeout, move || Ok(sys::is_network(&path))).unwrap_or(true)
+}
+
+/// Determine whether the given file system magic number, as reported by
+/// `statfs` on Linux, is that of a network file system.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn is_network_magic(magic: i64) -> bool {
+	NETWORK_MAGICS.contains(&magic)
+}
+
+/// Determine whether the given file system type name, as reported by `statfs`
+/// on macOS, is that of a network file system.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn is_network_name(name: &str) -> bool {
+	NETWORK_NAMES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+	use std::ffi::CString;
+	use std::path::Path;
+
+	/// Determine whether the file system containing the given path is backed
+	/// by the network, from its magic number.
+	pub fn is_network(path: &Path) -> bool {
+		let Ok(path) = CString::new(path.as_os_str().as_encoded_bytes()) else {
+			return false;
+		};
+		let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+		if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+			return false;
+		}
+		// The type of the field differs across architectures.
+		#[allow(clippy::unnecessary_cast)]
+		let magic = stat.f_type as i64;
+		super::is_network_magic(magic)
+	}
+}
+
+#[cfg(target_os = "macos")]
+mod sys {
+	use std::ffi::{CStr, CString};
+	use std::path::Path;
+
+	/// Determine whether the file system containing the given path is backed
+	/// by the network, from its type name.
+	pub fn is_network(path: &Path) -> bool {
+		let Ok(path) = CString::new(path.as_os_str().as_encoded_bytes()) else {
+			return false;
+		};
+		let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+		if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+			return false;
+		}
+		let name = unsafe { CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+		super::is_network_name(&name.to_string_lossy())
+	}
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod sys {
+	use std::path::Path;
+
+	/// Determine whether the file system containing the given path is backed
+	/// by the network, which is not supported on this platform.
+	pub fn is_network(_path: &Path) -> bool {
+		false
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{is_network_magic, is_network_name, read_dir, timeout_in, within};
+	use std::io::ErrorKind;
+	use std::thread;
+	use std::time::Duration;
+
+	macro_rules! make_magic_test {
+		( $($name:ident: $magic:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(is_network_magic($magic), $expected);
+				}
+			)*
+		};
+	}
+
+	make_magic_test!(
+		test_nfs_magic: 0x6969 => true,
+		test_fuse_magic: 0x65735546 => true,
+		test_ext4_magic: 0xef53 => false,
+	);
+
+	macro_rules! make_name_test {
+		( $($name:ident: $fs:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(is_network_name($fs), $expected);
+				}
+			)*
+		};
+	}
+
+	make_name_test!(
+		test_nfs_name: "nfs" => true,
+		test_macfuse_name: "macfuse_sshfs" => true,
+		test_apfs_name: "apfs" => false,
+	);
+
+	#[test]
+	fn test_within_returns_result() {
+		let res = within(Some(Duration::from_secs(5)), || Ok(1));
+		assert_eq!(res.unwrap(), 1);
+	}
+
+	#[test]
+	fn test_timeout_in_local_dir() {
+		let dir = std::env::current_dir().unwrap();
+		assert_eq!(timeout_in(&dir, Some(Duration::from_secs(5))), None);
+	}
+
+	#[test]
+	fn test_read_dir_lists_entries() {
+		let dir = std::env::current_dir().unwrap();
+		let entries = read_dir(&dir, Some(Duration::from_secs(5))).unwrap();
+		assert!(entries
+			.iter()
+			.any(|entry| entry.file_name() == "Cargo.toml"));
+	}
+
+	#[test]
+	fn test_within_times_out() {
+		let res = within(Some(Duration::from_millis(10)), || {
+			thread::sleep(Duration::from_millis(500));
+			Ok(())
+		});
+		assert_eq!(res.unwrap_err().kind(), ErrorKind::TimedOut);
+	}
+}
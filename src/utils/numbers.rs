@@ -0,0 +1,45 @@
+//! This module contains some helper functions for formatting numbers.
+//!
+//! The public interface of the module consists of one function:
+//!
+//! * [`group_digits`]
+
+/// Format the number with commas separating groups of thousands.
+///
+/// # Arguments
+///
+/// * `num` - the number to format
+pub fn group_digits(num: u64) -> String {
+	let digits = num.to_string();
+	let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+	for (idx, ch) in digits.chars().enumerate() {
+		if idx > 0 && (digits.len() - idx) % 3 == 0 {
+			grouped.push(',');
+		}
+		grouped.push(ch);
+	}
+	grouped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::group_digits;
+
+	macro_rules! make_group_digits_test {
+		( $($name:ident: $num:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(group_digits($num), $expected);
+				}
+			)*
+		};
+	}
+
+	make_group_digits_test!(
+		test_single_digit: 7 => "7",
+		test_three_digits: 999 => "999",
+		test_four_digits: 1284 => "1,284",
+		test_seven_digits: 1234567 => "1,234,567",
+	);
+}
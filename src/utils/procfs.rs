@@ -0,0 +1,162 @@
+//! This module contains code for reading the `/proc` file system on Linux.
+//!
+//! The public interface of the module consists of four functions:
+//!
+//! * [`is_pid`]
+//! * [`comm`]
+//! * [`driver`]
+//! * [`mount_source`]
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// mapping of whether the device is a block device and its major number to
+/// the name of its driver, read from `/proc/devices`
+static DRIVERS: LazyLock<HashMap<(bool, u32), String>> = LazyLock::new(|| {
+	fs::read_to_string("/proc/devices")
+		.map(|text| parse_devices(&text))
+		.unwrap_or_default()
+});
+
+/// Determine whether the given name is that of a process directory, i.e. it
+/// consists only of ASCII digits.
+pub fn is_pid(name: &str) -> bool {
+	!name.is_empty() && name.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// Get the command name of the process whose directory is at the given path.
+///
+/// This is read from the `comm` file inside the directory and is `None` if
+/// the directory is not a process directory or the process has exited.
+pub fn comm(path: &Path) -> Option<String> {
+	let name = path.file_name()?.to_str()?;
+	if !is_pid(name) {
+		return None;
+	}
+	let comm = fs::read_to_string(path.join("comm")).ok()?;
+	Some(comm.trim_end_matches('\n').to_string())
+}
+
+/// Get the name of the driver, like `tty` or `sd`, that handles devices with
+/// the given major number.
+///
+/// The name identifies the subsystem of the device. It is `None` on systems
+/// without `/proc/devices` or if the major number is not registered.
+pub fn driver(is_block: bool, major: u32) -> Option<&'static str> {
+	DRIVERS.get(&(is_block, major)).map(String::as_str)
+}
+
+/// Get the source, usually a block device like `/dev/sda1`, of the file
+/// system mounted with the given device numbers.
+///
+/// This is read from `/proc/self/mountinfo` and is `None` on systems without
+/// it or if no mount has the given device numbers.
+pub fn mount_source(major: u32, minor: u32) -> Option<String> {
+	let text = fs::read_to_string("/proc/self/mountinfo").ok()?;
+	parse_mount_source(&text, major, minor)
+}
+
+/// Parse the contents of `/proc/self/mountinfo` to find the source of the
+/// mount with the given device numbers.
+///
+/// Each line describes one mount, with the device numbers, as
+/// `"<major>:<minor>"`, in the third field and the source in the second field
+/// after the `-` separator, since the optional fields before the separator
+/// vary in number.
+fn parse_mount_source(text: &str, major: u32, minor: u32) -> Option<String> {
+	let dev = format!("{major}:{minor}");
+	text.lines().find_map(|line| {
+		let (fields, rest) = line.split_once(" - ")?;
+		if fields.split(' ').nth(2)? != dev {
+			return None;
+		}
+		rest.split(' ').nth(1).map(String::from)
+	})
+}
+
+/// Parse the contents of `/proc/devices` into a mapping of whether the device
+/// is a block device and its major number to the name of its driver.
+///
+/// The file consists of a section for character devices followed by one for
+/// block devices, each listing lines of the form `"<major> <name>"`.
+fn parse_devices(text: &str) -> HashMap<(bool, u32), String> {
+	let mut is_block = false;
+	let mut drivers = HashMap::new();
+	for line in text.lines() {
+		if line.starts_with("Block devices") {
+			is_block = true;
+			continue;
+		}
+		let Some((major, name)) = line.trim().split_once(' ') else {
+			continue;
+		};
+		// Some drivers share a major number, in which case the first named
+		// one is kept, skipping aliases like `/dev/vc/0`.
+		let name = name.trim();
+		if let (Ok(major), false) = (major.parse(), name.starts_with('/')) {
+			drivers
+				.entry((is_block, major))
+				.or_insert_with(|| name.to_string());
+		}
+	}
+	drivers
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{comm, is_pid, parse_devices, parse_mount_source};
+	use std::path::Path;
+
+	macro_rules! make_is_pid_test {
+		( $($name:ident: $input:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(is_pid($input), $expected);
+				}
+			)*
+		};
+	}
+
+	make_is_pid_test!(
+		test_pid: "1234" => true,
+		test_non_pid: "self" => false,
+		test_mixed: "12ab" => false,
+		test_empty: "" => false,
+	);
+
+	#[test]
+	fn test_parses_devices() {
+		let text = "Character devices:\n  1 mem\n  4 /dev/vc/0\n  4 tty\n  4 ttyS\n\nBlock devices:\n  8 sd\n";
+		let drivers = parse_devices(text);
+		assert_eq!(drivers.get(&(false, 4)).map(String::as_str), Some("tty"));
+		assert_eq!(drivers.get(&(true, 8)).map(String::as_str), Some("sd"));
+		assert_eq!(drivers.get(&(true, 4)), None);
+	}
+
+	#[test]
+	fn test_parses_mount_source() {
+		let text = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+35 22 0:32 / /home rw,nosuid shared:2 master:1 - ext4 /dev/sdb1 rw,usrquota
+40 22 0:45 / /mnt/nfs rw - nfs4 server:/export rw,vers=4.2
+";
+		assert_eq!(parse_mount_source(text, 8, 1).as_deref(), Some("/dev/sda1"));
+		assert_eq!(
+			parse_mount_source(text, 0, 32).as_deref(),
+			Some("/dev/sdb1")
+		);
+		assert_eq!(
+			parse_mount_source(text, 0, 45).as_deref(),
+			Some("server:/export")
+		);
+		assert_eq!(parse_mount_source(text, 8, 2), None);
+	}
+
+	#[test]
+	fn test_comm_of_non_pid_is_none() {
+		assert_eq!(comm(Path::new("/proc/self")), None);
+	}
+}
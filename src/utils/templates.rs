@@ -0,0 +1,102 @@
+//! This module contains some helper functions for filling mini-templates.
+//!
+//! A mini-template is a string with placeholders, like `{user}:{group}`, in
+//! which each placeholder is replaced by the value that its key refers to.
+//!
+//! The public interface of the module consists of two functions:
+//!
+//! * [`fill`]
+//! * [`keys`]
+
+/// Fill the placeholders in the given template.
+///
+/// Placeholders whose key has no value, and braces that are not closed, are
+/// kept as they are, so that mistakes in the template remain visible.
+///
+/// # Arguments
+///
+/// * `template` - the template containing placeholders like `{key}`
+/// * `lookup` - the function that maps a key to its value
+pub fn fill(template: &str, mut lookup: impl FnMut(&str) -> Option<String>) -> String {
+	let mut filled = String::with_capacity(template.len());
+	let mut rest = template;
+	while let Some(start) = rest.find('{') {
+		filled.push_str(&rest[..start]);
+		rest = &rest[start..];
+		let Some(len) = rest.find('}') else {
+			break;
+		};
+		let placeholder = &rest[..=len];
+		match lookup(&rest[1..len]) {
+			Some(value) => filled.push_str(&value),
+			None => filled.push_str(placeholder),
+		}
+		rest = &rest[len + 1..];
+	}
+	filled.push_str(rest);
+	filled
+}
+
+/// Get the keys of all placeholders in the given template, in order.
+pub fn keys(template: &str) -> Vec<&str> {
+	let mut keys = vec![];
+	let mut rest = template;
+	while let Some(start) = rest.find('{') {
+		let Some(len) = rest[start..].find('}') else {
+			break;
+		};
+		keys.push(&rest[start + 1..start + len]);
+		rest = &rest[start + len + 1..];
+	}
+	keys
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{fill, keys};
+
+	fn lookup(key: &str) -> Option<String> {
+		match key {
+			"user" => Some(String::from("root")),
+			"group" => Some(String::from("wheel")),
+			_ => None,
+		}
+	}
+
+	macro_rules! make_fill_test {
+		( $($name:ident: $template:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(fill($template, lookup), $expected);
+				}
+			)*
+		};
+	}
+
+	make_fill_test!(
+		test_fill_plain: "owner" => "owner",
+		test_fill_placeholders: "{user}:{group}" => "root:wheel",
+		test_fill_surrounding_text: "by {user}!" => "by root!",
+		test_fill_unknown_key: "{user} ({nope})" => "root ({nope})",
+		test_fill_unclosed: "{user} {group" => "root {group",
+		test_fill_empty: "" => "",
+	);
+
+	macro_rules! make_keys_test {
+		( $($name:ident: $template:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(keys($template), $expected as Vec<&str>);
+				}
+			)*
+		};
+	}
+
+	make_keys_test!(
+		test_keys_none: "owner" => vec![],
+		test_keys_many: "{mtime} ({age})" => vec!["mtime", "age"],
+		test_keys_unclosed: "{user} {group" => vec!["user"],
+	);
+}
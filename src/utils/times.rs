@@ -0,0 +1,108 @@
+//! This module contains some helper functions for formatting times.
+//!
+//! The public interface of the module consists of two functions:
+//!
+//! * [`epoch`]
+//! * [`relative`]
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// the width of the longest relative time, like "59 minutes ago"
+const RELATIVE_WIDTH: usize = 14;
+
+/// the units of relative times, with their lengths in seconds, in decreasing
+/// order of length
+const UNITS: [(&str, i64); 6] = [
+	("year", 365 * 86_400),
+	("month", 30 * 86_400),
+	("week", 7 * 86_400),
+	("day", 86_400),
+	("hour", 3_600),
+	("minute", 60),
+];
+
+/// Get the number of seconds between the Unix epoch and the given time,
+/// negative for times before the epoch.
+pub fn epoch(time: SystemTime) -> i64 {
+	match time.duration_since(UNIX_EPOCH) {
+		Ok(since) => since.as_secs() as i64,
+		Err(err) => -(err.duration().as_secs() as i64),
+	}
+}
+
+/// Describe the given age in the largest unit that fits it, like "3 hours
+/// ago", or "in 3 hours" for times in the future.
+///
+/// The description is right-aligned to a fixed width so that relative times
+/// line up in columns like absolute ones do.
+///
+/// # Arguments
+///
+/// * `age` - the number of seconds elapsed since the time, negative for times
+///   in the future
+pub fn relative(age: i64) -> String {
+	let secs = age.abs();
+	let text = if secs < 60 {
+		String::from("just now")
+	} else {
+		let (unit, len) = UNITS
+			.into_iter()
+			.find(|(_, len)| secs >= *len)
+			.unwrap_or(UNITS[UNITS.len() - 1]);
+		let count = secs / len;
+		let plural = if count == 1 { "" } else { "s" };
+		if age < 0 {
+			format!("in {count} {unit}{plural}")
+		} else {
+			format!("{count} {unit}{plural} ago")
+		}
+	};
+	format!("{text:>RELATIVE_WIDTH$}")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{epoch, relative};
+	use std::time::{Duration, UNIX_EPOCH};
+
+	macro_rules! make_relative_test {
+		( $($name:ident: $age:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(relative($age), $expected);
+				}
+			)*
+		};
+	}
+
+	make_relative_test!(
+		test_seconds_are_now: 59 => "      just now",
+		test_singular_unit: 60 => "  1 minute ago",
+		test_plural_unit: 3 * 3_600 + 59 => "   3 hours ago",
+		test_longest_fits: 59 * 60 => "59 minutes ago",
+		test_days: 2 * 86_400 => "    2 days ago",
+		test_weeks: 15 * 86_400 => "   2 weeks ago",
+		test_months: 61 * 86_400 => "  2 months ago",
+		test_years: 800 * 86_400 => "   2 years ago",
+		test_future: -2 * 3_600 => "    in 2 hours",
+		test_future_now: -5 => "      just now",
+	);
+
+	macro_rules! make_epoch_test {
+		( $($name:ident: $time:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(epoch($time), $expected);
+				}
+			)*
+		};
+	}
+
+	make_epoch_test!(
+		test_epoch_itself: UNIX_EPOCH => 0,
+		test_after_epoch: UNIX_EPOCH + Duration::from_millis(1_500) => 1,
+		test_before_epoch: UNIX_EPOCH - Duration::from_secs(60) => -60,
+	);
+}
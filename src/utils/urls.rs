@@ -1,10 +1,12 @@
 //! This module contains code for working with URLs.
 //!
-//! The public interface of the module consists of one function:
+//! The public interface of the module consists of two functions:
 //!
+//! * [`file_url`]
 //! * [`get_osc`]
 
-use std::fmt::Display;
+use std::fmt::{Display, Write};
+use std::path::Path;
 
 /// Get the OSC-8 escape sequence for the given URL.
 ///
@@ -24,9 +26,37 @@ where
 	format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
 }
 
+/// Get the `file://` URL for the given absolute path.
+///
+/// All bytes of the path, other than unreserved characters and separators,
+/// are percent-encoded, so that the URL never contains spaces, markup tags or
+/// escape sequences. On Windows, backslashes become forward slashes and the
+/// drive letter follows an extra slash, like `file:///C:/Users`.
+///
+/// # Arguments
+///
+/// * `path` - the absolute path to generate the URL for
+pub fn file_url(path: &Path) -> String {
+	let path = path.to_string_lossy();
+	let path = if cfg!(windows) {
+		format!("/{}", path.replace('\\', "/"))
+	} else {
+		path.to_string()
+	};
+	path.bytes().fold(String::from("file://"), |mut url, byte| {
+		if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+			url.push(byte as char);
+		} else {
+			let _ = write!(url, "%{byte:02X}"); // `write!`-ing into a `String` can never fail.
+		}
+		url
+	})
+}
+
 #[cfg(test)]
 mod tests {
-	use super::get_osc;
+	use super::{file_url, get_osc};
+	use std::path::Path;
 
 	macro_rules! make_test {
 		( $($name:ident: $url:expr, $text:expr => $expected:expr,)* ) => {
@@ -43,4 +73,23 @@ mod tests {
 		test_url_and_test: "https://example.com", Some("Example") => "\x1b]8;;https://example.com\x1b\\Example\x1b]8;;\x1b\\",
 		test_url_only: "https://example.com", None => "\x1b]8;;https://example.com\x1b\\https://example.com\x1b]8;;\x1b\\",
 	);
+
+	macro_rules! make_file_url_test {
+		( $($name:ident: $path:expr => $expected:expr,)* ) => {
+			$(
+				#[test]
+				#[cfg(unix)]
+				fn $name() {
+					assert_eq!(file_url(Path::new($path)), $expected);
+				}
+			)*
+		};
+	}
+
+	make_file_url_test!(
+		test_file_url_plain: "/home/pls/main.rs" => "file:///home/pls/main.rs",
+		test_file_url_space: "/tmp/my notes.txt" => "file:///tmp/my%20notes.txt",
+		test_file_url_markup: "/tmp/<b>\\" => "file:///tmp/%3Cb%3E%5C",
+		test_file_url_unicode: "/tmp/café" => "file:///tmp/caf%C3%A9",
+	);
 }
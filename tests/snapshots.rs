@@ -0,0 +1,159 @@
+//! Golden snapshot tests for the views of `pls`.
+//!
+//! Each test lists a fixture directory with `--deterministic` and
+//! `--render-to`, and compares the styled output, escape sequences included,
+//! with a snapshot in `tests/snapshots/`. Set `PLS_UPDATE_SNAPSHOTS=1` to
+//! write the current output to the snapshots instead, and review the diff
+//! before committing it.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// the files in the fixture directory, paired with their contents
+const FIXTURE: &[(&str, &str)] = &[
+	("Cargo.toml", "[package]\nname = \"fixture\"\n"),
+	("README.md", "# Fixture\n"),
+	("docs/guide.md", "## Guide\n"),
+	(
+		"docs/a_guide_with_a_name_so_long_that_it_cannot_fit_in_the_terminal.md",
+		"## Guide\n",
+	),
+	("src/lib.rs", ""),
+	("src/main.rs", "fn main() {}\n"),
+	(".hidden", "secret\n"),
+];
+
+/// a config that restyles some of the nodes in the fixture
+const SPECS_CONF: &str = "\
+specs:
+  - pattern: \\.md$
+    icons:
+      - book
+    style: blue underline
+  - pattern: ^src$
+    style: red bold
+";
+
+/// a config that paints the background of some of the nodes in the fixture
+const FILL_CONF: &str = "\
+specs:
+  - pattern: ^README\\.md$
+    style: bg:blue bold
+";
+
+/// a config that wraps long names onto continuation lines
+const WRAP_CONF: &str = "\
+app_const:
+  table:
+    wrap_names: true
+";
+
+/// a config that shows the size of each node in the grid
+const GRID_DETAILS_CONF: &str = "\
+app_const:
+  grid_details:
+    - size
+";
+
+/// Create the fixture directory for the named test, inside a temporary
+/// directory.
+///
+/// The temporary directory, along with the config and the rendered output
+/// next to the fixture, is removed when the returned value is dropped, so
+/// that no state leaks between runs.
+fn fixture(name: &str) -> (TempDir, PathBuf) {
+	let temp = tempfile::Builder::new()
+		.prefix("pls-snapshots-")
+		.tempdir()
+		.unwrap();
+	let root = temp.path().join(name);
+	for (path, contents) in FIXTURE {
+		let path = root.join(path);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(path, contents).unwrap();
+	}
+	(temp, root)
+}
+
+/// Render the fixture with the given config and arguments and compare the
+/// output with the snapshot of the same name.
+///
+/// The config is passed with `--config`, and without one, `--no-config` is
+/// used, so that the config files of the user never affect the output.
+fn assert_snapshot(name: &str, conf: Option<&str>, args: &[&str]) {
+	let (_temp, root) = fixture(name);
+	let rendered = root.with_extension("ansi");
+
+	let mut cmd = Command::new(env!("CARGO_BIN_EXE_pls"));
+	match conf {
+		Some(conf) => {
+			let conf_file = root.with_extension("yml");
+			fs::write(&conf_file, conf).unwrap();
+			cmd.arg("--config").arg(conf_file)
+		}
+		None => cmd.arg("--no-config"),
+	};
+	let status = cmd
+		.current_dir(&root)
+		.env_remove("PLS_COLUMNS")
+		.args(["--deterministic", "0", "--render-to"])
+		.arg(&rendered)
+		.args(args)
+		.status()
+		.unwrap();
+	assert!(status.success(), "`pls` failed for snapshot {name}");
+
+	let actual = fs::read_to_string(&rendered).unwrap();
+	let snapshot = Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests")
+		.join("snapshots")
+		.join(format!("{name}.ansi"));
+	if env::var_os("PLS_UPDATE_SNAPSHOTS").is_some() {
+		fs::create_dir_all(snapshot.parent().unwrap()).unwrap();
+		fs::write(&snapshot, &actual).unwrap();
+		return;
+	}
+
+	let expected = fs::read_to_string(&snapshot).unwrap_or_else(|_| {
+		panic!("Missing snapshot {name}, run with `PLS_UPDATE_SNAPSHOTS=1` to write it.")
+	});
+	assert_eq!(
+		actual, expected,
+		"Output differs from snapshot {name}, run with `PLS_UPDATE_SNAPSHOTS=1` to update it."
+	);
+}
+
+macro_rules! make_snapshot_test {
+	( $($name:ident: $conf:expr, [$($arg:expr),*],)* ) => {
+		$(
+			#[test]
+			fn $name() {
+				assert_snapshot(stringify!($name), $conf, &[$($arg),*]);
+			}
+		)*
+	};
+}
+
+make_snapshot_test!(
+	table_default: None, [],
+	table_details: None, ["--det", "typ", "--det", "size", "--header", "true"],
+	table_all_sorted: None, ["--imp=-1", "--sort", "size_", "--det", "size"],
+	table_specs: Some(SPECS_CONF), ["--det", "typ"],
+	table_wrapped: Some(WRAP_CONF), ["--det", "typ", "--det", "size", "--tree", "true"],
+	table_elided: None, ["--det", "typ", "--det", "size", "--elide-uniform", "true", "src"],
+	grid_default: None, ["--grid", "true"],
+	grid_down: None, ["--grid", "true", "--down", "true", "--imp=-1"],
+	grid_specs: Some(SPECS_CONF), ["--grid", "true"],
+	grid_packed: None, ["--grid", "true", "--balance", "false", "--imp=-1"],
+	grid_fill: Some(FILL_CONF), ["--grid", "true", "--down", "true"],
+	grid_details: Some(GRID_DETAILS_CONF), ["--grid", "true", "--imp=-1"],
+	tree_default: None, ["--tree", "true"],
+	tree_details: None, ["--tree", "true", "--det", "typ", "--det", "size"],
+	tree_specs: Some(SPECS_CONF), ["--tree", "true", "--imp=-1"],
+	record_details: None, ["--record", "true", "--det", "typ", "--det", "size"],
+	recurse_only: None, ["--recurse", "true", "--only", "\\.rs$"],
+	tree_only: None, ["--tree", "true", "--only", "\\.rs$"],
+);